@@ -0,0 +1,5 @@
+fn main() {
+	// sets the linker arguments for loading the Node.js addon of the node feature, e.g. on macOS
+	#[cfg(feature = "node")]
+	napi_build::setup();
+}
@@ -0,0 +1,78 @@
+#ifndef JSONNLP_H
+#define JSONNLP_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef struct JSONNLP JSONNLP;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// parses a string containing JSON-NLP and returns the handle of the collection. On failure, it returns NULL and,
+// if `error` is not NULL, stores an error message in `*error`, which must be released with jsonnlp_string_free().
+//
+// # Safety
+//
+// `json` must be NULL or a NUL-terminated string, and `error` must be NULL or valid for writes.
+JSONNLP *jsonnlp_parse(const char *json,
+                       char **error);
+
+// reads a JSON-NLP file and returns the handle of the collection, see jsonnlp_parse().
+//
+// # Safety
+//
+// `path` must be NULL or a NUL-terminated string, and `error` must be NULL or valid for writes.
+JSONNLP *jsonnlp_parse_file(const char *path, char **error);
+
+// validates a collection and returns the number of problems found. If `report` is not NULL, the problems are
+// stored in `*report` as one line per problem, to be released with jsonnlp_string_free(). A panic while validating
+// counts as one problem with the panic message.
+//
+// # Safety
+//
+// `j` must be NULL or a handle that has not been freed, and `report` must be NULL or valid for writes.
+size_t jsonnlp_validate(const JSONNLP *j,
+                        char **report);
+
+// returns the JSON of a collection, indented with two spaces if `pretty` is not 0, or NULL if `j` is NULL, the
+// collection cannot be serialized, or serializing panics. The string must be released with jsonnlp_string_free().
+//
+// # Safety
+//
+// `j` must be NULL or a handle that has not been freed.
+char *jsonnlp_serialize(const JSONNLP *j,
+                        int pretty);
+
+// returns the number of documents of a collection, or 0 if `j` is NULL.
+//
+// # Safety
+//
+// `j` must be NULL or a handle that has not been freed.
+size_t jsonnlp_doc_count(const JSONNLP *j);
+
+// releases a collection. Passing NULL has no effect.
+//
+// # Safety
+//
+// `j` must be NULL or a handle that has not been freed.
+void jsonnlp_free(JSONNLP *j);
+
+// releases a string returned by the API. Passing NULL has no effect.
+//
+// # Safety
+//
+// `s` must be NULL or a string returned by the API that has not been freed.
+void jsonnlp_string_free(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JSONNLP_H */
@@ -0,0 +1,411 @@
+//! Arena allocation of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents with
+//! [bumpalo](https://github.com/fitzgen/bumpalo), behind the `arena` feature.
+//! All tokens, dependencies, and strings of a document are allocated in one bump arena and freed at once when it
+//! is reset or dropped. The properties without a field, like the layers other than tokens and dependency trees,
+//! are kept in the arena as the JSON text of an object.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use bumpalo::collections::Vec as BumpVec;
+use bumpalo::Bump;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+use crate::{Dependency, DependencyTree, Document, DocumentId, JsonNlpError, Prob, Result, SentenceId, Token, TokenId};
+
+/// This struct contains a token allocated in an arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaToken<'b> {
+	pub id: TokenId,
+	pub sentence_id: SentenceId,
+	pub text: &'b str,
+	pub lemma: &'b str,
+	pub xpos: &'b str,
+	pub xpos_prob: Option<Prob>,
+	pub upos: &'b str,
+	pub upos_prob: Option<Prob>,
+	pub entity_iob: &'b str,
+	pub entity: &'b str,
+	pub shape: &'b str,
+	pub char_offset_begin: Option<u64>,
+	pub char_offset_end: Option<u64>,
+	/// the other properties as a JSON object, or empty if there are none.
+	pub other: &'b str,
+}
+
+/// This struct contains a dependency allocated in an arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaDependency<'b> {
+	pub lab: &'b str,
+	pub gov: TokenId,
+	pub dep: TokenId,
+	pub prob: Option<Prob>,
+	/// the other properties as a JSON object, or empty if there are none.
+	pub other: &'b str,
+}
+
+/// This struct contains a dependency tree allocated in an arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaDependencyTree<'b> {
+	pub sentence_id: Option<SentenceId>,
+	pub style: &'b str,
+	pub dependencies: &'b [ArenaDependency<'b>],
+	pub prob: Option<Prob>,
+	/// the other properties as a JSON object, or empty if there are none.
+	pub other: &'b str,
+}
+
+/// This struct contains a document allocated in an arena.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArenaDocument<'b> {
+	pub id: DocumentId,
+	pub text: &'b str,
+	pub tokens: &'b [ArenaToken<'b>],
+	pub dependency_trees: &'b [ArenaDependencyTree<'b>],
+	/// the other properties, including `meta` and the other layers, as a JSON object, or empty if there are none.
+	pub other: &'b str,
+}
+
+/// returns the other properties of an arena object as a map.
+fn other_map(other: &str) -> Result<Map<String, Value>> {
+	if other.is_empty() {
+		return Ok(Map::new());
+	}
+	let mut de = serde_json::Deserializer::from_str(other);
+	let r = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+	Ok(r)
+}
+
+/// returns the JSON text of the other properties of an arena object, allocated in the arena.
+fn other_str<E: de::Error>(bump: &Bump, other: Map<String, Value>) -> std::result::Result<&str, E> {
+	if other.is_empty() {
+		return Ok("");
+	}
+	let json = serde_json::to_string(&other).map_err(E::custom)?;
+	Ok(bump.alloc_str(&json))
+}
+
+impl ArenaToken<'_> {
+	/// returns the token with owned strings.
+	pub fn to_token(&self) -> Result<Token> {
+		let mut m = other_map(self.other)?;
+		m.insert("id".to_string(), self.id.0.into());
+		m.insert("sentence_id".to_string(), self.sentence_id.0.into());
+		m.insert("text".to_string(), self.text.into());
+		m.insert("lemma".to_string(), self.lemma.into());
+		let mut token: Token = serde_json::from_value(Value::Object(m))?;
+		token.xpos = self.xpos.to_string();
+		token.xpos_prob = self.xpos_prob;
+		token.upos = self.upos.to_string();
+		token.upos_prob = self.upos_prob;
+		token.entity_iob = self.entity_iob.to_string();
+		token.entity = self.entity.to_string();
+		token.shape = self.shape.to_string();
+		token.char_offset_begin = self.char_offset_begin;
+		token.char_offset_end = self.char_offset_end;
+		Ok(token)
+	}
+}
+
+impl ArenaDependency<'_> {
+	/// returns the dependency with owned strings.
+	pub fn to_dependency(&self) -> Result<Dependency> {
+		Ok(Dependency { lab: self.lab.to_string(), gov: self.gov, dep: self.dep, prob: self.prob, extra: other_map(self.other)? })
+	}
+}
+
+impl ArenaDependencyTree<'_> {
+	/// returns the dependency tree with owned strings.
+	pub fn to_dependency_tree(&self) -> Result<DependencyTree> {
+		Ok(DependencyTree {
+			sentence_id: self.sentence_id,
+			style: self.style.to_string(),
+			dependencies: self.dependencies.iter().map(ArenaDependency::to_dependency).collect::<Result<_>>()?,
+			prob: self.prob,
+			extra: other_map(self.other)?,
+		})
+	}
+}
+
+impl ArenaDocument<'_> {
+	/// returns the document with owned strings and all layers parsed.
+	pub fn to_document(&self) -> Result<Document> {
+		let mut m = other_map(self.other)?;
+		m.insert("id".to_string(), self.id.0.into());
+		let mut document: Document = serde_json::from_value(Value::Object(m))?;
+		document.text = self.text.to_string();
+		document.token_list = self.tokens.iter().map(ArenaToken::to_token).collect::<Result<_>>()?;
+		document.dependency_trees = self.dependency_trees.iter().map(ArenaDependencyTree::to_dependency_tree).collect::<Result<_>>()?;
+		Ok(document)
+	}
+}
+
+/// This struct deserializes a string into the arena.
+struct StrSeed<'b>(&'b Bump);
+
+impl<'de, 'b> DeserializeSeed<'de> for StrSeed<'b> {
+	type Value = &'b str;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<&'b str, D::Error> {
+		deserializer.deserialize_str(self)
+	}
+}
+
+impl<'de, 'b> Visitor<'de> for StrSeed<'b> {
+	type Value = &'b str;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a string")
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> std::result::Result<&'b str, E> {
+		Ok(self.0.alloc_str(v))
+	}
+}
+
+/// This struct deserializes a list into a slice in the arena, with the elements deserialized by seeds of type S.
+struct SliceSeed<'b, S>(&'b Bump, PhantomData<S>);
+
+/// This trait creates the seeds of the elements of SliceSeed.
+trait ArenaSeed<'b> {
+	fn new(bump: &'b Bump) -> Self;
+}
+
+impl<'de, 'b, S: ArenaSeed<'b> + DeserializeSeed<'de>> DeserializeSeed<'de> for SliceSeed<'b, S>
+where
+	S::Value: 'b,
+{
+	type Value = &'b [S::Value];
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_seq(self)
+	}
+}
+
+impl<'de, 'b, S: ArenaSeed<'b> + DeserializeSeed<'de>> Visitor<'de> for SliceSeed<'b, S>
+where
+	S::Value: 'b,
+{
+	type Value = &'b [S::Value];
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a list")
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<Self::Value, A::Error> {
+		let mut v = BumpVec::new_in(self.0);
+		while let Some(e) = seq.next_element_seed(S::new(self.0))? {
+			v.push(e);
+		}
+		Ok(v.into_bump_slice())
+	}
+}
+
+struct TokenSeed<'b>(&'b Bump);
+struct DependencySeed<'b>(&'b Bump);
+struct DependencyTreeSeed<'b>(&'b Bump);
+struct DocumentSeed<'b>(&'b Bump);
+
+impl<'b> ArenaSeed<'b> for TokenSeed<'b> {
+	fn new(bump: &'b Bump) -> Self {
+		TokenSeed(bump)
+	}
+}
+
+impl<'b> ArenaSeed<'b> for DependencySeed<'b> {
+	fn new(bump: &'b Bump) -> Self {
+		DependencySeed(bump)
+	}
+}
+
+impl<'b> ArenaSeed<'b> for DependencyTreeSeed<'b> {
+	fn new(bump: &'b Bump) -> Self {
+		DependencyTreeSeed(bump)
+	}
+}
+
+impl<'de, 'b> DeserializeSeed<'de> for TokenSeed<'b> {
+	type Value = ArenaToken<'b>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'b> Visitor<'de> for TokenSeed<'b> {
+	type Value = ArenaToken<'b>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP token")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+		let (mut id, mut sentence_id, mut text, mut lemma) = (None, None, None, None);
+		let mut t = ArenaToken {
+			id: TokenId::default(),
+			sentence_id: SentenceId::default(),
+			text: "",
+			lemma: "",
+			xpos: "",
+			xpos_prob: None,
+			upos: "",
+			upos_prob: None,
+			entity_iob: "",
+			entity: "",
+			shape: "",
+			char_offset_begin: None,
+			char_offset_end: None,
+			other: "",
+		};
+		let mut other = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"id" => id = Some(map.next_value()?),
+				"sentence_id" => sentence_id = Some(map.next_value()?),
+				"text" => text = Some(map.next_value_seed(StrSeed(self.0))?),
+				"lemma" => lemma = Some(map.next_value_seed(StrSeed(self.0))?),
+				"xpos" => t.xpos = map.next_value_seed(StrSeed(self.0))?,
+				"xpos_prob" => t.xpos_prob = map.next_value()?,
+				"upos" => t.upos = map.next_value_seed(StrSeed(self.0))?,
+				"upos_prob" => t.upos_prob = map.next_value()?,
+				"entity_iob" => t.entity_iob = map.next_value_seed(StrSeed(self.0))?,
+				"entity" => t.entity = map.next_value_seed(StrSeed(self.0))?,
+				"shape" => t.shape = map.next_value_seed(StrSeed(self.0))?,
+				"characterOffsetBegin" => t.char_offset_begin = map.next_value()?,
+				"characterOffsetEnd" => t.char_offset_end = map.next_value()?,
+				_ => {
+					other.insert(key, map.next_value()?);
+				}
+			}
+		}
+		t.id = id.ok_or_else(|| de::Error::missing_field("id"))?;
+		t.sentence_id = sentence_id.ok_or_else(|| de::Error::missing_field("sentence_id"))?;
+		t.text = text.ok_or_else(|| de::Error::missing_field("text"))?;
+		t.lemma = lemma.ok_or_else(|| de::Error::missing_field("lemma"))?;
+		t.other = other_str(self.0, other)?;
+		Ok(t)
+	}
+}
+
+impl<'de, 'b> DeserializeSeed<'de> for DependencySeed<'b> {
+	type Value = ArenaDependency<'b>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'b> Visitor<'de> for DependencySeed<'b> {
+	type Value = ArenaDependency<'b>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP dependency")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+		let (mut lab, mut gov, mut dep, mut prob) = (None, None, None, None);
+		let mut other = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"lab" => lab = Some(map.next_value_seed(StrSeed(self.0))?),
+				"gov" => gov = Some(map.next_value()?),
+				"dep" => dep = Some(map.next_value()?),
+				"prob" => prob = map.next_value()?,
+				_ => {
+					other.insert(key, map.next_value()?);
+				}
+			}
+		}
+		Ok(ArenaDependency {
+			lab: lab.ok_or_else(|| de::Error::missing_field("lab"))?,
+			gov: gov.ok_or_else(|| de::Error::missing_field("gov"))?,
+			dep: dep.ok_or_else(|| de::Error::missing_field("dep"))?,
+			prob,
+			other: other_str(self.0, other)?,
+		})
+	}
+}
+
+impl<'de, 'b> DeserializeSeed<'de> for DependencyTreeSeed<'b> {
+	type Value = ArenaDependencyTree<'b>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'b> Visitor<'de> for DependencyTreeSeed<'b> {
+	type Value = ArenaDependencyTree<'b>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP dependency tree")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+		let mut tree = ArenaDependencyTree { sentence_id: None, style: "", dependencies: &[], prob: None, other: "" };
+		let mut other = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"sentenceId" => tree.sentence_id = map.next_value()?,
+				"style" => tree.style = map.next_value_seed(StrSeed(self.0))?,
+				"dependencies" => tree.dependencies = map.next_value_seed(SliceSeed::<DependencySeed>(self.0, PhantomData))?,
+				"prob" => tree.prob = map.next_value()?,
+				_ => {
+					other.insert(key, map.next_value()?);
+				}
+			}
+		}
+		tree.other = other_str(self.0, other)?;
+		Ok(tree)
+	}
+}
+
+impl<'de, 'b> DeserializeSeed<'de> for DocumentSeed<'b> {
+	type Value = ArenaDocument<'b>;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Self::Value, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, 'b> Visitor<'de> for DocumentSeed<'b> {
+	type Value = ArenaDocument<'b>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP document")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Self::Value, A::Error> {
+		let mut id = None;
+		let mut d = ArenaDocument { id: DocumentId::default(), text: "", tokens: &[], dependency_trees: &[], other: "" };
+		let mut other = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"id" => id = Some(map.next_value()?),
+				"text" => d.text = map.next_value_seed(StrSeed(self.0))?,
+				"tokenList" => d.tokens = map.next_value_seed(SliceSeed::<TokenSeed>(self.0, PhantomData))?,
+				"dependencyTrees" => d.dependency_trees = map.next_value_seed(SliceSeed::<DependencyTreeSeed>(self.0, PhantomData))?,
+				_ => {
+					other.insert(key, map.next_value()?);
+				}
+			}
+		}
+		if !other.contains_key("meta") {
+			return Err(de::Error::missing_field("meta"));
+		}
+		d.id = id.ok_or_else(|| de::Error::missing_field("id"))?;
+		d.other = other_str(self.0, other)?;
+		Ok(d)
+	}
+}
+
+/// This function converts a string containing one [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document, e.g.
+/// a line of NDJSON, allocating it in the given arena.
+pub fn document_from_str_in<'b>(json: &str, bump: &'b Bump) -> Result<ArenaDocument<'b>> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let r = DocumentSeed(bump).deserialize(&mut de).map_err(JsonNlpError::from)?;
+	de.end()?;
+	Ok(r)
+}
@@ -0,0 +1,187 @@
+//! Export of the tokens, entities, and dependencies of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP)
+//! collections as [Apache Arrow](https://arrow.apache.org/) record batches and [Parquet](https://parquet.apache.org/)
+//! files, one row per item, for analytics in e.g. DuckDB or Spark. The columns are named after the JSON-NLP
+//! properties, with the ID of the document of every row in `docId`.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use arrow_array::builder::{Float64Builder, ListBuilder, StringBuilder, UInt64Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+
+use crate::{JsonNlpError, Prob, Result, JSONNLP};
+
+fn error(format: &str, e: impl std::fmt::Display) -> JsonNlpError {
+	JsonNlpError::Format(format!("{}: {}", format, e))
+}
+
+fn prob(p: Option<Prob>) -> Option<f64> {
+	p.map(Prob::get)
+}
+
+fn batch(columns: Vec<(&str, ArrayRef, bool)>) -> Result<RecordBatch> {
+	RecordBatch::try_from_iter_with_nullable(columns).map_err(|e| error("Arrow", e))
+}
+
+/// This function returns the tokens of all documents as a record batch with the columns `docId`, `id`,
+/// `sentence_id`, `text`, `lemma`, `xpos`, `xpos_prob`, `upos`, `upos_prob`, `entity_iob`, `entity`, `shape`,
+/// `characterOffsetBegin`, and `characterOffsetEnd`.
+pub fn token_batch(j: &JSONNLP) -> Result<RecordBatch> {
+	let mut doc = UInt64Builder::new();
+	let mut id = UInt64Builder::new();
+	let mut sentence_id = UInt64Builder::new();
+	let mut text = StringBuilder::new();
+	let mut lemma = StringBuilder::new();
+	let mut xpos = StringBuilder::new();
+	let mut xpos_prob = Float64Builder::new();
+	let mut upos = StringBuilder::new();
+	let mut upos_prob = Float64Builder::new();
+	let mut entity_iob = StringBuilder::new();
+	let mut entity = StringBuilder::new();
+	let mut shape = StringBuilder::new();
+	let mut begin = UInt64Builder::new();
+	let mut end = UInt64Builder::new();
+	for d in &j.docs {
+		for t in &d.token_list {
+			doc.append_value(d.id.into());
+			id.append_value(t.id.into());
+			sentence_id.append_value(t.sentence_id.into());
+			text.append_value(&t.text);
+			lemma.append_value(&t.lemma);
+			xpos.append_value(&t.xpos);
+			xpos_prob.append_option(prob(t.xpos_prob));
+			upos.append_value(&t.upos);
+			upos_prob.append_option(prob(t.upos_prob));
+			entity_iob.append_value(&t.entity_iob);
+			entity.append_value(&t.entity);
+			shape.append_value(&t.shape);
+			begin.append_option(t.char_offset_begin);
+			end.append_option(t.char_offset_end);
+		}
+	}
+	batch(vec![
+		("docId", Arc::new(doc.finish()), false),
+		("id", Arc::new(id.finish()), false),
+		("sentence_id", Arc::new(sentence_id.finish()), false),
+		("text", Arc::new(text.finish()), false),
+		("lemma", Arc::new(lemma.finish()), false),
+		("xpos", Arc::new(xpos.finish()), false),
+		("xpos_prob", Arc::new(xpos_prob.finish()), true),
+		("upos", Arc::new(upos.finish()), false),
+		("upos_prob", Arc::new(upos_prob.finish()), true),
+		("entity_iob", Arc::new(entity_iob.finish()), false),
+		("entity", Arc::new(entity.finish()), false),
+		("shape", Arc::new(shape.finish()), false),
+		("characterOffsetBegin", Arc::new(begin.finish()), true),
+		("characterOffsetEnd", Arc::new(end.finish()), true),
+	])
+}
+
+/// This function returns the entities of all documents as a record batch with the columns `docId`, `id`, `label`,
+/// `type`, `url`, `head`, `tokenFrom`, `tokenTo`, `tokens` as a list of token IDs, `sentiment`, `sentimentProb`,
+/// and `count`.
+pub fn entity_batch(j: &JSONNLP) -> Result<RecordBatch> {
+	let mut doc = UInt64Builder::new();
+	let mut id = UInt64Builder::new();
+	let mut label = StringBuilder::new();
+	let mut etype = StringBuilder::new();
+	let mut url = StringBuilder::new();
+	let mut head = UInt64Builder::new();
+	let mut token_from = UInt64Builder::new();
+	let mut token_to = UInt64Builder::new();
+	let mut tokens = ListBuilder::new(UInt64Builder::new());
+	let mut sentiment = StringBuilder::new();
+	let mut sentiment_prob = Float64Builder::new();
+	let mut count = UInt64Builder::new();
+	for d in &j.docs {
+		for e in &d.entities {
+			doc.append_value(d.id.into());
+			id.append_value(e.id.into());
+			label.append_value(&e.label);
+			etype.append_value(&e.etype);
+			url.append_value(&e.url);
+			head.append_option(e.head.map(u64::from));
+			token_from.append_option(e.token_from.map(u64::from));
+			token_to.append_option(e.token_to.map(u64::from));
+			tokens.append_value(e.tokens.iter().map(|t| Some(u64::from(*t))));
+			sentiment.append_value(&e.sentiment);
+			sentiment_prob.append_option(prob(e.sentiment_prob));
+			count.append_option(e.count);
+		}
+	}
+	batch(vec![
+		("docId", Arc::new(doc.finish()), false),
+		("id", Arc::new(id.finish()), false),
+		("label", Arc::new(label.finish()), false),
+		("type", Arc::new(etype.finish()), false),
+		("url", Arc::new(url.finish()), false),
+		("head", Arc::new(head.finish()), true),
+		("tokenFrom", Arc::new(token_from.finish()), true),
+		("tokenTo", Arc::new(token_to.finish()), true),
+		("tokens", Arc::new(tokens.finish()), false),
+		("sentiment", Arc::new(sentiment.finish()), false),
+		("sentimentProb", Arc::new(sentiment_prob.finish()), true),
+		("count", Arc::new(count.finish()), true),
+	])
+}
+
+/// This function returns the dependencies of all dependency trees as a record batch with the columns `docId`,
+/// `tree` as the index of the dependency tree in its document, `sentenceId`, `style`, `lab`, `gov`, `dep`, and
+/// `prob`.
+pub fn dependency_batch(j: &JSONNLP) -> Result<RecordBatch> {
+	let mut doc = UInt64Builder::new();
+	let mut tree = UInt64Builder::new();
+	let mut sentence_id = UInt64Builder::new();
+	let mut style = StringBuilder::new();
+	let mut lab = StringBuilder::new();
+	let mut gov = UInt64Builder::new();
+	let mut dep = UInt64Builder::new();
+	let mut p = Float64Builder::new();
+	for d in &j.docs {
+		for (i, t) in d.dependency_trees.iter().enumerate() {
+			for dependency in &t.dependencies {
+				doc.append_value(d.id.into());
+				tree.append_value(i as u64);
+				sentence_id.append_option(t.sentence_id.map(u64::from));
+				style.append_value(&t.style);
+				lab.append_value(&dependency.lab);
+				gov.append_value(dependency.gov.into());
+				dep.append_value(dependency.dep.into());
+				p.append_option(prob(dependency.prob));
+			}
+		}
+	}
+	batch(vec![
+		("docId", Arc::new(doc.finish()), false),
+		("tree", Arc::new(tree.finish()), false),
+		("sentenceId", Arc::new(sentence_id.finish()), true),
+		("style", Arc::new(style.finish()), false),
+		("lab", Arc::new(lab.finish()), false),
+		("gov", Arc::new(gov.finish()), false),
+		("dep", Arc::new(dep.finish()), false),
+		("prob", Arc::new(p.finish()), true),
+	])
+}
+
+/// This function writes a record batch as a Snappy-compressed Parquet file to a writer.
+pub fn write_parquet<W: Write + Send>(batch: &RecordBatch, writer: W) -> Result<()> {
+	let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+	let mut writer = ArrowWriter::try_new(writer, batch.schema(), Some(properties)).map_err(|e| error("Parquet", e))?;
+	writer.write(batch).map_err(|e| error("Parquet", e))?;
+	writer.close().map_err(|e| error("Parquet", e))?;
+	Ok(())
+}
+
+/// This function writes the tokens, entities, and dependencies of a collection to the Parquet files
+/// `tokens.parquet`, `entities.parquet`, and `dependencies.parquet` in a directory, which must exist.
+pub fn to_parquet<P: AsRef<Path>>(j: &JSONNLP, dir: P) -> Result<()> {
+	let dir = dir.as_ref();
+	write_parquet(&token_batch(j)?, File::create(dir.join("tokens.parquet"))?)?;
+	write_parquet(&entity_batch(j)?, File::create(dir.join("entities.parquet"))?)?;
+	write_parquet(&dependency_batch(j)?, File::create(dir.join("dependencies.parquet"))?)
+}
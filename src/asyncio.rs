@@ -0,0 +1,37 @@
+//! Reading and writing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) with [tokio](https://tokio.rs/)
+//! asynchronous I/O. The JSON is read completely before it is parsed and serialized completely before it is
+//! written, so the I/O does not block the runtime, while parsing and serializing run on the calling task.
+
+use std::path::Path;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{from_slice, JsonNlpError, Result, JSONNLP};
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from a file asynchronously.
+pub async fn from_file_async<P: AsRef<Path>>(path: P) -> Result<JSONNLP> {
+	let bytes = tokio::fs::read(path).await?;
+	from_slice(&bytes)
+}
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from an asynchronous reader.
+pub async fn from_reader_async<R: AsyncRead + Unpin>(mut reader: R) -> Result<JSONNLP> {
+	let mut bytes = Vec::new();
+	reader.read_to_end(&mut bytes).await?;
+	from_slice(&bytes)
+}
+
+/// This function writes a JSONNLP struct/object as JSON to an asynchronous writer and flushes it.
+pub async fn to_writer_async<W: AsyncWrite + Unpin>(j: &JSONNLP, mut writer: W) -> Result<()> {
+	let bytes = serde_json::to_vec(j).map_err(JsonNlpError::Serialize)?;
+	writer.write_all(&bytes).await?;
+	writer.flush().await?;
+	Ok(())
+}
+
+/// This function writes a JSONNLP struct/object as JSON to a file asynchronously, replacing an existing file.
+pub async fn to_file_async<P: AsRef<Path>>(j: &JSONNLP, path: P) -> Result<()> {
+	let bytes = serde_json::to_vec(j).map_err(JsonNlpError::Serialize)?;
+	tokio::fs::write(path, bytes).await?;
+	Ok(())
+}
@@ -0,0 +1,678 @@
+//! [Apache Avro](https://avro.apache.org/) object container files and single datums of
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents, with the bundled Avro schema of `Document`.
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::sync::OnceLock;
+
+use serde_json::{Map, Number, Value};
+
+use crate::{Document, JsonNlpError, Meta, Result, JSONNLP};
+
+/// The Avro schema of JSON-NLP documents bundled with this crate.
+pub const AVRO_SCHEMA: &str = include_str!("../schema/jsonnlp.avsc");
+
+const MAGIC: &[u8; 4] = b"Obj\x01";
+
+/// the number of documents written per block of a container file.
+const BLOCK_SIZE: usize = 64;
+
+/// the largest number of items per array or map block whose items are encoded in no bytes.
+const MAX_EMPTY_ITEMS: u64 = 1 << 16;
+
+fn error(msg: &str) -> JsonNlpError {
+	JsonNlpError::Format(format!("Avro: {}", msg))
+}
+
+/// This enum contains the Avro types, with records and enums given by their index in the Codec.
+#[derive(Debug, Clone)]
+enum Schema {
+	Null,
+	Boolean,
+	Int,
+	Long,
+	Float,
+	Double,
+	Bytes,
+	String,
+	Array(Box<Schema>),
+	Map(Box<Schema>),
+	Union(Vec<Schema>),
+	Record(usize),
+	Enum(Vec<String>),
+	Fixed(usize),
+}
+
+/// This struct contains a record field with its name in JSON-NLP, given by the `jsonName` property if it differs.
+/// The map of a `flatten` field holds the JSON-encoded properties of the record that are not fields.
+#[derive(Debug)]
+struct Field {
+	json_name: String,
+	flatten: bool,
+	schema: Schema,
+}
+
+/// This struct contains a parsed schema, encoding JSON values to Avro and decoding them back.
+#[derive(Debug)]
+struct Codec {
+	root: Schema,
+	records: Vec<Vec<Field>>,
+}
+
+impl Codec {
+	fn new(schema: &Value) -> Result<Codec> {
+		let mut codec = Codec { root: Schema::Null, records: Vec::new() };
+		codec.root = codec.parse(schema, "", &mut HashMap::new())?;
+		Ok(codec)
+	}
+
+	fn parse(&mut self, schema: &Value, namespace: &str, names: &mut HashMap<String, Schema>) -> Result<Schema> {
+		let full_name = |name: &str, namespace: &str| {
+			if name.contains('.') || namespace.is_empty() { name.to_string() } else { format!("{}.{}", namespace, name) }
+		};
+		let name = match schema {
+			Value::Array(branches) => {
+				return Ok(Schema::Union(branches.iter().map(|b| self.parse(b, namespace, names)).collect::<Result<_>>()?));
+			}
+			Value::String(name) => name.as_str(),
+			Value::Object(o) => o.get("type").and_then(Value::as_str).ok_or_else(|| error("schema without type"))?,
+			_ => return Err(error(&format!("invalid schema {}", schema))),
+		};
+		Ok(match name {
+			"null" => Schema::Null,
+			"boolean" => Schema::Boolean,
+			"int" => Schema::Int,
+			"long" => Schema::Long,
+			"float" => Schema::Float,
+			"double" => Schema::Double,
+			"bytes" => Schema::Bytes,
+			"string" => Schema::String,
+			"array" => Schema::Array(Box::new(self.parse(&schema["items"], namespace, names)?)),
+			"map" => Schema::Map(Box::new(self.parse(&schema["values"], namespace, names)?)),
+			"record" | "error" | "enum" | "fixed" if schema.is_object() => {
+				let namespace = schema["namespace"].as_str().unwrap_or(namespace);
+				let full = full_name(schema["name"].as_str().unwrap_or_default(), namespace);
+				let namespace = full.rsplit_once('.').map_or("", |n| n.0).to_string();
+				let parsed = match name {
+					"enum" => Schema::Enum(schema["symbols"].as_array().into_iter().flatten().filter_map(Value::as_str).map(str::to_string).collect()),
+					"fixed" => Schema::Fixed(schema["size"].as_u64().ok_or_else(|| error("fixed without size"))? as usize),
+					_ => {
+						let index = self.records.len();
+						self.records.push(Vec::new());
+						// the record is named before its fields, which may refer to it
+						names.insert(full.clone(), Schema::Record(index));
+						let mut fields = Vec::new();
+						for f in schema["fields"].as_array().into_iter().flatten() {
+							let name = f["name"].as_str().ok_or_else(|| error("field without name"))?;
+							fields.push(Field {
+								json_name: f["jsonName"].as_str().unwrap_or(name).to_string(),
+								flatten: f["flatten"].as_bool().unwrap_or(false),
+								schema: self.parse(&f["type"], &namespace, names)?,
+							});
+						}
+						self.records[index] = fields;
+						Schema::Record(index)
+					}
+				};
+				names.insert(full, parsed.clone());
+				parsed
+			}
+			reference => names.get(&full_name(reference, namespace)).or_else(|| names.get(reference)).cloned()
+				.ok_or_else(|| error(&format!("unknown type {:?}", reference)))?,
+		})
+	}
+
+	/// checks whether a JSON value can be encoded as the type, choosing the branch of a union.
+	fn accepts(&self, schema: &Schema, value: &Value) -> bool {
+		match schema {
+			Schema::Null => value.is_null(),
+			Schema::Boolean => value.is_boolean(),
+			Schema::Int | Schema::Long => value.is_i64() || value.is_u64(),
+			Schema::Float | Schema::Double => value.is_number(),
+			Schema::Bytes | Schema::String | Schema::Enum(_) | Schema::Fixed(_) => value.is_string(),
+			Schema::Array(_) => value.is_array(),
+			Schema::Map(_) | Schema::Record(_) => value.is_object(),
+			Schema::Union(branches) => branches.iter().any(|b| self.accepts(b, value)),
+		}
+	}
+
+	/// encodes a JSON value, with null for missing values written as the default of the type.
+	fn encode(&self, schema: &Schema, value: &Value, out: &mut Vec<u8>) -> Result<()> {
+		let mismatch = || error(&format!("cannot encode {} as {:?}", value, schema));
+		match schema {
+			Schema::Null => {}
+			Schema::Boolean => out.push(value.as_bool().unwrap_or(false) as u8),
+			Schema::Int | Schema::Long => {
+				let n = match value {
+					Value::Null => 0,
+					_ => value.as_i64().or_else(|| value.as_u64().map(|n| n as i64)).ok_or_else(mismatch)?,
+				};
+				write_long(out, n);
+			}
+			Schema::Float => out.extend_from_slice(&(value.as_f64().unwrap_or_default() as f32).to_le_bytes()),
+			Schema::Double => out.extend_from_slice(&value.as_f64().unwrap_or_default().to_le_bytes()),
+			Schema::Bytes | Schema::String => match value {
+				Value::Null => write_long(out, 0),
+				Value::String(s) => write_bytes(out, s.as_bytes()),
+				_ => return Err(mismatch()),
+			},
+			Schema::Fixed(size) => {
+				let mut bytes = value.as_str().unwrap_or_default().as_bytes().to_vec();
+				bytes.resize(*size, 0);
+				out.extend_from_slice(&bytes);
+			}
+			Schema::Enum(symbols) => {
+				let s = value.as_str().unwrap_or_default();
+				write_long(out, symbols.iter().position(|x| x == s).ok_or_else(mismatch)? as i64);
+			}
+			Schema::Array(items) => {
+				let values = match value {
+					Value::Null => &[][..],
+					Value::Array(values) => values.as_slice(),
+					_ => return Err(mismatch()),
+				};
+				if !values.is_empty() {
+					write_long(out, values.len() as i64);
+					for v in values {
+						self.encode(items, v, out)?;
+					}
+				}
+				write_long(out, 0);
+			}
+			Schema::Map(values) => {
+				let empty = Map::new();
+				let entries = match value {
+					Value::Null => &empty,
+					Value::Object(o) => o,
+					_ => return Err(mismatch()),
+				};
+				if !entries.is_empty() {
+					write_long(out, entries.len() as i64);
+					for (k, v) in entries {
+						write_bytes(out, k.as_bytes());
+						self.encode(values, v, out)?;
+					}
+				}
+				write_long(out, 0);
+			}
+			Schema::Union(branches) => {
+				let index = branches.iter().position(|b| self.accepts(b, value))
+					.or_else(|| branches.iter().position(|b| !matches!(b, Schema::Null)).filter(|_| value.is_null()))
+					.ok_or_else(mismatch)?;
+				write_long(out, index as i64);
+				self.encode(&branches[index], value, out)?;
+			}
+			Schema::Record(index) => {
+				let empty = Map::new();
+				let object = match value {
+					Value::Null => &empty,
+					Value::Object(o) => o,
+					_ => return Err(mismatch()),
+				};
+				let fields = &self.records[*index];
+				for f in fields {
+					if f.flatten {
+						let extra: Map<String, Value> = object.iter()
+							.filter(|(k, _)| !fields.iter().any(|f| !f.flatten && &f.json_name == *k))
+							.map(|(k, v)| (k.clone(), Value::String(v.to_string())))
+							.collect();
+						self.encode(&f.schema, &Value::Object(extra), out)?;
+					} else {
+						self.encode(&f.schema, object.get(&f.json_name).unwrap_or(&Value::Null), out)?;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// decodes a JSON value, leaving out the null values of record fields.
+	/// returns the least number of bytes a value of the schema is encoded in, where records nested deeper than
+	/// `depth` count as empty.
+	fn min_size(&self, schema: &Schema, depth: usize) -> usize {
+		match schema {
+			Schema::Null => 0,
+			Schema::Fixed(size) => *size,
+			Schema::Float => 4,
+			Schema::Double => 8,
+			Schema::Record(_) if depth == 0 => 0,
+			Schema::Record(index) => self.records[*index].iter().map(|f| self.min_size(&f.schema, depth - 1)).sum(),
+			_ => 1,
+		}
+	}
+
+	/// checks the item count of an array or map block against the remaining input, so that malformed counts fail
+	/// instead of decoding items until memory runs out. Items that take no bytes, like `null`, are limited to
+	/// `MAX_EMPTY_ITEMS` per block.
+	fn check_count(&self, items: &Schema, count: u64, input: &[u8]) -> Result<()> {
+		let size = self.min_size(items, 8) as u64;
+		let max = (input.len() as u64).checked_div(size).unwrap_or(MAX_EMPTY_ITEMS);
+		if count > max {
+			return Err(error(&format!("block of {} items exceeds the remaining {} bytes", count, input.len())));
+		}
+		Ok(())
+	}
+
+	fn decode(&self, schema: &Schema, input: &mut &[u8]) -> Result<Value> {
+		Ok(match schema {
+			Schema::Null => Value::Null,
+			Schema::Boolean => Value::Bool(take(input, 1)?[0] != 0),
+			Schema::Int | Schema::Long => Value::from(read_long(input)?),
+			Schema::Float => {
+				let bytes = take(input, 4)?;
+				float(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64)
+			}
+			Schema::Double => {
+				let mut bytes = [0; 8];
+				bytes.copy_from_slice(take(input, 8)?);
+				float(f64::from_le_bytes(bytes))
+			}
+			Schema::String => Value::String(read_string(input)?),
+			Schema::Bytes => {
+				let length = read_length(input)?;
+				Value::String(String::from_utf8_lossy(take(input, length)?).into_owned())
+			}
+			Schema::Fixed(size) => Value::String(String::from_utf8_lossy(take(input, *size)?).into_owned()),
+			Schema::Enum(symbols) => {
+				let index = read_long(input)?;
+				Value::String(symbols.get(index as usize).ok_or_else(|| error(&format!("enum index {} out of range", index)))?.clone())
+			}
+			Schema::Array(items) => {
+				let mut values = Vec::new();
+				while let Some(count) = read_block(input)? {
+					self.check_count(items, count, input)?;
+					for _ in 0..count {
+						values.push(self.decode(items, input)?);
+					}
+				}
+				Value::Array(values)
+			}
+			Schema::Map(values) => {
+				let mut entries = Map::new();
+				while let Some(count) = read_block(input)? {
+					// every entry has at least the length of its key
+					self.check_count(&Schema::Long, count, input)?;
+					for _ in 0..count {
+						let key = read_string(input)?;
+						entries.insert(key, self.decode(values, input)?);
+					}
+				}
+				Value::Object(entries)
+			}
+			Schema::Union(branches) => {
+				let index = read_long(input)?;
+				let branch = branches.get(index as usize).ok_or_else(|| error(&format!("union index {} out of range", index)))?;
+				self.decode(branch, input)?
+			}
+			Schema::Record(index) => {
+				let mut object = Map::new();
+				for f in &self.records[*index] {
+					match self.decode(&f.schema, input)? {
+						Value::Null => {}
+						Value::Object(extra) if f.flatten => {
+							for (k, v) in extra {
+								let value = match v {
+									Value::String(s) => serde_json::from_str(&s).unwrap_or(Value::String(s)),
+									v => v,
+								};
+								object.insert(k, value);
+							}
+						}
+						value => {
+							object.insert(f.json_name.clone(), value);
+						}
+					}
+				}
+				Value::Object(object)
+			}
+		})
+	}
+}
+
+/// returns the parsed bundled schema.
+fn bundled() -> &'static Codec {
+	static CODEC: OnceLock<Codec> = OnceLock::new();
+	CODEC.get_or_init(|| {
+		let schema: Value = serde_json::from_str(AVRO_SCHEMA).expect("bundled Avro schema is valid JSON");
+		Codec::new(&schema).expect("bundled Avro schema is valid")
+	})
+}
+
+fn float(f: f64) -> Value {
+	Number::from_f64(f).map_or(Value::Null, Value::Number)
+}
+
+fn write_long(out: &mut Vec<u8>, n: i64) {
+	let mut z = ((n << 1) ^ (n >> 63)) as u64;
+	while z >= 0x80 {
+		out.push((z as u8 & 0x7f) | 0x80);
+		z >>= 7;
+	}
+	out.push(z as u8);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+	write_long(out, bytes.len() as i64);
+	out.extend_from_slice(bytes);
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8]> {
+	if input.len() < n {
+		return Err(error("unexpected end of data"));
+	}
+	let (head, tail) = input.split_at(n);
+	*input = tail;
+	Ok(head)
+}
+
+fn read_long(input: &mut &[u8]) -> Result<i64> {
+	let mut z: u64 = 0;
+	for shift in (0..64).step_by(7) {
+		let b = take(input, 1)?[0];
+		z |= ((b & 0x7f) as u64) << shift;
+		if b < 0x80 {
+			return Ok((z >> 1) as i64 ^ -((z & 1) as i64));
+		}
+	}
+	Err(error("invalid variable-length integer"))
+}
+
+fn read_length(input: &mut &[u8]) -> Result<usize> {
+	let n = read_long(input)?;
+	usize::try_from(n).map_err(|_| error(&format!("negative length {}", n)))
+}
+
+fn read_string(input: &mut &[u8]) -> Result<String> {
+	let length = read_length(input)?;
+	String::from_utf8(take(input, length)?.to_vec()).map_err(|_| error("string is not UTF-8"))
+}
+
+/// returns the number of items of the next block of an array or map, or None after the last block.
+fn read_block(input: &mut &[u8]) -> Result<Option<u64>> {
+	match read_long(input)? {
+		0 => Ok(None),
+		n if n < 0 => {
+			// negative counts are followed by the size of the block in bytes
+			read_long(input)?;
+			Ok(Some(n.unsigned_abs()))
+		}
+		n => Ok(Some(n as u64)),
+	}
+}
+
+/// returns the next variable-length integer of a reader, or None at the end of the input.
+fn read_long_from<R: Read>(reader: &mut R) -> Result<Option<i64>> {
+	let mut bytes = Vec::new();
+	let mut b = [0; 1];
+	loop {
+		if reader.read(&mut b)? == 0 {
+			return if bytes.is_empty() { Ok(None) } else { Err(error("unexpected end of data")) };
+		}
+		bytes.push(b[0]);
+		if b[0] < 0x80 || bytes.len() == 10 {
+			return read_long(&mut bytes.as_slice()).map(Some);
+		}
+	}
+}
+
+/// returns the next `n` bytes of a reader, which are read as they arrive so that a length or block size
+/// taken from malformed input cannot allocate more memory than the input has.
+fn read_exact<R: Read>(reader: &mut R, n: usize) -> Result<Vec<u8>> {
+	let mut bytes = Vec::new();
+	reader.by_ref().take(n as u64).read_to_end(&mut bytes)?;
+	if bytes.len() < n {
+		return Err(error("unexpected end of data"));
+	}
+	Ok(bytes)
+}
+
+/// This function encodes a Document as an Avro datum of the bundled schema, without a header, e.g. for Kafka messages.
+pub fn to_avro_datum(doc: &Document) -> Result<Vec<u8>> {
+	let mut out = Vec::new();
+	let codec = bundled();
+	codec.encode(&codec.root, &serde_json::to_value(doc)?, &mut out)?;
+	Ok(out)
+}
+
+/// This function decodes a Document from an Avro datum of the bundled schema.
+pub fn from_avro_datum(datum: &[u8]) -> Result<Document> {
+	let codec = bundled();
+	let mut input = datum;
+	let value = codec.decode(&codec.root, &mut input)?;
+	if !input.is_empty() {
+		return Err(error("data after the datum"));
+	}
+	Ok(serde_json::from_value(value)?)
+}
+
+/// This struct writes Documents to an Avro object container file with the bundled schema, uncompressed.
+/// The metadata of the collection is kept in the `jsonnlp.meta` metadata of the file. Documents are written
+/// in blocks, and finish() writes the last block.
+pub struct AvroWriter<W: Write> {
+	writer: W,
+	sync: [u8; 16],
+	block: Vec<u8>,
+	count: usize,
+}
+
+impl<W: Write> AvroWriter<W> {
+	/// returns a writer after writing the header of the file.
+	pub fn new(mut writer: W, meta: &Meta) -> Result<Self> {
+		let mut sync = [0; 16];
+		for half in sync.chunks_mut(8) {
+			half.copy_from_slice(&RandomState::new().build_hasher().finish().to_le_bytes());
+		}
+		let mut header = MAGIC.to_vec();
+		write_long(&mut header, 3);
+		for (key, value) in [("avro.schema", serde_json::from_str::<Value>(AVRO_SCHEMA)?.to_string()), ("avro.codec", "null".to_string()), ("jsonnlp.meta", serde_json::to_string(meta)?)].iter() {
+			write_bytes(&mut header, key.as_bytes());
+			write_bytes(&mut header, value.as_bytes());
+		}
+		write_long(&mut header, 0);
+		header.extend_from_slice(&sync);
+		writer.write_all(&header)?;
+		Ok(AvroWriter { writer, sync, block: Vec::new(), count: 0 })
+	}
+
+	/// appends a document, writing a block when enough documents are buffered.
+	pub fn write(&mut self, doc: &Document) -> Result<()> {
+		let codec = bundled();
+		codec.encode(&codec.root, &serde_json::to_value(doc)?, &mut self.block)?;
+		self.count += 1;
+		if self.count >= BLOCK_SIZE {
+			self.flush()?;
+		}
+		Ok(())
+	}
+
+	/// writes the buffered documents as a block.
+	pub fn flush(&mut self) -> Result<()> {
+		if self.count > 0 {
+			let mut header = Vec::new();
+			write_long(&mut header, self.count as i64);
+			write_long(&mut header, self.block.len() as i64);
+			self.writer.write_all(&header)?;
+			self.writer.write_all(&self.block)?;
+			self.writer.write_all(&self.sync)?;
+			self.block.clear();
+			self.count = 0;
+		}
+		self.writer.flush()?;
+		Ok(())
+	}
+
+	/// writes the buffered documents and returns the underlying writer.
+	pub fn finish(mut self) -> Result<W> {
+		self.flush()?;
+		Ok(self.writer)
+	}
+}
+
+/// This struct reads the Documents of an Avro object container file as an iterator. The documents are decoded
+/// with the schema of the file, so files written with older schemas can be read, with the properties missing
+/// from them left at their defaults. Only uncompressed files are supported.
+pub struct AvroReader<R: Read> {
+	reader: R,
+	codec: Codec,
+	sync: Vec<u8>,
+	meta: Meta,
+	block: Vec<u8>,
+	position: usize,
+	remaining: u64,
+}
+
+impl<R: Read> AvroReader<R> {
+	/// returns a reader after reading the header of the file.
+	pub fn new(mut reader: R) -> Result<Self> {
+		if read_exact(&mut reader, 4)? != MAGIC {
+			return Err(error("not an object container file"));
+		}
+		let mut metadata = HashMap::new();
+		loop {
+			let count = match read_long_from(&mut reader)? {
+				Some(0) => break,
+				Some(n) if n < 0 => {
+					read_long_from(&mut reader)?;
+					n.unsigned_abs()
+				}
+				Some(n) => n as u64,
+				None => return Err(error("unexpected end of data")),
+			};
+			for _ in 0..count {
+				let mut entry = Vec::new();
+				for _ in 0..2 {
+					let length = read_long_from(&mut reader)?.ok_or_else(|| error("unexpected end of data"))?;
+					entry.push(read_exact(&mut reader, usize::try_from(length).map_err(|_| error("negative length"))?)?);
+				}
+				let value = entry.pop().unwrap_or_default();
+				metadata.insert(String::from_utf8_lossy(&entry[0]).into_owned(), value);
+			}
+		}
+		let sync = read_exact(&mut reader, 16)?;
+
+		match metadata.get("avro.codec").map(|c| c.as_slice()) {
+			None | Some(b"null") => {}
+			Some(c) => return Err(error(&format!("unsupported codec {:?}", String::from_utf8_lossy(c)))),
+		}
+		let schema: Value = serde_json::from_slice(metadata.get("avro.schema").ok_or_else(|| error("missing schema"))?)?;
+		let meta = match metadata.get("jsonnlp.meta") {
+			Some(meta) => serde_json::from_slice(meta)?,
+			None => Meta::default(),
+		};
+		Ok(AvroReader { reader, codec: Codec::new(&schema)?, sync, meta, block: Vec::new(), position: 0, remaining: 0 })
+	}
+
+	/// returns the metadata of the collection.
+	pub fn meta(&self) -> &Meta {
+		&self.meta
+	}
+
+	fn next_document(&mut self) -> Result<Option<Document>> {
+		while self.remaining == 0 {
+			if self.position < self.block.len() {
+				return Err(error("data after the documents of a block"));
+			}
+			let count = match read_long_from(&mut self.reader)? {
+				Some(count) => count,
+				None => return Ok(None),
+			};
+			let size = read_long_from(&mut self.reader)?.ok_or_else(|| error("unexpected end of data"))?;
+			self.block = read_exact(&mut self.reader, usize::try_from(size).map_err(|_| error("negative block size"))?)?;
+			if read_exact(&mut self.reader, 16)? != self.sync {
+				return Err(error("invalid sync marker"));
+			}
+			self.position = 0;
+			self.remaining = u64::try_from(count).map_err(|_| error("negative block count"))?;
+		}
+		let mut input = &self.block[self.position..];
+		let value = self.codec.decode(&self.codec.root, &mut input)?;
+		self.position = self.block.len() - input.len();
+		self.remaining -= 1;
+		Ok(Some(serde_json::from_value(value)?))
+	}
+}
+
+impl<R: Read> Iterator for AvroReader<R> {
+	type Item = Result<Document>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_document().transpose()
+	}
+}
+
+/// This function writes JSON-NLP as an Avro object container file, see AvroWriter. The `extra` map of the
+/// collection is not written.
+pub fn to_avro<W: Write>(j: &JSONNLP, writer: W) -> Result<()> {
+	let mut writer = AvroWriter::new(writer, &j.meta)?;
+	for doc in &j.docs {
+		writer.write(doc)?;
+	}
+	writer.finish()?;
+	Ok(())
+}
+
+/// This function reads JSON-NLP from an Avro object container file, see AvroReader.
+pub fn from_avro<R: Read>(reader: R) -> Result<JSONNLP> {
+	let mut reader = AvroReader::new(reader)?;
+	let docs = reader.by_ref().collect::<Result<Vec<_>>>()?;
+	Ok(JSONNLP { meta: reader.meta, docs, ..Default::default() })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{from_conllu, TokenId};
+
+	fn collection() -> JSONNLP {
+		let mut j = from_conllu("# sent_id = 1\n1\tDogs\tdog\tNOUN\tNNS\tNumber=Plur\t2\tnsubj\t_\t_\n\
+			2\tbark\tbark\tVERB\tVBP\t_\t0\troot\t_\tSpaceAfter=No\n3\t.\t.\tPUNCT\t.\t_\t2\tpunct\t_\t_\n\n\
+			# newdoc id = 2\n1\tHi\thi\tINTJ\tUH\t_\t0\troot\t_\t_\n\n")
+			.expect("valid CoNLL-U");
+		j.docs[0].token_list[0].extra.insert("score".to_string(), Value::from(0.5));
+		j.meta.title = "Avro".to_string();
+		j
+	}
+
+	#[test]
+	fn datum_round_trip() {
+		for doc in collection().docs {
+			assert_eq!(from_avro_datum(&to_avro_datum(&doc).expect("encodable")).expect("decodable"), doc);
+		}
+	}
+
+	#[test]
+	fn container_round_trip() {
+		let j = collection();
+		let mut file = Vec::new();
+		to_avro(&j, &mut file).expect("encodable");
+		let back = from_avro(file.as_slice()).expect("decodable");
+		assert_eq!(back.docs, j.docs);
+		assert_eq!(back.meta.title, "Avro");
+		assert_eq!(back.docs[0].token_list[1].id, TokenId(2));
+	}
+
+	#[test]
+	fn malformed_lengths() {
+		// a metadata key of 2^60 bytes in a 17-byte header
+		let mut header = MAGIC.to_vec();
+		header.extend_from_slice(&[0x02, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x20, b'a', b'b', b'c']);
+		assert_eq!(header.len(), 17);
+		assert!(AvroReader::new(header.as_slice()).is_err());
+		// an array of 2^40 nulls in a file with a schema of its own
+		let schema = br#"{"type":"array","items":"null"}"#;
+		let mut file = MAGIC.to_vec();
+		file.extend_from_slice(&[0x02, 0x16]);
+		file.extend_from_slice(b"avro.schema");
+		file.push((schema.len() * 2) as u8);
+		file.extend_from_slice(schema);
+		file.push(0);
+		file.extend_from_slice(&[7; 16]);
+		file.extend_from_slice(&[0x02, 0x10, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40, 0x00, 0x00]);
+		file.extend_from_slice(&[7; 16]);
+		let mut reader = AvroReader::new(file.as_slice()).expect("a valid header");
+		assert!(reader.next().expect("a block").is_err());
+	}
+}
@@ -0,0 +1,300 @@
+//! Conversion of [Azure AI Language](https://learn.microsoft.com/azure/ai-services/language-service/) responses
+//! to [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::offsets::{OffsetUnit, SpanLocator};
+use crate::{
+	Document, DocumentId, Entity, EntityId, JsonNlpError, LanguageTag, Prob, RawId, Result, Sentence, SentenceId,
+	JSONNLP,
+};
+
+/// This struct contains a response of the Text Analytics REST API, or the `results` of a task of the
+/// `analyze-text` API.
+#[derive(Deserialize)]
+struct AzureResponse {
+	#[serde(default)]
+	documents: Vec<AzureDocument>,
+	#[serde(default)]
+	results: Option<Box<AzureResponse>>,
+}
+
+/// This struct contains the result of a document with the fields of the entity recognition, entity linking,
+/// sentiment analysis, and language detection tasks.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureDocument {
+	id: String,
+	#[serde(default)]
+	entities: Vec<AzureEntity>,
+	#[serde(default)]
+	sentiment: String,
+	#[serde(default)]
+	confidence_scores: Map<String, Value>,
+	#[serde(default)]
+	sentences: Vec<AzureSentence>,
+	#[serde(default)]
+	detected_language: Option<AzureLanguage>,
+}
+
+/// This struct contains a recognized entity, or a linked entity with its matches in the text.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureEntity {
+	#[serde(default)]
+	text: String,
+	#[serde(default)]
+	category: String,
+	#[serde(default)]
+	subcategory: String,
+	#[serde(default)]
+	offset: Option<u64>,
+	#[serde(default)]
+	confidence_score: Option<f64>,
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	matches: Vec<AzureMatch>,
+	/// the ID of the linked entity in its data source, e.g. the title of the Wikipedia article.
+	#[serde(default)]
+	id: String,
+	#[serde(default)]
+	url: String,
+	#[serde(default)]
+	bing_id: String,
+	#[serde(default)]
+	data_source: String,
+}
+
+/// This struct contains a match of a linked entity.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureMatch {
+	text: String,
+	#[serde(default)]
+	offset: Option<u64>,
+	#[serde(default)]
+	confidence_score: Option<f64>,
+}
+
+/// This struct contains a sentence of the sentiment analysis with its opinions, if they were mined.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureSentence {
+	text: String,
+	#[serde(default)]
+	offset: Option<u64>,
+	#[serde(default)]
+	sentiment: String,
+	#[serde(default)]
+	confidence_scores: Map<String, Value>,
+	#[serde(default)]
+	targets: Vec<Value>,
+	#[serde(default)]
+	assessments: Vec<Value>,
+}
+
+/// This struct contains the detected language of a document.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AzureLanguage {
+	iso6391_name: String,
+}
+
+/// returns the `extra` map of an entity with its score and character span.
+fn entity_extra(score: Option<f64>, begin: u64, end: u64) -> Map<String, Value> {
+	let mut extra = Map::new();
+	if let Some(score) = score {
+		extra.insert("score".to_string(), json!(score));
+	}
+	extra.insert("spans".to_string(), json!([[begin, end]]));
+	extra
+}
+
+/// adds the results of a document to the Document.
+fn add_results(doc: &mut Document, result: AzureDocument, locator: &SpanLocator) -> Result<()> {
+	if let Some(language) = result.detected_language.filter(|l| !l.iso6391_name.is_empty()) {
+		doc.meta.language = LanguageTag::new(&language.iso6391_name)?;
+	}
+	if !result.sentiment.is_empty() {
+		doc.extra.insert(
+			"sentiment".to_string(),
+			json!({ "sentiment": result.sentiment, "confidenceScores": result.confidence_scores }),
+		);
+	}
+
+	if !result.sentences.is_empty() {
+		doc.sentences.clear();
+	}
+	let mut from = 0;
+	for (i, s) in result.sentences.into_iter().enumerate() {
+		let mut sentence = Sentence {
+			id: SentenceId(i as RawId + 1),
+			text: s.text.clone(),
+			sentiment: s.sentiment.clone(),
+			sentiment_prob: s.confidence_scores.get(&s.sentiment).and_then(Value::as_f64).and_then(Prob::new),
+			..Default::default()
+		};
+		if let Some((begin, end)) = locator.locate(&s.text, s.offset, from) {
+			sentence.extra.insert("spans".to_string(), json!([[begin, end]]));
+			from = end;
+		}
+		if !s.confidence_scores.is_empty() {
+			sentence.extra.insert("confidenceScores".to_string(), Value::Object(s.confidence_scores));
+		}
+		if !s.targets.is_empty() {
+			sentence.extra.insert("targets".to_string(), Value::Array(s.targets));
+			sentence.extra.insert("assessments".to_string(), Value::Array(s.assessments));
+		}
+		doc.sentences.push(sentence);
+	}
+
+	for e in result.entities {
+		if e.matches.is_empty() {
+			let (begin, end) = match locator.locate(&e.text, e.offset, 0) {
+				Some(span) => span,
+				None => continue,
+			};
+			let mut extra = entity_extra(e.confidence_score, begin, end);
+			if !e.subcategory.is_empty() {
+				extra.insert("subcategory".to_string(), json!(e.subcategory));
+			}
+			// a linked entity of the same span may have been added before
+			match doc.entities.iter_mut().find(|x| x.extra.get("spans") == extra.get("spans") && x.etype.is_empty()) {
+				Some(entity) => {
+					entity.etype = e.category;
+					entity.extra.extend(extra.into_iter().filter(|(k, _)| k != "score"));
+				}
+				None => doc.entities.push(Entity {
+					id: EntityId(doc.entities.len() as RawId + 1),
+					label: e.text,
+					etype: e.category,
+					extra,
+					..Default::default()
+				}),
+			}
+			continue;
+		}
+
+		for m in &e.matches {
+			let (begin, end) = match locator.locate(&m.text, m.offset, 0) {
+				Some(span) => span,
+				None => continue,
+			};
+			let spans = json!([[begin, end]]);
+			let entity =
+				match doc.entities.iter_mut().position(|x| x.extra.get("spans") == Some(&spans) && x.url.is_empty()) {
+					Some(i) => &mut doc.entities[i],
+					None => {
+						doc.entities.push(Entity {
+							id: EntityId(doc.entities.len() as RawId + 1),
+							label: m.text.clone(),
+							extra: entity_extra(m.confidence_score, begin, end),
+							..Default::default()
+						});
+						doc.entities.last_mut().expect("an entity was just added")
+					}
+				};
+			entity.url = e.url.clone();
+			for (key, value) in
+				&[("name", &e.name), ("linkedId", &e.id), ("bingId", &e.bing_id), ("dataSource", &e.data_source)]
+			{
+				if !value.is_empty() {
+					entity.extra.insert(key.to_string(), json!(value));
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+/// This function converts the JSON responses of Azure AI Language for a batch of documents, given by their ID and
+/// the text that was sent, to a collection with a Document per text, in their order. The responses may be those of
+/// the entity recognition, entity linking, sentiment analysis, and language detection tasks of the Text Analytics
+/// REST API or of the `analyze-text` API, in any order. `unit` is the request's `stringIndexType`; for the default
+/// `TextElement_v8`, which counts graphemes, use `OffsetUnit::CodePoint`, and spans whose offsets do not select
+/// their text are located at the nearest occurrence of their text.
+///
+/// The Azure document ID becomes the document's `DC.identifier`. As the service does not tokenize, entities and
+/// sentences carry their character span in their `extra` map as `spans`. Recognized entities get their category
+/// as type and their subcategory and score in the `extra` map. Every match of a linked entity becomes an entity,
+/// or extends the recognized entity of the same span, with the URL of the data source, e.g. Wikipedia, and the
+/// entity's name, its ID in the data source as `linkedId`, `bingId`, and `dataSource` in the `extra` map.
+/// The sentences of the sentiment analysis replace the sentence layer with their sentiment, the confidence score
+/// of the sentiment as `sentimentProb`, and all scores and mined opinions in the `extra` map. The document
+/// sentiment is kept in the document's `extra` map, and the detected language becomes the document language.
+pub fn from_azure_language_json(documents: &[(&str, &str)], responses: &[&str], unit: OffsetUnit) -> Result<JSONNLP> {
+	let mut j = JSONNLP::default();
+	for (i, (id, text)) in documents.iter().enumerate() {
+		let mut doc = Document { id: DocumentId(i as RawId + 1), text: text.to_string(), ..Default::default() };
+		doc.meta.identifier = id.to_string();
+		j.docs.push(doc);
+	}
+	let locators: Vec<SpanLocator> = documents.iter().map(|(_, text)| SpanLocator::new(text, unit)).collect();
+
+	for json in responses {
+		let mut de = serde_json::Deserializer::from_str(json);
+		let mut response: AzureResponse = serde_path_to_error::deserialize(&mut de)?;
+		de.end()?;
+		if let Some(results) = response.results {
+			response.documents.extend(results.documents);
+		}
+		for result in response.documents {
+			let i = documents.iter().position(|(id, _)| *id == result.id).ok_or_else(|| {
+				JsonNlpError::Format(format!("the response has the unknown document ID {:?}", result.id))
+			})?;
+			add_results(&mut j.docs[i], result, &locators[i])?;
+		}
+	}
+	Ok(j)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DOCUMENTS: &[(&str, &str)] = &[("a", "Paris is nice. I love Paris."), ("b", "Hallo Welt")];
+
+	#[test]
+	fn azure_import() {
+		let entities = r#"{"documents":[{"id":"a","entities":[
+			{"text":"Paris","category":"Location","subcategory":"City","offset":0,"length":5,
+				"confidenceScore":0.75}]}]}"#;
+		let linking = r#"{"kind":"EntityLinkingResults","results":{"documents":[{"id":"a","entities":[
+			{"name":"Paris","id":"Paris","url":"https://en.wikipedia.org/wiki/Paris","dataSource":"Wikipedia",
+				"matches":[{"text":"Paris","offset":0,"confidenceScore":0.5},{"text":"Paris","offset":22}]}]}]}}"#;
+		let sentiment = r#"{"documents":[{"id":"a","sentiment":"positive",
+			"confidenceScores":{"positive":0.75,"neutral":0.25,"negative":0.0},"sentences":[
+				{"text":"Paris is nice.","offset":0,"sentiment":"positive","confidenceScores":{"positive":0.5}},
+				{"text":"I love Paris.","sentiment":"positive","confidenceScores":{"positive":1.0}}]}]}"#;
+		let language = r#"{"documents":[{"id":"b","detectedLanguage":{"name":"German","iso6391Name":"de"}}]}"#;
+		let j = from_azure_language_json(DOCUMENTS, &[entities, linking, sentiment, language], OffsetUnit::CodePoint)
+			.expect("valid responses");
+		let (a, b) = (&j.docs[0], &j.docs[1]);
+		assert_eq!((a.meta.identifier.as_str(), b.meta.identifier.as_str(), b.meta.language.as_str()), ("a", "b", "de"));
+
+		let entities: Vec<_> =
+			a.entities.iter().map(|e| (e.etype.as_str(), e.url.as_str(), &e.extra["spans"])).collect();
+		let url = "https://en.wikipedia.org/wiki/Paris";
+		assert_eq!(entities, [("Location", url, &json!([[0, 5]])), ("", url, &json!([[22, 27]]))]);
+		assert_eq!((&a.entities[0].extra["score"], &a.entities[0].extra["subcategory"]), (&json!(0.75), &json!("City")));
+		assert_eq!((&a.entities[1].extra["linkedId"], &a.entities[1].extra["dataSource"]), (
+			&json!("Paris"),
+			&json!("Wikipedia"),
+		));
+
+		let sentences: Vec<_> = a.sentences.iter().map(|s| (s.sentiment_prob, &s.extra["spans"])).collect();
+		assert_eq!(sentences, [(Prob::new(0.5), &json!([[0, 14]])), (Prob::new(1.0), &json!([[15, 28]]))]);
+		assert_eq!(a.extra["sentiment"]["sentiment"], "positive");
+	}
+
+	#[test]
+	fn azure_errors() {
+		let e = from_azure_language_json(DOCUMENTS, &[r#"{"documents":[{"id":"c"}]}"#], OffsetUnit::CodePoint)
+			.unwrap_err();
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == "the response has the unknown document ID \"c\""));
+		let missing_id = r#"{"documents":[{"entities":[]}]}"#;
+		assert!(from_azure_language_json(DOCUMENTS, &[missing_id], OffsetUnit::Utf16).is_err());
+	}
+}
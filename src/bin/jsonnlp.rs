@@ -0,0 +1,248 @@
+//! The `jsonnlp` command line tool for validating, converting, pretty-printing, and summarizing
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) files.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde_json::Value;
+
+use jsonnlp::{
+	from_conllu, from_msgpack, from_slice_with_options, read_ndjson, to_conllu, to_msgpack, to_writer_with_options,
+	validate_schema, Document, NdjsonWriter, ParseOptions, Result, WriteOptions, JSONNLP,
+};
+
+#[derive(Parser)]
+#[command(name = "jsonnlp", version, about = "Validate, convert, and summarize JSON-NLP files")]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Check files against the JSON-NLP schema and the referential integrity of their documents
+	Validate {
+		/// Reject properties that are not part of the JSON-NLP model
+		#[arg(long)]
+		strict: bool,
+		/// The files to check, `-` for standard input
+		#[arg(required = true)]
+		files: Vec<PathBuf>,
+	},
+	/// Convert between JSON-NLP and other formats
+	Convert {
+		/// The input format, by default given by the extension of the input file
+		#[arg(long)]
+		from: Option<Format>,
+		/// The output format, by default given by the extension of the output file
+		#[arg(long)]
+		to: Option<Format>,
+		/// The input file, `-` for standard input
+		input: PathBuf,
+		/// The output file, standard output if not given
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Pretty-print a JSON-NLP file
+	Pretty {
+		/// The number of spaces per indentation level
+		#[arg(long, default_value_t = 2)]
+		indent: usize,
+		/// The input file, `-` for standard input
+		input: PathBuf,
+		/// The output file, standard output if not given
+		#[arg(short, long)]
+		output: Option<PathBuf>,
+	},
+	/// Print corpus statistics of JSON-NLP files
+	Stats {
+		/// The files to summarize, `-` for standard input
+		#[arg(required = true)]
+		files: Vec<PathBuf>,
+	},
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+	/// JSON-NLP
+	Json,
+	/// newline-delimited JSON with one document per line
+	Ndjson,
+	/// CoNLL-U
+	Conllu,
+	/// MessagePack-encoded JSON-NLP
+	Msgpack,
+}
+
+impl Format {
+	/// returns the format of a file by its extension.
+	fn of(path: &Path) -> Option<Format> {
+		match path.extension()?.to_str()? {
+			"json" => Some(Format::Json),
+			"ndjson" | "jsonl" => Some(Format::Ndjson),
+			"conllu" | "conll" => Some(Format::Conllu),
+			"msgpack" | "mpk" => Some(Format::Msgpack),
+			_ => None,
+		}
+	}
+}
+
+fn read_input(path: &Path) -> Result<Vec<u8>> {
+	let mut buf = Vec::new();
+	if path == Path::new("-") {
+		io::stdin().lock().read_to_end(&mut buf)?;
+	} else {
+		File::open(path)?.read_to_end(&mut buf)?;
+	}
+	Ok(buf)
+}
+
+fn output(path: Option<&Path>) -> Result<Box<dyn Write>> {
+	Ok(match path {
+		Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+		None => Box::new(BufWriter::new(io::stdout().lock())),
+	})
+}
+
+fn text(bytes: Vec<u8>) -> Result<String> {
+	String::from_utf8(bytes).map_err(|e| jsonnlp::JsonNlpError::Format(format!("input is not UTF-8: {}", e)))
+}
+
+fn read(bytes: Vec<u8>, format: Format) -> Result<JSONNLP> {
+	match format {
+		Format::Json => from_slice_with_options(&bytes, &ParseOptions::default()),
+		Format::Ndjson => {
+			let docs = read_ndjson::<Document, _>(bytes.as_slice()).collect::<Result<Vec<Document>>>()?;
+			Ok(JSONNLP { docs, ..Default::default() })
+		}
+		Format::Conllu => from_conllu(&text(bytes)?),
+		Format::Msgpack => from_msgpack(&bytes),
+	}
+}
+
+fn write(j: &JSONNLP, format: Format, mut out: Box<dyn Write>) -> Result<()> {
+	match format {
+		Format::Json => to_writer_with_options(j, &mut out, &WriteOptions::pretty("  "))?,
+		Format::Ndjson => {
+			let mut writer = NdjsonWriter::new(&mut out);
+			for doc in &j.docs {
+				writer.write(doc)?;
+			}
+		}
+		Format::Conllu => {
+			for doc in &j.docs {
+				out.write_all(to_conllu(doc).as_bytes())?;
+			}
+		}
+		Format::Msgpack => out.write_all(&to_msgpack(j)?)?,
+	}
+	out.flush()?;
+	Ok(())
+}
+
+/// validates a file and prints its problems, returning whether it is valid.
+fn validate(path: &Path, strict: bool) -> Result<bool> {
+	let bytes = read_input(path)?;
+	let name = path.display();
+	let value: Value = match serde_json::from_slice(&bytes) {
+		Ok(value) => value,
+		Err(e) => {
+			println!("{}: {}", name, e);
+			return Ok(false);
+		}
+	};
+	let violations = validate_schema(&value);
+	for v in &violations {
+		println!("{}: schema: {}: {}", name, v.pointer, v.message);
+	}
+	let options = ParseOptions { strict, ..Default::default() };
+	let j = match from_slice_with_options(&bytes, &options) {
+		Ok(j) => j,
+		Err(e) => {
+			println!("{}: {}", name, e);
+			return Ok(false);
+		}
+	};
+	let report = j.validate();
+	for issue in &report.issues {
+		println!("{}: {}", name, issue);
+	}
+	let valid = violations.is_empty() && report.is_valid();
+	if valid {
+		println!("{}: valid", name);
+	}
+	Ok(valid)
+}
+
+fn stats(path: &Path) -> Result<()> {
+	let j = read(read_input(path)?, Format::of(path).unwrap_or(Format::Json))?;
+	let count = |layer: fn(&Document) -> usize| j.docs.iter().map(layer).sum::<usize>();
+	println!("{}", path.display());
+	println!("  documents:        {}", j.docs.len());
+	println!("  tokens:           {}", count(|d| d.token_list.len()));
+	println!("  sentences:        {}", count(|d| d.sentences.len()));
+	println!("  paragraphs:       {}", count(|d| d.paragraphs.len()));
+	println!("  dependency trees: {}", count(|d| d.dependency_trees.len()));
+	println!("  entities:         {}", count(|d| d.entities.len()));
+	println!("  relations:        {}", count(|d| d.relations.len()));
+	println!("  coreferences:     {}", count(|d| d.coreferences.len()));
+	let mut upos: BTreeMap<&str, usize> = BTreeMap::new();
+	for t in j.docs.iter().flat_map(|d| &d.token_list).filter(|t| !t.upos.is_empty()) {
+		*upos.entry(t.upos.as_str()).or_insert(0) += 1;
+	}
+	if !upos.is_empty() {
+		println!("  UPOS tags:");
+		for (tag, n) in upos {
+			println!("    {:<8}{}", tag, n);
+		}
+	}
+	Ok(())
+}
+
+fn run(cli: Cli) -> Result<bool> {
+	match cli.command {
+		Command::Validate { strict, files } => {
+			let mut valid = true;
+			for path in &files {
+				valid &= validate(path, strict)?;
+			}
+			Ok(valid)
+		}
+		Command::Convert { from, to, input, output: path } => {
+			let from = from.or_else(|| Format::of(&input)).unwrap_or(Format::Json);
+			let to = to.or_else(|| path.as_deref().and_then(Format::of)).unwrap_or(Format::Json);
+			let j = read(read_input(&input)?, from)?;
+			write(&j, to, output(path.as_deref())?)?;
+			Ok(true)
+		}
+		Command::Pretty { indent, input, output: path } => {
+			let j = read(read_input(&input)?, Format::Json)?;
+			let mut out = output(path.as_deref())?;
+			to_writer_with_options(&j, &mut out, &WriteOptions::pretty(&" ".repeat(indent)))?;
+			out.write_all(b"\n")?;
+			out.flush()?;
+			Ok(true)
+		}
+		Command::Stats { files } => {
+			for path in &files {
+				stats(path)?;
+			}
+			Ok(true)
+		}
+	}
+}
+
+fn main() -> ExitCode {
+	match run(Cli::parse()) {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(e) => {
+			eprintln!("jsonnlp: {}", e);
+			ExitCode::from(2)
+		}
+	}
+}
@@ -0,0 +1,320 @@
+//! Zero-copy deserialization of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents, with the strings of the
+//! text, tokens, and dependencies borrowed from the input where they contain no escape sequences.
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+	AmrGraph, Clause, ConstituentParse, Coreference, Dependency, DependencyTree, Document, DocumentId, Entity, Expression,
+	LanguageTag, Meta, Paragraph, Predicate, Prob, Relation, Result, Sentence, SentenceId, Token, TokenFeatures, TokenId,
+	Triple, JSONNLP,
+};
+
+/// This struct contains the token information like Token, with the strings borrowed from the input.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct TokenRef<'a> {
+	pub id: TokenId,
+	pub sentence_id: SentenceId,
+	#[serde(borrow)]
+	pub text: Cow<'a, str>,
+	#[serde(borrow)]
+	pub lemma: Cow<'a, str>,
+	#[serde(borrow,
+		default,
+		skip_serializing_if = "str::is_empty")]
+	pub xpos: Cow<'a, str>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub xpos_prob: Option<Prob>,
+	#[serde(borrow,
+		default,
+		skip_serializing_if = "str::is_empty")]
+	pub upos: Cow<'a, str>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub upos_prob: Option<Prob>,
+	#[serde(borrow,
+		default,
+		skip_serializing_if = "str::is_empty")]
+	pub entity_iob: Cow<'a, str>,
+	#[serde(default,
+		rename = "characterOffsetBegin",
+		skip_serializing_if = "Option::is_none")]
+	pub char_offset_begin: Option<u64>,
+	#[serde(default,
+		rename = "characterOffsetEnd",
+		skip_serializing_if = "Option::is_none")]
+	pub char_offset_end: Option<u64>,
+	#[serde(borrow,
+		default,
+		skip_serializing_if = "str::is_empty",
+		rename = "propID")]
+	pub prop_id: Cow<'a, str>,
+	#[serde(rename = "propIDProbability",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub prop_id_prob: Option<Prob>,
+	#[serde(rename = "frameID",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub frame_id: Option<u64>,
+	#[serde(rename = "frameIDProb",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub frame_id_prob: Option<Prob>,
+	#[serde(rename = "wordNetID",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub wordnet_id: Option<u64>,
+	#[serde(rename = "wordNetIDProb",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub wordnet_id_prob: Option<Prob>,
+	#[serde(rename = "verbNetID",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub verbnet_id: Option<u64>,
+	#[serde(rename = "verbNetIDProb",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub verbnet_id_prob: Option<Prob>,
+	#[serde(skip_serializing_if = "LanguageTag::is_empty",
+		default)]
+	pub lang: LanguageTag,
+	#[serde(default,
+		with = "crate::features")]
+	pub features: Option<Box<TokenFeatures>>,
+	#[serde(borrow,
+		skip_serializing_if = "str::is_empty",
+		default)]
+	pub shape: Cow<'a, str>,
+	#[serde(borrow,
+		skip_serializing_if = "str::is_empty",
+		default)]
+	pub entity: Cow<'a, str>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct contains a dependency like Dependency, with the label borrowed from the input.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct DependencyRef<'a> {
+	#[serde(borrow)]
+	pub lab: Cow<'a, str>,
+	pub gov: TokenId,
+	pub dep: TokenId,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct contains a dependency tree like DependencyTree, with borrowed dependency labels.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct DependencyTreeRef<'a> {
+	#[serde(rename = "sentenceId",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentence_id: Option<SentenceId>,
+	#[serde(skip_serializing_if = "String::is_empty",
+		default)]
+	pub style: String,
+	#[serde(borrow,
+		default)]
+	pub dependencies: Vec<DependencyRef<'a>>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct contains a document like Document, with the text, tokens, and dependency labels borrowed from the
+/// input. The other layers are owned.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct DocumentRef<'a> {
+	pub meta: Meta,
+	pub id: DocumentId,
+	#[serde(borrow,
+		default,
+		skip_serializing_if = "str::is_empty")]
+	pub text: Cow<'a, str>,
+	#[serde(borrow,
+		rename = "tokenList",
+		default)]
+	pub token_list: Vec<TokenRef<'a>>,
+	#[serde(default)]
+	pub clauses: Vec<Clause>,
+	#[serde(default)]
+	pub sentences: Vec<Sentence>,
+	#[serde(default)]
+	pub paragraphs: Vec<Paragraph>,
+	#[serde(borrow,
+		rename = "dependencyTrees",
+		default)]
+	pub dependency_trees: Vec<DependencyTreeRef<'a>>,
+	#[serde(default)]
+	pub coreferences: Vec<Coreference>,
+	#[serde(default)]
+	pub constituents: Vec<ConstituentParse>,
+	#[serde(default)]
+	pub expressions: Vec<Expression>,
+	#[serde(default)]
+	pub entities: Vec<Entity>,
+	#[serde(default)]
+	pub relations: Vec<Relation>,
+	#[serde(default)]
+	pub triples: Vec<Triple>,
+	#[serde(rename = "semanticRoles",
+		default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub semantic_roles: Vec<Predicate>,
+	#[serde(rename = "amrGraphs",
+		default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub amr_graphs: Vec<AmrGraph>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct contains a collection like JSONNLP, with borrowed documents.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct JSONNLPRef<'a> {
+	pub meta: Meta,
+	#[serde(borrow,
+		default)]
+	pub docs: Vec<DocumentRef<'a>>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl From<TokenRef<'_>> for Token {
+	fn from(t: TokenRef<'_>) -> Self {
+		Token {
+			id: t.id,
+			sentence_id: t.sentence_id,
+			text: t.text.into_owned(),
+			lemma: t.lemma.into_owned(),
+			xpos: t.xpos.into_owned(),
+			xpos_prob: t.xpos_prob,
+			upos: t.upos.into_owned(),
+			upos_prob: t.upos_prob,
+			entity_iob: t.entity_iob.into_owned(),
+			char_offset_begin: t.char_offset_begin,
+			char_offset_end: t.char_offset_end,
+			prop_id: t.prop_id.into_owned(),
+			prop_id_prob: t.prop_id_prob,
+			frame_id: t.frame_id,
+			frame_id_prob: t.frame_id_prob,
+			wordnet_id: t.wordnet_id,
+			wordnet_id_prob: t.wordnet_id_prob,
+			verbnet_id: t.verbnet_id,
+			verbnet_id_prob: t.verbnet_id_prob,
+			lang: t.lang,
+			features: t.features,
+			shape: t.shape.into_owned(),
+			entity: t.entity.into_owned(),
+			extra: t.extra,
+		}
+	}
+}
+
+impl From<DependencyRef<'_>> for Dependency {
+	fn from(d: DependencyRef<'_>) -> Self {
+		Dependency { lab: d.lab.into_owned(), gov: d.gov, dep: d.dep, prob: d.prob, extra: d.extra }
+	}
+}
+
+impl From<DependencyTreeRef<'_>> for DependencyTree {
+	fn from(d: DependencyTreeRef<'_>) -> Self {
+		DependencyTree {
+			sentence_id: d.sentence_id,
+			style: d.style,
+			dependencies: d.dependencies.into_iter().map(Into::into).collect(),
+			prob: d.prob,
+			extra: d.extra,
+		}
+	}
+}
+
+impl From<DocumentRef<'_>> for Document {
+	fn from(d: DocumentRef<'_>) -> Self {
+		Document {
+			meta: d.meta,
+			id: d.id,
+			text: d.text.into_owned(),
+			token_list: d.token_list.into_iter().map(Into::into).collect(),
+			clauses: d.clauses,
+			sentences: d.sentences,
+			paragraphs: d.paragraphs,
+			dependency_trees: d.dependency_trees.into_iter().map(Into::into).collect(),
+			coreferences: d.coreferences,
+			constituents: d.constituents,
+			expressions: d.expressions,
+			entities: d.entities,
+			relations: d.relations,
+			triples: d.triples,
+			semantic_roles: d.semantic_roles,
+			amr_graphs: d.amr_graphs,
+			extra: d.extra,
+		}
+	}
+}
+
+impl From<JSONNLPRef<'_>> for JSONNLP {
+	fn from(j: JSONNLPRef<'_>) -> Self {
+		JSONNLP { meta: j.meta, docs: j.docs.into_iter().map(Into::into).collect(), extra: j.extra }
+	}
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) without copying
+/// the strings of the text, tokens, and dependencies, which borrow from the input unless they contain escape sequences.
+pub fn from_str_borrowed(json: &str) -> Result<JSONNLPRef<'_>> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let r = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+	Ok(r)
+}
+
+/// This function converts a byte slice containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) without
+/// copying strings, see from_str_borrowed().
+pub fn from_slice_borrowed(json: &[u8]) -> Result<JSONNLPRef<'_>> {
+	let mut de = serde_json::Deserializer::from_slice(json);
+	let r = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+	Ok(r)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{from_string, JsonNlpError};
+
+	#[test]
+	fn strings_are_borrowed() {
+		let json = r#"{"meta":{},"docs":[{"meta":{},"id":1,"text":"say \"hi\"","tokenList":[
+			{"id":1,"sentence_id":1,"text":"say","lemma":"say","upos":"VERB"},
+			{"id":2,"sentence_id":1,"text":"\"","lemma":"\""}],
+			"dependencyTrees":[{"style":"universal","dependencies":[{"lab":"punct","gov":1,"dep":2}]}]}]}"#;
+		let j = from_str_borrowed(json).expect("valid JSON-NLP");
+		let d = &j.docs[0];
+		assert!(matches!(d.text, Cow::Owned(ref s) if s == "say \"hi\""));
+		assert!(matches!(d.token_list[0].text, Cow::Borrowed("say")));
+		assert!(matches!(d.token_list[0].upos, Cow::Borrowed("VERB")));
+		assert!(matches!(d.token_list[1].text, Cow::Owned(ref s) if s == "\""));
+		assert!(matches!(d.dependency_trees[0].dependencies[0].lab, Cow::Borrowed("punct")));
+		assert_eq!(from_slice_borrowed(json.as_bytes()).unwrap(), j);
+		assert_eq!(JSONNLP::from(j), from_string(json).unwrap());
+	}
+
+	#[test]
+	fn borrowed_errors() {
+		let e = from_str_borrowed(r#"{"meta":{},"docs":[{"meta":{},"id":1,"tokenList":[{"id":1}]}]}"#).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Parse(ref p) if p.pointer == "/docs/0/tokenList/0"), "{}", e);
+		assert!(from_str_borrowed(r#"{"meta":{},"docs":[]} []"#).is_err());
+		assert!(from_slice_borrowed(b"{\"meta\":{},\"docs\":[{\"text\":\"\xff\"}]}").is_err());
+	}
+}
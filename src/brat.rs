@@ -0,0 +1,425 @@
+//! Conversion between [brat](https://brat.nlplab.org/standoff.html) standoff annotations and
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents.
+//!
+//! Text-bound annotations become entities, or expressions if they are event triggers, relations become
+//! relations linked to their arguments by triples, and attributes, normalizations, and notes become attributes.
+//! The brat IDs and character spans are kept in the `extra` maps as `bratId` and `spans`, and the text of
+//! normalizations in the `extra` map of their attribute as `text`.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::offsets::{char_boundaries, slice_chars};
+use crate::{
+	Attribute, Document, DocumentId, Entity, EntityId, Expression, ExpressionId, JsonNlpError, RawId, Relation,
+	RelationId, Result, TokenId, Triple, TripleId,
+};
+
+/// This type contains the begin and end character offsets of the parts of an annotation.
+type Spans = Vec<(u64, u64)>;
+
+/// This type contains the roles and target IDs of event or relation arguments.
+type Arguments<'a> = Vec<(&'a str, &'a str)>;
+
+/// returns the character spans kept in an `extra` map.
+fn spans_of(extra: &serde_json::Map<String, Value>) -> Spans {
+	extra.get("spans").and_then(Value::as_array).map(|spans| {
+		spans.iter().filter_map(|s| Some((s.get(0)?.as_u64()?, s.get(1)?.as_u64()?))).collect()
+	}).unwrap_or_default()
+}
+
+/// returns the attributes kept in an `extra` map under the key.
+fn attributes_of(extra: &serde_json::Map<String, Value>, key: &str) -> Vec<Attribute> {
+	extra.get(key).and_then(Value::as_array).into_iter().flatten()
+		.filter_map(|a| serde_json::from_value(a.clone()).ok())
+		.collect()
+}
+
+/// adds an attribute to the attributes kept in an `extra` map under the key.
+fn push_attribute(extra: &mut serde_json::Map<String, Value>, key: &str, attribute: &Attribute) {
+	let list = extra.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+	if let Value::Array(list) = list {
+		list.push(json!(attribute));
+	}
+}
+
+/// returns a value usable as a brat type or attribute value, with whitespace replaced by `_`.
+fn name(s: &str) -> String {
+	s.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+impl Document {
+	/// returns the character spans of a list of tokens, merging adjacent tokens.
+	fn token_spans(&self, ids: &[TokenId]) -> Spans {
+		let boundaries = char_boundaries(&self.text);
+		let mut spans: Spans = Vec::new();
+		for t in self.token_list.iter().filter(|t| ids.contains(&t.id)) {
+			if let (Some(b), Some(e)) = (t.char_offset_begin, t.char_offset_end) {
+				let between = |last: &(u64, u64)| slice_chars(&self.text, &boundaries, last.1, b.max(last.1));
+				match spans.last_mut() {
+					Some(last) if between(last).is_some_and(|s| s.chars().all(char::is_whitespace)) => last.1 = e,
+					_ => spans.push((b, e)),
+				}
+			}
+		}
+		spans
+	}
+
+	/// replaces the entity, expression, relation, and triple layers with the annotations of a brat `.ann` file.
+	/// Entities and expressions are linked to the tokens overlapping their character spans, and the tokens'
+	/// `entity` and `entity_iob` tags are set from the new entities. Event arguments are kept in the expression's
+	/// `extra` map as `arguments` with their role and entity or expression ID, and their attributes as `attributes`,
+	/// or as `triggerAttributes` if they annotate the trigger.
+	pub fn apply_brat(&mut self, ann: &str) -> Result<()> {
+		let error = |n: usize, msg: &str| JsonNlpError::Format(format!("line {}: {}", n + 1, msg));
+		// text-bound annotations with type and spans, and the annotations of the other kinds
+		let mut text_bound: Vec<(&str, &str, Spans, &str)> = Vec::new();
+		let mut events: Vec<(&str, &str, &str, Arguments)> = Vec::new();
+		let mut relations: Vec<(&str, &str, Arguments)> = Vec::new();
+		let mut attributes: Vec<(&str, Attribute)> = Vec::new();
+		for (n, line) in ann.lines().enumerate() {
+			let line = line.trim_end_matches('\r');
+			if line.trim().is_empty() {
+				continue;
+			}
+			let mut fields = line.splitn(3, '\t');
+			let id = fields.next().unwrap_or_default();
+			let annotation = fields.next().ok_or_else(|| error(n, "missing annotation"))?;
+			let text = fields.next().unwrap_or_default();
+			let mut parts = annotation.split_whitespace();
+			let kind = parts.next().ok_or_else(|| error(n, "missing annotation type"))?;
+			let attribute = |val: &str| Attribute { lab: kind.to_string(), val: val.to_string(), ..Default::default() };
+			match id.chars().next() {
+				Some('T') => {
+					let offsets = annotation.trim_start().split_once(char::is_whitespace).map_or("", |(_, o)| o);
+					let spans = offsets.split(';').map(|s| {
+						let mut offsets = s.split_whitespace().map(str::parse::<u64>);
+						match (offsets.next(), offsets.next()) {
+							(Some(Ok(b)), Some(Ok(e))) if b <= e => Ok((b, e)),
+							_ => Err(error(n, "invalid character span")),
+						}
+					}).collect::<Result<Vec<_>>>()?;
+					text_bound.push((id, kind, spans, text));
+				}
+				Some('E') => {
+					let (etype, trigger) = kind.split_once(':').ok_or_else(|| error(n, "missing event trigger"))?;
+					let args = parts.filter_map(|a| a.split_once(':')).collect();
+					events.push((id, etype, trigger, args));
+				}
+				Some('R') => {
+					let args = parts.filter_map(|a| a.split_once(':')).collect();
+					relations.push((id, kind, args));
+				}
+				Some('A') | Some('M') => {
+					let target = parts.next().ok_or_else(|| error(n, "missing attribute target"))?;
+					let value = parts.next().unwrap_or("true");
+					attributes.push((target, attribute(value)));
+				}
+				Some('N') => {
+					let target = parts.next().ok_or_else(|| error(n, "missing normalization target"))?;
+					let reference = parts.next().ok_or_else(|| error(n, "missing normalization reference"))?;
+					let mut attribute = attribute(reference);
+					if !text.is_empty() {
+						attribute.extra.insert("text".to_string(), json!(text));
+					}
+					attributes.push((target, attribute));
+				}
+				Some('#') => {
+					let target = parts.next().ok_or_else(|| error(n, "missing note target"))?;
+					attributes.push((target, attribute(text)));
+				}
+				// equivalence sets have no counterpart in JSON-NLP
+				Some('*') => {}
+				_ => return Err(error(n, &format!("unknown annotation ID {:?}", id))),
+			}
+		}
+
+		let triggers: Vec<&str> = events.iter().map(|e| e.2).collect();
+		let mut entity_ids: HashMap<&str, EntityId> = HashMap::new();
+		let mut entities = Vec::new();
+		for (id, etype, spans, text) in text_bound.iter().filter(|t| !triggers.contains(&t.0)) {
+			let entity_id = EntityId(entities.len() as RawId + 1);
+			let tokens = self.tokens_overlapping(spans);
+			let mut entity = Entity {
+				id: entity_id,
+				label: text.to_string(),
+				etype: etype.to_string(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			};
+			entity.extra.insert("bratId".to_string(), json!(id));
+			entity.extra.insert("spans".to_string(), json!(spans.iter().map(|s| [s.0, s.1]).collect::<Vec<_>>()));
+			entity_ids.insert(id, entity_id);
+			entities.push(entity);
+		}
+
+		let mut expression_ids: HashMap<&str, ExpressionId> = HashMap::new();
+		for (i, e) in events.iter().enumerate() {
+			expression_ids.insert(e.0, ExpressionId(i as RawId + 1));
+		}
+		let mut expressions = Vec::new();
+		for (id, etype, trigger, args) in &events {
+			let (spans, text) = match text_bound.iter().find(|t| t.0 == *trigger) {
+				Some(t) => (&t.2, t.3),
+				None => return Err(JsonNlpError::Format(format!("event {} refers to the missing trigger {}", id, trigger))),
+			};
+			let tokens = self.tokens_overlapping(spans);
+			let mut expression = Expression {
+				id: expression_ids[id],
+				etype: etype.to_string(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			};
+			let mut arguments = Vec::new();
+			for (role, target) in args {
+				let argument = match (entity_ids.get(target), expression_ids.get(target)) {
+					(Some(e), _) => json!({ "role": role, "entity": e }),
+					(None, Some(e)) => json!({ "role": role, "expression": e }),
+					_ => return Err(JsonNlpError::Format(format!("event {} refers to the missing argument {}", id, target))),
+				};
+				arguments.push(argument);
+			}
+			expression.extra.insert("bratId".to_string(), json!(id));
+			expression.extra.insert("trigger".to_string(), json!(text));
+			expression.extra.insert("spans".to_string(), json!(spans.iter().map(|s| [s.0, s.1]).collect::<Vec<_>>()));
+			if !arguments.is_empty() {
+				expression.extra.insert("arguments".to_string(), Value::Array(arguments));
+			}
+			expressions.push(expression);
+		}
+
+		let mut relation_list = Vec::new();
+		let mut triples = Vec::new();
+		let mut relation_ids: HashMap<&str, usize> = HashMap::new();
+		for (id, rtype, args) in &relations {
+			let relation_id = RelationId(relation_list.len() as RawId + 1);
+			let entity = |i: usize| -> Result<Option<EntityId>> {
+				match args.get(i) {
+					Some((_, target)) => entity_ids.get(target).copied().map(Some).ok_or_else(|| {
+						JsonNlpError::Format(format!("relation {} refers to the missing entity {}", id, target))
+					}),
+					None => Ok(None),
+				}
+			};
+			let mut relation = Relation { id: relation_id, rtype: rtype.to_string(), ..Default::default() };
+			relation.extra.insert("bratId".to_string(), json!(id));
+			triples.push(Triple {
+				id: TripleId(triples.len() as RawId + 1),
+				from_entity: entity(0)?,
+				to_entity: entity(1)?,
+				rel: Some(relation_id),
+				directional: Some(true),
+				..Default::default()
+			});
+			relation_ids.insert(id, relation_list.len());
+			relation_list.push(relation);
+		}
+
+		for (target, attribute) in attributes {
+			if let Some(id) = entity_ids.get(target) {
+				entities[id.0 as usize - 1].attributes.push(attribute);
+			} else if let Some(i) = relation_ids.get(target) {
+				relation_list[*i].attributes.push(attribute);
+			} else if let Some(id) = expression_ids.get(target) {
+				push_attribute(&mut expressions[id.0 as usize - 1].extra, "attributes", &attribute);
+			} else if triggers.contains(&target) {
+				for (e, _) in expressions.iter_mut().zip(&events).filter(|(_, event)| event.2 == target) {
+					push_attribute(&mut e.extra, "triggerAttributes", &attribute);
+				}
+			} else {
+				return Err(JsonNlpError::Format(format!("attribute {} refers to the missing annotation {}", attribute.lab, target)));
+			}
+		}
+
+		for t in self.token_list.iter_mut() {
+			match entities.iter().find(|e| e.tokens.contains(&t.id)) {
+				Some(e) => {
+					t.entity_iob = if e.tokens.first() == Some(&t.id) { "B" } else { "I" }.to_string();
+					t.entity = e.etype.clone();
+				}
+				None if !entities.is_empty() => {
+					t.entity_iob = "O".to_string();
+					t.entity = String::new();
+				}
+				None => {}
+			}
+		}
+		self.entities = entities;
+		self.expressions = expressions;
+		self.relations = relation_list;
+		self.triples = triples;
+		Ok(())
+	}
+}
+
+/// This function reads a brat `.txt` file and its `.ann` annotations into a Document without tokens,
+/// see Document::apply_brat().
+pub fn from_brat(text: &str, ann: &str) -> Result<Document> {
+	let mut doc = Document {
+		id: DocumentId(1),
+		text: text.to_string(),
+		..Default::default()
+	};
+	doc.apply_brat(ann)?;
+	Ok(doc)
+}
+
+/// This function converts a Document to the contents of a brat `.txt` and `.ann` file. Entities and expressions
+/// are written as text-bound annotations with the spans kept from brat, or else the character offsets of their
+/// tokens, and expressions as events with their arguments. Triples with a relation and two entities become
+/// relations, and attributes become attributes, normalizations, or notes, depending on how they were read from brat.
+/// Elements without character offsets are skipped.
+pub fn to_brat(doc: &Document) -> (String, String) {
+	let boundaries = char_boundaries(&doc.text);
+	let mut ann = String::new();
+	let mut next = HashMap::new();
+	let mut new_id = |prefix: char| -> String {
+		let n = next.entry(prefix).or_insert(0);
+		*n += 1;
+		format!("{}{}", prefix, n)
+	};
+	let text_bound = |ann: &mut String, id: &str, etype: &str, spans: &[(u64, u64)]| {
+		let offsets = spans.iter().map(|(b, e)| format!("{} {}", b, e)).collect::<Vec<_>>().join(";");
+		let text = spans.iter().filter_map(|(b, e)| slice_chars(&doc.text, &boundaries, *b, *e)).collect::<Vec<_>>().join(" ");
+		ann.push_str(&format!("{}\t{} {}\t{}\n", id, name(etype), offsets, text.replace(['\n', '\r', '\t'], " ")));
+	};
+	let attribute_lines = |ann: &mut String, new_id: &mut dyn FnMut(char) -> String, target: &str, attributes: &[Attribute]| {
+		for a in attributes {
+			match a.lab.as_str() {
+				"AnnotatorNotes" => ann.push_str(&format!("{}\t{} {}\t{}\n", new_id('#'), a.lab, target, a.val)),
+				_ if a.val.contains(':') => {
+					let text = a.extra.get("text").and_then(Value::as_str).unwrap_or_default();
+					ann.push_str(&format!("{}\t{} {} {}\t{}\n", new_id('N'), name(&a.lab), target, name(&a.val), text))
+				}
+				_ if a.val == "true" => ann.push_str(&format!("{}\t{} {}\n", new_id('A'), name(&a.lab), target)),
+				_ => ann.push_str(&format!("{}\t{} {} {}\n", new_id('A'), name(&a.lab), target, name(&a.val))),
+			}
+		}
+	};
+	let spans = |extra: &serde_json::Map<String, Value>, tokens: &[TokenId]| match spans_of(extra) {
+		s if s.is_empty() => doc.token_spans(tokens),
+		s => s,
+	};
+
+	let mut entity_ids: HashMap<EntityId, String> = HashMap::new();
+	for e in &doc.entities {
+		let tokens = if e.tokens.is_empty() {
+			doc.token_list.iter().filter(|t| e.covers(t.id)).map(|t| t.id).collect()
+		} else {
+			e.tokens.clone()
+		};
+		let spans = spans(&e.extra, &tokens);
+		if spans.is_empty() {
+			continue;
+		}
+		let id = new_id('T');
+		text_bound(&mut ann, &id, &e.etype, &spans);
+		attribute_lines(&mut ann, &mut new_id, &id, &e.attributes);
+		entity_ids.insert(e.id, id);
+	}
+
+	let mut event_ids: HashMap<ExpressionId, String> = HashMap::new();
+	let mut events = Vec::new();
+	for e in &doc.expressions {
+		let spans = spans(&e.extra, &e.tokens);
+		if spans.is_empty() {
+			continue;
+		}
+		let trigger = new_id('T');
+		text_bound(&mut ann, &trigger, &e.etype, &spans);
+		let id = new_id('E');
+		event_ids.insert(e.id, id.clone());
+		attribute_lines(&mut ann, &mut new_id, &trigger, &attributes_of(&e.extra, "triggerAttributes"));
+		events.push((id, trigger, e));
+	}
+	for (id, trigger, e) in &events {
+		let mut line = format!("{}\t{}:{}", id, name(&e.etype), trigger);
+		for a in e.extra.get("arguments").and_then(Value::as_array).into_iter().flatten() {
+			let role = a.get("role").and_then(Value::as_str).unwrap_or("Arg");
+			let target = match (a.get("entity").and_then(Value::as_u64), a.get("expression").and_then(Value::as_u64)) {
+				(Some(e), _) => EntityId::from_u64(e).and_then(|e| entity_ids.get(&e)),
+				(None, Some(e)) => ExpressionId::from_u64(e).and_then(|e| event_ids.get(&e)),
+				_ => None,
+			};
+			if let Some(target) = target {
+				line.push_str(&format!(" {}:{}", name(role), target));
+			}
+		}
+		ann.push_str(&line);
+		ann.push('\n');
+		attribute_lines(&mut ann, &mut new_id, id, &attributes_of(&e.extra, "attributes"));
+	}
+
+	for t in &doc.triples {
+		let relation = t.rel.and_then(|id| doc.relations.iter().find(|r| r.id == id));
+		let from = t.from_entity.and_then(|id| entity_ids.get(&id));
+		let to = t.to_entity.and_then(|id| entity_ids.get(&id));
+		if let (Some(r), Some(from), Some(to)) = (relation, from, to) {
+			let rtype = if r.rtype.is_empty() { &r.label } else { &r.rtype };
+			let id = new_id('R');
+			ann.push_str(&format!("{}\t{} Arg1:{} Arg2:{}\t\n", id, name(rtype), from, to));
+			attribute_lines(&mut ann, &mut new_id, &id, &r.attributes);
+		}
+	}
+	(doc.text.clone(), ann)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEXT: &str = "Aspirin inhibits COX-1 in humans.\n";
+	const ANN: &str = "T1\tDrug 0 7\tAspirin\nT2\tProtein 17 22\tCOX-1\nT3\tInhibition 8 16\tinhibits\n\
+		E1\tInhibition:T3 Agent:T1 Theme:T2\nR1\tTargets Arg1:T1 Arg2:T2\nA1\tNegated E1\nA2\tConfidence T1 High\n\
+		#1\tAnnotatorNotes T1\tcommon drug\nT4\tOrganism 26 32\thumans\n";
+
+	#[test]
+	fn brat_round_trip() {
+		let doc = from_brat(TEXT, ANN).expect("valid brat");
+		assert_eq!(doc.entities.len(), 3);
+		assert_eq!(doc.expressions.len(), 1);
+		assert_eq!(doc.triples.len(), 1);
+		let (text, ann) = to_brat(&doc);
+		assert_eq!(text, TEXT);
+		// the writer numbers the annotations by their order, and relations end with an empty text
+		let written: Vec<&str> = ann.lines().map(|l| l.trim_end_matches('\t')).collect();
+		assert_eq!(written.len(), ANN.lines().count());
+		assert!(written.iter().any(|l| l.ends_with("Inhibition:T4 Agent:T1 Theme:T2")));
+		assert!(written.contains(&"A1\tConfidence T1 High"));
+		assert!(written.contains(&"A2\tNegated E1"));
+		assert!(written.contains(&"#1\tAnnotatorNotes T1\tcommon drug"));
+
+		let again = from_brat(&text, &ann).expect("valid brat");
+		assert_eq!(to_brat(&again), (text, ann));
+		let attributes = |d: &Document| d.entities.iter().map(|e| e.attributes.clone()).collect::<Vec<_>>();
+		assert_eq!(attributes(&again), attributes(&doc));
+	}
+
+	#[test]
+	fn brat_trigger_attributes_and_normalizations() {
+		let ann = "T1\tProtein 17 22\tCOX-1\nN1\tReference T1 Wikipedia:123\tPTGS1\nT2\tInhibition 8 16\tinhibits\n\
+			E1\tInhibition:T2 Theme:T1\nA1\tSpeculated T2\n";
+		let doc = from_brat(TEXT, ann).expect("valid brat");
+		assert_eq!(doc.entities[0].attributes[0].extra["text"], "PTGS1");
+		assert_eq!(doc.expressions[0].extra["triggerAttributes"], json!([{ "lab": "Speculated", "val": "true" }]));
+		let (_, written) = to_brat(&doc);
+		assert!(written.contains("N1\tReference T1 Wikipedia:123\tPTGS1\n"));
+		assert!(written.contains("A1\tSpeculated T2\n"));
+		assert_eq!(from_brat(TEXT, &written).expect("valid brat"), doc);
+	}
+
+	#[test]
+	fn brat_errors() {
+		assert!(from_brat(TEXT, "T1\tDrug 0 x\tAspirin\n").is_err());
+		assert!(from_brat(TEXT, "T1\t é 0 1\ta\n").is_ok());
+		assert_eq!(from_brat(TEXT, "T1\t Drug 0 7\tAspirin\n").expect("valid brat").entities[0].etype, "Drug");
+		assert!(from_brat(TEXT, "T1\tDrug\tAspirin\n").is_err());
+		assert!(from_brat(TEXT, "A1\tNegated T9\n").is_err());
+		assert!(from_brat(TEXT, "E1\tInhibition:T9\n").is_err());
+	}
+}
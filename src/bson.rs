@@ -0,0 +1,31 @@
+//! Conversion between [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) and [BSON](https://bsonspec.org/)
+//! documents, e.g. for storing annotations in MongoDB.
+
+use crate::{Document, JsonNlpError, Result, JSONNLP};
+
+fn error(e: impl std::fmt::Display) -> JsonNlpError {
+	JsonNlpError::Format(format!("BSON: {}", e))
+}
+
+/// This function converts a JSONNLP struct/object to a BSON document.
+pub fn to_bson(j: &JSONNLP) -> Result<bson::Document> {
+	bson::to_document(j).map_err(error)
+}
+
+/// This function converts a BSON document to a JSONNLP struct/object.
+pub fn from_bson(doc: bson::Document) -> Result<JSONNLP> {
+	bson::from_document(doc).map_err(error)
+}
+
+impl Document {
+	/// returns the document as a BSON document, e.g. for inserting it into a MongoDB collection.
+	pub fn to_bson(&self) -> Result<bson::Document> {
+		bson::to_document(self).map_err(error)
+	}
+
+	/// returns the Document of a BSON document. Properties that are not part of the JSON-NLP model, like the `_id`
+	/// of MongoDB, are kept in the `extra` map.
+	pub fn from_bson(doc: bson::Document) -> Result<Document> {
+		bson::from_document(doc).map_err(error)
+	}
+}
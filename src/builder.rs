@@ -0,0 +1,405 @@
+//! Builders for constructing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents programmatically.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+	AmrGraph, Clause, ConstituentParse, Coreference, DependencyTree, Document, DocumentId, Entity, Expression,
+	LanguageTag, Meta, Paragraph, Predicate, Prob, Relation, Sentence, SentenceId, Token, TokenFeatures, TokenId, Triple,
+};
+
+/// This struct incrementally collects the layers of a Document.
+/// All layers that are not added stay empty, and `build()` fills in sentence token spans
+/// that can be derived from the token list.
+#[derive(Default, Debug, Clone)]
+pub struct DocumentBuilder {
+	doc: Document,
+}
+
+impl DocumentBuilder {
+	/// creates a new builder for a document with the given ID.
+	pub fn new(id: DocumentId) -> Self {
+		DocumentBuilder {
+			doc: Document { id, ..Default::default() },
+		}
+	}
+
+	/// sets the document metadata.
+	pub fn meta(mut self, meta: Meta) -> Self {
+		self.doc.meta = meta;
+		self
+	}
+
+	/// sets the source text of the document.
+	pub fn text(mut self, text: &str) -> Self {
+		self.doc.text = text.to_string();
+		self
+	}
+
+	/// appends a token to the token list.
+	pub fn token(mut self, token: Token) -> Self {
+		self.doc.token_list.push(token);
+		self
+	}
+
+	/// appends all tokens to the token list.
+	pub fn tokens<I: IntoIterator<Item = Token>>(mut self, tokens: I) -> Self {
+		self.doc.token_list.extend(tokens);
+		self
+	}
+
+	/// appends a sentence.
+	pub fn sentence(mut self, sentence: Sentence) -> Self {
+		self.doc.sentences.push(sentence);
+		self
+	}
+
+	/// appends a clause.
+	pub fn clause(mut self, clause: Clause) -> Self {
+		self.doc.clauses.push(clause);
+		self
+	}
+
+	/// appends a paragraph.
+	pub fn paragraph(mut self, paragraph: Paragraph) -> Self {
+		self.doc.paragraphs.push(paragraph);
+		self
+	}
+
+	/// appends a dependency tree.
+	pub fn dependency_tree(mut self, tree: DependencyTree) -> Self {
+		self.doc.dependency_trees.push(tree);
+		self
+	}
+
+	/// appends a coreference chain.
+	pub fn coreference(mut self, coreference: Coreference) -> Self {
+		self.doc.coreferences.push(coreference);
+		self
+	}
+
+	/// appends a constituent parse.
+	pub fn constituent(mut self, constituent: ConstituentParse) -> Self {
+		self.doc.constituents.push(constituent);
+		self
+	}
+
+	/// appends an expression.
+	pub fn expression(mut self, expression: Expression) -> Self {
+		self.doc.expressions.push(expression);
+		self
+	}
+
+	/// appends an entity.
+	pub fn entity(mut self, entity: Entity) -> Self {
+		self.doc.entities.push(entity);
+		self
+	}
+
+	/// appends a relation.
+	pub fn relation(mut self, relation: Relation) -> Self {
+		self.doc.relations.push(relation);
+		self
+	}
+
+	/// appends a triple.
+	pub fn triple(mut self, triple: Triple) -> Self {
+		self.doc.triples.push(triple);
+		self
+	}
+
+	/// appends a predicate with its semantic role arguments.
+	pub fn predicate(mut self, predicate: Predicate) -> Self {
+		self.doc.semantic_roles.push(predicate);
+		self
+	}
+
+	/// appends the abstract meaning representation graph of a sentence.
+	pub fn amr_graph(mut self, graph: AmrGraph) -> Self {
+		self.doc.amr_graphs.push(graph);
+		self
+	}
+
+	/// returns the Document. Sentences without a token list get the IDs of all tokens
+	/// carrying their sentence ID, and an unset `tokenFrom`/`tokenTo` span is derived from these.
+	pub fn build(mut self) -> Document {
+		for sentence in self.doc.sentences.iter_mut() {
+			if sentence.tokens.is_empty() {
+				sentence.tokens = self.doc.token_list.iter()
+					.filter(|t| t.sentence_id == sentence.id)
+					.map(|t| t.id)
+					.collect();
+			}
+			if sentence.token_from.is_none() && sentence.token_to.is_none() {
+				sentence.token_from = sentence.tokens.first().copied();
+				sentence.token_to = sentence.tokens.last().copied();
+			}
+		}
+		self.doc
+	}
+}
+
+/// This struct builds a Token from the required ID, sentence ID, text, and lemma.
+/// All other properties are optional and default to empty or zero values.
+#[derive(Debug, Clone)]
+pub struct TokenBuilder {
+	token: Token,
+}
+
+impl TokenBuilder {
+	/// creates a new builder with the required token properties.
+	pub fn new(id: TokenId, sentence_id: SentenceId, text: &str, lemma: &str) -> Self {
+		TokenBuilder {
+			token: Token {
+				id,
+				sentence_id,
+				text: text.to_string(),
+				lemma: lemma.to_string(),
+				..Default::default()
+			},
+		}
+	}
+
+	/// sets the `xpos` property.
+	pub fn xpos(mut self, xpos: &str) -> Self {
+		self.token.xpos = xpos.to_string();
+		self
+	}
+
+	/// sets the `xpos_prob` property.
+	pub fn xpos_prob(mut self, xpos_prob: Prob) -> Self {
+		self.token.xpos_prob = Some(xpos_prob);
+		self
+	}
+
+	/// sets the `upos` property.
+	pub fn upos(mut self, upos: &str) -> Self {
+		self.token.upos = upos.to_string();
+		self
+	}
+
+	/// sets the `upos_prob` property.
+	pub fn upos_prob(mut self, upos_prob: Prob) -> Self {
+		self.token.upos_prob = Some(upos_prob);
+		self
+	}
+
+	/// sets the `entity_iob` property.
+	pub fn entity_iob(mut self, entity_iob: &str) -> Self {
+		self.token.entity_iob = entity_iob.to_string();
+		self
+	}
+
+	/// sets the `char_offset_begin` property.
+	pub fn char_offset_begin(mut self, char_offset_begin: u64) -> Self {
+		self.token.char_offset_begin = Some(char_offset_begin);
+		self
+	}
+
+	/// sets the `char_offset_end` property.
+	pub fn char_offset_end(mut self, char_offset_end: u64) -> Self {
+		self.token.char_offset_end = Some(char_offset_end);
+		self
+	}
+
+	/// sets the `prop_id` property.
+	pub fn prop_id(mut self, prop_id: &str) -> Self {
+		self.token.prop_id = prop_id.to_string();
+		self
+	}
+
+	/// sets the `prop_id_prob` property.
+	pub fn prop_id_prob(mut self, prop_id_prob: Prob) -> Self {
+		self.token.prop_id_prob = Some(prop_id_prob);
+		self
+	}
+
+	/// sets the `frame_id` property.
+	pub fn frame_id(mut self, frame_id: u64) -> Self {
+		self.token.frame_id = Some(frame_id);
+		self
+	}
+
+	/// sets the `frame_id_prob` property.
+	pub fn frame_id_prob(mut self, frame_id_prob: Prob) -> Self {
+		self.token.frame_id_prob = Some(frame_id_prob);
+		self
+	}
+
+	/// sets the `wordnet_id` property.
+	pub fn wordnet_id(mut self, wordnet_id: u64) -> Self {
+		self.token.wordnet_id = Some(wordnet_id);
+		self
+	}
+
+	/// sets the `wordnet_id_prob` property.
+	pub fn wordnet_id_prob(mut self, wordnet_id_prob: Prob) -> Self {
+		self.token.wordnet_id_prob = Some(wordnet_id_prob);
+		self
+	}
+
+	/// sets the `verbnet_id` property.
+	pub fn verbnet_id(mut self, verbnet_id: u64) -> Self {
+		self.token.verbnet_id = Some(verbnet_id);
+		self
+	}
+
+	/// sets the `verbnet_id_prob` property.
+	pub fn verbnet_id_prob(mut self, verbnet_id_prob: Prob) -> Self {
+		self.token.verbnet_id_prob = Some(verbnet_id_prob);
+		self
+	}
+
+	/// sets the `lang` property.
+	pub fn lang(mut self, lang: LanguageTag) -> Self {
+		self.token.lang = lang;
+		self
+	}
+
+	/// sets the `shape` property.
+	pub fn shape(mut self, shape: &str) -> Self {
+		self.token.shape = shape.to_string();
+		self
+	}
+
+	/// sets the `entity` property.
+	pub fn entity(mut self, entity: &str) -> Self {
+		self.token.entity = entity.to_string();
+		self
+	}
+
+	/// sets the character offsets of the token.
+	pub fn offsets(mut self, begin: u64, end: u64) -> Self {
+		self.token.char_offset_begin = Some(begin);
+		self.token.char_offset_end = Some(end);
+		self
+	}
+
+	/// sets the token features.
+	pub fn features(mut self, features: TokenFeatures) -> Self {
+		self.token.features = features.boxed();
+		self
+	}
+
+	/// returns the Token.
+	pub fn build(self) -> Token {
+		self.token
+	}
+}
+
+/// The URI of the JSON-NLP specification implemented by this crate, used for `DC.conformsTo`.
+pub const CONFORMS_TO: &str = "https://github.com/SemiringInc/JSON-NLP";
+
+/// returns the current UTC time as an ISO-8601 timestamp, e.g. `2021-03-14T15:09:26Z`.
+pub(crate) fn now_iso8601() -> String {
+	let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+	let (days, rem) = ((secs / 86400) as i64, secs % 86400);
+	// civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let doe = z - era * 146097;
+	let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+	let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+	let mp = (5 * doy + 2) / 153;
+	let day = doy - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+	format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+impl Meta {
+	/// returns metadata with `DC.conformsTo` set to the JSON-NLP specification and
+	/// `DC.created` and `DC.date` set to the current time.
+	pub fn new() -> Self {
+		let now = now_iso8601();
+		Meta {
+			conforms_to: CONFORMS_TO.to_string(),
+			created: now.clone(),
+			date: now,
+			..Default::default()
+		}
+	}
+
+	/// returns a MetaBuilder starting from Meta::new().
+	pub fn builder() -> MetaBuilder {
+		MetaBuilder { meta: Meta::new() }
+	}
+}
+
+/// This struct builds Meta with the specification and creation time filled in automatically.
+#[derive(Debug, Clone)]
+pub struct MetaBuilder {
+	meta: Meta,
+}
+
+impl MetaBuilder {
+	/// sets the `author` property.
+	pub fn author(mut self, author: &str) -> Self {
+		self.meta.author = author.to_string();
+		self
+	}
+
+	/// sets the `source` property.
+	pub fn source(mut self, source: &str) -> Self {
+		self.meta.source = source.to_string();
+		self
+	}
+
+	/// sets the `language` property.
+	pub fn language(mut self, language: LanguageTag) -> Self {
+		self.meta.language = language;
+		self
+	}
+
+	/// sets the `creator` property.
+	pub fn creator(mut self, creator: &str) -> Self {
+		self.meta.creator = creator.to_string();
+		self
+	}
+
+	/// sets the `publisher` property.
+	pub fn publisher(mut self, publisher: &str) -> Self {
+		self.meta.publisher = publisher.to_string();
+		self
+	}
+
+	/// sets the `title` property.
+	pub fn title(mut self, title: &str) -> Self {
+		self.meta.title = title.to_string();
+		self
+	}
+
+	/// sets the `description` property.
+	pub fn description(mut self, description: &str) -> Self {
+		self.meta.description = description.to_string();
+		self
+	}
+
+	/// sets the `identifier` property.
+	pub fn identifier(mut self, identifier: &str) -> Self {
+		self.meta.identifier = identifier.to_string();
+		self
+	}
+
+	/// sets the `created` property, which must be ISO-8601 to serialize with the `chrono` feature.
+	pub fn created(mut self, created: &str) -> Self {
+		self.meta.created = created.to_string();
+		self
+	}
+
+	/// sets the `date` property, which must be ISO-8601 to serialize with the `chrono` feature.
+	pub fn date(mut self, date: &str) -> Self {
+		self.meta.date = date.to_string();
+		self
+	}
+
+	/// sets the `conforms_to` property.
+	pub fn conforms_to(mut self, conforms_to: &str) -> Self {
+		self.meta.conforms_to = conforms_to.to_string();
+		self
+	}
+
+	/// returns the Meta.
+	pub fn build(self) -> Meta {
+		self.meta
+	}
+}
@@ -0,0 +1,127 @@
+//! Canonical serialization producing byte-identical output for semantically identical documents.
+
+use serde_json::Value;
+
+use crate::{Document, JsonNlpError, Result, JSONNLP};
+
+impl Document {
+	/// sorts all layers into their canonical order: elements with an ID by ID, dependency trees,
+	/// constituent parses, and AMR graphs by sentence ID, dependencies by governor, dependent, and label,
+	/// AMR nodes by variable, and AMR edges and attributes by source, role, and target or value.
+	/// Lists of referenced IDs keep their order, since it can be meaningful.
+	pub fn canonicalize(&mut self) {
+		self.token_list.sort_by_key(|t| t.id);
+		self.clauses.sort_by_key(|c| c.id);
+		self.sentences.sort_by_key(|s| s.id);
+		self.paragraphs.sort_by_key(|p| p.id);
+		self.dependency_trees.sort_by_key(|t| t.sentence_id);
+		for tree in self.dependency_trees.iter_mut() {
+			tree.dependencies.sort_by(|a, b| (a.gov, a.dep, &a.lab).cmp(&(b.gov, b.dep, &b.lab)));
+		}
+		self.coreferences.sort_by_key(|c| c.id);
+		self.constituents.sort_by_key(|c| c.sentence_id);
+		for c in self.constituents.iter_mut() {
+			c.scopes.sort_by_key(|s| s.id);
+		}
+		self.expressions.sort_by_key(|e| e.id);
+		self.entities.sort_by_key(|e| e.id);
+		self.relations.sort_by_key(|r| r.id);
+		self.triples.sort_by_key(|t| t.id);
+		self.semantic_roles.sort_by_key(|p| p.id);
+		self.amr_graphs.sort_by_key(|g| g.sentence_id);
+		for g in self.amr_graphs.iter_mut() {
+			g.nodes.sort_by(|a, b| a.id.cmp(&b.id));
+			g.edges.sort_by(|a, b| (&a.source, &a.role, &a.target).cmp(&(&b.source, &b.role, &b.target)));
+			g.attributes.sort_by(|a, b| (&a.source, &a.role, &a.value).cmp(&(&b.source, &b.role, &b.value)));
+		}
+	}
+}
+
+impl JSONNLP {
+	/// sorts the documents by ID and canonicalizes each of them, see Document::canonicalize().
+	pub fn canonicalize(&mut self) {
+		self.docs.sort_by_key(|d| d.id);
+		for doc in self.docs.iter_mut() {
+			doc.canonicalize();
+		}
+	}
+}
+
+/// This function returns the canonical JSON representation of a JSONNLP struct/object.
+/// Layers are sorted as described in Document::canonicalize(), object keys are sorted,
+/// no whitespace is emitted, and negative zero is written as zero.
+pub fn to_canonical_json(j: &JSONNLP) -> Result<String> {
+	let mut j = j.clone();
+	j.canonicalize();
+	let value = serde_json::to_value(&j).map_err(JsonNlpError::Serialize)?;
+	let mut out = String::new();
+	write_canonical(&value, &mut out);
+	Ok(out)
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+	match value {
+		Value::Object(map) => {
+			let mut keys: Vec<&String> = map.keys().collect();
+			keys.sort();
+			out.push('{');
+			for (i, key) in keys.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				out.push_str(&Value::String(key.to_string()).to_string());
+				out.push(':');
+				write_canonical(&map[key.as_str()], out);
+			}
+			out.push('}');
+		}
+		Value::Array(items) => {
+			out.push('[');
+			for (i, item) in items.iter().enumerate() {
+				if i > 0 {
+					out.push(',');
+				}
+				write_canonical(item, out);
+			}
+			out.push(']');
+		}
+		Value::Number(n) => match n.as_f64() {
+			Some(f) if n.is_f64() && f == 0.0 => out.push_str("0.0"),
+			_ => out.push_str(&n.to_string()),
+		},
+		_ => out.push_str(&value.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::from_string;
+
+	#[test]
+	fn canonical_output_is_deterministic() {
+		let a = from_string(r#"{"meta":{},"z":-0.0,"docs":[{"meta":{},"id":2},{"meta":{},"id":1,"tokenList":[
+			{"id":2,"sentence_id":1,"text":"b","lemma":"b"},{"id":1,"sentence_id":1,"text":"a","lemma":"a"}],
+			"dependencyTrees":[{"sentenceId":1,"dependencies":[{"lab":"b","gov":1,"dep":2},
+			{"lab":"a","gov":1,"dep":2}]}],
+			"entities":[{"id":2,"type":"X","tokens":[2,1]},{"id":1,"type":"X","tokens":[1]}]}]}"#)
+		.unwrap();
+		let b = from_string(r#"{"docs":[{"id":1,"meta":{},"dependencyTrees":[{"dependencies":[
+			{"gov":1,"dep":2,"lab":"a"},{"gov":1,"dep":2,"lab":"b"}],"sentenceId":1}],
+			"entities":[{"id":1,"type":"X","tokens":[1]},
+			{"tokens":[2,1],"id":2,"type":"X"}],"tokenList":[{"sentence_id":1,"id":1,"text":"a","lemma":"a"},
+			{"id":2,"sentence_id":1,"text":"b","lemma":"b"}]},{"id":2,"meta":{}}],"z":0.0,"meta":{}}"#)
+		.unwrap();
+		let json = to_canonical_json(&a).unwrap();
+		assert_eq!(json, to_canonical_json(&b).unwrap());
+		assert!(!json.contains(char::is_whitespace) && json.ends_with(r#""z":0.0}"#), "{}", json);
+		assert!(json.contains(r#""lab":"a"},{"dep":2,"gov":1,"lab":"b""#), "{}", json);
+		assert!(json.contains(r#""tokens":[2,1]"#), "{}", json);
+
+		let mut canonical = a.clone();
+		canonical.canonicalize();
+		let ids: Vec<_> = canonical.docs.iter().map(|d| d.id.0).collect();
+		assert_eq!(ids, [1, 2]);
+		assert_eq!(from_string(&json).unwrap(), canonical);
+	}
+}
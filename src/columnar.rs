@@ -0,0 +1,142 @@
+//! A struct-of-arrays representation of the tokens of a document, for analytics that scan single token properties.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::{Document, Prob, SentenceId, Token, TokenId};
+
+/// This struct contains a document with its tokens stored as parallel columns, one element per token.
+/// The token properties without a column are kept in `rest` as a token with the column properties left empty,
+/// if any of them is set. The document keeps all other properties, with an empty token list.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ColumnarDocument {
+	pub document: Document,
+	pub id: Vec<TokenId>,
+	pub sentence_id: Vec<SentenceId>,
+	pub text: Vec<String>,
+	pub lemma: Vec<String>,
+	pub xpos: Vec<String>,
+	pub xpos_prob: Vec<Option<Prob>>,
+	pub upos: Vec<String>,
+	pub upos_prob: Vec<Option<Prob>>,
+	pub entity_iob: Vec<String>,
+	pub entity: Vec<String>,
+	pub shape: Vec<String>,
+	pub char_offset_begin: Vec<Option<u64>>,
+	pub char_offset_end: Vec<Option<u64>>,
+	pub rest: Vec<Option<Box<Token>>>,
+}
+
+impl ColumnarDocument {
+	/// returns the number of tokens.
+	pub fn len(&self) -> usize {
+		self.id.len()
+	}
+
+	/// checks whether the document has no tokens.
+	pub fn is_empty(&self) -> bool {
+		self.id.is_empty()
+	}
+
+	/// appends a token to the columns.
+	pub fn push(&mut self, mut token: Token) {
+		self.id.push(std::mem::take(&mut token.id));
+		self.sentence_id.push(std::mem::take(&mut token.sentence_id));
+		self.text.push(std::mem::take(&mut token.text));
+		self.lemma.push(std::mem::take(&mut token.lemma));
+		self.xpos.push(std::mem::take(&mut token.xpos));
+		self.xpos_prob.push(token.xpos_prob.take());
+		self.upos.push(std::mem::take(&mut token.upos));
+		self.upos_prob.push(token.upos_prob.take());
+		self.entity_iob.push(std::mem::take(&mut token.entity_iob));
+		self.entity.push(std::mem::take(&mut token.entity));
+		self.shape.push(std::mem::take(&mut token.shape));
+		self.char_offset_begin.push(token.char_offset_begin.take());
+		self.char_offset_end.push(token.char_offset_end.take());
+		self.rest.push(if token == Token::default() { None } else { Some(Box::new(token)) });
+	}
+
+	/// returns the token at the given index, or None if the index is out of range.
+	pub fn token(&self, i: usize) -> Option<Token> {
+		if i >= self.len() {
+			return None;
+		}
+		let mut token = self.rest[i].as_deref().cloned().unwrap_or_default();
+		token.id = self.id[i];
+		token.sentence_id = self.sentence_id[i];
+		token.text = self.text[i].clone();
+		token.lemma = self.lemma[i].clone();
+		token.xpos = self.xpos[i].clone();
+		token.xpos_prob = self.xpos_prob[i];
+		token.upos = self.upos[i].clone();
+		token.upos_prob = self.upos_prob[i];
+		token.entity_iob = self.entity_iob[i].clone();
+		token.entity = self.entity[i].clone();
+		token.shape = self.shape[i].clone();
+		token.char_offset_begin = self.char_offset_begin[i];
+		token.char_offset_end = self.char_offset_end[i];
+		Some(token)
+	}
+
+	/// returns the indices of the tokens with the given universal POS tag.
+	pub fn find_upos<'a>(&'a self, upos: &'a str) -> impl Iterator<Item = usize> + 'a {
+		self.upos.iter().enumerate().filter(move |(_, u)| *u == upos).map(|(i, _)| i)
+	}
+
+	/// returns the number of tokens per universal POS tag.
+	pub fn upos_counts(&self) -> HashMap<&str, usize> {
+		let mut counts = HashMap::new();
+		for upos in &self.upos {
+			*counts.entry(upos.as_str()).or_insert(0) += 1;
+		}
+		counts
+	}
+
+	/// returns the range of the indices of the tokens of a sentence, assuming that the tokens of a sentence are
+	/// contiguous. The range is empty if the sentence has no tokens.
+	pub fn sentence_range(&self, sentence: SentenceId) -> Range<usize> {
+		match self.sentence_id.iter().position(|s| *s == sentence) {
+			Some(start) => {
+				let len = self.sentence_id[start..].iter().take_while(|s| **s == sentence).count();
+				start..start + len
+			}
+			None => 0..0,
+		}
+	}
+}
+
+impl From<Document> for ColumnarDocument {
+	fn from(mut document: Document) -> Self {
+		let tokens = std::mem::take(&mut document.token_list);
+		let mut c = ColumnarDocument { document, ..Default::default() };
+		for token in tokens {
+			c.push(token);
+		}
+		c
+	}
+}
+
+impl From<ColumnarDocument> for Document {
+	fn from(mut c: ColumnarDocument) -> Self {
+		let mut tokens = Vec::with_capacity(c.len());
+		for i in 0..c.len() {
+			let mut token = c.rest[i].take().map(|t| *t).unwrap_or_default();
+			token.id = c.id[i];
+			token.sentence_id = c.sentence_id[i];
+			token.text = std::mem::take(&mut c.text[i]);
+			token.lemma = std::mem::take(&mut c.lemma[i]);
+			token.xpos = std::mem::take(&mut c.xpos[i]);
+			token.xpos_prob = c.xpos_prob[i];
+			token.upos = std::mem::take(&mut c.upos[i]);
+			token.upos_prob = c.upos_prob[i];
+			token.entity_iob = std::mem::take(&mut c.entity_iob[i]);
+			token.entity = std::mem::take(&mut c.entity[i]);
+			token.shape = std::mem::take(&mut c.shape[i]);
+			token.char_offset_begin = c.char_offset_begin[i];
+			token.char_offset_end = c.char_offset_end[i];
+			tokens.push(token);
+		}
+		c.document.token_list = tokens;
+		c.document
+	}
+}
@@ -0,0 +1,264 @@
+//! Conversion of [Amazon Comprehend](https://aws.amazon.com/comprehend/) outputs to
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::offsets::{OffsetUnit, SpanLocator};
+use crate::{
+	Document, DocumentId, Entity, EntityId, Expression, ExpressionId, JsonNlpError, LanguageTag, Prob, RawId, Result,
+	Sentence, SentenceId, Token, TokenId,
+};
+
+/// This struct contains the fields of the outputs of the `DetectSyntax`, `DetectEntities`, `DetectKeyPhrases`,
+/// `DetectSentiment`, and `DetectDominantLanguage` operations, of which every output has some.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+struct ComprehendOutput {
+	#[serde(default)]
+	syntax_tokens: Vec<ComprehendToken>,
+	#[serde(default)]
+	entities: Vec<ComprehendSpan>,
+	#[serde(default)]
+	key_phrases: Vec<ComprehendSpan>,
+	#[serde(default)]
+	sentiment: String,
+	#[serde(default)]
+	sentiment_score: Map<String, Value>,
+	#[serde(default)]
+	languages: Vec<ComprehendLanguage>,
+}
+
+/// This struct contains a token of the `DetectSyntax` output.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ComprehendToken {
+	text: String,
+	#[serde(default)]
+	begin_offset: Option<u64>,
+	#[serde(default)]
+	part_of_speech: Option<ComprehendTag>,
+}
+
+/// This struct contains a POS tag with its score.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ComprehendTag {
+	tag: String,
+	#[serde(default)]
+	score: Option<f64>,
+}
+
+/// This struct contains an entity or key phrase with its score.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ComprehendSpan {
+	text: String,
+	#[serde(rename = "Type", default)]
+	etype: String,
+	#[serde(default)]
+	begin_offset: Option<u64>,
+	#[serde(default)]
+	score: Option<f64>,
+}
+
+/// This struct contains a language of the `DetectDominantLanguage` output.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ComprehendLanguage {
+	language_code: String,
+}
+
+/// returns the entities or key phrases that are found in the text, with the tokens overlapping them and their
+/// `extra` map.
+fn locate_spans<'a>(
+	doc: &Document,
+	locator: &SpanLocator,
+	spans: &'a [ComprehendSpan],
+) -> Vec<(&'a ComprehendSpan, Vec<TokenId>, Map<String, Value>)> {
+	spans
+		.iter()
+		.filter_map(|s| {
+			let (begin, end) = locator.locate(&s.text, s.begin_offset, 0)?;
+			let mut extra = Map::new();
+			if let Some(score) = s.score {
+				extra.insert("score".to_string(), json!(score));
+			}
+			extra.insert("spans".to_string(), json!([[begin, end]]));
+			Some((s, doc.tokens_overlapping(&[(begin, end)]), extra))
+		})
+		.collect()
+}
+
+/// This function converts the JSON outputs of Amazon Comprehend's `DetectSyntax`, `DetectEntities`,
+/// `DetectKeyPhrases`, `DetectSentiment`, and `DetectDominantLanguage` operations for the same text to a Document
+/// with that text, which the outputs do not contain. Every output may be given in any order, and operations that
+/// were not run are left out.
+///
+/// Comprehend counts its offsets in UTF-16 code units, which are mapped to character offsets; spans whose offsets do
+/// not select their text are located at the nearest occurrence of their text instead. The syntax tokens become
+/// tokens with their UPOS tag, where Comprehend's `O` becomes `X`, and the tag score in their `extra` map as
+/// `posScore`. As Comprehend does not split sentences, the text is one sentence with the sentiment in lower case,
+/// its score as `sentimentProb`, and all scores in its `extra` map as `sentimentScore`. Entities become entities
+/// and key phrases expressions of type `keyPhrase`, linked to the tokens overlapping them, with their score and
+/// character span in the `extra` map as `score` and `spans`, and the tokens' `entity` and `entity_iob` tags are set
+/// from the entities. The dominant language becomes the language of the document.
+pub fn from_comprehend_json(text: &str, outputs: &[&str]) -> Result<Document> {
+	let mut merged = ComprehendOutput::default();
+	for json in outputs {
+		let mut de = serde_json::Deserializer::from_str(json);
+		let output: ComprehendOutput = serde_path_to_error::deserialize(&mut de)?;
+		de.end()?;
+		merged.syntax_tokens.extend(output.syntax_tokens);
+		merged.entities.extend(output.entities);
+		merged.key_phrases.extend(output.key_phrases);
+		if !output.sentiment.is_empty() {
+			merged.sentiment = output.sentiment;
+			merged.sentiment_score = output.sentiment_score;
+		}
+		merged.languages.extend(output.languages);
+	}
+
+	let locator = SpanLocator::new(text, OffsetUnit::Utf16);
+	let mut doc = Document { id: DocumentId(1), text: text.to_string(), ..Default::default() };
+	if let Some(language) = merged.languages.first() {
+		doc.meta.language = LanguageTag::new(&language.language_code)?;
+	}
+
+	let mut from = 0;
+	for (i, t) in merged.syntax_tokens.iter().enumerate() {
+		let (begin, end) = locator
+			.locate(&t.text, t.begin_offset, from)
+			.ok_or_else(|| JsonNlpError::Format(format!("token {} {:?} is not in the text", i + 1, t.text)))?;
+		let mut token = Token {
+			id: TokenId(i as RawId + 1),
+			sentence_id: SentenceId(1),
+			text: t.text.clone(),
+			char_offset_begin: Some(begin),
+			char_offset_end: Some(end),
+			..Default::default()
+		};
+		if let Some(tag) = &t.part_of_speech {
+			token.upos = if tag.tag == "O" { "X".to_string() } else { tag.tag.clone() };
+			if let Some(score) = tag.score {
+				token.extra.insert("posScore".to_string(), json!(score));
+			}
+		}
+		if doc.token_list.last().is_some_and(|last| last.char_offset_end == Some(begin)) {
+			doc.token_list.last_mut().expect("there is a last token").features_mut().spaceafter = Some(false);
+		}
+		doc.token_list.push(token);
+		from = end;
+	}
+
+	if !doc.token_list.is_empty() || !merged.sentiment.is_empty() {
+		let tokens: Vec<TokenId> = doc.token_list.iter().map(|t| t.id).collect();
+		let mut sentence = Sentence {
+			id: SentenceId(1),
+			text: text.to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			sentiment: merged.sentiment.to_lowercase(),
+			..Default::default()
+		};
+		// the scores are keyed by the sentiment in title case, e.g. `Positive` for `POSITIVE`
+		sentence.sentiment_prob = merged
+			.sentiment_score
+			.iter()
+			.find(|(k, _)| k.eq_ignore_ascii_case(&merged.sentiment))
+			.and_then(|(_, v)| v.as_f64())
+			.and_then(Prob::new);
+		if !merged.sentiment_score.is_empty() {
+			sentence.extra.insert("sentimentScore".to_string(), Value::Object(merged.sentiment_score));
+		}
+		doc.sentences.push(sentence);
+	}
+
+	let entities = locate_spans(&doc, &locator, &merged.entities);
+	let key_phrases = locate_spans(&doc, &locator, &merged.key_phrases);
+
+	if !entities.is_empty() {
+		for t in doc.token_list.iter_mut() {
+			t.entity_iob = "O".to_string();
+		}
+	}
+	for (s, tokens, extra) in entities {
+		for (i, id) in tokens.iter().enumerate() {
+			if let Some(t) = doc.token_list.iter_mut().find(|t| t.id == *id && t.entity_iob == "O") {
+				t.entity_iob = if i == 0 { "B" } else { "I" }.to_string();
+				t.entity = s.etype.clone();
+			}
+		}
+		doc.entities.push(Entity {
+			id: EntityId(doc.entities.len() as RawId + 1),
+			label: s.text.clone(),
+			etype: s.etype.clone(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			extra,
+			..Default::default()
+		});
+	}
+	for (s, tokens, mut extra) in key_phrases {
+		extra.insert("text".to_string(), json!(s.text));
+		doc.expressions.push(Expression {
+			id: ExpressionId(doc.expressions.len() as RawId + 1),
+			etype: "keyPhrase".to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			extra,
+			..Default::default()
+		});
+	}
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEXT: &str = "😀 Amazon rocks.";
+
+	const SYNTAX: &str = r#"{"SyntaxTokens":[
+		{"TokenId":1,"Text":"😀","BeginOffset":0,"EndOffset":2,"PartOfSpeech":{"Tag":"O","Score":0.5}},
+		{"TokenId":2,"Text":"Amazon","BeginOffset":3,"EndOffset":9,"PartOfSpeech":{"Tag":"PROPN","Score":0.99}},
+		{"TokenId":3,"Text":"rocks","BeginOffset":10,"EndOffset":15,"PartOfSpeech":{"Tag":"VERB"}},
+		{"TokenId":4,"Text":".","BeginOffset":15,"EndOffset":16,"PartOfSpeech":{"Tag":"PUNCT"}}]}"#;
+
+	#[test]
+	fn comprehend_import() {
+		let entities =
+			r#"{"Entities":[{"Text":"Amazon","Type":"ORGANIZATION","BeginOffset":3,"EndOffset":9,"Score":0.9}]}"#;
+		let phrases = r#"{"KeyPhrases":[{"Text":"Amazon","Score":0.8}]}"#;
+		let sentiment = r#"{"Sentiment":"POSITIVE","SentimentScore":{"Positive":0.75,"Negative":0.25}}"#;
+		let language = r#"{"Languages":[{"LanguageCode":"en","Score":0.99}]}"#;
+		let doc = from_comprehend_json(TEXT, &[entities, SYNTAX, phrases, sentiment, language]).expect("valid outputs");
+		assert_eq!(doc.meta.language.as_str(), "en");
+		let tokens: Vec<_> = doc.token_list.iter().map(|t| (t.char_offset_begin.unwrap(), t.upos.as_str())).collect();
+		assert_eq!(tokens, [(0, "X"), (2, "PROPN"), (9, "VERB"), (14, "PUNCT")]);
+		assert_eq!(doc.token_list[1].extra["posScore"], 0.99);
+		assert_eq!(doc.token_list[2].features().spaceafter, Some(false));
+
+		let s = &doc.sentences[0];
+		assert_eq!((s.sentiment.as_str(), s.sentiment_prob, s.tokens.len()), ("positive", Prob::new(0.75), 4));
+		let e = &doc.entities[0];
+		assert_eq!((e.etype.as_str(), e.tokens.as_slice()), ("ORGANIZATION", &[TokenId(2)][..]));
+		assert_eq!((&e.extra["score"], &e.extra["spans"]), (&json!(0.9), &json!([[2, 8]])));
+		let iob: Vec<_> = doc.token_list.iter().map(|t| t.entity_iob.as_str()).collect();
+		assert_eq!(iob, ["O", "B", "O", "O"]);
+		let phrase = &doc.expressions[0];
+		assert_eq!((phrase.etype.as_str(), &phrase.extra["text"]), ("keyPhrase", &json!("Amazon")));
+	}
+
+	#[test]
+	fn comprehend_errors() {
+		let e = from_comprehend_json("Amazon rocks.", &[SYNTAX]).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == "token 1 \"😀\" is not in the text"), "{}", e);
+		let e = from_comprehend_json(TEXT, &[r#"{"Entities":[{"Type":"PERSON"}]}"#]).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Parse(ref p) if p.pointer == "/Entities/0"), "{}", e);
+		assert!(from_comprehend_json(TEXT, &[r#"{"SyntaxTokens":[{"BeginOffset":0}]}"#]).is_err());
+	}
+}
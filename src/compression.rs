@@ -0,0 +1,162 @@
+//! Transparent compression of JSON-NLP files, with gzip and zstd support behind the `gzip` and `zstd` features.
+
+use std::fs::File;
+#[cfg(feature = "zstd")]
+use std::io::Read;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::Result;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+use crate::{from_reader, to_writer, JSONNLP};
+
+#[cfg(feature = "gzip")]
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// returns a buffered reader of a file, decompressing it if it starts with the magic bytes of a supported
+/// compression format.
+pub(crate) fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead>> {
+	let reader = BufReader::new(File::open(path)?);
+	#[cfg(any(feature = "gzip", feature = "zstd"))]
+	let reader = {
+		let mut reader = reader;
+		let header = reader.fill_buf()?;
+		#[cfg(feature = "gzip")]
+		{
+			if header.starts_with(&GZIP_MAGIC) {
+				return Ok(Box::new(BufReader::new(flate2::bufread::MultiGzDecoder::new(reader))));
+			}
+		}
+		#[cfg(feature = "zstd")]
+		{
+			if header.starts_with(&ZSTD_MAGIC) {
+				return Ok(Box::new(BufReader::new(zstd::Decoder::with_buffer(reader)?)));
+			}
+		}
+		reader
+	};
+	Ok(Box::new(reader))
+}
+
+/// This enum contains a buffered writer to a file, compressing the data if the file name asks for it.
+pub(crate) enum Output {
+	Plain(BufWriter<File>),
+	#[cfg(feature = "gzip")]
+	Gzip(flate2::write::GzEncoder<BufWriter<File>>),
+	#[cfg(feature = "zstd")]
+	Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl Output {
+	/// returns a writer to a new file, compressing with gzip for the `.gz` extension and with zstd for `.zst`.
+	pub(crate) fn create<P: AsRef<Path>>(path: P) -> Result<Output> {
+		#[cfg(feature = "gzip")]
+		{
+			if path.as_ref().extension().is_some_and(|e| e == "gz") {
+				return Output::gzip(path);
+			}
+		}
+		#[cfg(feature = "zstd")]
+		{
+			if path.as_ref().extension().is_some_and(|e| e == "zst") {
+				return Output::zstd(path, zstd::DEFAULT_COMPRESSION_LEVEL);
+			}
+		}
+		Ok(Output::Plain(BufWriter::new(File::create(path)?)))
+	}
+
+	#[cfg(feature = "gzip")]
+	fn gzip<P: AsRef<Path>>(path: P) -> Result<Output> {
+		let file = BufWriter::new(File::create(path)?);
+		Ok(Output::Gzip(flate2::write::GzEncoder::new(file, flate2::Compression::default())))
+	}
+
+	#[cfg(feature = "zstd")]
+	fn zstd<P: AsRef<Path>>(path: P, level: i32) -> Result<Output> {
+		let file = BufWriter::new(File::create(path)?);
+		Ok(Output::Zstd(zstd::Encoder::new(file, level)?))
+	}
+
+	/// writes the end of the compressed data and flushes the file.
+	pub(crate) fn finish(self) -> Result<()> {
+		match self {
+			Output::Plain(mut w) => w.flush()?,
+			#[cfg(feature = "gzip")]
+			Output::Gzip(w) => w.finish()?.flush()?,
+			#[cfg(feature = "zstd")]
+			Output::Zstd(w) => w.finish()?.flush()?,
+		}
+		Ok(())
+	}
+}
+
+impl Write for Output {
+	fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+		match self {
+			Output::Plain(w) => w.write(buf),
+			#[cfg(feature = "gzip")]
+			Output::Gzip(w) => w.write(buf),
+			#[cfg(feature = "zstd")]
+			Output::Zstd(w) => w.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> std::io::Result<()> {
+		match self {
+			Output::Plain(w) => w.flush(),
+			#[cfg(feature = "gzip")]
+			Output::Gzip(w) => w.flush(),
+			#[cfg(feature = "zstd")]
+			Output::Zstd(w) => w.flush(),
+		}
+	}
+}
+
+/// This function reads a gzip-compressed [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) file, e.g. `corpus.json.gz`.
+#[cfg(feature = "gzip")]
+pub fn from_gzip_file<P: AsRef<Path>>(path: P) -> Result<JSONNLP> {
+	let reader = BufReader::new(File::open(path)?);
+	from_reader(flate2::bufread::MultiGzDecoder::new(reader))
+}
+
+/// This function writes a JSONNLP struct/object as gzip-compressed JSON to a file, whatever its extension.
+#[cfg(feature = "gzip")]
+pub fn to_gzip_file<P: AsRef<Path>>(j: &JSONNLP, path: P) -> Result<()> {
+	let mut output = Output::gzip(path)?;
+	to_writer(j, &mut output)?;
+	output.finish()
+}
+
+/// This function reads a zstd-compressed [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) file, e.g. `corpus.json.zst`.
+#[cfg(feature = "zstd")]
+pub fn from_zstd_file<P: AsRef<Path>>(path: P) -> Result<JSONNLP> {
+	from_zstd_reader(File::open(path)?)
+}
+
+/// This function reads zstd-compressed [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) from a reader,
+/// decompressing it while it is parsed.
+#[cfg(feature = "zstd")]
+pub fn from_zstd_reader<R: Read>(reader: R) -> Result<JSONNLP> {
+	from_reader(zstd::Decoder::new(reader)?)
+}
+
+/// This function writes a JSONNLP struct/object as zstd-compressed JSON to a file, whatever its extension,
+/// with the given compression level, where 0 selects the default level.
+#[cfg(feature = "zstd")]
+pub fn to_zstd_file<P: AsRef<Path>>(j: &JSONNLP, path: P, level: i32) -> Result<()> {
+	let mut output = Output::zstd(path, level)?;
+	to_writer(j, &mut output)?;
+	output.finish()
+}
+
+/// This function writes a JSONNLP struct/object as zstd-compressed JSON to a writer, compressing it while it is
+/// serialized, with the given compression level, where 0 selects the default level.
+#[cfg(feature = "zstd")]
+pub fn to_zstd_writer<W: Write>(j: &JSONNLP, writer: W, level: i32) -> Result<W> {
+	let mut encoder = zstd::Encoder::new(writer, level)?;
+	to_writer(j, &mut encoder)?;
+	Ok(encoder.finish()?)
+}
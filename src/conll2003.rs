@@ -0,0 +1,243 @@
+//! Conversion between [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents and the
+//! CoNLL-2003 named entity format with one token per line and IOB tags in the last column.
+
+use serde_json::Value;
+
+use crate::limits::Line;
+use crate::{
+	Document, DocumentId, Entity, EntityId, JsonNlpError, Meta, RawId, Result, Sentence, SentenceId, Token, TokenId,
+	JSONNLP,
+};
+
+const DOCSTART: &str = "-DOCSTART-";
+
+/// returns a column value without whitespace, and `_` for empty values.
+fn column(s: &str) -> String {
+	let s = s.trim();
+	if s.is_empty() {
+		"_".to_string()
+	} else {
+		s.split_whitespace().collect::<Vec<_>>().join("_")
+	}
+}
+
+/// returns the IOB tag of every token of a sentence, taken from the entity layer if the document has one,
+/// else from the tokens' `entity_iob` and `entity` properties.
+fn tags(doc: &Document, tokens: &[&Token]) -> Vec<String> {
+	if doc.entities.is_empty() {
+		return tokens.iter().map(|t| match t.entity_iob.as_str() {
+			"" | "O" => "O".to_string(),
+			iob if t.entity.is_empty() => iob.to_string(),
+			iob => format!("{}-{}", iob, t.entity),
+		}).collect();
+	}
+	let mut previous: Option<&Entity> = None;
+	tokens.iter().map(|t| {
+		let entity = doc.entities.iter().find(|e| e.covers(t.id));
+		let tag = match entity {
+			None => "O".to_string(),
+			Some(e) if previous.is_some_and(|p| p.id == e.id) => format!("I-{}", e.etype),
+			Some(e) => format!("B-{}", e.etype),
+		};
+		previous = entity;
+		tag
+	}).collect()
+}
+
+/// This function converts a Document to the CoNLL-2003 format. The document starts with a `-DOCSTART-` line,
+/// sentences are separated by blank lines, and every token is written with its `xpos`, its chunk tag
+/// (kept in the token's `extra` map as `chunk`), and an IOB2 entity tag. The entity tags are derived
+/// from the entity layer, or from the tokens' `entity_iob` and `entity` properties if there are no entities.
+pub fn to_conll2003(doc: &Document) -> String {
+	let mut out = format!("{} -X- -X- O\n\n", DOCSTART);
+	for (_, _, tokens) in doc.sentence_groups() {
+		for (t, tag) in tokens.iter().zip(tags(doc, &tokens)) {
+			let chunk = t.extra.get("chunk").and_then(Value::as_str).unwrap_or("");
+			out.push_str(&format!("{} {} {} {}\n", column(&t.text), column(&t.xpos), column(chunk), tag));
+		}
+		out.push('\n');
+	}
+	out
+}
+
+/// This struct collects the documents while reading CoNLL-2003.
+#[derive(Default)]
+struct Reader {
+	docs: Vec<Document>,
+	sentence: Vec<Token>,
+	/// the entity type of the previous token in the sentence, if it is part of an entity.
+	previous: Option<String>,
+}
+
+impl Reader {
+	fn current(&mut self) -> &mut Document {
+		if self.docs.is_empty() {
+			self.new_document();
+		}
+		self.docs.last_mut().expect("a document was just added")
+	}
+
+	fn new_document(&mut self) {
+		let id = DocumentId(self.docs.len() as RawId + 1);
+		self.docs.push(Document { id, ..Default::default() });
+	}
+
+	/// adds a token with its IOB tag, starting a new entity or extending the current one.
+	fn token(&mut self, text: &str, xpos: &str, chunk: Option<&str>, tag: &str, line: usize) -> Result<()> {
+		let (iob, etype) = match tag.split_once('-') {
+			_ if tag == "O" => ("O", ""),
+			Some((iob @ "B", etype)) | Some((iob @ "I", etype)) if !etype.is_empty() => (iob, etype),
+			_ => return Err(JsonNlpError::Format(format!("line {}: invalid IOB tag {:?}", line, tag))),
+		};
+		let pending = self.sentence.len() as RawId;
+		let doc = self.current();
+		let id = TokenId(doc.token_list.len() as RawId + pending + 1);
+		let sentence_id = SentenceId(doc.sentences.len() as RawId + 1);
+		// IOB1 starts entities with I- and uses B- only between adjacent entities of the same type
+		let starts = iob == "B" || self.previous.as_deref() != Some(etype);
+		if iob != "O" {
+			let doc = self.docs.last_mut().expect("a document was just added");
+			if starts {
+				let entity_id = EntityId(doc.entities.len() as RawId + 1);
+				doc.entities.push(Entity {
+					id: entity_id,
+					label: text.to_string(),
+					etype: etype.to_string(),
+					..Default::default()
+				});
+			} else if let Some(e) = doc.entities.last_mut() {
+				e.label.push(' ');
+				e.label.push_str(text);
+			}
+			let e = doc.entities.last_mut().expect("an entity was just added");
+			e.tokens.push(id);
+			e.token_from = e.tokens.first().copied();
+			e.token_to = Some(id);
+		}
+		self.previous = if iob == "O" { None } else { Some(etype.to_string()) };
+
+		let mut token = Token {
+			id,
+			sentence_id,
+			text: text.to_string(),
+			xpos: if xpos == "_" { String::new() } else { xpos.to_string() },
+			entity_iob: if iob == "O" { iob.to_string() } else if starts { "B".to_string() } else { "I".to_string() },
+			entity: etype.to_string(),
+			..Default::default()
+		};
+		if let Some(chunk) = chunk.filter(|c| *c != "_") {
+			token.extra.insert("chunk".to_string(), Value::String(chunk.to_string()));
+		}
+		self.sentence.push(token);
+		Ok(())
+	}
+
+	/// adds the tokens collected for the current sentence as a Sentence.
+	fn finish(&mut self) {
+		self.previous = None;
+		if self.sentence.is_empty() {
+			return;
+		}
+		let tokens = std::mem::take(&mut self.sentence);
+		let doc = self.current();
+		let text = tokens.iter().map(|t| t.text.as_str()).collect::<Vec<_>>().join(" ");
+		if !doc.text.is_empty() {
+			doc.text.push(' ');
+		}
+		doc.text.push_str(&text);
+		doc.sentences.push(Sentence {
+			id: tokens[0].sentence_id,
+			text,
+			token_from: tokens.first().map(|t| t.id),
+			token_to: tokens.last().map(|t| t.id),
+			tokens: tokens.iter().map(|t| t.id).collect(),
+			..Default::default()
+		});
+		doc.token_list.extend(tokens);
+	}
+}
+
+/// returns the kind of a CoNLL-2003 line for checking the limits of the input.
+#[allow(dead_code)]
+pub(crate) fn line_kind(line: &str) -> Line<'_> {
+	let cols: Vec<&str> = line.split_whitespace().collect();
+	match cols.first() {
+		None => Line::Other,
+		Some(&DOCSTART) => Line::Document,
+		Some(_) => Line::Token(cols),
+	}
+}
+
+/// This function reads CoNLL-2003 named entity data into JSON-NLP. Every line contains a token, optionally
+/// followed by its part-of-speech and chunk tags, and ends with an IOB entity tag. Blank lines separate
+/// sentences and `-DOCSTART-` lines documents. Both IOB1 and IOB2 tagging are accepted: the tokens get
+/// IOB2 `entity_iob` tags and their `entity` type, and every tagged span is added as an Entity.
+pub fn from_conll2003(input: &str) -> Result<JSONNLP> {
+	let mut reader = Reader::default();
+	for (n, line) in input.lines().enumerate() {
+		let cols: Vec<&str> = line.split_whitespace().collect();
+		match cols.as_slice() {
+			[] => reader.finish(),
+			[DOCSTART, ..] => {
+				reader.finish();
+				reader.new_document();
+			}
+			[text, tag] => reader.token(text, "_", None, tag, n + 1)?,
+			[text, xpos, tag] => reader.token(text, xpos, None, tag, n + 1)?,
+			[text, xpos, chunk, .., tag] => reader.token(text, xpos, Some(chunk), tag, n + 1)?,
+			[_] => return Err(JsonNlpError::Format(format!("line {}: missing the entity tag", n + 1))),
+		}
+	}
+	reader.finish();
+	// documents of the -DOCSTART- lines without any tokens
+	reader.docs.retain(|d| !d.token_list.is_empty());
+	for (i, doc) in reader.docs.iter_mut().enumerate() {
+		doc.id = DocumentId(i as RawId + 1);
+	}
+
+	Ok(JSONNLP {
+		meta: Meta::new(),
+		docs: reader.docs,
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const IOB1: &str = "-DOCSTART- -X- -X- O\n\nEU NNP B-NP I-ORG\nrejects VBZ B-VP O\nPeter NNP B-NP I-PER\n\
+		Blackburn NNP I-NP I-PER\nJohn NNP B-NP B-PER\n\n-DOCSTART- -X- -X- O\n\nHi UH O\n";
+
+	#[test]
+	fn conll2003_round_trip() {
+		let j = from_conll2003(IOB1).expect("valid CoNLL-2003");
+		assert_eq!(j.docs.len(), 2);
+		let doc = &j.docs[0];
+		assert_eq!(doc.text, "EU rejects Peter Blackburn John");
+		let entities: Vec<_> =
+			doc.entities.iter().map(|e| (e.etype.as_str(), e.label.as_str(), e.tokens.clone())).collect();
+		assert_eq!(entities, [
+			("ORG", "EU", vec![TokenId(1)]),
+			("PER", "Peter Blackburn", vec![TokenId(3), TokenId(4)]),
+			("PER", "John", vec![TokenId(5)]),
+		]);
+		let iob: Vec<_> = doc.token_list.iter().map(|t| t.entity_iob.as_str()).collect();
+		assert_eq!(iob, ["B", "O", "B", "I", "B"]);
+		assert_eq!(doc.token_list[1].extra["chunk"], "B-VP");
+		assert_eq!(j.docs[1].token_list[0].xpos, "UH");
+
+		let out = to_conll2003(doc);
+		assert_eq!(out, "-DOCSTART- -X- -X- O\n\nEU NNP B-NP B-ORG\nrejects VBZ B-VP O\nPeter NNP B-NP B-PER\n\
+			Blackburn NNP I-NP I-PER\nJohn NNP B-NP B-PER\n\n");
+		assert_eq!(from_conll2003(&out).unwrap().docs[0], *doc);
+	}
+
+	#[test]
+	fn conll2003_errors() {
+		let e = from_conll2003("EU NNP B-NP X-ORG\n").unwrap_err();
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == "line 1: invalid IOB tag \"X-ORG\""), "{}", e);
+		assert!(from_conll2003("EU O\nrejects\n").is_err());
+		assert!(from_conll2003("EU B-\n").is_err());
+	}
+}
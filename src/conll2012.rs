@@ -0,0 +1,342 @@
+//! Import of the OntoNotes CoNLL-2012 format with coreference chains, semantic roles,
+//! named entities, and constituent parses into [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::{
+	Argument, ConstituentParse, Coreference, CoreferenceId, CoreferenceReferents, CoreferenceRepresentantive, Document,
+	DocumentId, Entity, EntityId, JsonNlpError, Meta, Predicate, PredicateId, RawId, Result, Sentence, SentenceId,
+	Token, TokenId, JSONNLP,
+};
+
+/// the number of columns before the predicate argument columns.
+const FIXED_COLUMNS: usize = 11;
+
+/// returns the labeled spans of a bracketed column like `(ARG0*`, `*`, `*)`, as labels with
+/// the indexes of their first and last row.
+fn spans<'a, I: Iterator<Item = &'a str>>(cells: I, line: usize) -> Result<Vec<(String, usize, usize)>> {
+	let mut open: Vec<(String, usize)> = Vec::new();
+	let mut spans = Vec::new();
+	for (i, cell) in cells.enumerate() {
+		let mut chars = cell.chars().peekable();
+		while let Some(c) = chars.next() {
+			match c {
+				'(' => {
+					let mut label = String::new();
+					while let Some(&c) = chars.peek() {
+						if c == '*' || c == '(' || c == ')' {
+							break;
+						}
+						label.push(c);
+						chars.next();
+					}
+					open.push((label, i));
+				}
+				')' => match open.pop() {
+					Some((label, start)) => spans.push((label, start, i)),
+					None => return Err(JsonNlpError::Format(format!("line {}: unbalanced bracket in {:?}", line + i, cell))),
+				},
+				_ => {}
+			}
+		}
+	}
+	if let Some((label, start)) = open.pop() {
+		return Err(JsonNlpError::Format(format!("line {}: span {:?} is not closed", line + start, label)));
+	}
+	spans.sort_by_key(|s| (s.1, s.2));
+	Ok(spans)
+}
+
+/// This struct collects the documents while reading CoNLL-2012.
+#[derive(Default)]
+struct Reader {
+	docs: Vec<Document>,
+	/// the columns of the rows of the current sentence, and the line of its first row.
+	rows: Vec<Vec<String>>,
+	line: usize,
+	/// the mentions of the coreference chains of the current document, in order of their first mention.
+	chains: Vec<(RawId, Vec<Vec<TokenId>>)>,
+	/// the first token of the open mentions of every chain.
+	open: HashMap<RawId, Vec<TokenId>>,
+}
+
+impl Reader {
+	fn current(&mut self) -> &mut Document {
+		if self.docs.is_empty() {
+			self.new_document(None, None);
+		}
+		self.docs.last_mut().expect("a document was just added")
+	}
+
+	fn new_document(&mut self, name: Option<&str>, part: Option<u64>) {
+		let mut doc = Document {
+			id: DocumentId(self.docs.len() as RawId + 1),
+			..Default::default()
+		};
+		if let Some(name) = name {
+			doc.meta.identifier = name.to_string();
+		}
+		if let Some(part) = part {
+			doc.extra.insert("part".to_string(), Value::from(part));
+		}
+		self.docs.push(doc);
+	}
+
+	/// adds the coreference chains of the current document.
+	fn finish_document(&mut self) {
+		let chains = std::mem::take(&mut self.chains);
+		self.open.clear();
+		if chains.is_empty() {
+			return;
+		}
+		let doc = self.current();
+		for (id, mentions) in chains {
+			let mut mentions = mentions.into_iter();
+			let representative = match mentions.next() {
+				Some(tokens) => tokens,
+				None => continue,
+			};
+			doc.coreferences.push(Coreference {
+				id: CoreferenceId(id),
+				representative: CoreferenceRepresentantive {
+					head: *representative.last().expect("mentions are not empty"),
+					tokens: representative,
+					..Default::default()
+				},
+				referents: mentions.map(|tokens| CoreferenceReferents {
+					head: *tokens.last().expect("mentions are not empty"),
+					tokens,
+					..Default::default()
+				}).collect(),
+				..Default::default()
+			});
+		}
+	}
+
+	/// adds the tokens and annotations of the rows collected for the current sentence.
+	fn finish_sentence(&mut self) -> Result<()> {
+		let rows = std::mem::take(&mut self.rows);
+		if rows.is_empty() {
+			return Ok(());
+		}
+		let line = self.line;
+		let columns = rows[0].len();
+		if let Some(i) = rows.iter().position(|r| r.len() != columns) {
+			return Err(JsonNlpError::Format(format!("line {}: expected {} columns, found {}", line + i, columns, rows[i].len())));
+		}
+
+		let doc = self.current();
+		let first = doc.token_list.len() as RawId + 1;
+		let sentence_id = SentenceId(doc.sentences.len() as RawId + 1);
+		let ids: Vec<TokenId> = (0..rows.len() as RawId).map(|i| TokenId(first + i)).collect();
+		let value = |s: &str| if s == "-" { String::new() } else { s.to_string() };
+
+		let mut bracketing = String::new();
+		for (r, id) in rows.iter().zip(&ids) {
+			let mut token = Token {
+				id: *id,
+				sentence_id,
+				text: r[3].clone(),
+				xpos: r[4].clone(),
+				lemma: value(&r[6]),
+				..Default::default()
+			};
+			if r[9] != "-" {
+				token.extra.insert("speaker".to_string(), Value::String(r[9].clone()));
+			}
+			if !bracketing.is_empty() {
+				bracketing.push(' ');
+			}
+			bracketing.push_str(&r[5].replace('*', &format!("({} {})", r[4], r[3])));
+			doc.token_list.push(token);
+		}
+		let text = rows.iter().map(|r| r[3].as_str()).collect::<Vec<_>>().join(" ");
+		if !doc.text.is_empty() {
+			doc.text.push(' ');
+		}
+		doc.text.push_str(&text);
+		doc.sentences.push(Sentence {
+			id: sentence_id,
+			text,
+			token_from: ids.first().copied(),
+			token_to: ids.last().copied(),
+			tokens: ids.clone(),
+			..Default::default()
+		});
+		if rows.iter().any(|r| r[5] != "-" && r[5] != "*") {
+			doc.constituents.push(ConstituentParse {
+				sentence_id,
+				labeled_bracketing: bracketing,
+				..Default::default()
+			});
+		}
+
+		for (label, start, end) in spans(rows.iter().map(|r| r[10].as_str()), line)? {
+			let id = EntityId(doc.entities.len() as RawId + 1);
+			doc.entities.push(Entity {
+				id,
+				label: rows[start..=end].iter().map(|r| r[3].as_str()).collect::<Vec<_>>().join(" "),
+				etype: label,
+				token_from: Some(ids[start]),
+				token_to: Some(ids[end]),
+				tokens: ids[start..=end].to_vec(),
+				..Default::default()
+			});
+		}
+
+		for column in FIXED_COLUMNS..columns - 1 {
+			let mut arguments = Vec::new();
+			let mut head = None;
+			for (label, start, end) in spans(rows.iter().map(|r| r[column].as_str()), line)? {
+				if label == "V" {
+					head = Some(start);
+				} else {
+					arguments.push(Argument {
+						label,
+						token_from: Some(ids[start]),
+						token_to: Some(ids[end]),
+						tokens: ids[start..=end].to_vec(),
+						..Default::default()
+					});
+				}
+			}
+			let head = match head {
+				Some(h) => h,
+				None => continue,
+			};
+			let lemma = value(&rows[head][6]);
+			let frame = if rows[head][7] == "-" { String::new() } else { format!("{}.{}", lemma, rows[head][7]) };
+			doc.semantic_roles.push(Predicate {
+				id: PredicateId(doc.semantic_roles.len() as RawId + 1),
+				sentence_id: Some(sentence_id),
+				head: ids[head],
+				lemma,
+				frame,
+				arguments,
+				..Default::default()
+			});
+		}
+
+		for (i, r) in rows.iter().enumerate() {
+			let cell = &r[columns - 1];
+			if cell == "-" {
+				continue;
+			}
+			for part in cell.split('|') {
+				let opens = part.starts_with('(');
+				let closes = part.ends_with(')');
+				let chain: RawId = part.trim_matches(|c| c == '(' || c == ')').parse().map_err(|_| {
+					JsonNlpError::Format(format!("line {}: invalid coreference {:?}", line + i, part))
+				})?;
+				if opens {
+					self.open.entry(chain).or_default().push(ids[i]);
+				}
+				if closes {
+					let start = self.open.get_mut(&chain).and_then(Vec::pop).ok_or_else(|| {
+						JsonNlpError::Format(format!("line {}: coreference {:?} is not opened", line + i, part))
+					})?;
+					let tokens: Vec<TokenId> = (start.0..=ids[i].0).map(TokenId).collect();
+					match self.chains.iter_mut().find(|c| c.0 == chain) {
+						Some(c) => c.1.push(tokens),
+						None => self.chains.push((chain, vec![tokens])),
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// This function reads [CoNLL-2012](https://conll.cemantix.org/2012/data.html) data as distributed with OntoNotes.
+/// Every `#begin document` starts a Document, with the document name as its `identifier` and the part number in
+/// its `extra` map. Words become tokens with their part-of-speech, predicate lemma, and speaker, the parse bits
+/// constituent parses, and the named entity column entities. Every predicate argument column becomes a Predicate
+/// of the semantic role layer with the `V` span as its head, and the coreference column fills the coreference
+/// chains, with the first mention of a chain as its representative and the last token of a mention as its head.
+pub fn from_conll2012(input: &str) -> Result<JSONNLP> {
+	let mut reader = Reader::default();
+	for (n, line) in input.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() {
+			reader.finish_sentence()?;
+		} else if let Some(header) = line.strip_prefix("#begin document") {
+			reader.finish_sentence()?;
+			reader.finish_document();
+			// e.g. `#begin document (bc/cctv/00/cctv_0001); part 000`
+			let (name, part) = match header.split_once(';') {
+				Some((name, part)) => (name, part.trim().strip_prefix("part").and_then(|p| p.trim().parse().ok())),
+				None => (header, None),
+			};
+			let name = name.trim().trim_start_matches('(').trim_end_matches(')');
+			reader.new_document(Some(name), part);
+		} else if line.starts_with("#end document") {
+			reader.finish_sentence()?;
+			reader.finish_document();
+		} else if !line.starts_with('#') {
+			let cols: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+			if cols.len() < FIXED_COLUMNS + 1 {
+				return Err(JsonNlpError::Format(format!(
+					"line {}: expected at least {} columns, found {}", n + 1, FIXED_COLUMNS + 1, cols.len()
+				)));
+			}
+			if reader.rows.is_empty() {
+				reader.line = n + 1;
+			}
+			reader.rows.push(cols);
+		}
+	}
+	reader.finish_sentence()?;
+	reader.finish_document();
+
+	Ok(JSONNLP {
+		meta: Meta::new(),
+		docs: reader.docs,
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CONLL: &str = "#begin document (bc/x); part 000\n\
+		bc/x 0 0 John NNP (TOP(S(NP*) - - - A (PERSON) (ARG0*) (0)\n\
+		bc/x 0 1 saw VBD (VP* see 01 - A * (V*) -\n\
+		bc/x 0 2 him PRP (NP*)) - - - A * (ARG1*) (0)\n\
+		bc/x 0 3 . . *)) - - - A * * -\n\
+		\n#end document\n";
+
+	#[test]
+	fn conll2012_import() {
+		let j = from_conll2012(CONLL).expect("valid CoNLL-2012");
+		let doc = &j.docs[0];
+		assert_eq!((doc.meta.identifier.as_str(), &doc.extra["part"]), ("bc/x", &Value::from(0)));
+		assert_eq!(doc.text, "John saw him .");
+		assert_eq!((doc.token_list[1].lemma.as_str(), doc.token_list[1].xpos.as_str()), ("see", "VBD"));
+		assert_eq!(doc.token_list[0].extra["speaker"], "A");
+		assert_eq!(doc.constituents[0].labeled_bracketing, "(TOP(S(NP(NNP John)) (VP(VBD saw) (NP(PRP him))) (. .)))");
+		assert_eq!((doc.entities[0].etype.as_str(), doc.entities[0].tokens.as_slice()), ("PERSON", &[TokenId(1)][..]));
+
+		let p = &doc.semantic_roles[0];
+		assert_eq!((p.head, p.lemma.as_str(), p.frame.as_str()), (TokenId(2), "see", "see.01"));
+		let arguments: Vec<_> = p.arguments.iter().map(|a| (a.label.as_str(), a.tokens.clone())).collect();
+		assert_eq!(arguments, [("ARG0", vec![TokenId(1)]), ("ARG1", vec![TokenId(3)])]);
+
+		let c = &doc.coreferences[0];
+		assert_eq!((c.representative.head, c.representative.tokens.as_slice()), (TokenId(1), &[TokenId(1)][..]));
+		assert_eq!(c.referents.iter().map(|r| r.head).collect::<Vec<_>>(), [TokenId(3)]);
+	}
+
+	#[test]
+	fn conll2012_errors() {
+		let e = from_conll2012(&CONLL.replace("(ARG1*)", "(ARG1*))")).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == "line 4: unbalanced bracket in \"(ARG1*))\""), "{}", e);
+		assert!(from_conll2012(&CONLL.replace("(ARG1*)", "(ARG1*")).is_err());
+		assert!(from_conll2012(&CONLL.replace(" (V*) -", " (V*)")).is_err());
+		assert!(from_conll2012(&CONLL.replace("(0)\n", "(x)\n")).is_err());
+		assert!(from_conll2012(&CONLL.replace("A * (ARG1*) (0)", "A * (ARG1*) 1)")).is_err());
+		assert!(from_conll2012("bc/x 0 0 John NNP\n").is_err());
+	}
+}
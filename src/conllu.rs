@@ -0,0 +1,614 @@
+//! Conversion between [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents and the
+//! [CoNLL-U](https://universaldependencies.org/format.html) format of Universal Dependencies,
+//! as well as the older CoNLL-X format of MaltParser, MSTParser, and similar parsers.
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::limits::Line;
+use crate::{
+	Dependency, DependencyTree, Document, DocumentId, Expression, ExpressionId, JsonNlpError, LanguageTag, Meta,
+	Paragraph, ParagraphId, RawId, Result, Sentence, SentenceId, Token, TokenFeatures, TokenId, JSONNLP,
+};
+
+/// This enum selects the variant of the 10-column CoNLL format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+	/// CoNLL-U with comments, UD features, and a MISC column.
+	U,
+	/// CoNLL-X with CPOSTAG/POSTAG, free-form features, and projective head columns.
+	X,
+}
+
+/// maps the numeric `number` feature to the UD `Number` values.
+const NUMBERS: &[(u8, &str)] = &[(1, "Sing"), (2, "Plur")];
+
+/// returns a CoNLL-U field: tabs and line breaks are replaced by spaces, and empty values by `_`.
+fn field(s: &str) -> String {
+	let s = s.trim();
+	if s.is_empty() {
+		"_".to_string()
+	} else {
+		s.replace(['\t', '\n', '\r'], " ")
+	}
+}
+
+/// returns a feature value with an upper case first letter, e.g. `Nom` for `nom`.
+fn feature_value(s: &str) -> String {
+	let mut chars = s.chars();
+	match chars.next() {
+		Some(c) => c.to_uppercase().chain(chars).collect(),
+		None => String::new(),
+	}
+}
+
+/// returns the FEATS column for the token features.
+pub(crate) fn feats(f: &TokenFeatures) -> String {
+	let mut feats: Vec<(String, String)> = Vec::new();
+	for (name, value) in &[("Case", &f.case), ("Gender", &f.gender), ("Mood", &f.mood), ("Tense", &f.tense)] {
+		if !value.is_empty() {
+			feats.push((name.to_string(), feature_value(value)));
+		}
+	}
+	if let Some(n) = f.number {
+		let value = NUMBERS.iter().find(|(k, _)| *k == n).map(|(_, v)| v.to_string()).unwrap_or_else(|| n.to_string());
+		feats.push(("Number".to_string(), value));
+	}
+	if let Some(p) = f.person {
+		feats.push(("Person".to_string(), p.to_string()));
+	}
+	if f.negated == Some(true) {
+		feats.push(("Polarity".to_string(), "Neg".to_string()));
+	}
+	if f.foreign == Some(true) {
+		feats.push(("Foreign".to_string(), "Yes".to_string()));
+	}
+	// features of other sources, e.g. imported from CoNLL-U
+	for (name, value) in &f.extra {
+		if let Some(value) = value.as_str() {
+			if !feats.iter().any(|(n, _)| n == name) {
+				feats.push((name.clone(), value.to_string()));
+			}
+		}
+	}
+	if feats.is_empty() {
+		return "_".to_string();
+	}
+	feats.sort_by_key(|(name, _)| name.to_lowercase());
+	feats.iter().map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<_>>().join("|")
+}
+
+impl Document {
+	/// returns the tokens of every sentence in document order. Sentences take their tokens
+	/// from `tokens`, else from the `tokenFrom`/`tokenTo` span, else from the tokens carrying
+	/// their sentence ID. Without a sentence layer the tokens are grouped by their sentence ID.
+	pub(crate) fn sentence_groups(&self) -> Vec<(SentenceId, &str, Vec<&Token>)> {
+		if self.sentences.is_empty() {
+			let mut groups: Vec<(SentenceId, &str, Vec<&Token>)> = Vec::new();
+			for t in &self.token_list {
+				match groups.iter_mut().find(|g| g.0 == t.sentence_id) {
+					Some(g) => g.2.push(t),
+					None => groups.push((t.sentence_id, "", vec![t])),
+				}
+			}
+			return groups;
+		}
+		let by_id: HashMap<TokenId, &Token> = self.token_list.iter().map(|t| (t.id, t)).collect();
+		self.sentences.iter().map(|s| {
+			let tokens = if !s.tokens.is_empty() {
+				s.tokens.iter().filter_map(|id| by_id.get(id).copied()).collect()
+			} else if let (Some(from), Some(to)) = (s.token_from, s.token_to) {
+				self.token_list.iter().filter(|t| t.id >= from && t.id <= to).collect()
+			} else {
+				self.token_list.iter().filter(|t| t.sentence_id == s.id).collect()
+			};
+			(s.id, s.text.as_str(), tokens)
+		}).collect()
+	}
+}
+
+/// This function converts a Document to [CoNLL-U](https://universaldependencies.org/format.html).
+/// Every sentence starts with `# sent_id` and `# text` comments, and tokens are numbered from 1
+/// within their sentence. HEAD and DEPREL are taken from the dependency trees, with 0 for the root,
+/// and the token features are written as UD FEATS. A `spaceAfter` of false becomes `SpaceAfter=No`,
+/// followed by the `misc` string of the token's `extra` map, and its `deps` string becomes DEPS.
+/// Expressions of type `mwt` are written as multiword token range lines with their `text` as form.
+pub fn to_conllu(doc: &Document) -> String {
+	write(doc, Dialect::U)
+}
+
+/// This function converts a Document to CoNLL-X, with `upos` in the CPOSTAG and `xpos` in the POSTAG
+/// column. Sentences are separated by blank lines without comments, and PHEAD and PDEPREL are left empty.
+/// Features read from CoNLL-X are written back unchanged, other token features as `Name=Value` pairs.
+pub fn to_conllx(doc: &Document) -> String {
+	write(doc, Dialect::X)
+}
+
+fn write(doc: &Document, dialect: Dialect) -> String {
+	let mut deps: HashMap<TokenId, &Dependency> = HashMap::new();
+	for tree in &doc.dependency_trees {
+		for d in &tree.dependencies {
+			deps.entry(d.dep).or_insert(d);
+		}
+	}
+
+	// the multiword tokens by their first word, with their last word
+	let mut mwts: HashMap<TokenId, (TokenId, &Expression)> = HashMap::new();
+	for e in doc.expressions.iter().filter(|e| e.etype == "mwt" && dialect == Dialect::U) {
+		let first = e.tokens.first().copied().or(e.token_from);
+		let last = e.tokens.last().copied().or(e.token_to);
+		if let (Some(first), Some(last)) = (first, last) {
+			mwts.entry(first).or_insert((last, e));
+		}
+	}
+
+	let mut out = String::new();
+	if dialect == Dialect::U {
+		out.push_str(&format!("# newdoc id = {}\n", doc.id));
+	}
+	for (sentence_id, text, tokens) in doc.sentence_groups() {
+		let index: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i + 1)).collect();
+		// the multiword tokens of the sentence by the number of their first word, with the number of their last
+		let mut ranges: HashMap<usize, (usize, &Expression)> = HashMap::new();
+		for (i, t) in tokens.iter().enumerate() {
+			if let Some((last, e)) = mwts.get(&t.id) {
+				if let Some(end) = index.get(last).filter(|end| **end > i + 1) {
+					ranges.insert(i + 1, (*end, *e));
+				}
+			}
+		}
+		let form = |e: &Expression| e.extra.get("text").and_then(Value::as_str).unwrap_or_default().to_string();
+		let text = if text.is_empty() {
+			let mut s = String::new();
+			let mut i = 0;
+			while i < tokens.len() {
+				let (end, text) = match ranges.get(&(i + 1)) {
+					Some((end, e)) => (*end, form(e)),
+					None => (i + 1, tokens[i].text.clone()),
+				};
+				s.push_str(&text);
+				if tokens[end - 1].features().spaceafter != Some(false) {
+					s.push(' ');
+				}
+				i = end;
+			}
+			s
+		} else {
+			text.to_string()
+		};
+		if dialect == Dialect::U {
+			out.push_str(&format!("# sent_id = {}\n", sentence_id));
+			out.push_str(&format!("# text = {}\n", text.trim().replace(['\n', '\r'], " ")));
+		}
+		// the last word of the multiword token being written
+		let mut mwt_end = None;
+		for (i, t) in tokens.iter().enumerate() {
+			if let Some((end, e)) = ranges.get(&(i + 1)) {
+				let space_after = tokens[end - 1].features().spaceafter != Some(false);
+				let misc = misc(&e.extra, space_after);
+				out.push_str(&format!("{}-{}\t{}\t_\t_\t_\t_\t_\t_\t_\t{}\n", i + 1, end, field(&form(e)), misc));
+				mwt_end = Some(*end);
+			}
+			// the SpaceAfter of the words of a multiword token is written on its range line
+			let in_mwt = mwt_end.is_some_and(|end| i < end);
+			let (head, deprel) = match deps.get(&t.id) {
+				Some(d) if d.is_root() => ("0".to_string(), field(&d.lab)),
+				Some(d) => match index.get(&d.gov) {
+					Some(h) => (h.to_string(), field(&d.lab)),
+					None => ("_".to_string(), "_".to_string()),
+				},
+				None => ("_".to_string(), "_".to_string()),
+			};
+			let (features, enhanced, misc) = match dialect {
+				Dialect::U => (
+					feats(t.features()),
+					field(t.extra.get("deps").and_then(Value::as_str).unwrap_or_default()),
+					misc(&t.extra, in_mwt || t.features().spaceafter != Some(false)),
+				),
+				Dialect::X => match t.extra.get("feats").and_then(Value::as_str) {
+					Some(f) => (field(f), "_".to_string(), "_".to_string()),
+					None => (feats(t.features()), "_".to_string(), "_".to_string()),
+				},
+			};
+			out.push_str(&format!(
+				"{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+				i + 1,
+				field(&t.text),
+				field(&t.lemma),
+				field(&t.upos),
+				field(&t.xpos),
+				features,
+				head,
+				deprel,
+				enhanced,
+				misc
+			));
+		}
+		out.push('\n');
+	}
+	out
+}
+
+/// returns the MISC column of the `misc` string of an `extra` map, preceded by `SpaceAfter=No` if no space follows.
+fn misc(extra: &Map<String, Value>, space_after: bool) -> String {
+	let mut items = Vec::new();
+	if !space_after {
+		items.push("SpaceAfter=No");
+	}
+	if let Some(m) = extra.get("misc").and_then(Value::as_str) {
+		items.extend(m.split('|').filter(|m| !m.is_empty() && *m != "_"));
+	}
+	field(&items.join("|"))
+}
+
+/// returns the token features for a FEATS column. Features without a JSON-NLP counterpart
+/// are kept as strings in `extra`.
+pub(crate) fn parse_feats(feats: &str) -> TokenFeatures {
+	let mut f = TokenFeatures::default();
+	if feats == "_" {
+		return f;
+	}
+	for feat in feats.split('|') {
+		let (name, value) = match feat.split_once('=') {
+			Some(nv) => nv,
+			None => continue,
+		};
+		match name {
+			"Case" => f.case = value.to_string(),
+			"Gender" => f.gender = value.to_string(),
+			"Mood" => f.mood = value.to_string(),
+			"Tense" => f.tense = value.to_string(),
+			"Number" if NUMBERS.iter().any(|(_, v)| *v == value) => {
+				f.number = NUMBERS.iter().find(|(_, v)| *v == value).map(|(k, _)| *k)
+			}
+			"Person" if value.parse::<u8>().is_ok() => f.person = value.parse().ok(),
+			"Polarity" if value == "Neg" => f.negated = Some(true),
+			"Foreign" if value == "Yes" => f.foreign = Some(true),
+			_ => {
+				f.extra.insert(name.to_string(), Value::String(value.to_string()));
+			}
+		}
+	}
+	f
+}
+
+/// This struct collects the annotations of the current sentence while reading CoNLL-U.
+#[derive(Default)]
+struct SentenceReader {
+	sentence: Sentence,
+	id: Option<RawId>,
+	tokens: Vec<Token>,
+	heads: Vec<(String, String)>,
+	/// the multiword tokens by the sentence-internal IDs of their first and last word, with their form and MISC.
+	mwts: Vec<(RawId, RawId, String, String)>,
+	line: usize,
+}
+
+/// This struct collects the documents while reading CoNLL-U.
+struct DocumentReader {
+	docs: Vec<Document>,
+	next_token: RawId,
+	next_sentence: RawId,
+	new_paragraph: bool,
+	dialect: Dialect,
+}
+
+impl DocumentReader {
+	fn current(&mut self) -> &mut Document {
+		if self.docs.is_empty() {
+			self.new_document(None);
+		}
+		self.docs.last_mut().expect("a document was just added")
+	}
+
+	fn new_document(&mut self, id: Option<&str>) {
+		let index = self.docs.len() as RawId + 1;
+		let mut doc = Document {
+			id: DocumentId(id.and_then(|id| id.parse().ok()).unwrap_or(index)),
+			..Default::default()
+		};
+		if let Some(id) = id {
+			doc.meta.identifier = id.to_string();
+		}
+		self.docs.push(doc);
+		self.next_token = 1;
+		self.next_sentence = 1;
+	}
+
+	/// adds a completed sentence with its tokens and dependency tree to the current document.
+	fn finish(&mut self, mut s: SentenceReader) -> Result<()> {
+		if s.tokens.is_empty() {
+			return Ok(());
+		}
+		let first = s.tokens[0].id.0;
+		let mut tree = DependencyTree {
+			sentence_id: Some(s.sentence.id),
+			style: if self.dialect == Dialect::U { "universal".to_string() } else { String::new() },
+			..Default::default()
+		};
+		for (t, (head, deprel)) in s.tokens.iter().zip(&s.heads) {
+			if head == "_" {
+				continue;
+			}
+			let head: RawId = head.parse().map_err(|_| {
+				JsonNlpError::Format(format!("line {}: invalid HEAD {:?} of token {:?}", s.line, head, t.text))
+			})?;
+			if head as usize > s.tokens.len() {
+				return Err(JsonNlpError::Format(format!("line {}: HEAD {} of token {:?} is not in the sentence", s.line, head, t.text)));
+			}
+			let gov = if head == 0 { 0 } else { first + head - 1 };
+			tree.dependencies.push(Dependency {
+				lab: if deprel == "_" { String::new() } else { deprel.clone() },
+				gov: TokenId(gov),
+				dep: t.id,
+				..Default::default()
+			});
+		}
+		let mut mwts = Vec::new();
+		for (from, to, form, misc) in &s.mwts {
+			if *from < 1 || from > to || *to as usize > s.tokens.len() {
+				let msg = format!("line {}: multiword token {}-{} is not in the sentence", s.line, from, to);
+				return Err(JsonNlpError::Format(msg));
+			}
+			let tokens: Vec<TokenId> = (*from..=*to).map(|id| TokenId(first + id - 1)).collect();
+			let mut expression = Expression {
+				etype: "mwt".to_string(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			};
+			expression.extra.insert("text".to_string(), Value::String(form.clone()));
+			let mut rest = Vec::new();
+			for item in misc.split('|').filter(|m| *m != "_") {
+				if item == "SpaceAfter=No" {
+					s.tokens[*to as usize - 1].features_mut().spaceafter = Some(false);
+				} else {
+					rest.push(item);
+				}
+			}
+			if !rest.is_empty() {
+				expression.extra.insert("misc".to_string(), Value::String(rest.join("|")));
+			}
+			mwts.push(expression);
+		}
+		s.sentence.tokens = s.tokens.iter().map(|t| t.id).collect();
+		s.sentence.token_from = s.sentence.tokens.first().copied();
+		s.sentence.token_to = s.sentence.tokens.last().copied();
+
+		let new_paragraph = std::mem::replace(&mut self.new_paragraph, false);
+		let doc = self.current();
+		if new_paragraph {
+			let id = ParagraphId(doc.paragraphs.len() as RawId + 1);
+			doc.paragraphs.push(Paragraph { id, ..Default::default() });
+		}
+		if let Some(p) = doc.paragraphs.last_mut() {
+			p.sentences.push(s.sentence.id);
+			p.tokens.extend(s.sentence.tokens.iter().copied());
+			p.token_from = p.tokens.first().copied();
+			p.token_to = p.tokens.last().copied();
+		}
+		if !s.sentence.text.is_empty() {
+			if !doc.text.is_empty() {
+				doc.text.push(' ');
+			}
+			doc.text.push_str(&s.sentence.text);
+		}
+		if !tree.dependencies.is_empty() {
+			doc.dependency_trees.push(tree);
+		}
+		for mut expression in mwts {
+			expression.id = ExpressionId(doc.expressions.len() as RawId + 1);
+			doc.expressions.push(expression);
+		}
+		doc.token_list.append(&mut s.tokens);
+		doc.sentences.push(s.sentence);
+		Ok(())
+	}
+}
+
+/// This function reads [CoNLL-U](https://universaldependencies.org/format.html) into JSON-NLP.
+/// Every sentence becomes a Sentence with a dependency tree, and its words are added to the
+/// token list with IDs numbered through the document. `# newdoc` and `# newpar` comments start
+/// new documents and paragraphs, `# sent_id` and `# text` set the sentence ID and text, and the
+/// Dublin Core comments `# title`, `# author`, `# source`, `# date`, and `# language` set the document
+/// metadata. Other comments are kept in the sentence `extra` map. DEPS and MISC other than
+/// `SpaceAfter=No` are kept in the token's `extra` map as `deps` and `misc`. Multiword tokens become
+/// Expressions of type `mwt` with their form as `text` and their MISC as `misc` in the `extra` map,
+/// and empty nodes are skipped.
+pub fn from_conllu(input: &str) -> Result<JSONNLP> {
+	read(input, Dialect::U)
+}
+
+/// This function reads CoNLL-X as written by MaltParser, MSTParser, and other legacy parsers.
+/// CPOSTAG becomes `upos`, POSTAG `xpos`, and every sentence a Sentence with a dependency tree.
+/// `Name=Value` features are mapped as in from_conllu(), while the original FEATS column is kept
+/// in the token's `extra` map as `feats`. The PHEAD and PDEPREL columns are ignored.
+pub fn from_conllx(input: &str) -> Result<JSONNLP> {
+	read(input, Dialect::X)
+}
+
+/// returns the kind of a CoNLL-U or CoNLL-X line for checking the limits of the input.
+#[allow(dead_code)]
+pub(crate) fn line_kind(line: &str) -> Line<'_> {
+	if line.trim().is_empty() {
+		return Line::Other;
+	}
+	match line.strip_prefix('#').map(|c| c.split_once('=').map_or((c.trim(), ""), |(k, v)| (k.trim(), v.trim()))) {
+		Some(("newdoc", _)) | Some(("newdoc id", _)) => Line::Document,
+		Some((_, value)) => Line::Text(value),
+		None => Line::Token(line.split('\t').collect()),
+	}
+}
+
+fn read(input: &str, dialect: Dialect) -> Result<JSONNLP> {
+	let mut reader = DocumentReader { docs: Vec::new(), next_token: 1, next_sentence: 1, new_paragraph: false, dialect };
+	let mut s = SentenceReader::default();
+	for (n, line) in input.lines().enumerate() {
+		let line = line.trim_end_matches('\r');
+		if line.trim().is_empty() {
+			reader.finish(std::mem::take(&mut s))?;
+			continue;
+		}
+		if s.line == 0 {
+			s.line = n + 1;
+		}
+		if let Some(comment) = line.strip_prefix('#') {
+			let (key, value) = match comment.split_once('=') {
+				Some((k, v)) => (k.trim(), Some(v.trim())),
+				None => (comment.trim(), None),
+			};
+			match (key, value) {
+				("newdoc", _) | ("newdoc id", _) => {
+					reader.finish(std::mem::take(&mut s))?;
+					reader.new_document(value);
+					s.line = n + 1;
+				}
+				("newpar", _) | ("newpar id", _) => reader.new_paragraph = true,
+				("sent_id", Some(v)) => {
+					if let Ok(id) = v.parse() {
+						s.id = Some(id);
+					} else {
+						s.sentence.extra.insert("sent_id".to_string(), Value::String(v.to_string()));
+					}
+				}
+				("text", Some(v)) => s.sentence.text = v.to_string(),
+				(key, Some(v)) => {
+					let meta: &mut Meta = &mut reader.current().meta;
+					match key {
+						"title" => meta.title = v.to_string(),
+						"author" => meta.author = v.to_string(),
+						"source" => meta.source = v.to_string(),
+						"date" => meta.date = v.to_string(),
+						"language" | "lang" => meta.language = LanguageTag::new(v)?,
+						_ => {
+							s.sentence.extra.insert(key.to_string(), Value::String(v.to_string()));
+						}
+					}
+				}
+				(_, None) => {}
+			}
+			continue;
+		}
+
+		let cols: Vec<&str> = line.split('\t').collect();
+		if cols.len() != 10 {
+			return Err(JsonNlpError::Format(format!("line {}: expected 10 columns, found {}", n + 1, cols.len())));
+		}
+		if let Some((from, to)) = cols[0].split_once('-') {
+			if dialect == Dialect::U {
+				let range = from.parse().and_then(|from| Ok((from, to.parse()?))).map_err(|_| {
+					JsonNlpError::Format(format!("line {}: invalid multiword token ID {:?}", n + 1, cols[0]))
+				})?;
+				s.mwts.push((range.0, range.1, cols[1].to_string(), cols[9].to_string()));
+			}
+			continue;
+		}
+		// empty nodes
+		if cols[0].contains('.') {
+			continue;
+		}
+		if s.tokens.is_empty() {
+			// creates the first document before its token and sentence numbering is used
+			reader.current();
+			let id = s.id.unwrap_or(reader.next_sentence);
+			s.sentence.id = SentenceId(id);
+			reader.next_sentence = id + 1;
+		}
+		let value = |c: &str| if c == "_" { String::new() } else { c.to_string() };
+		let mut features = parse_feats(cols[5]);
+		let mut misc = Vec::new();
+		for item in cols[9].split('|').filter(|m| *m != "_" && dialect == Dialect::U) {
+			if item == "SpaceAfter=No" {
+				features.spaceafter = Some(false);
+			} else {
+				misc.push(item);
+			}
+		}
+		let mut token = Token {
+			id: TokenId(reader.next_token),
+			sentence_id: s.sentence.id,
+			text: cols[1].to_string(),
+			lemma: value(cols[2]),
+			upos: value(cols[3]),
+			xpos: value(cols[4]),
+			features: features.boxed(),
+			..Default::default()
+		};
+		if !misc.is_empty() {
+			token.extra.insert("misc".to_string(), Value::String(misc.join("|")));
+		}
+		if dialect == Dialect::U && cols[8] != "_" {
+			token.extra.insert("deps".to_string(), Value::String(cols[8].to_string()));
+		}
+		if dialect == Dialect::X && cols[5] != "_" {
+			token.extra.insert("feats".to_string(), Value::String(cols[5].to_string()));
+		}
+		reader.next_token += 1;
+		s.tokens.push(token);
+		s.heads.push((cols[6].to_string(), cols[7].to_string()));
+	}
+	reader.finish(s)?;
+
+	Ok(JSONNLP {
+		meta: Meta::new(),
+		docs: reader.docs,
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SENTENCE: &str = "# newdoc id = 1
+# sent_id = 1
+# text = ¡Vámonos al mar, Ana!
+1	¡	¡	PUNCT	_	_	2	punct	_	SpaceAfter=No
+2-3	Vámonos	_	_	_	_	_	_	_	_
+2	Vamos	ir	VERB	_	Mood=Imp|Person=1	0	root	0:root	_
+3	nos	nosotros	PRON	_	Case=Acc	2	obj	2:obj	_
+4-5	al	_	_	_	_	_	_	_	_
+4	a	a	ADP	_	_	6	case	6:case	_
+5	el	el	DET	_	_	6	det	6:det	_
+6	mar	mar	NOUN	_	_	2	obl	2:obl:a	SpaceAfter=No|Gloss=sea
+7	,	,	PUNCT	_	_	8	punct	8:punct	_
+8	Ana	Ana	PROPN	_	_	2	vocative	2:vocative	SpaceAfter=No
+9	!	!	PUNCT	_	_	2	punct	2:punct	Gloss=hi
+
+";
+
+	#[test]
+	fn conllu_round_trip() {
+		let j = from_conllu(SENTENCE).expect("valid CoNLL-U");
+		let doc = &j.docs[0];
+		assert_eq!(doc.token_list.len(), 9);
+		assert_eq!(doc.token_list[8].extra.get("misc"), Some(&Value::String("Gloss=hi".to_string())));
+		assert_eq!(doc.token_list[7].extra.get("deps"), Some(&Value::String("2:vocative".to_string())));
+		let mwts: Vec<&str> = doc.expressions.iter().filter(|e| e.etype == "mwt")
+			.filter_map(|e| e.extra.get("text").and_then(Value::as_str))
+			.collect();
+		assert_eq!(mwts, ["Vámonos", "al"]);
+		assert_eq!(to_conllu(doc), SENTENCE);
+	}
+
+	#[test]
+	fn conllu_text_from_multiword_tokens() {
+		let input = "1-2\tdel\t_\t_\t_\t_\t_\t_\t_\tSpaceAfter=No\n1\tde\tde\tADP\t_\t_\t0\troot\t_\t_\n\
+			2\tel\tel\tDET\t_\t_\t1\tdet\t_\t_\n3\t.\t.\tPUNCT\t_\t_\t1\tpunct\t_\t_\n\n";
+		let doc = &from_conllu(input).expect("valid CoNLL-U").docs[0];
+		let output = to_conllu(doc);
+		assert!(output.contains("# text = del.\n"));
+		assert!(output.ends_with(input));
+	}
+
+	#[test]
+	fn conllx_round_trip() {
+		let input = "1\tThe\tthe\tDT\tDT\tdet=def\t2\tNMOD\t_\t_\n2\tdog\tdog\tNN\tNN\t_\t3\tSBJ\t_\t_\n\
+			3\tbarks\tbark\tVB\tVBZ\t_\t0\tROOT\t_\t_\n\n";
+		let doc = &from_conllx(input).expect("valid CoNLL-X").docs[0];
+		assert_eq!(doc.dependency_trees[0].dependencies.len(), 3);
+		assert_eq!(to_conllx(doc), input);
+	}
+}
@@ -0,0 +1,331 @@
+//! Typed constituent trees, parsed from and serialized to Penn Treebank labeled bracketing.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ConstituentParse, Document, JsonNlpError, Result, TokenId};
+
+/// This struct contains a node of a constituent tree. Phrases and part-of-speech tags have a label and
+/// children, and terminals have the word as label, no children, and the ID of their token once aligned.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct ConstituentTree {
+	pub label: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub token: Option<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub children: Vec<ConstituentTree>,
+}
+
+/// This enum contains the lexical units of a labeled bracketing.
+enum Bracket<'a> {
+	Open,
+	Close,
+	Atom(&'a str),
+}
+
+/// returns the brackets and atoms of a labeled bracketing.
+fn brackets(s: &str) -> Vec<Bracket<'_>> {
+	let mut out = Vec::new();
+	let mut start = None;
+	for (i, c) in s.char_indices() {
+		if c == '(' || c == ')' || c.is_whitespace() {
+			if let Some(b) = start.take() {
+				out.push(Bracket::Atom(&s[b..i]));
+			}
+			match c {
+				'(' => out.push(Bracket::Open),
+				')' => out.push(Bracket::Close),
+				_ => {}
+			}
+		} else if start.is_none() {
+			start = Some(i);
+		}
+	}
+	if let Some(b) = start {
+		out.push(Bracket::Atom(&s[b..]));
+	}
+	out
+}
+
+/// This struct is an iterator over the nodes of a constituent tree in preorder, see ConstituentTree::preorder().
+#[derive(Debug, Clone)]
+pub struct ConstituentPreorder<'a> {
+	stack: Vec<&'a ConstituentTree>,
+}
+
+impl<'a> Iterator for ConstituentPreorder<'a> {
+	type Item = &'a ConstituentTree;
+
+	fn next(&mut self) -> Option<&'a ConstituentTree> {
+		let node = self.stack.pop()?;
+		self.stack.extend(node.children.iter().rev());
+		Some(node)
+	}
+}
+
+impl ConstituentTree {
+	/// returns the tree of a Penn Treebank labeled bracketing like `(ROOT (S (NP (DT The) (NN dog)) (VP (VBZ barks))))`.
+	/// The unlabeled outer brackets of treebank files like `( (S ...) )` become a root with an empty label.
+	/// Escaped brackets like `-LRB-` are kept as they are.
+	pub fn parse(bracketing: &str) -> Result<ConstituentTree> {
+		let error = |msg: &str| JsonNlpError::Format(format!("labeled bracketing {:?}: {}", bracketing, msg));
+		let brackets = brackets(bracketing);
+		let mut stack: Vec<ConstituentTree> = Vec::new();
+		let mut root = None;
+		let mut i = 0;
+		while i < brackets.len() {
+			if root.is_some() {
+				return Err(error("text after the tree"));
+			}
+			match brackets[i] {
+				Bracket::Open => {
+					let mut node = ConstituentTree::default();
+					if let Some(Bracket::Atom(label)) = brackets.get(i + 1) {
+						node.label = label.to_string();
+						i += 1;
+					}
+					stack.push(node);
+				}
+				Bracket::Close => {
+					let node = stack.pop().ok_or_else(|| error("unbalanced closing bracket"))?;
+					match stack.last_mut() {
+						Some(parent) => parent.children.push(node),
+						None => root = Some(node),
+					}
+				}
+				Bracket::Atom(word) => match stack.last_mut() {
+					Some(parent) => parent.children.push(ConstituentTree { label: word.to_string(), ..Default::default() }),
+					None => return Err(error("terminal outside of brackets")),
+				},
+			}
+			i += 1;
+		}
+		if !stack.is_empty() {
+			return Err(error("unbalanced opening bracket"));
+		}
+		root.ok_or_else(|| error("no tree"))
+	}
+
+	/// returns the Penn Treebank labeled bracketing of the tree, with brackets in words escaped as `-LRB-` and `-RRB-`.
+	pub fn to_bracketing(&self) -> String {
+		let mut out = String::new();
+		self.write_bracketing(&mut out);
+		out
+	}
+
+	fn write_bracketing(&self, out: &mut String) {
+		if self.is_terminal() {
+			out.push_str(&self.label.replace('(', "-LRB-").replace(')', "-RRB-"));
+			return;
+		}
+		out.push('(');
+		out.push_str(&self.label);
+		for c in &self.children {
+			out.push(' ');
+			c.write_bracketing(out);
+		}
+		out.push(')');
+	}
+
+	/// checks whether the node is a terminal, i.e. a word without children.
+	pub fn is_terminal(&self) -> bool {
+		self.children.is_empty()
+	}
+
+	/// checks whether the node is a part-of-speech tag, i.e. has exactly one child, which is a terminal.
+	pub fn is_preterminal(&self) -> bool {
+		self.children.len() == 1 && self.children[0].is_terminal()
+	}
+
+	/// returns the terminals of the tree from left to right.
+	pub fn leaves(&self) -> Vec<&ConstituentTree> {
+		let mut leaves = Vec::new();
+		let mut stack = vec![self];
+		while let Some(node) = stack.pop() {
+			if node.is_terminal() {
+				leaves.push(node);
+			}
+			stack.extend(node.children.iter().rev());
+		}
+		leaves
+	}
+
+	/// returns the words of the terminals from left to right.
+	pub fn words(&self) -> Vec<&str> {
+		self.leaves().into_iter().map(|l| l.label.as_str()).collect()
+	}
+
+	/// returns the part-of-speech tags with their words from left to right.
+	pub fn pos_tags(&self) -> Vec<(&str, &str)> {
+		let mut tags = Vec::new();
+		let mut stack = vec![self];
+		while let Some(node) = stack.pop() {
+			if node.is_preterminal() {
+				tags.push((node.label.as_str(), node.children[0].label.as_str()));
+			} else {
+				stack.extend(node.children.iter().rev());
+			}
+		}
+		tags
+	}
+
+	/// returns the number of nodes on the longest path from the node to a terminal, 1 for a terminal.
+	pub fn height(&self) -> usize {
+		1 + self.children.iter().map(ConstituentTree::height).max().unwrap_or(0)
+	}
+
+	/// assigns the token IDs to the terminals from left to right, and returns an error if their numbers differ.
+	pub fn align_tokens(&mut self, tokens: &[TokenId]) -> Result<()> {
+		let mut terminals = Vec::new();
+		let mut stack = vec![self];
+		while let Some(node) = stack.pop() {
+			if node.is_terminal() {
+				terminals.push(node);
+			} else {
+				stack.extend(node.children.iter_mut().rev());
+			}
+		}
+		if terminals.len() != tokens.len() {
+			return Err(JsonNlpError::Format(format!("the tree has {} terminals for {} tokens", terminals.len(), tokens.len())));
+		}
+		for (terminal, id) in terminals.into_iter().zip(tokens) {
+			terminal.token = Some(*id);
+		}
+		Ok(())
+	}
+
+	/// returns an iterator over the nodes of the tree in preorder, i.e. every node before its children and the
+	/// children from left to right, starting with the node itself.
+	pub fn preorder(&self) -> ConstituentPreorder<'_> {
+		ConstituentPreorder { stack: vec![self] }
+	}
+
+	/// returns the token IDs of the terminals from left to right, skipping terminals that are not aligned to tokens.
+	pub fn terminals(&self) -> Vec<TokenId> {
+		self.preorder().filter_map(|n| n.token).collect()
+	}
+
+	/// returns the token IDs of the first and last aligned terminals of the node, or None if it has none.
+	pub fn token_span(&self) -> Option<(TokenId, TokenId)> {
+		let terminals = self.terminals();
+		Some((*terminals.first()?, *terminals.last()?))
+	}
+
+	/// returns the lowest phrase or part-of-speech tag whose terminals include all aligned terminals with token IDs
+	/// from `from` to `to`, or None if there are no such terminals.
+	pub fn lowest_constituent_covering(&self, from: TokenId, to: TokenId) -> Option<&ConstituentTree> {
+		let count = |node: &ConstituentTree| node.terminals().into_iter().filter(|t| *t >= from && *t <= to).count();
+		let n = count(self);
+		if n == 0 || self.is_terminal() {
+			return None;
+		}
+		let mut node = self;
+		while let Some(child) = node.children.iter().find(|c| !c.is_terminal() && count(c) == n) {
+			node = child;
+		}
+		Some(node)
+	}
+
+	/// returns the phrases and part-of-speech tags with the label in preorder. A label also matches labels with
+	/// function tags or indices like `NP-SBJ` or `NP=2`.
+	pub fn find_all(&self, label: &str) -> Vec<&ConstituentTree> {
+		self.preorder()
+			.filter(|n| !n.is_terminal())
+			.filter(|n| match n.label.strip_prefix(label) {
+				Some("") => true,
+				Some(rest) => !label.is_empty() && (rest.starts_with('-') || rest.starts_with('=')),
+				None => false,
+			})
+			.collect()
+	}
+}
+
+impl FromStr for ConstituentTree {
+	type Err = JsonNlpError;
+
+	fn from_str(s: &str) -> Result<Self> {
+		ConstituentTree::parse(s)
+	}
+}
+
+impl fmt::Display for ConstituentTree {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.to_bracketing())
+	}
+}
+
+impl ConstituentParse {
+	/// returns the tree of the labeled bracketing, without token IDs, see Document::constituent_tree().
+	pub fn tree(&self) -> Result<ConstituentTree> {
+		ConstituentTree::parse(&self.labeled_bracketing)
+	}
+}
+
+impl Document {
+	/// returns the tree of a constituent parse with its terminals aligned to the tokens of its sentence,
+	/// or an error if the bracketing is malformed or has another number of terminals than the sentence has tokens.
+	pub fn constituent_tree(&self, parse: &ConstituentParse) -> Result<ConstituentTree> {
+		let mut tree = parse.tree()?;
+		let tokens: Vec<TokenId> = self.sentence_groups().into_iter()
+			.find(|g| g.0 == parse.sentence_id)
+			.map(|g| g.2.iter().map(|t| t.id).collect())
+			.unwrap_or_default();
+		tree.align_tokens(&tokens)?;
+		Ok(tree)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const BRACKETING: &str = "(ROOT (S (NP-SBJ (DT The) (NN dog)) (VP (VBD saw) (NP (DT a) (NN cat)))))";
+
+	fn tree() -> ConstituentTree {
+		let doc: Document = serde_json::from_str(r#"{"meta":{},"id":1,"tokenList":[
+			{"id":1,"sentence_id":1,"text":"The","lemma":"the"},{"id":2,"sentence_id":1,"text":"dog","lemma":"dog"},
+			{"id":3,"sentence_id":1,"text":"saw","lemma":"see"},{"id":4,"sentence_id":1,"text":"a","lemma":"a"},
+			{"id":5,"sentence_id":1,"text":"cat","lemma":"cat"}]}"#)
+		.unwrap();
+		let parse = ConstituentParse {
+			sentence_id: crate::SentenceId(1),
+			labeled_bracketing: BRACKETING.into(),
+			..Default::default()
+		};
+		doc.constituent_tree(&parse).expect("aligned tree")
+	}
+
+	#[test]
+	fn constituent_navigation() {
+		let tree = tree();
+		assert_eq!(tree.to_string(), BRACKETING);
+		let labels: Vec<_> = tree.preorder().filter(|n| !n.is_terminal()).map(|n| n.label.as_str()).collect();
+		assert_eq!(labels, ["ROOT", "S", "NP-SBJ", "DT", "NN", "VP", "VBD", "NP", "DT", "NN"]);
+		assert_eq!(tree.terminals(), (1..=5).map(TokenId).collect::<Vec<_>>());
+		assert_eq!((tree.height(), tree.pos_tags()[2]), (6, ("VBD", "saw")));
+
+		let np: Vec<_> = tree.find_all("NP").into_iter().map(|n| n.token_span()).collect();
+		assert_eq!(np, [Some((TokenId(1), TokenId(2))), Some((TokenId(4), TokenId(5)))]);
+		assert!(tree.find_all("N").is_empty());
+		let covering = |from, to| tree.lowest_constituent_covering(TokenId(from), TokenId(to)).map(|n| n.label.as_str());
+		assert_eq!((covering(4, 5), covering(3, 4), covering(2, 3)), (Some("NP"), Some("VP"), Some("S")));
+		assert_eq!(covering(5, 5), Some("NN"));
+		assert_eq!(covering(6, 7), None);
+	}
+
+	#[test]
+	fn constituent_errors() {
+		let e = ConstituentTree::parse("(S (NP a)) b").unwrap_err();
+		let message = "labeled bracketing \"(S (NP a)) b\": text after the tree";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		for malformed in ["(S (NP a)", "(S a))", "a", ""] {
+			assert!(malformed.parse::<ConstituentTree>().is_err(), "{:?}", malformed);
+		}
+		let mut tree = tree();
+		assert!(tree.align_tokens(&[TokenId(1)]).is_err());
+	}
+}
@@ -0,0 +1,113 @@
+//! The formats between which the services of the `server` and `grpc` features and the Node.js bindings convert.
+
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::{
+	check_options, conll2003, conllu, from_conll2003, from_conllu, from_conllx, from_slice_with_options, prob,
+	read_ndjson, to_conll2003, to_conllu, to_conllx, to_xml, Document, JsonNlpError, NdjsonWriter, ParseOptions, Result,
+	JSONNLP,
+};
+
+/// The formats of the conversion services, named in lowercase.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Format {
+	#[default]
+	Json,
+	Ndjson,
+	Conllu,
+	Conllx,
+	Conll2003,
+	Xml,
+}
+
+impl FromStr for Format {
+	type Err = JsonNlpError;
+
+	fn from_str(s: &str) -> Result<Self> {
+		match s {
+			"" | "json" => Ok(Format::Json),
+			"ndjson" => Ok(Format::Ndjson),
+			"conllu" => Ok(Format::Conllu),
+			"conllx" => Ok(Format::Conllx),
+			"conll2003" => Ok(Format::Conll2003),
+			"xml" => Ok(Format::Xml),
+			_ => Err(JsonNlpError::Format(format!("unknown format {:?}", s))),
+		}
+	}
+}
+
+impl Format {
+	/// returns the media type of the format.
+	#[allow(dead_code)]
+	pub(crate) fn content_type(self) -> &'static str {
+		match self {
+			Format::Json => "application/json",
+			Format::Ndjson => "application/x-ndjson",
+			Format::Xml => "application/xml",
+			_ => "text/plain; charset=utf-8",
+		}
+	}
+}
+
+fn text(bytes: &[u8]) -> Result<&str> {
+	std::str::from_utf8(bytes).map_err(|e| JsonNlpError::Format(format!("input is not UTF-8: {}", e)))
+}
+
+/// returns the collection of the input in a format, parsing it with the given options. The limits apply to the
+/// documents of NDJSON as to one collection, and to the documents, tokens, and strings of the CoNLL formats, whose
+/// lines are checked before they are parsed. XML cannot be checked and is rejected if limits are set.
+pub(crate) fn read(bytes: &[u8], format: Format, options: &ParseOptions) -> Result<JSONNLP> {
+	let limits = &options.limits;
+	match format {
+		Format::Json => from_slice_with_options(bytes, options),
+		Format::Ndjson => {
+			limits.check_ndjson(bytes)?;
+			let docs = prob::with_policy(options.prob_policy, || {
+				read_ndjson::<Document, _>(bytes).collect::<Result<Vec<Document>>>()
+			})?;
+			check_options(JSONNLP { docs, ..Default::default() }, options)
+		}
+		Format::Conllu => {
+			limits.check_lines(text(bytes)?, conllu::line_kind)?;
+			from_conllu(text(bytes)?)
+		}
+		Format::Conllx => {
+			limits.check_lines(text(bytes)?, conllu::line_kind)?;
+			from_conllx(text(bytes)?)
+		}
+		Format::Conll2003 => {
+			limits.check_lines(text(bytes)?, conll2003::line_kind)?;
+			from_conll2003(text(bytes)?)
+		}
+		Format::Xml if !limits.is_unlimited() => {
+			Err(JsonNlpError::Format("XML input cannot be checked against the limits".to_string()))
+		}
+		#[cfg(feature = "xml")]
+		Format::Xml => crate::from_xml(text(bytes)?),
+		#[cfg(not(feature = "xml"))]
+		Format::Xml => Err(JsonNlpError::Format("reading XML requires the xml feature".to_string())),
+	}
+}
+
+/// returns a collection in a format.
+pub(crate) fn write(j: &JSONNLP, format: Format) -> Result<String> {
+	let per_doc = |f: fn(&Document) -> String| j.docs.iter().map(f).collect::<String>();
+	match format {
+		Format::Json => crate::get_json(j),
+		Format::Ndjson => {
+			let mut buf = Vec::new();
+			let mut writer = NdjsonWriter::new(&mut buf);
+			for doc in &j.docs {
+				writer.write(doc)?;
+			}
+			Ok(String::from_utf8(buf).expect("serde_json produced invalid UTF-8"))
+		}
+		Format::Conllu => Ok(per_doc(to_conllu)),
+		Format::Conllx => Ok(per_doc(to_conllx)),
+		Format::Conll2003 => Ok(per_doc(to_conll2003)),
+		Format::Xml => to_xml(j),
+	}
+}
@@ -0,0 +1,131 @@
+//! Resolution of the coreference chains of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents to the
+//! tokens, character spans, and text of their mentions.
+
+use std::borrow::Cow;
+
+use crate::tei::space_after;
+use crate::{Coreference, Document, DocumentIndex, Prob, Token, TokenId};
+
+/// This struct contains a mention of a coreference chain with its tokens, its head, its character span, and its text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoreferenceMention<'a> {
+	pub tokens: Vec<&'a Token>,
+	pub head: Option<&'a Token>,
+	pub char_offset_begin: Option<u64>,
+	pub char_offset_end: Option<u64>,
+	/// the document text covered by the character span, or else the tokens' text joined by the spaces after them.
+	pub text: Cow<'a, str>,
+	pub representative: bool,
+	pub prob: Option<Prob>,
+}
+
+impl<'a> DocumentIndex<'a> {
+	/// returns the mention of the tokens, skipping IDs of tokens that are not in the document.
+	fn mention(
+		&self,
+		ids: &[TokenId],
+		head: TokenId,
+		representative: bool,
+		prob: Option<Prob>,
+	) -> CoreferenceMention<'a> {
+		let doc = self.document();
+		let tokens: Vec<&'a Token> = ids.iter().filter_map(|id| self.token(id)).collect();
+		let char_offset_begin = tokens.iter().filter_map(|t| t.char_offset_begin).min();
+		let char_offset_end = tokens.iter().filter_map(|t| t.char_offset_end).max();
+		let text = match (char_offset_begin, char_offset_end) {
+			(Some(begin), Some(end)) => doc.span_text(begin, end).map(Cow::Borrowed),
+			_ => None,
+		};
+		let text = text.unwrap_or_else(|| {
+			let mut text = String::new();
+			for (i, t) in tokens.iter().enumerate() {
+				if i > 0 && space_after(tokens[i - 1], t) {
+					text.push(' ');
+				}
+				text.push_str(&t.text);
+			}
+			Cow::Owned(text)
+		});
+		CoreferenceMention {
+			head: self.token(&head),
+			tokens,
+			char_offset_begin,
+			char_offset_end,
+			text,
+			representative,
+			prob,
+		}
+	}
+
+	/// returns the mentions of a coreference chain, the representative first and then the referents in their order.
+	pub fn coreference_mentions(&self, coreference: &Coreference) -> Vec<CoreferenceMention<'a>> {
+		let r = &coreference.representative;
+		let mut mentions = vec![self.mention(&r.tokens, r.head, true, None)];
+		mentions.extend(coreference.referents.iter().map(|m| self.mention(&m.tokens, m.head, false, m.prob)));
+		mentions
+	}
+}
+
+impl Document {
+	/// returns the mentions of a coreference chain like DocumentIndex::coreference_mentions(), which indexes the
+	/// document first; index it once to resolve many chains.
+	pub fn coreference_mentions(&self, coreference: &Coreference) -> Vec<CoreferenceMention<'_>> {
+		self.index().coreference_mentions(coreference)
+	}
+
+	/// returns the first coreference chain with a mention that contains the token or has it as head.
+	pub fn coreference_of(&self, token: TokenId) -> Option<&Coreference> {
+		self.coreferences.iter().find(|c| {
+			let r = &c.representative;
+			r.head == token
+				|| r.tokens.contains(&token)
+				|| c.referents.iter().any(|m| m.head == token || m.tokens.contains(&token))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn doc() -> Document {
+		serde_json::from_str(r#"{"meta":{},"id":1,"text":"Mary Ann said she can't.","tokenList":[
+			{"id":1,"sentence_id":1,"text":"Mary","lemma":"Mary","characterOffsetBegin":0,"characterOffsetEnd":4},
+			{"id":2,"sentence_id":1,"text":"Ann","lemma":"Ann","characterOffsetBegin":5,"characterOffsetEnd":8},
+			{"id":3,"sentence_id":1,"text":"said","lemma":"say","characterOffsetBegin":9,"characterOffsetEnd":13},
+			{"id":4,"sentence_id":1,"text":"she","lemma":"she","characterOffsetBegin":14,"characterOffsetEnd":17},
+			{"id":5,"sentence_id":1,"text":"ca","lemma":"can","features":{"spaceAfter":false}},
+			{"id":6,"sentence_id":1,"text":"n't","lemma":"not"}],
+			"coreferences":[{"id":1,"representative":{"tokens":[1,2],"head":2},
+				"referents":[{"tokens":[4],"head":4,"prob":0.5},{"tokens":[9],"head":9}]},
+				{"id":2,"representative":{"tokens":[5,6],"head":5},"referents":[]}]}"#)
+		.unwrap()
+	}
+
+	#[test]
+	fn mentions_are_resolved() {
+		let doc = doc();
+		let mentions = doc.coreference_mentions(&doc.coreferences[0]);
+		let resolved: Vec<_> = mentions
+			.iter()
+			.map(|m| (m.text.as_ref(), m.head.map(|t| t.id.0), m.char_offset_begin, m.representative, m.prob))
+			.collect();
+		assert_eq!(resolved, [
+			("Mary Ann", Some(2), Some(0), true, None),
+			("she", Some(4), Some(14), false, Prob::new(0.5)),
+			("", None, None, false, None),
+		]);
+		assert!(matches!(mentions[0].text, Cow::Borrowed(_)) && mentions[2].tokens.is_empty());
+
+		let index = doc.index();
+		let mention = &index.coreference_mentions(&doc.coreferences[1])[0];
+		assert_eq!((mention.text.as_ref(), mention.tokens.len(), mention.char_offset_end), ("can't", 2, None));
+	}
+
+	#[test]
+	fn chain_of_token() {
+		let doc = doc();
+		let chain = |id| doc.coreference_of(TokenId(id)).map(|c| c.id.0);
+		assert_eq!((chain(1), chain(4), chain(9), chain(6), chain(3)), (Some(1), Some(1), Some(1), Some(2), None));
+	}
+}
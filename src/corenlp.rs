@@ -0,0 +1,443 @@
+//! Conversion between [Stanford CoreNLP](https://stanfordnlp.github.io/CoreNLP/) JSON output and
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+	ConstituentParse, Coreference, CoreferenceId, CoreferenceReferents, CoreferenceRepresentantive, Dependency,
+	DependencyTree, Document, DocumentId, Entity, EntityId, JsonNlpError, RawId, Result, Sentence, SentenceId, Token,
+	TokenId,
+};
+
+/// the dependency layers of CoreNLP with the style of the resulting dependency trees.
+const DEPENDENCY_STYLES: &[(&str, &str)] = &[
+	("basicDependencies", "basic"),
+	("enhancedDependencies", "enhanced"),
+	("enhancedPlusPlusDependencies", "enhanced++"),
+];
+
+/// This struct contains the JSON output of a CoreNLP pipeline.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoreNlpDoc {
+	#[serde(default)]
+	doc_id: String,
+	sentences: Vec<CoreNlpSentence>,
+	#[serde(default)]
+	corefs: BTreeMap<String, Vec<CoreNlpMention>>,
+}
+
+/// This struct contains a sentence with its tokens and annotation layers.
+#[derive(Deserialize)]
+struct CoreNlpSentence {
+	index: RawId,
+	tokens: Vec<CoreNlpToken>,
+	#[serde(default)]
+	parse: String,
+	#[serde(default)]
+	entitymentions: Vec<CoreNlpEntityMention>,
+	#[serde(default)]
+	sentiment: String,
+	#[serde(flatten)]
+	layers: Map<String, Value>,
+}
+
+/// This struct contains a token with the whitespace around it.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoreNlpToken {
+	index: RawId,
+	word: String,
+	#[serde(default)]
+	original_text: Option<String>,
+	#[serde(default)]
+	lemma: String,
+	#[serde(default)]
+	pos: String,
+	#[serde(default)]
+	ner: String,
+	#[serde(default)]
+	before: String,
+	#[serde(default)]
+	after: Option<String>,
+}
+
+/// This struct contains a dependency with token indexes starting at 1 and 0 for the root.
+#[derive(Deserialize)]
+struct CoreNlpDependency {
+	dep: String,
+	governor: RawId,
+	dependent: RawId,
+}
+
+/// This struct contains an entity mention with token indexes starting at 0.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoreNlpEntityMention {
+	token_begin: RawId,
+	token_end: RawId,
+	#[serde(default)]
+	text: String,
+	#[serde(default)]
+	ner: String,
+}
+
+/// This struct contains a coreference mention with token indexes starting at 1 and an exclusive end.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CoreNlpMention {
+	start_index: RawId,
+	end_index: RawId,
+	head_index: RawId,
+	sent_num: RawId,
+	#[serde(default)]
+	is_representative_mention: bool,
+}
+
+/// This function converts the JSON output of a CoreNLP pipeline to a Document. The document text is rebuilt from
+/// the tokens' `originalText`, `before`, and `after`, and the character offsets count the Unicode characters of
+/// this text instead of CoreNLP's UTF-16 code units. Tokens are numbered through the document. The `basic`,
+/// `enhanced`, and `enhanced++` dependencies become dependency trees of these styles, the parses constituent parses,
+/// the entity mentions entities, and the coreference chains coreferences headed by their representative mention.
+pub fn from_corenlp_json(json: &str) -> Result<Document> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let corenlp: CoreNlpDoc = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let mut doc = Document { id: DocumentId(1), ..Default::default() };
+	doc.meta.identifier = corenlp.doc_id;
+	// the token ID of the first token and the number of tokens of every sentence
+	let mut sentence_tokens = Vec::new();
+	let mut offset = 0;
+	for s in &corenlp.sentences {
+		let sentence_id = SentenceId(s.index + 1);
+		let first = doc.token_list.len() as RawId + 1;
+		sentence_tokens.push((first, s.tokens.len() as RawId));
+		let token_id = |index: RawId| -> Result<TokenId> {
+			if index == 0 || index > s.tokens.len() as RawId {
+				return Err(JsonNlpError::Format(format!("token {} is not in sentence {}", index, s.index)));
+			}
+			Ok(TokenId(first + index - 1))
+		};
+
+		let mut previous_ner = "O";
+		// byte range of the sentence in the text
+		let mut span: Option<(usize, usize)> = None;
+		for t in &s.tokens {
+			if doc.token_list.is_empty() {
+				doc.text.push_str(&t.before);
+				offset = t.before.chars().count() as u64;
+			}
+			let text = t.original_text.as_deref().unwrap_or(&t.word);
+			let start = offset;
+			offset += text.chars().count() as u64;
+			let byte_start = doc.text.len();
+			doc.text.push_str(text);
+			span = Some((span.map_or(byte_start, |s| s.0), doc.text.len()));
+			let after = t.after.as_deref().unwrap_or(" ");
+			offset += after.chars().count() as u64;
+			doc.text.push_str(after);
+
+			let ner = if t.ner.is_empty() { "O" } else { t.ner.as_str() };
+			let mut token = Token {
+				id: token_id(t.index)?,
+				sentence_id,
+				text: t.word.clone(),
+				lemma: t.lemma.clone(),
+				xpos: t.pos.clone(),
+				char_offset_begin: Some(start),
+				char_offset_end: Some(start + text.chars().count() as u64),
+				entity_iob: match ner {
+					"O" => "O",
+					_ if ner == previous_ner => "I",
+					_ => "B",
+				}.to_string(),
+				entity: if ner == "O" { String::new() } else { ner.to_string() },
+				..Default::default()
+			};
+			if after.is_empty() {
+				token.features_mut().spaceafter = Some(false);
+			}
+			previous_ner = ner;
+			doc.token_list.push(token);
+		}
+
+		let ids: Vec<TokenId> = (0..s.tokens.len() as RawId).map(|i| TokenId(first + i)).collect();
+		doc.sentences.push(Sentence {
+			id: sentence_id,
+			text: span.map(|(b, e)| doc.text[b..e].to_string()).unwrap_or_default(),
+			token_from: ids.first().copied(),
+			token_to: ids.last().copied(),
+			tokens: ids,
+			sentiment: s.sentiment.clone(),
+			..Default::default()
+		});
+
+		for (layer, style) in DEPENDENCY_STYLES {
+			let deps: Vec<CoreNlpDependency> = match s.layers.get(*layer) {
+				Some(v) => serde_path_to_error::deserialize(v.clone())?,
+				None => continue,
+			};
+			let mut tree = DependencyTree {
+				sentence_id: Some(sentence_id),
+				style: style.to_string(),
+				..Default::default()
+			};
+			for d in deps {
+				tree.dependencies.push(Dependency {
+					lab: d.dep,
+					gov: if d.governor == 0 { TokenId(0) } else { token_id(d.governor)? },
+					dep: token_id(d.dependent)?,
+					..Default::default()
+				});
+			}
+			doc.dependency_trees.push(tree);
+		}
+		if !s.parse.is_empty() {
+			doc.constituents.push(ConstituentParse {
+				sentence_id,
+				labeled_bracketing: s.parse.split_whitespace().collect::<Vec<_>>().join(" "),
+				..Default::default()
+			});
+		}
+		for m in &s.entitymentions {
+			let tokens: Vec<TokenId> = (m.token_begin..m.token_end).map(|i| token_id(i + 1)).collect::<Result<_>>()?;
+			doc.entities.push(Entity {
+				id: EntityId(doc.entities.len() as RawId + 1),
+				label: m.text.clone(),
+				etype: m.ner.clone(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			});
+		}
+	}
+
+	for (id, mentions) in &corenlp.corefs {
+		let id: RawId = id.parse().map_err(|_| JsonNlpError::Format(format!("invalid coreference chain ID {:?}", id)))?;
+		let mut representative = None;
+		let mut referents = Vec::new();
+		for m in mentions {
+			let (first, count) = match m.sent_num.checked_sub(1).and_then(|i| sentence_tokens.get(i as usize)) {
+				Some(sentence) => *sentence,
+				None => return Err(JsonNlpError::Format(format!("coreference {} refers to the missing sentence {}", id, m.sent_num))),
+			};
+			if m.start_index == 0 || m.start_index >= m.end_index || m.end_index > count + 1 {
+				return Err(JsonNlpError::Format(format!(
+					"coreference {} has the tokens {} to {} outside of the {} tokens of sentence {}",
+					id, m.start_index, m.end_index, count, m.sent_num
+				)));
+			}
+			if m.head_index < m.start_index || m.head_index >= m.end_index {
+				let msg = format!("coreference {} has the head {} outside of its tokens", id, m.head_index);
+				return Err(JsonNlpError::Format(msg));
+			}
+			let tokens: Vec<TokenId> = (m.start_index..m.end_index).map(|i| TokenId(first + i - 1)).collect();
+			let head = TokenId(first + m.head_index - 1);
+			if m.is_representative_mention && representative.is_none() {
+				representative = Some(CoreferenceRepresentantive { tokens, head, ..Default::default() });
+			} else {
+				referents.push(CoreferenceReferents { tokens, head, ..Default::default() });
+			}
+		}
+		doc.coreferences.push(Coreference {
+			id: CoreferenceId(id),
+			representative: representative.unwrap_or_default(),
+			referents,
+			..Default::default()
+		});
+	}
+	Ok(doc)
+}
+
+/// This function converts a Document to the JSON output format of CoreNLP, so that JSON-NLP documents can be read
+/// by CoreNLP clients. Tokens are numbered from 1 within their sentence, and their `before` and `after` whitespace
+/// and UTF-16 character offsets are derived from the document text. The first dependency tree of every sentence
+/// becomes its `basicDependencies`, unless trees with the styles `enhanced` or `enhanced++` exist for them, and
+/// entities, coreferences, constituent parses, and sentence sentiment are written as well.
+pub fn to_corenlp_json(doc: &Document) -> Value {
+	// UTF-16 offsets of all character positions in the text
+	let mut utf16 = vec![0u64];
+	for c in doc.text.chars() {
+		utf16.push(utf16.last().copied().unwrap_or(0) + c.len_utf16() as u64);
+	}
+	let to_utf16 = |offset: u64| utf16.get(offset as usize).copied().unwrap_or(offset);
+	let chars: Vec<char> = doc.text.chars().collect();
+	let gap = |from: u64, to: u64| -> String {
+		chars.get(from as usize..to as usize).map(|c| c.iter().collect()).unwrap_or_default()
+	};
+
+	let groups = doc.sentence_groups();
+	// sentence index and token index of every token
+	let mut position: HashMap<TokenId, (usize, usize)> = HashMap::new();
+	for (i, g) in groups.iter().enumerate() {
+		for (j, t) in g.2.iter().enumerate() {
+			position.insert(t.id, (i, j + 1));
+		}
+	}
+	let all: Vec<&Token> = groups.iter().flat_map(|g| g.2.iter().copied()).collect();
+	let by_id: HashMap<TokenId, &Token> = all.iter().map(|t| (t.id, *t)).collect();
+
+	let mut sentences = Vec::new();
+	let mut doc_token = 0;
+	for (i, (sentence_id, _, tokens)) in groups.iter().enumerate() {
+		let mut out_tokens = Vec::new();
+		for (j, t) in tokens.iter().enumerate() {
+			let k = doc_token + j;
+			let before = match (k.checked_sub(1).and_then(|p| all.get(p)), t.char_offset_begin) {
+				(Some(p), Some(b)) => p.char_offset_end.map(|e| gap(e, b)).unwrap_or_default(),
+				(None, Some(b)) => gap(0, b),
+				_ => String::new(),
+			};
+			let after = match (all.get(k + 1), t.char_offset_end) {
+				(Some(n), Some(e)) => n.char_offset_begin.map(|b| gap(e, b)).unwrap_or_default(),
+				(None, Some(e)) => gap(e, chars.len() as u64),
+				_ if t.features().spaceafter == Some(false) => String::new(),
+				_ => " ".to_string(),
+			};
+			let mut token = json!({
+				"index": j + 1,
+				"word": t.text,
+				"originalText": t.text,
+				"lemma": t.lemma,
+				"pos": t.xpos,
+				"ner": if t.entity.is_empty() { "O" } else { t.entity.as_str() },
+				"before": before,
+				"after": after,
+			});
+			if let (Some(b), Some(e)) = (t.char_offset_begin, t.char_offset_end) {
+				token["characterOffsetBegin"] = json!(to_utf16(b));
+				token["characterOffsetEnd"] = json!(to_utf16(e));
+			}
+			out_tokens.push(token);
+		}
+		doc_token += tokens.len();
+
+		let mut sentence = json!({ "index": i, "tokens": out_tokens });
+		let trees: Vec<&DependencyTree> = doc.dependency_trees.iter()
+			.filter(|t| t.sentence_id == Some(*sentence_id) || (t.sentence_id.is_none() && groups.len() == 1))
+			.collect();
+		for (n, tree) in trees.iter().enumerate() {
+			let layer = match tree.style.as_str() {
+				"enhanced" => "enhancedDependencies",
+				"enhanced++" => "enhancedPlusPlusDependencies",
+				_ if n == 0 => "basicDependencies",
+				_ => continue,
+			};
+			let gloss = |id: TokenId| by_id.get(&id).map_or("ROOT", |t| t.text.as_str());
+			let deps: Vec<Value> = tree.dependencies.iter().map(|d| {
+				let governor = if d.is_root() { 0 } else { position.get(&d.gov).map_or(0, |p| p.1) };
+				json!({
+					"dep": if d.is_root() { "ROOT" } else { d.lab.as_str() },
+					"governor": governor,
+					"governorGloss": if governor == 0 { "ROOT" } else { gloss(d.gov) },
+					"dependent": position.get(&d.dep).map_or(0, |p| p.1),
+					"dependentGloss": gloss(d.dep),
+				})
+			}).collect();
+			sentence[layer] = json!(deps);
+		}
+		if let Some(c) = doc.constituents.iter().find(|c| c.sentence_id == *sentence_id) {
+			sentence["parse"] = json!(c.labeled_bracketing);
+		}
+		let mentions: Vec<Value> = doc.entities.iter().filter_map(|e| {
+			let indexes: Vec<(usize, usize)> = tokens.iter().filter(|t| e.covers(t.id)).filter_map(|t| position.get(&t.id).copied()).collect();
+			let (first, last) = (indexes.first()?, indexes.last()?);
+			Some(json!({
+				"tokenBegin": first.1 - 1,
+				"tokenEnd": last.1,
+				"text": e.label,
+				"ner": e.etype,
+			}))
+		}).collect();
+		if !mentions.is_empty() {
+			sentence["entitymentions"] = json!(mentions);
+		}
+		if let Some(s) = doc.sentences.iter().find(|s| s.id == *sentence_id).filter(|s| !s.sentiment.is_empty()) {
+			sentence["sentiment"] = json!(s.sentiment);
+		}
+		sentences.push(sentence);
+	}
+
+	let mut corefs = Map::new();
+	let mut mention_id = 0;
+	for c in &doc.coreferences {
+		let mentions = std::iter::once((&c.representative.tokens, c.representative.head, true))
+			.chain(c.referents.iter().map(|r| (&r.tokens, r.head, false)))
+			.filter_map(|(tokens, head, representative)| {
+				let first = position.get(tokens.first()?)?;
+				let last = position.get(tokens.last()?)?;
+				mention_id += 1;
+				Some(json!({
+					"id": mention_id,
+					"text": tokens.iter().filter_map(|id| by_id.get(id)).map(|t| t.text.as_str()).collect::<Vec<_>>().join(" "),
+					"startIndex": first.1,
+					"endIndex": last.1 + 1,
+					"headIndex": position.get(&head).map_or(first.1, |p| p.1),
+					"sentNum": first.0 + 1,
+					"isRepresentativeMention": representative,
+				}))
+			})
+			.collect::<Vec<_>>();
+		corefs.insert(c.id.to_string(), Value::Array(mentions));
+	}
+
+	let doc_id = if doc.meta.identifier.is_empty() { doc.id.to_string() } else { doc.meta.identifier.clone() };
+	json!({ "docId": doc_id, "sentences": sentences, "corefs": corefs })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CORENLP: &str = r#"{"docId":"d1","sentences":[{"index":0,"tokens":[
+		{"index":1,"word":"Mary","originalText":"Mary","lemma":"Mary","pos":"NNP","ner":"PERSON",
+		"before":"","after":" "},
+		{"index":2,"word":"sleeps","originalText":"sleeps","lemma":"sleep","pos":"VBZ","ner":"O",
+		"before":" ","after":""},
+		{"index":3,"word":".","originalText":".","lemma":".","pos":".","ner":"O","before":"","after":" "}],
+		"basicDependencies":[{"dep":"ROOT","governor":0,"dependent":2},{"dep":"nsubj","governor":2,"dependent":1},
+		{"dep":"punct","governor":2,"dependent":3}],
+		"entitymentions":[{"tokenBegin":0,"tokenEnd":1,"text":"Mary","ner":"PERSON"}]},
+		{"index":1,"tokens":[{"index":1,"word":"She","originalText":"She","lemma":"she","pos":"PRP",
+		"before":" ","after":""}]}],
+		"corefs":{"1":[{"startIndex":1,"endIndex":2,"headIndex":1,"sentNum":1,"isRepresentativeMention":true},
+		{"startIndex":1,"endIndex":2,"headIndex":1,"sentNum":2,"isRepresentativeMention":false}]}}"#;
+
+	#[test]
+	fn corenlp_round_trip() {
+		let doc = from_corenlp_json(CORENLP).expect("valid CoreNLP");
+		assert_eq!(doc.text, "Mary sleeps. She");
+		assert_eq!(doc.token_list.len(), 4);
+		assert_eq!(doc.dependency_trees[0].dependencies.len(), 3);
+		assert_eq!(doc.entities[0].tokens, [TokenId(1)]);
+		assert_eq!(doc.coreferences[0].representative.head, TokenId(1));
+		assert_eq!(doc.coreferences[0].referents[0].tokens, [TokenId(4)]);
+		let again = from_corenlp_json(&to_corenlp_json(&doc).to_string()).expect("valid CoreNLP");
+		assert_eq!(again.text, doc.text);
+		assert_eq!(again.token_list.iter().map(|t| &t.lemma).collect::<Vec<_>>(), ["Mary", "sleep", ".", "she"]);
+		assert_eq!(again.coreferences, doc.coreferences);
+	}
+
+	#[test]
+	fn corenlp_errors() {
+		let invalid = |from: &str, to: &str| from_corenlp_json(&CORENLP.replacen(from, to, 1)).is_err();
+		// the referent in the second sentence, which has one token
+		let referent = r#""startIndex":1,"endIndex":2,"headIndex":1,"sentNum":2"#;
+		let mention = |start: u64, end: u64, head: u64, sentence: u64| {
+			format!(r#""startIndex":{},"endIndex":{},"headIndex":{},"sentNum":{}"#, start, end, head, sentence)
+		};
+		assert!(invalid(referent, &mention(1, 4000000000, 1, 2)));
+		assert!(invalid(referent, &mention(1, 3, 1, 2)));
+		assert!(invalid(referent, &mention(0, 2, 1, 2)));
+		assert!(invalid(referent, &mention(2, 1, 1, 2)));
+		assert!(invalid(referent, &mention(1, 2, 2, 2)));
+		assert!(invalid(referent, &mention(1, 2, 1, 3)));
+		assert!(invalid(r#""dependent":3"#, r#""dependent":4"#));
+		assert!(!invalid(referent, &mention(1, 4, 3, 1)));
+	}
+}
@@ -0,0 +1,162 @@
+//! ISO-8601 handling of the `DC.created` and `DC.date` metadata, available with the `chrono` feature. The fields
+//! stay strings rather than `DateTime<FixedOffset>`, so that documents keep the dates as they were written, e.g.
+//! without an offset or with a week date, and Meta::created_at() and Meta::date_at() return them as timestamps.
+//! The strings are checked when they are deserialized or serialized; assigning them directly is not checked,
+//! so set them with Meta::set_created_at() and Meta::set_date_at() to keep them valid.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use serde::de::Error;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serializer};
+
+use crate::Meta;
+
+/// returns the date of an ISO-8601 calendar, week, or ordinal date in the extended or basic format.
+fn parse_date(s: &str) -> Option<NaiveDate> {
+	let formats: &[&str] = match (s.len(), s.contains('-'), s.contains('W')) {
+		(10, true, false) => &["%Y-%m-%d"],
+		(8, false, false) => &["%Y%m%d"],
+		(10, true, true) => &["%G-W%V-%u"],
+		(8, false, true) => &["%GW%V%u"],
+		(8, true, false) => &["%Y-%j"],
+		(7, false, false) => &["%Y%j"],
+		_ => &[],
+	};
+	formats.iter().find_map(|f| NaiveDate::parse_from_str(s, f).ok())
+}
+
+/// returns the time of an ISO-8601 time of day in the extended or basic format, with optional fractional seconds.
+fn parse_time(s: &str) -> Option<NaiveTime> {
+	let s = s.replace(',', ".");
+	let formats: &[&str] = if s.contains(':') { &["%H:%M:%S%.f", "%H:%M"] } else { &["%H%M%S%.f", "%H%M"] };
+	formats.iter().find_map(|f| NaiveTime::parse_from_str(&s, f).ok())
+}
+
+/// returns the offset of an ISO-8601 UTC designator `Z` or offset like `+01:00`, `+0100`, or `+01`.
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+	if s == "Z" {
+		return FixedOffset::east_opt(0);
+	}
+	let sign = match s.as_bytes().first()? {
+		b'+' => 1,
+		b'-' => -1,
+		_ => return None,
+	};
+	let digits = s[1..].replacen(':', "", 1);
+	if !digits.bytes().all(|b| b.is_ascii_digit()) || (digits.len() != 2 && digits.len() != 4) {
+		return None;
+	}
+	let hours: i32 = digits[..2].parse().ok()?;
+	let minutes: i32 = if digits.len() == 4 { digits[2..].parse().ok()? } else { 0 };
+	FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// parses an ISO-8601 date or date and time, e.g. `2019-01-25`, `2019-W04-5`, `2019-01-25T17:04:34.5Z`, or
+/// `20190125T170434+0100`. Dates are read as midnight, and times without offset as UTC.
+pub fn parse_iso8601(s: &str) -> Option<DateTime<FixedOffset>> {
+	if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+		return Some(dt);
+	}
+	let (date, time) = match s.split_once('T') {
+		Some((date, time)) => (date, Some(time)),
+		None => (s, None),
+	};
+	let date = parse_date(date)?;
+	let (time, offset) = match time {
+		Some(time) => {
+			let (time, offset) = match time.find(['Z', '+', '-']) {
+				Some(i) => (&time[..i], parse_offset(&time[i..])?),
+				None => (time, FixedOffset::east_opt(0)?),
+			};
+			(parse_time(time)?, offset)
+		}
+		None => (NaiveTime::MIN, FixedOffset::east_opt(0)?),
+	};
+	offset.from_local_datetime(&date.and_time(time)).single()
+}
+
+/// deserializes a string that is empty or an ISO-8601 date or timestamp.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+	let s = String::deserialize(deserializer)?;
+	if !s.is_empty() && parse_iso8601(&s).is_none() {
+		return Err(D::Error::custom(format!("{:?} is not an ISO-8601 date or timestamp", s)));
+	}
+	Ok(s)
+}
+
+/// serializes a string that is empty or an ISO-8601 date or timestamp, and returns an error for other strings.
+#[allow(clippy::ptr_arg)]
+pub(crate) fn serialize<S: Serializer>(s: &String, serializer: S) -> Result<S::Ok, S::Error> {
+	if !s.is_empty() && parse_iso8601(s).is_none() {
+		return Err(S::Error::custom(format!("{:?} is not an ISO-8601 date or timestamp", s)));
+	}
+	serializer.serialize_str(s)
+}
+
+impl Meta {
+	/// returns `DC.created` as a timestamp, or None if it is not set or not ISO-8601.
+	pub fn created_at(&self) -> Option<DateTime<FixedOffset>> {
+		parse_iso8601(&self.created)
+	}
+
+	/// returns `DC.date` as a timestamp, or None if it is not set or not ISO-8601.
+	pub fn date_at(&self) -> Option<DateTime<FixedOffset>> {
+		parse_iso8601(&self.date)
+	}
+
+	/// sets `DC.created` to the RFC 3339 representation of the timestamp.
+	pub fn set_created_at(&mut self, created: DateTime<FixedOffset>) {
+		self.created = created.to_rfc3339();
+	}
+
+	/// sets `DC.date` to the RFC 3339 representation of the timestamp.
+	pub fn set_date_at(&mut self, date: DateTime<FixedOffset>) {
+		self.date = date.to_rfc3339();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::parse_iso8601;
+	use crate::Meta;
+
+	#[test]
+	fn iso8601_forms() {
+		let utc = |s: &str| parse_iso8601(s).map(|dt| dt.naive_utc().to_string());
+		assert_eq!(utc("2019-01-25T17:04:34"), Some("2019-01-25 17:04:34".to_string()));
+		assert_eq!(utc("2019-01-25T17:04:34.250"), Some("2019-01-25 17:04:34.250".to_string()));
+		assert_eq!(utc("2019-01-25T17:04:34,5Z"), Some("2019-01-25 17:04:34.500".to_string()));
+		assert_eq!(utc("2019-01-25T17:04:34Z"), Some("2019-01-25 17:04:34".to_string()));
+		assert_eq!(utc("2019-01-25T18:04+01"), Some("2019-01-25 17:04:00".to_string()));
+		assert_eq!(utc("20190125T180434+0100"), Some("2019-01-25 17:04:34".to_string()));
+		assert_eq!(utc("20190125"), Some("2019-01-25 00:00:00".to_string()));
+		assert_eq!(utc("2019-W04-5"), Some("2019-01-25 00:00:00".to_string()));
+		assert_eq!(utc("2019W045"), Some("2019-01-25 00:00:00".to_string()));
+		assert_eq!(utc("2019-025"), Some("2019-01-25 00:00:00".to_string()));
+		assert_eq!(utc("2019025"), Some("2019-01-25 00:00:00".to_string()));
+		for invalid in ["yesterday", "2019-13-01", "2019-01-25T25:00", "2019-01-25T17:04+1", "2019-01-25T"] {
+			assert_eq!(utc(invalid), None, "{}", invalid);
+		}
+	}
+
+	#[test]
+	fn local_timestamps_are_parsed() {
+		let json = r#"{"meta":{"DC.created":"2019-01-25T17:04:34"},"documents":[]}"#;
+		let j = crate::from_string(json).expect("valid JSON-NLP");
+		assert_eq!(j.meta.created, "2019-01-25T17:04:34");
+		assert!(crate::get_json(&j).expect("valid dates").contains(r#"{"meta":{"DC.created":"2019-01-25T17:04:34"}"#));
+	}
+
+	#[test]
+	fn dates_are_checked_when_serialized() {
+		let mut meta = Meta { created: "2020-01-31".to_string(), ..Default::default() };
+		assert_eq!(serde_json::to_string(&meta).unwrap(), r#"{"DC.created":"2020-01-31"}"#);
+		meta.date = "yesterday".to_string();
+		assert!(serde_json::to_string(&meta).is_err());
+		meta.set_date_at(meta.created_at().unwrap());
+		assert_eq!(
+			serde_json::to_string(&meta).unwrap(),
+			r#"{"DC.created":"2020-01-31","DC.date":"2020-01-31T00:00:00+00:00"}"#
+		);
+	}
+}
@@ -0,0 +1,159 @@
+//! Navigation of the dependency trees of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents: the lowest
+//! common ancestor of two tokens and the labeled dependency path between them.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::{Dependency, DependencyTree, TokenId};
+
+/// This struct contains a step of a dependency path, which follows a dependency up from its dependent to its governor
+/// or down from its governor to its dependent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStep<'a> {
+	pub dependency: &'a Dependency,
+	pub up: bool,
+}
+
+/// This struct contains the path between two tokens of a dependency tree, which goes up from the first token to
+/// their lowest common ancestor and down to the second token. It is written like `↑nsubj ↓obj`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyPath<'a> {
+	pub from: TokenId,
+	pub to: TokenId,
+	pub ancestor: TokenId,
+	pub steps: Vec<PathStep<'a>>,
+}
+
+impl DependencyPath<'_> {
+	/// returns the tokens on the path from its first to its last token.
+	pub fn tokens(&self) -> Vec<TokenId> {
+		let mut tokens = vec![self.from];
+		for step in &self.steps {
+			tokens.push(if step.up { step.dependency.gov } else { step.dependency.dep });
+		}
+		tokens
+	}
+
+	/// returns the labels of the dependencies on the path.
+	pub fn labels(&self) -> Vec<&str> {
+		self.steps.iter().map(|s| s.dependency.lab.as_str()).collect()
+	}
+
+	/// returns the number of dependencies on the path.
+	pub fn len(&self) -> usize {
+		self.steps.len()
+	}
+
+	/// checks if the path has no dependencies, i.e. it leads from a token to itself.
+	pub fn is_empty(&self) -> bool {
+		self.steps.is_empty()
+	}
+}
+
+impl fmt::Display for DependencyPath<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, step) in self.steps.iter().enumerate() {
+			if i > 0 {
+				f.write_str(" ")?;
+			}
+			write!(f, "{}{}", if step.up { '↑' } else { '↓' }, step.dependency.lab)?;
+		}
+		Ok(())
+	}
+}
+
+impl DependencyTree {
+	/// returns the dependency that attaches the token to its governor, the first one if there are several, or None
+	/// if the token is the root or not a dependent in the tree.
+	pub fn governor(&self, token: TokenId) -> Option<&Dependency> {
+		self.dependencies.iter().find(|d| d.dep == token).filter(|d| !d.is_root())
+	}
+
+	/// returns the dependencies from the token up to the root, stopping before a dependency that would close a cycle.
+	fn ascent(&self, token: TokenId) -> Vec<&Dependency> {
+		let mut visited = HashSet::new();
+		visited.insert(token);
+		let mut ascent = Vec::new();
+		let mut current = token;
+		while let Some(d) = self.governor(current) {
+			if !visited.insert(d.gov) {
+				break;
+			}
+			ascent.push(d);
+			current = d.gov;
+		}
+		ascent
+	}
+
+	/// returns the lowest common ancestor of two tokens, i.e. the nearest token that dominates both, where every token
+	/// dominates itself, or None if they are in different parts of the tree.
+	pub fn lowest_common_ancestor(&self, a: TokenId, b: TokenId) -> Option<TokenId> {
+		let ancestors: HashSet<TokenId> = std::iter::once(a).chain(self.ascent(a).iter().map(|d| d.gov)).collect();
+		std::iter::once(b).chain(self.ascent(b).iter().map(|d| d.gov)).find(|t| ancestors.contains(t))
+	}
+
+	/// returns the shortest path from token `a` up to the lowest common ancestor of both tokens and down to token `b`,
+	/// or None if they are in different parts of the tree.
+	pub fn path_between(&self, a: TokenId, b: TokenId) -> Option<DependencyPath<'_>> {
+		let ancestor = self.lowest_common_ancestor(a, b)?;
+		let mut steps: Vec<PathStep> = self
+			.ascent(a)
+			.into_iter()
+			.take_while(|d| d.dep != ancestor)
+			.map(|dependency| PathStep { dependency, up: true })
+			.collect();
+		let down: Vec<&Dependency> = self.ascent(b).into_iter().take_while(|d| d.dep != ancestor).collect();
+		steps.extend(down.into_iter().rev().map(|dependency| PathStep { dependency, up: false }));
+		Some(DependencyPath { from: a, to: b, ancestor, steps })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// returns the tree of "The dog chased a cat", a fragment of tokens 6 and 7, and a cycle of tokens 8 and 9.
+	fn tree() -> DependencyTree {
+		let dependencies = [(2, 1, "det"), (3, 2, "nsubj"), (0, 3, "root"), (5, 4, "det"), (3, 5, "obj"), (7, 6, "dep"),
+			(9, 8, "dep"), (8, 9, "dep")];
+		DependencyTree {
+			dependencies: dependencies
+				.iter()
+				.map(|&(gov, dep, lab)| Dependency {
+					gov: TokenId(gov),
+					dep: TokenId(dep),
+					lab: lab.into(),
+					..Default::default()
+				})
+				.collect(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn paths_between_tokens() {
+		let tree = tree();
+		assert_eq!(tree.governor(TokenId(2)).map(|d| d.gov), Some(TokenId(3)));
+		assert_eq!((tree.governor(TokenId(3)), tree.governor(TokenId(7))), (None, None));
+		assert_eq!(tree.lowest_common_ancestor(TokenId(1), TokenId(4)), Some(TokenId(3)));
+		assert_eq!(tree.lowest_common_ancestor(TokenId(1), TokenId(2)), Some(TokenId(2)));
+
+		let path = tree.path_between(TokenId(1), TokenId(4)).unwrap();
+		assert_eq!(path.to_string(), "↑det ↑nsubj ↓obj ↓det");
+		assert_eq!((path.ancestor, path.len(), path.labels()), (TokenId(3), 4, vec!["det", "nsubj", "obj", "det"]));
+		assert_eq!(path.tokens(), [TokenId(1), TokenId(2), TokenId(3), TokenId(5), TokenId(4)]);
+		assert_eq!(tree.path_between(TokenId(3), TokenId(1)).unwrap().to_string(), "↓nsubj ↓det");
+		let empty = tree.path_between(TokenId(5), TokenId(5)).unwrap();
+		assert!(empty.is_empty() && empty.tokens() == [TokenId(5)]);
+	}
+
+	#[test]
+	fn disconnected_tokens() {
+		let tree = tree();
+		assert_eq!(tree.lowest_common_ancestor(TokenId(1), TokenId(6)), None);
+		assert_eq!(tree.path_between(TokenId(6), TokenId(10)), None);
+		// the ascent stops before closing the cycle
+		assert_eq!(tree.path_between(TokenId(8), TokenId(9)).unwrap().to_string(), "↑dep");
+		assert_eq!(tree.lowest_common_ancestor(TokenId(8), TokenId(1)), None);
+	}
+}
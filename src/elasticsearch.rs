@@ -0,0 +1,232 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) collections as the NDJSON body of an
+//! [Elasticsearch bulk request](https://www.elastic.co/guide/en/elasticsearch/reference/current/docs-bulk.html),
+//! together with the index mappings of the fields written.
+//!
+//! Every document becomes one Elasticsearch document with its metadata, its text, its entities as nested objects,
+//! and optionally its tokens, so that a corpus is searched e.g. for documents mentioning an entity of some type.
+
+use serde_json::{json, Map, Value};
+
+use crate::{Document, Meta, Token, JSONNLP};
+
+/// This enum selects how the tokens of documents are indexed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenMapping {
+	/// do not index the tokens.
+	#[default]
+	None,
+	/// index every token as a nested object, so that queries can match the lemma and the POS of the same token.
+	Nested,
+	/// index the values of the token fields as arrays, e.g. all lemmas in `tokens.lemma`, which is smaller and
+	/// faster to query, but does not keep which values belong to the same token.
+	Flattened,
+}
+
+/// This struct contains the options for the Elasticsearch export. By default the metadata, text, and entities of
+/// the documents are indexed into the index `jsonnlp`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElasticsearchOptions {
+	/// the index the documents are written to, or empty to leave it to the URL of the bulk request.
+	pub index: String,
+	/// the prefix of the `_id` of the documents, e.g. the name of the corpus, so that the documents of several
+	/// collections can share an index.
+	pub id_prefix: String,
+	/// the analyzer of the document text, e.g. `english`, or empty for the default analyzer of the index.
+	pub analyzer: String,
+	/// index the title, author, date, source, and language of the documents.
+	pub meta: bool,
+	/// index the document text.
+	pub text: bool,
+	/// index the entities as nested objects with their type, label, URL, text, and character offsets.
+	pub entities: bool,
+	/// index the tokens with their text, lemma, POS tags, and IOB tag.
+	pub tokens: TokenMapping,
+}
+
+impl Default for ElasticsearchOptions {
+	fn default() -> Self {
+		ElasticsearchOptions {
+			index: "jsonnlp".to_string(),
+			id_prefix: String::new(),
+			analyzer: String::new(),
+			meta: true,
+			text: true,
+			entities: true,
+			tokens: TokenMapping::None,
+		}
+	}
+}
+
+impl ElasticsearchOptions {
+	/// returns the mapping of a text field, with the analyzer if one is given.
+	fn text_field(&self) -> Value {
+		if self.analyzer.is_empty() {
+			json!({ "type": "text" })
+		} else {
+			json!({ "type": "text", "analyzer": self.analyzer })
+		}
+	}
+
+	/// returns the body of the request creating the index, with the mappings of the fields written by
+	/// to_elasticsearch_bulk() with these options.
+	pub fn mapping(&self) -> Value {
+		let keyword = json!({ "type": "keyword" });
+		let long = json!({ "type": "long" });
+		let mut properties = Map::new();
+		properties.insert("id".to_string(), long.clone());
+		if self.meta {
+			properties.insert("title".to_string(), self.text_field());
+			for field in &["author", "date", "source", "language"] {
+				properties.insert(field.to_string(), keyword.clone());
+			}
+		}
+		if self.text {
+			properties.insert("text".to_string(), self.text_field());
+		}
+		if self.entities {
+			properties.insert(
+				"entities".to_string(),
+				json!({
+					"type": "nested",
+					"properties": {
+						"id": long,
+						"type": keyword,
+						"label": keyword,
+						"url": keyword,
+						"text": { "type": "text", "fields": { "keyword": keyword } },
+						"begin": long,
+						"end": long,
+						"sentiment": keyword,
+					}
+				}),
+			);
+		}
+		let token_properties = json!({
+			"text": keyword,
+			"lemma": keyword,
+			"upos": keyword,
+			"xpos": keyword,
+			"entity_iob": keyword,
+		});
+		match self.tokens {
+			TokenMapping::None => {}
+			TokenMapping::Nested => {
+				let mut token_properties = token_properties;
+				for field in &["id", "sentence", "begin", "end"] {
+					token_properties[field] = long.clone();
+				}
+				properties.insert("tokens".to_string(), json!({ "type": "nested", "properties": token_properties }));
+			}
+			TokenMapping::Flattened => {
+				properties.insert("tokens".to_string(), json!({ "properties": token_properties }));
+			}
+		}
+		json!({ "mappings": { "properties": properties } })
+	}
+}
+
+/// inserts a string into an object unless it is empty.
+fn insert(object: &mut Map<String, Value>, key: &str, value: &str) {
+	if !value.is_empty() {
+		object.insert(key.to_string(), Value::String(value.to_string()));
+	}
+}
+
+/// returns the Elasticsearch document of a Document, with the metadata of the collection for the fields that the
+/// document's metadata leaves empty.
+fn source(doc: &Document, meta: &Meta, options: &ElasticsearchOptions) -> Value {
+	let mut out = Map::new();
+	out.insert("id".to_string(), json!(doc.id));
+	if options.meta {
+		let pick = |own: &str, shared: &str| if own.is_empty() { shared.to_string() } else { own.to_string() };
+		insert(&mut out, "title", &pick(&doc.meta.title, &meta.title));
+		insert(&mut out, "author", &pick(&doc.meta.author, &meta.author));
+		insert(&mut out, "date", &pick(&doc.meta.date, &meta.date));
+		insert(&mut out, "source", &pick(&doc.meta.source, &meta.source));
+		insert(&mut out, "language", &pick(doc.meta.language.as_str(), meta.language.as_str()));
+	}
+	if options.text {
+		insert(&mut out, "text", &doc.text);
+	}
+	if options.entities {
+		let entities: Vec<Value> = doc
+			.entities
+			.iter()
+			.map(|e| {
+				let mut entity = Map::new();
+				entity.insert("id".to_string(), json!(e.id));
+				insert(&mut entity, "type", &e.etype);
+				insert(&mut entity, "label", &e.label);
+				insert(&mut entity, "url", &e.url);
+				insert(&mut entity, "text", doc.entity_text(e).unwrap_or_default());
+				if let Some((begin, end)) = doc.char_span(|id| e.covers(id)) {
+					entity.insert("begin".to_string(), json!(begin));
+					entity.insert("end".to_string(), json!(end));
+				}
+				insert(&mut entity, "sentiment", &e.sentiment);
+				Value::Object(entity)
+			})
+			.collect();
+		out.insert("entities".to_string(), Value::Array(entities));
+	}
+	match options.tokens {
+		TokenMapping::None => {}
+		TokenMapping::Nested => {
+			let tokens: Vec<Value> = doc
+				.token_list
+				.iter()
+				.map(|t| {
+					let mut token = Map::new();
+					token.insert("id".to_string(), json!(t.id));
+					token.insert("sentence".to_string(), json!(t.sentence_id));
+					insert(&mut token, "text", &t.text);
+					insert(&mut token, "lemma", &t.lemma);
+					insert(&mut token, "upos", &t.upos);
+					insert(&mut token, "xpos", &t.xpos);
+					insert(&mut token, "entity_iob", &t.entity_iob);
+					if let (Some(begin), Some(end)) = (t.char_offset_begin, t.char_offset_end) {
+						token.insert("begin".to_string(), json!(begin));
+						token.insert("end".to_string(), json!(end));
+					}
+					Value::Object(token)
+				})
+				.collect();
+			out.insert("tokens".to_string(), Value::Array(tokens));
+		}
+		TokenMapping::Flattened => {
+			let column = |field: fn(&Token) -> &str| -> Value {
+				doc.token_list.iter().map(field).filter(|v| !v.is_empty()).collect::<Vec<_>>().into()
+			};
+			out.insert(
+				"tokens".to_string(),
+				json!({
+					"text": column(|t| &t.text),
+					"lemma": column(|t| &t.lemma),
+					"upos": column(|t| &t.upos),
+					"xpos": column(|t| &t.xpos),
+					"entity_iob": column(|t| &t.entity_iob),
+				}),
+			);
+		}
+	}
+	Value::Object(out)
+}
+
+/// This function converts the documents of a collection to the NDJSON body of an Elasticsearch bulk request,
+/// with an `index` action followed by the document for every Document. The `_id` of a document is its ID after the
+/// ID prefix of the options, so that indexing a collection again replaces its documents. The index should be
+/// created with the mappings of ElasticsearchOptions::mapping() beforehand, since the entities and tokens are only
+/// searchable as nested objects with these mappings.
+pub fn to_elasticsearch_bulk(j: &JSONNLP, options: &ElasticsearchOptions) -> String {
+	let mut out = String::new();
+	for doc in &j.docs {
+		let mut action = Map::new();
+		insert(&mut action, "_index", &options.index);
+		action.insert("_id".to_string(), Value::String(format!("{}{}", options.id_prefix, doc.id)));
+		out.push_str(&json!({ "index": action }).to_string());
+		out.push('\n');
+		out.push_str(&source(doc, &j.meta, options).to_string());
+		out.push('\n');
+	}
+	out
+}
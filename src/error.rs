@@ -0,0 +1,165 @@
+//! Error types returned by the [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) functions.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// This enum contains the errors that can occur when reading, writing, or checking JSON-NLP data.
+#[derive(Debug)]
+pub enum JsonNlpError {
+	/// reading from or writing to a file or stream failed.
+	Io(io::Error),
+	/// the input is not valid JSON or does not match the JSON-NLP structure.
+	Parse(ParseError),
+	/// the JSON-NLP data could not be serialized.
+	Serialize(serde_json::Error),
+	/// the data is well-formed but violates a JSON-NLP constraint.
+	Validation(String),
+	/// input in another annotation format, e.g. CoNLL-U, is malformed.
+	Format(String),
+	/// strict parsing found properties that are not part of the JSON-NLP model, given as JSON pointers.
+	UnknownFields(Vec<String>),
+	/// the input exceeds one of the configured resource limits.
+	LimitExceeded(LimitError),
+}
+
+/// This struct contains a parse error together with the location of the offending element.
+#[derive(Debug)]
+pub struct ParseError {
+	/// the JSON pointer of the element that failed to parse, e.g. `/docs/0/tokenList/412/features`.
+	pub pointer: String,
+	/// the underlying serde_json error.
+	pub error: serde_json::Error,
+}
+
+impl ParseError {
+	/// creates a new ParseError from a serde_json error and the JSON pointer of the failing element.
+	pub fn new(pointer: String, error: serde_json::Error) -> Self {
+		ParseError { pointer, error }
+	}
+
+	/// returns the one-based line number of the error in the input.
+	pub fn line(&self) -> usize {
+		self.error.line()
+	}
+
+	/// returns the one-based column number of the error in the input.
+	pub fn column(&self) -> usize {
+		self.error.column()
+	}
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.pointer.is_empty() {
+			write!(f, "{}", self.error)
+		} else {
+			write!(f, "{} (at {})", self.error, self.pointer)
+		}
+	}
+}
+
+/// This enum names the resource limits of parsing, see Limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+	/// the number of documents of a collection.
+	Docs,
+	/// the number of tokens of all documents of a collection.
+	Tokens,
+	/// the length of a string in bytes.
+	StringLength,
+	/// the nesting depth of objects and arrays.
+	Depth,
+}
+
+/// This struct contains the limit that was exceeded and where.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LimitError {
+	/// the exceeded limit.
+	pub limit: Limit,
+	/// the configured maximum.
+	pub max: usize,
+	/// the byte offset in the input at which the limit was exceeded.
+	pub offset: usize,
+}
+
+impl fmt::Display for LimitError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let what = match self.limit {
+			Limit::Docs => "documents",
+			Limit::Tokens => "tokens",
+			Limit::StringLength => "bytes per string",
+			Limit::Depth => "nesting levels",
+		};
+		write!(f, "more than {} {} at byte {}", self.max, what, self.offset)
+	}
+}
+
+/// returns the JSON pointer of a path of serde_path_to_error.
+pub(crate) fn pointer(path: &serde_path_to_error::Path) -> String {
+	let mut pointer = String::new();
+	for segment in path.iter() {
+		match segment {
+			serde_path_to_error::Segment::Seq { index } => pointer.push_str(&format!("/{}", index)),
+			serde_path_to_error::Segment::Map { key } => pointer.push_str(&format!("/{}", key.replace('~', "~0").replace('/', "~1"))),
+			serde_path_to_error::Segment::Enum { variant } => pointer.push_str(&format!("/{}", variant)),
+			serde_path_to_error::Segment::Unknown => {}
+		}
+	}
+	pointer
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for ParseError {
+	fn from(e: serde_path_to_error::Error<serde_json::Error>) -> Self {
+		ParseError::new(pointer(e.path()), e.into_inner())
+	}
+}
+
+/// A Result type using JsonNlpError.
+pub type Result<T> = std::result::Result<T, JsonNlpError>;
+
+impl fmt::Display for JsonNlpError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			JsonNlpError::Io(e) => write!(f, "I/O error: {}", e),
+			JsonNlpError::Parse(e) => write!(f, "parse error: {}", e),
+			JsonNlpError::Serialize(e) => write!(f, "serialization error: {}", e),
+			JsonNlpError::Validation(msg) => write!(f, "validation error: {}", msg),
+			JsonNlpError::Format(msg) => write!(f, "format error: {}", msg),
+			JsonNlpError::UnknownFields(fields) => write!(f, "unknown fields: {}", fields.join(", ")),
+			JsonNlpError::LimitExceeded(e) => write!(f, "limit exceeded: {}", e),
+		}
+	}
+}
+
+impl Error for JsonNlpError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			JsonNlpError::Io(e) => Some(e),
+			JsonNlpError::Parse(e) => Some(&e.error),
+			JsonNlpError::Serialize(e) => Some(e),
+			JsonNlpError::Validation(_)
+			| JsonNlpError::Format(_)
+			| JsonNlpError::UnknownFields(_)
+			| JsonNlpError::LimitExceeded(_) => None,
+		}
+	}
+}
+
+impl From<io::Error> for JsonNlpError {
+	fn from(e: io::Error) -> Self {
+		JsonNlpError::Io(e)
+	}
+}
+
+impl From<serde_json::Error> for JsonNlpError {
+	fn from(e: serde_json::Error) -> Self {
+		JsonNlpError::Parse(ParseError::new(String::new(), e))
+	}
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for JsonNlpError {
+	fn from(e: serde_path_to_error::Error<serde_json::Error>) -> Self {
+		JsonNlpError::Parse(e.into())
+	}
+}
@@ -0,0 +1,22 @@
+//! Serialization of the optional token features, which are only allocated when a token has any.
+//! Absent and empty feature objects are read as None, and None is written as an empty object.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::TokenFeatures;
+
+/// returns the empty features of tokens without features.
+pub(crate) fn empty() -> &'static TokenFeatures {
+	static EMPTY: OnceLock<TokenFeatures> = OnceLock::new();
+	EMPTY.get_or_init(TokenFeatures::default)
+}
+
+pub(crate) fn serialize<S: Serializer>(features: &Option<Box<TokenFeatures>>, serializer: S) -> Result<S::Ok, S::Error> {
+	features.as_deref().unwrap_or(empty()).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Box<TokenFeatures>>, D::Error> {
+	Ok(Option::<TokenFeatures>::deserialize(deserializer)?.and_then(TokenFeatures::boxed))
+}
@@ -0,0 +1,178 @@
+//! A C API for embedding this crate in other runtimes, e.g. C and C++ programs or Java via JNI. The declarations
+//! are in `include/jsonnlp.h`, which is generated by [cbindgen](https://github.com/mozilla/cbindgen) with
+//! `cbindgen --config cbindgen.toml --output include/jsonnlp.h src/ffi.rs`.
+//!
+//! Collections are opaque handles created by jsonnlp_parse() or jsonnlp_parse_file() and released with
+//! jsonnlp_free(). Strings returned by the API are NUL-terminated UTF-8 and must be released with
+//! jsonnlp_string_free().
+//!
+//! Panics do not unwind into the caller: a function that panics returns like on failure, with the panic message as
+//! error where the function reports errors.
+
+use std::any::Any;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::{Result, JSONNLP};
+
+/// returns a string allocated for the caller, or NULL if it contains a NUL character.
+fn into_c_string(s: String) -> *mut c_char {
+	CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// returns the string of a C string, or an error message if it is NULL or not UTF-8.
+unsafe fn from_c_string<'a>(s: *const c_char, name: &str) -> std::result::Result<&'a str, String> {
+	if s.is_null() {
+		return Err(format!("{} is NULL", name));
+	}
+	CStr::from_ptr(s).to_str().map_err(|e| format!("{} is not UTF-8: {}", name, e))
+}
+
+/// returns the message of a panic.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+	let msg = match payload.downcast::<String>() {
+		Ok(msg) => *msg,
+		Err(payload) => payload.downcast_ref::<&str>().map_or("unknown error", |msg| msg).to_string(),
+	};
+	format!("panic: {}", msg)
+}
+
+/// returns the result of f, or the panic message as error if it panics.
+fn catch<T>(f: impl FnOnce() -> std::result::Result<T, String>) -> std::result::Result<T, String> {
+	panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| Err(panic_message(payload)))
+}
+
+/// returns a handle of a parsed collection, or NULL with the error message stored in error if it is not NULL.
+/// A panic while parsing is an error.
+unsafe fn handle(
+	f: impl FnOnce() -> std::result::Result<Result<JSONNLP>, String>,
+	error: *mut *mut c_char,
+) -> *mut JSONNLP {
+	match catch(|| f().and_then(|j| j.map_err(|e| e.to_string()))) {
+		Ok(j) => Box::into_raw(Box::new(j)),
+		Err(msg) => {
+			if !error.is_null() {
+				*error = into_c_string(msg);
+			}
+			ptr::null_mut()
+		}
+	}
+}
+
+/// parses a string containing JSON-NLP and returns the handle of the collection. On failure, it returns NULL and,
+/// if `error` is not NULL, stores an error message in `*error`, which must be released with jsonnlp_string_free().
+///
+/// # Safety
+///
+/// `json` must be NULL or a NUL-terminated string, and `error` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_parse(json: *const c_char, error: *mut *mut c_char) -> *mut JSONNLP {
+	handle(|| from_c_string(json, "json").map(crate::from_string), error)
+}
+
+/// reads a JSON-NLP file and returns the handle of the collection, see jsonnlp_parse().
+///
+/// # Safety
+///
+/// `path` must be NULL or a NUL-terminated string, and `error` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_parse_file(path: *const c_char, error: *mut *mut c_char) -> *mut JSONNLP {
+	handle(|| from_c_string(path, "path").map(crate::from_file), error)
+}
+
+/// validates a collection and returns the number of problems found. If `report` is not NULL, the problems are
+/// stored in `*report` as one line per problem, to be released with jsonnlp_string_free(). A panic while validating
+/// counts as one problem with the panic message.
+///
+/// # Safety
+///
+/// `j` must be NULL or a handle that has not been freed, and `report` must be NULL or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_validate(j: *const JSONNLP, report: *mut *mut c_char) -> usize {
+	let j = match j.as_ref() {
+		Some(j) => j,
+		None => return 0,
+	};
+	let lines = catch(|| Ok(j.validate().issues.iter().map(ToString::to_string).collect()))
+		.unwrap_or_else(|msg| vec![msg]);
+	if !report.is_null() {
+		*report = into_c_string(lines.join("\n"));
+	}
+	lines.len()
+}
+
+/// returns the JSON of a collection, indented with two spaces if `pretty` is not 0, or NULL if `j` is NULL, the
+/// collection cannot be serialized, or serializing panics. The string must be released with jsonnlp_string_free().
+///
+/// # Safety
+///
+/// `j` must be NULL or a handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_serialize(j: *const JSONNLP, pretty: c_int) -> *mut c_char {
+	let j = match j.as_ref() {
+		Some(j) => j,
+		None => return ptr::null_mut(),
+	};
+	let json = catch(|| {
+		let json = if pretty != 0 { crate::to_string_pretty(j) } else { crate::get_json(j) };
+		json.map_err(|e| e.to_string())
+	});
+	json.map_or(ptr::null_mut(), into_c_string)
+}
+
+/// returns the number of documents of a collection, or 0 if `j` is NULL.
+///
+/// # Safety
+///
+/// `j` must be NULL or a handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_doc_count(j: *const JSONNLP) -> usize {
+	catch(|| Ok(j.as_ref().map_or(0, |j| j.docs.len()))).unwrap_or(0)
+}
+
+/// releases a collection. Passing NULL has no effect.
+///
+/// # Safety
+///
+/// `j` must be NULL or a handle that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_free(j: *mut JSONNLP) {
+	if !j.is_null() {
+		let _ = catch(|| {
+			drop(Box::from_raw(j));
+			Ok(())
+		});
+	}
+}
+
+/// releases a string returned by the API. Passing NULL has no effect.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by the API that has not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jsonnlp_string_free(s: *mut c_char) {
+	if !s.is_null() {
+		let _ = catch(|| {
+			drop(CString::from_raw(s));
+			Ok(())
+		});
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn panics_are_errors() {
+		let mut error = ptr::null_mut();
+		let j = unsafe { handle(|| panic!("parser bug {}", 1), &mut error) };
+		assert!(j.is_null());
+		assert_eq!(unsafe { CStr::from_ptr(error) }.to_str(), Ok("panic: parser bug 1"));
+		unsafe { jsonnlp_string_free(error) };
+		assert_eq!(catch::<()>(|| panic!("static")), Err("panic: static".to_string()));
+	}
+}
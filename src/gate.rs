@@ -0,0 +1,248 @@
+//! Conversion of [GATE](https://gate.ac.uk/) standoff XML documents to [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+use crate::{
+	Attribute, Document, DocumentId, Entity, EntityId, JsonNlpError, RawId, Result, Sentence, SentenceId, Token,
+	TokenId,
+};
+
+/// the annotation types that are not converted to entities.
+const LAYOUT_TYPES: [&str; 4] = ["SpaceToken", "Split", "Lookup", "Paragraph"];
+
+/// This struct contains an annotation with its character offsets and features.
+struct GateAnnotation<'a> {
+	id: &'a str,
+	atype: &'a str,
+	set: &'a str,
+	begin: u64,
+	end: u64,
+	features: Vec<(String, String)>,
+}
+
+impl GateAnnotation<'_> {
+	fn feature(&self, name: &str) -> Option<&str> {
+		self.features.iter().find(|f| f.0 == name).map(|f| f.1.as_str())
+	}
+}
+
+/// returns the text of the named child element.
+fn child_text(node: roxmltree::Node, name: &str) -> String {
+	node.children().find(|c| c.has_tag_name(name)).map(|c| {
+		c.descendants().filter(|d| d.is_text()).filter_map(|d| d.text()).collect()
+	}).unwrap_or_default()
+}
+
+/// returns the name and value of the `<Feature>` children of a node.
+fn features(node: roxmltree::Node) -> Vec<(String, String)> {
+	node.children().filter(|c| c.has_tag_name("Feature")).map(|f| (child_text(f, "Name"), child_text(f, "Value"))).collect()
+}
+
+/// This function converts a GATE XML document to a Document. The text is taken from `<TextWithNodes>`, and the
+/// annotations of all annotation sets except `Original markups` are converted: `Token` annotations become tokens
+/// with the `string`, `category`, and `root` features as text, XPOS tag, and lemma and the other features in their
+/// `extra` map, `Sentence` annotations become sentences, and annotations of other types, e.g. `Person` or
+/// `Location`, become entities of the tokens they overlap, with their features as attributes and their GATE ID in
+/// the `extra` map as `gateId`. Whitespace, sentence split, gazetteer lookup, and paragraph annotations are skipped.
+/// Offsets count characters, and the `gate.SourceURL` document feature becomes the source of the metadata.
+pub fn from_gate_xml(xml: &str) -> Result<Document> {
+	let error = |msg: String| JsonNlpError::Format(format!("GATE XML: {}", msg));
+	let tree = roxmltree::Document::parse(xml).map_err(|e| error(e.to_string()))?;
+	let root = tree.root_element();
+	if !root.has_tag_name("GateDocument") {
+		return Err(error(format!("unexpected root element <{}>", root.tag_name().name())));
+	}
+
+	let mut doc = Document { id: DocumentId(1), ..Default::default() };
+	// the character offset of every node
+	let mut nodes: HashMap<&str, u64> = HashMap::new();
+	let mut length = 0;
+	let text = root.children().find(|c| c.has_tag_name("TextWithNodes")).ok_or_else(|| error("missing <TextWithNodes>".to_string()))?;
+	for c in text.children() {
+		if c.has_tag_name("Node") {
+			if let Some(id) = c.attribute("id") {
+				nodes.insert(id, length);
+			}
+		} else if let Some(t) = c.text().filter(|_| c.is_text()) {
+			doc.text.push_str(t);
+			length += t.chars().count() as u64;
+		}
+	}
+
+	let mut document_features = Map::new();
+	if let Some(f) = root.children().find(|c| c.has_tag_name("GateDocumentFeatures")) {
+		for (name, value) in features(f) {
+			match name.as_str() {
+				"gate.SourceURL" => doc.meta.source = value,
+				_ => {
+					document_features.insert(name, Value::String(value));
+				}
+			}
+		}
+	}
+	if !document_features.is_empty() {
+		doc.extra.insert("features".to_string(), Value::Object(document_features));
+	}
+
+	let mut annotations = Vec::new();
+	for set in root.children().filter(|c| c.has_tag_name("AnnotationSet")) {
+		let set_name = set.attribute("Name").unwrap_or_default();
+		if set_name == "Original markups" {
+			continue;
+		}
+		for a in set.children().filter(|c| c.has_tag_name("Annotation")) {
+			let id = a.attribute("Id").unwrap_or_default();
+			let offset = |attribute: &str| -> Result<u64> {
+				let node = a.attribute(attribute).unwrap_or_default();
+				nodes.get(node).copied().ok_or_else(|| error(format!("annotation {} refers to the missing node {:?}", id, node)))
+			};
+			annotations.push(GateAnnotation {
+				id,
+				atype: a.attribute("Type").unwrap_or_default(),
+				set: set_name,
+				begin: offset("StartNode")?,
+				end: offset("EndNode")?,
+				features: features(a),
+			});
+		}
+	}
+	annotations.sort_by_key(|a| (a.begin, std::cmp::Reverse(a.end)));
+
+	let sentences: Vec<&GateAnnotation> = annotations.iter().filter(|a| a.atype == "Sentence").collect();
+	let sentence_of = |begin: u64| sentences.iter().position(|s| begin >= s.begin && begin < s.end).map(|i| i as RawId + 1);
+	for a in annotations.iter().filter(|a| a.atype == "Token") {
+		let mut token = Token {
+			id: TokenId(doc.token_list.len() as RawId + 1),
+			sentence_id: SentenceId(sentence_of(a.begin).unwrap_or(0)),
+			text: match a.feature("string") {
+				Some(s) => s.to_string(),
+				None => doc.span_text(a.begin, a.end).unwrap_or_default().to_string(),
+			},
+			xpos: a.feature("category").unwrap_or_default().to_string(),
+			lemma: a.feature("root").unwrap_or_default().to_string(),
+			char_offset_begin: Some(a.begin),
+			char_offset_end: Some(a.end),
+			..Default::default()
+		};
+		for (name, value) in &a.features {
+			if !matches!(name.as_str(), "string" | "category" | "root") {
+				token.extra.insert(name.clone(), Value::String(value.clone()));
+			}
+		}
+		doc.token_list.push(token);
+	}
+
+	for (i, s) in sentences.iter().enumerate() {
+		let id = SentenceId(i as RawId + 1);
+		let tokens: Vec<TokenId> = doc.token_list.iter().filter(|t| t.sentence_id == id).map(|t| t.id).collect();
+		let mut sentence = Sentence {
+			id,
+			text: doc.span_text(s.begin, s.end).unwrap_or_default().to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			..Default::default()
+		};
+		sentence.extra.insert("gateId".to_string(), Value::String(s.id.to_string()));
+		doc.sentences.push(sentence);
+	}
+
+	for a in annotations.iter().filter(|a| !matches!(a.atype, "Token" | "Sentence") && !LAYOUT_TYPES.contains(&a.atype)) {
+		let tokens = doc.tokens_overlapping(&[(a.begin, a.end)]);
+		let mut entity = Entity {
+			id: EntityId(doc.entities.len() as RawId + 1),
+			label: doc.span_text(a.begin, a.end).unwrap_or_default().to_string(),
+			etype: a.atype.to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			attributes: a.features.iter().map(|(lab, val)| Attribute { lab: lab.clone(), val: val.clone(), ..Default::default() }).collect(),
+			..Default::default()
+		};
+		entity.extra.insert("gateId".to_string(), Value::String(a.id.to_string()));
+		if !a.set.is_empty() {
+			entity.extra.insert("annotationSet".to_string(), Value::String(a.set.to_string()));
+		}
+		doc.entities.push(entity);
+	}
+
+	if !doc.entities.is_empty() {
+		for t in doc.token_list.iter_mut() {
+			match doc.entities.iter().find(|e| e.tokens.contains(&t.id)) {
+				Some(e) => {
+					t.entity_iob = if e.tokens.first() == Some(&t.id) { "B" } else { "I" }.to_string();
+					t.entity = e.etype.clone();
+				}
+				None => t.entity_iob = "O".to_string(),
+			}
+		}
+	}
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const GATE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<GateDocument version="3">
+<GateDocumentFeatures>
+<Feature><Name>gate.SourceURL</Name><Value>file:/m.txt</Value></Feature>
+<Feature><Name>MimeType</Name><Value>text/plain</Value></Feature>
+</GateDocumentFeatures>
+<TextWithNodes><Node id="0"/>Mary<Node id="4"/> <Node id="5"/>sleeps<Node id="11"/>.<Node id="12"/></TextWithNodes>
+<AnnotationSet>
+<Annotation Id="1" Type="Token" StartNode="0" EndNode="4">
+<Feature><Name>string</Name><Value>Mary</Value></Feature><Feature><Name>category</Name><Value>NNP</Value></Feature>
+<Feature><Name>orth</Name><Value>upperInitial</Value></Feature>
+</Annotation>
+<Annotation Id="2" Type="SpaceToken" StartNode="4" EndNode="5"/>
+<Annotation Id="3" Type="Token" StartNode="5" EndNode="11">
+<Feature><Name>root</Name><Value>sleep</Value></Feature>
+</Annotation>
+<Annotation Id="4" Type="Token" StartNode="11" EndNode="12"/>
+<Annotation Id="5" Type="Sentence" StartNode="0" EndNode="12"/>
+</AnnotationSet>
+<AnnotationSet Name="NE">
+<Annotation Id="6" Type="Person" StartNode="0" EndNode="4">
+<Feature><Name>gender</Name><Value>female</Value></Feature>
+</Annotation>
+</AnnotationSet>
+<AnnotationSet Name="Original markups">
+<Annotation Id="7" Type="paragraph" StartNode="0" EndNode="12"/>
+</AnnotationSet>
+</GateDocument>"#;
+
+	#[test]
+	fn gate_import() {
+		let doc = from_gate_xml(GATE).expect("valid GATE XML");
+		assert_eq!((doc.text.as_str(), doc.meta.source.as_str()), ("Mary sleeps.", "file:/m.txt"));
+		assert_eq!(doc.extra["features"]["MimeType"], "text/plain");
+		let tokens: Vec<_> =
+			doc.token_list.iter().map(|t| (t.text.as_str(), t.xpos.as_str(), t.lemma.as_str())).collect();
+		assert_eq!(tokens, [("Mary", "NNP", ""), ("sleeps", "", "sleep"), (".", "", "")]);
+		assert_eq!(doc.token_list[0].extra["orth"], "upperInitial");
+		assert_eq!((doc.token_list[2].char_offset_begin, doc.token_list[2].char_offset_end), (Some(11), Some(12)));
+		assert_eq!((doc.sentences[0].text.as_str(), doc.sentences[0].tokens.len()), ("Mary sleeps.", 3));
+
+		assert_eq!(doc.entities.len(), 1);
+		let e = &doc.entities[0];
+		assert_eq!((e.etype.as_str(), e.label.as_str(), e.tokens.as_slice()), ("Person", "Mary", &[TokenId(1)][..]));
+		assert_eq!((e.attributes[0].lab.as_str(), e.attributes[0].val.as_str()), ("gender", "female"));
+		assert_eq!((&e.extra["gateId"], &e.extra["annotationSet"]), (&Value::from("6"), &Value::from("NE")));
+		let iob: Vec<_> = doc.token_list.iter().map(|t| t.entity_iob.as_str()).collect();
+		assert_eq!(iob, ["B", "O", "O"]);
+	}
+
+	#[test]
+	fn gate_errors() {
+		let e = from_gate_xml(&GATE.replace(r#"EndNode="11""#, r#"EndNode="10""#)).unwrap_err();
+		let message = "GATE XML: annotation 3 refers to the missing node \"10\"";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		assert!(from_gate_xml("<Document/>").is_err());
+		assert!(from_gate_xml("<GateDocument/>").is_err());
+		assert!(from_gate_xml("<GateDocument>").is_err());
+	}
+}
@@ -0,0 +1,439 @@
+//! Conversion of [Google Cloud Natural Language](https://cloud.google.com/natural-language) `annotateText`
+//! responses to [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::conllu::parse_feats;
+use crate::offsets::{OffsetUnit, SpanLocator};
+use crate::{
+	Coreference, CoreferenceId, CoreferenceReferents, CoreferenceRepresentantive, Dependency, DependencyTree, Document,
+	DocumentId, Entity, EntityId, JsonNlpError, LanguageTag, RawId, Result, Sentence, SentenceId, Token, TokenId,
+};
+
+/// the Google tags that differ from the UPOS tags, with their UPOS tag.
+const UPOS: &[(&str, &str)] = &[("CONJ", "CCONJ"), ("PRT", "PART"), ("AFFIX", "X")];
+
+/// the values of a morphological property of Google with their UD values.
+type Values = &'static [(&'static str, &'static str)];
+
+/// the morphological properties of Google's `partOfSpeech` with their UD feature and values.
+const FEATURES: &[(&str, &str, Values)] = &[
+	("gender", "Gender", &[("MASCULINE", "Masc"), ("FEMININE", "Fem"), ("NEUTER", "Neut")]),
+	("number", "Number", &[("SINGULAR", "Sing"), ("PLURAL", "Plur"), ("DUAL", "Dual")]),
+	("person", "Person", &[("FIRST", "1"), ("SECOND", "2"), ("THIRD", "3")]),
+	("reciprocity", "Reflex", &[("RECIPROCAL", "Yes")]),
+	(
+		"case",
+		"Case",
+		&[
+			("ACCUSATIVE", "Acc"),
+			("ADVERBIAL", "Adv"),
+			("COMPLEMENTIVE", "Cmp"),
+			("DATIVE", "Dat"),
+			("GENITIVE", "Gen"),
+			("INSTRUMENTAL", "Ins"),
+			("LOCATIVE", "Loc"),
+			("NOMINATIVE", "Nom"),
+			("OBLIQUE", "Obl"),
+			("PARTITIVE", "Par"),
+			("PREPOSITIONAL", "Pre"),
+			("REFLEXIVE_CASE", "Rfl"),
+			("RELATIVE_CASE", "Rel"),
+			("VOCATIVE", "Voc"),
+		],
+	),
+	("tense", "Tense", &[("PAST", "Past"), ("PRESENT", "Pres"), ("FUTURE", "Fut"), ("PLUPERFECT", "Pqp")]),
+	(
+		"mood",
+		"Mood",
+		&[
+			("CONDITIONAL_MOOD", "Cnd"),
+			("IMPERATIVE", "Imp"),
+			("INDICATIVE", "Ind"),
+			("INTERROGATIVE", "Int"),
+			("JUSSIVE", "Jus"),
+			("SUBJUNCTIVE", "Sub"),
+		],
+	),
+	("voice", "Voice", &[("ACTIVE", "Act"), ("CAUSATIVE", "Cau"), ("PASSIVE", "Pass")]),
+	("aspect", "Aspect", &[("PERFECTIVE", "Perf"), ("IMPERFECTIVE", "Imp"), ("PROGRESSIVE", "Prog")]),
+];
+
+/// This struct contains the response of the `annotateText` method.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleResponse {
+	#[serde(default)]
+	sentences: Vec<GoogleSentence>,
+	#[serde(default)]
+	tokens: Vec<GoogleToken>,
+	#[serde(default)]
+	entities: Vec<GoogleEntity>,
+	#[serde(default)]
+	document_sentiment: Option<GoogleSentiment>,
+	#[serde(default)]
+	language: String,
+	#[serde(default)]
+	categories: Vec<Value>,
+}
+
+/// This struct contains a text span with its offset, which is -1 if the request's encoding type was `NONE`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleText {
+	content: String,
+	#[serde(default = "no_offset")]
+	begin_offset: i64,
+}
+
+fn no_offset() -> i64 {
+	-1
+}
+
+impl GoogleText {
+	fn offset(&self) -> Option<u64> {
+		u64::try_from(self.begin_offset).ok()
+	}
+}
+
+/// This struct contains the sentiment of a text span, with a score from -1 to 1 and its magnitude.
+#[derive(Deserialize)]
+struct GoogleSentiment {
+	#[serde(default)]
+	score: f64,
+	#[serde(default)]
+	magnitude: f64,
+}
+
+/// This struct contains a sentence with its sentiment.
+#[derive(Deserialize)]
+struct GoogleSentence {
+	text: GoogleText,
+	#[serde(default)]
+	sentiment: Option<GoogleSentiment>,
+}
+
+/// This struct contains a token with its POS tag and morphology, lemma, and dependency edge.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleToken {
+	text: GoogleText,
+	#[serde(default)]
+	part_of_speech: HashMap<String, String>,
+	#[serde(default)]
+	dependency_edge: Option<GoogleEdge>,
+	#[serde(default)]
+	lemma: String,
+}
+
+/// This struct contains the dependency edge of a token, with the index of its head in the token list.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleEdge {
+	#[serde(default)]
+	head_token_index: usize,
+	#[serde(default)]
+	label: String,
+}
+
+/// This struct contains an entity with its mentions.
+#[derive(Deserialize)]
+struct GoogleEntity {
+	#[serde(default)]
+	name: String,
+	#[serde(rename = "type", default)]
+	etype: String,
+	#[serde(default)]
+	metadata: HashMap<String, String>,
+	#[serde(default)]
+	salience: f64,
+	#[serde(default)]
+	mentions: Vec<GoogleMention>,
+	#[serde(default)]
+	sentiment: Option<GoogleSentiment>,
+}
+
+/// This struct contains a mention of an entity.
+#[derive(Deserialize)]
+struct GoogleMention {
+	text: GoogleText,
+	#[serde(rename = "type", default)]
+	mtype: String,
+	#[serde(default)]
+	sentiment: Option<GoogleSentiment>,
+}
+
+/// sets the sentiment of an annotation to the score, and keeps the magnitude in its `extra` map.
+fn sentiment(s: Option<&GoogleSentiment>, target: &mut String, extra: &mut Map<String, Value>) {
+	if let Some(s) = s {
+		*target = s.score.to_string();
+		extra.insert("sentimentMagnitude".to_string(), json!(s.magnitude));
+	}
+}
+
+/// returns the UD FEATS of the morphological properties of a token, skipping the unknown values.
+fn feats(pos: &HashMap<String, String>) -> String {
+	let mut feats: Vec<String> = FEATURES
+		.iter()
+		.filter_map(|(property, name, values)| {
+			let value = pos.get(*property)?;
+			let (_, ud) = values.iter().find(|(google, _)| google == value)?;
+			Some(format!("{}={}", name, ud))
+		})
+		.collect();
+	feats.sort();
+	feats.join("|")
+}
+
+/// This function converts the JSON response of Google Cloud Natural Language's `annotateText` method, or of one of
+/// the `analyzeEntities`, `analyzeSentiment`, or `analyzeSyntax` methods, to a Document with the text that was sent,
+/// which the response does not contain. `unit` is the request's `encodingType`, in which the response counts its
+/// offsets; spans whose offsets do not select their content, e.g. with the encoding type `NONE`, are searched in the
+/// text instead, tokens and sentences after the previous one and mentions from the start.
+///
+/// Tokens keep the Google tag as XPOS with the corresponding UPOS, where proper nouns become `PROPN`, their lemma,
+/// and their morphological properties as UD features. The dependency edges form one dependency tree per sentence,
+/// with the root attached to 0 and the Google labels. Every mention of an entity becomes an Entity with the name,
+/// type, and Wikipedia URL of the entity, and its salience, mention type, and remaining metadata in the `extra` map,
+/// and the mentions of an entity mentioned more than once form a coreference chain represented by the first proper
+/// mention. Sentiment scores become the `sentiment` of sentences and entities, with the magnitude in their `extra`
+/// map, and the document sentiment and categories are kept in the document's `extra` map.
+pub fn from_google_nl_json(text: &str, json: &str, unit: OffsetUnit) -> Result<Document> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let google: GoogleResponse = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let locator = SpanLocator::new(text, unit);
+	let mut doc = Document { id: DocumentId(1), text: text.to_string(), ..Default::default() };
+	if !google.language.is_empty() {
+		doc.meta.language = LanguageTag::new(&google.language)?;
+	}
+
+	// the character offset where every sentence begins
+	let mut sentence_begins = Vec::new();
+	let mut from = 0;
+	for (i, s) in google.sentences.iter().enumerate() {
+		let (begin, end) = locator.locate(&s.text.content, s.text.offset(), from).ok_or_else(|| {
+			JsonNlpError::Format(format!("sentence {} {:?} is not in the text", i + 1, s.text.content))
+		})?;
+		let mut sentence =
+			Sentence { id: SentenceId(i as RawId + 1), text: s.text.content.clone(), ..Default::default() };
+		sentiment(s.sentiment.as_ref(), &mut sentence.sentiment, &mut sentence.extra);
+		doc.sentences.push(sentence);
+		sentence_begins.push(begin);
+		from = end;
+	}
+
+	let mut from = 0;
+	for (i, t) in google.tokens.iter().enumerate() {
+		let (begin, end) = locator
+			.locate(&t.text.content, t.text.offset(), from)
+			.ok_or_else(|| JsonNlpError::Format(format!("token {} {:?} is not in the text", i + 1, t.text.content)))?;
+		let sentence = sentence_begins.iter().rposition(|b| *b <= begin).map_or(1, |s| s as RawId + 1);
+		let tag = t.part_of_speech.get("tag").map(String::as_str).unwrap_or_default();
+		let upos = match UPOS.iter().find(|(google, _)| *google == tag) {
+			Some((_, upos)) => upos,
+			None if tag == "NOUN" && t.part_of_speech.get("proper").is_some_and(|p| p == "PROPER") => "PROPN",
+			None if tag == "UNKNOWN" => "",
+			None => tag,
+		};
+		doc.token_list.push(Token {
+			id: TokenId(i as RawId + 1),
+			sentence_id: SentenceId(sentence),
+			text: t.text.content.clone(),
+			lemma: t.lemma.clone(),
+			xpos: tag.to_string(),
+			upos: upos.to_string(),
+			char_offset_begin: Some(begin),
+			char_offset_end: Some(end),
+			features: parse_feats(&feats(&t.part_of_speech)).boxed(),
+			..Default::default()
+		});
+		from = end;
+	}
+	for i in 1..doc.token_list.len() {
+		if doc.token_list[i].char_offset_begin == doc.token_list[i - 1].char_offset_end {
+			doc.token_list[i - 1].features_mut().spaceafter = Some(false);
+		}
+	}
+	if doc.sentences.is_empty() && !doc.token_list.is_empty() {
+		doc.sentences.push(Sentence { id: SentenceId(1), ..Default::default() });
+	}
+	for s in doc.sentences.iter_mut() {
+		s.tokens = doc.token_list.iter().filter(|t| t.sentence_id == s.id).map(|t| t.id).collect();
+		s.token_from = s.tokens.first().copied();
+		s.token_to = s.tokens.last().copied();
+		let mut tree = DependencyTree { sentence_id: Some(s.id), ..Default::default() };
+		for id in &s.tokens {
+			let i = (id.0 - 1) as usize;
+			if let Some(edge) = &google.tokens[i].dependency_edge {
+				tree.dependencies.push(Dependency {
+					lab: edge.label.clone(),
+					gov: TokenId(if edge.head_token_index == i { 0 } else { edge.head_token_index as RawId + 1 }),
+					dep: *id,
+					..Default::default()
+				});
+			}
+		}
+		if !tree.dependencies.is_empty() {
+			doc.dependency_trees.push(tree);
+		}
+	}
+
+	for e in &google.entities {
+		let mut mentions: Vec<(Vec<TokenId>, bool)> = Vec::new();
+		for m in &e.mentions {
+			let (begin, end) = match locator.locate(&m.text.content, m.text.offset(), 0) {
+				Some(span) => span,
+				None => continue,
+			};
+			let tokens: Vec<TokenId> = doc
+				.token_list
+				.iter()
+				.filter(|t| t.char_offset_begin.is_some_and(|b| b >= begin && b < end))
+				.map(|t| t.id)
+				.collect();
+			let mut entity = Entity {
+				id: EntityId(doc.entities.len() as RawId + 1),
+				label: e.name.clone(),
+				etype: e.etype.clone(),
+				url: e.metadata.get("wikipedia_url").cloned().unwrap_or_default(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens: tokens.clone(),
+				..Default::default()
+			};
+			entity.extra.insert("salience".to_string(), json!(e.salience));
+			if !m.mtype.is_empty() {
+				entity.extra.insert("mentionType".to_string(), json!(m.mtype));
+			}
+			for (key, value) in e.metadata.iter().filter(|(key, _)| *key != "wikipedia_url") {
+				entity.extra.insert(key.clone(), json!(value));
+			}
+			let s = m.sentiment.as_ref().or(e.sentiment.as_ref());
+			sentiment(s, &mut entity.sentiment, &mut entity.extra);
+			doc.entities.push(entity);
+			if !tokens.is_empty() {
+				mentions.push((tokens, m.mtype == "PROPER"));
+			}
+		}
+		if mentions.len() > 1 {
+			let representative = mentions.iter().position(|m| m.1).unwrap_or(0);
+			let (tokens, _) = mentions.remove(representative);
+			doc.coreferences.push(Coreference {
+				id: CoreferenceId(doc.coreferences.len() as RawId + 1),
+				representative: CoreferenceRepresentantive {
+					head: tokens.last().copied().unwrap_or_default(),
+					tokens,
+					..Default::default()
+				},
+				referents: mentions
+					.into_iter()
+					.map(|(tokens, _)| CoreferenceReferents {
+						head: tokens.last().copied().unwrap_or_default(),
+						tokens,
+						..Default::default()
+					})
+					.collect(),
+				..Default::default()
+			});
+		}
+	}
+	if !doc.entities.is_empty() {
+		for t in doc.token_list.iter_mut() {
+			t.entity_iob = "O".to_string();
+		}
+		for e in &doc.entities {
+			for (i, id) in e.tokens.iter().enumerate() {
+				if let Some(t) = doc.token_list.get_mut((id.0 - 1) as usize).filter(|t| t.entity_iob == "O") {
+					t.entity_iob = if i == 0 { "B" } else { "I" }.to_string();
+					t.entity = e.etype.clone();
+				}
+			}
+		}
+	}
+
+	if let Some(s) = &google.document_sentiment {
+		doc.extra.insert("sentiment".to_string(), json!({ "score": s.score, "magnitude": s.magnitude }));
+	}
+	if !google.categories.is_empty() {
+		doc.extra.insert("categories".to_string(), Value::Array(google.categories));
+	}
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TEXT: &str = "Zoë Ball lives in Köln. She sings.";
+
+	const RESPONSE: &str = r#"{"language":"en","documentSentiment":{"score":0.5,"magnitude":1.0},
+		"sentences":[{"text":{"content":"Zoë Ball lives in Köln.","beginOffset":0},"sentiment":{"score":0.25}},
+			{"text":{"content":"She sings.","beginOffset":26}}],
+		"tokens":[
+			{"text":{"content":"Zoë","beginOffset":0},"partOfSpeech":{"tag":"NOUN","proper":"PROPER"},
+				"dependencyEdge":{"headTokenIndex":1,"label":"NN"}},
+			{"text":{"content":"Ball","beginOffset":5},"partOfSpeech":{"tag":"NOUN","proper":"PROPER"},
+				"dependencyEdge":{"headTokenIndex":2,"label":"NSUBJ"}},
+			{"text":{"content":"lives","beginOffset":10},"partOfSpeech":{"tag":"VERB","tense":"PRESENT"},"lemma":"live",
+				"dependencyEdge":{"headTokenIndex":2,"label":"ROOT"}},
+			{"text":{"content":"in","beginOffset":16},"partOfSpeech":{"tag":"ADP"}},
+			{"text":{"content":"Köln","beginOffset":19},"partOfSpeech":{"tag":"NOUN","proper":"PROPER"}},
+			{"text":{"content":".","beginOffset":24},"partOfSpeech":{"tag":"PUNCT"}},
+			{"text":{"content":"She","beginOffset":26},
+				"partOfSpeech":{"tag":"PRON","gender":"FEMININE","person":"THIRD"}},
+			{"text":{"content":"sings"},"partOfSpeech":{"tag":"VERB"}},
+			{"text":{"content":".","beginOffset":35},"partOfSpeech":{"tag":"PRT"}}],
+		"entities":[
+			{"name":"Zoë Ball","type":"PERSON","salience":0.75,"mentions":[{"text":{"content":"She","beginOffset":26},
+				"type":"COMMON"},{"text":{"content":"Zoë Ball","beginOffset":0},"type":"PROPER"}]},
+			{"name":"Köln","type":"LOCATION",
+				"metadata":{"wikipedia_url":"https://en.wikipedia.org/wiki/Cologne","mid":"/m/x"},
+				"mentions":[{"text":{"content":"Köln","beginOffset":19},"type":"PROPER"}]}]}"#;
+
+	#[test]
+	fn google_import() {
+		let doc = from_google_nl_json(TEXT, RESPONSE, OffsetUnit::Utf8).expect("valid annotateText response");
+		assert_eq!((doc.meta.language.as_str(), doc.text.as_str()), ("en", TEXT));
+		let offsets: Vec<_> = doc.token_list.iter().map(|t| (t.char_offset_begin.unwrap(), t.sentence_id.0)).collect();
+		assert_eq!(offsets, [(0, 1), (4, 1), (9, 1), (15, 1), (18, 1), (22, 1), (24, 2), (28, 2), (33, 2)]);
+		let tags: Vec<_> = doc.token_list.iter().map(|t| t.upos.as_str()).collect();
+		assert_eq!(tags, ["PROPN", "PROPN", "VERB", "ADP", "PROPN", "PUNCT", "PRON", "VERB", "PART"]);
+		assert_eq!((doc.token_list[2].lemma.as_str(), doc.token_list[2].features().tense.as_str()), ("live", "Pres"));
+		let she = doc.token_list[6].features();
+		assert_eq!((she.gender.as_str(), she.person), ("Fem", Some(3)));
+		assert_eq!(doc.token_list[4].features().spaceafter, Some(false));
+
+		assert_eq!((doc.sentences[0].sentiment.as_str(), doc.sentences[1].tokens.len()), ("0.25", 3));
+		let heads: Vec<_> = doc.dependency_trees[0].dependencies.iter().map(|d| (d.gov.0, d.dep.0)).collect();
+		assert_eq!(heads, [(2, 1), (3, 2), (0, 3)]);
+
+		let entities: Vec<_> = doc.entities.iter().map(|e| (e.label.as_str(), e.tokens.clone())).collect();
+		assert_eq!(entities, [
+			("Zoë Ball", vec![TokenId(7)]),
+			("Zoë Ball", vec![TokenId(1), TokenId(2)]),
+			("Köln", vec![TokenId(5)]),
+		]);
+		assert_eq!((doc.entities[2].url.as_str(), &doc.entities[2].extra["mid"]), (
+			"https://en.wikipedia.org/wiki/Cologne",
+			&json!("/m/x"),
+		));
+		let c = &doc.coreferences[0];
+		assert_eq!((c.representative.head, c.referents[0].tokens.as_slice()), (TokenId(2), &[TokenId(7)][..]));
+		assert_eq!(doc.extra["sentiment"], json!({ "score": 0.5, "magnitude": 1.0 }));
+	}
+
+	#[test]
+	fn google_errors() {
+		let e = from_google_nl_json("Zoe Ball lives in Köln. She sings.", RESPONSE, OffsetUnit::Utf8).unwrap_err();
+		let message = "sentence 1 \"Zoë Ball lives in Köln.\" is not in the text";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		assert!(from_google_nl_json(TEXT, &RESPONSE.replace("\"sings\"", "\"sang\""), OffsetUnit::Utf8).is_err());
+		assert!(from_google_nl_json(TEXT, r#"{"tokens":[{"partOfSpeech":{}}]}"#, OffsetUnit::Utf8).is_err());
+	}
+}
@@ -0,0 +1,102 @@
+//! Export of the entity, relation, and triple layers of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP)
+//! documents as [GraphML](http://graphml.graphdrawing.org/), e.g. for exploring the graphs in Gephi or yEd.
+
+use crate::tei::escape_xml;
+use crate::Document;
+
+/// This struct declares a GraphML attribute of the nodes or edges.
+struct Key {
+	id: String,
+	domain: &'static str,
+	name: String,
+	kind: &'static str,
+}
+
+/// writes a `<data>` element unless the value is empty.
+fn data(out: &mut String, key: &str, value: &str) {
+	if !value.is_empty() {
+		out.push_str(&format!("      <data key=\"{}\">{}</data>\n", key, escape_xml(value)));
+	}
+}
+
+/// This function converts the entities and triples of a Document to a GraphML document. Every entity becomes a
+/// node `e1` etc. with the attributes `label`, `type`, `url`, `text`, and `sentiment`, plus one attribute per label
+/// of the entity attributes, and every triple with both entities becomes an edge `t1` etc. with the attributes
+/// `label`, `type`, and `url` of its relation and its `prob`. The graph is directed, and triples that are not
+/// directional become undirected edges.
+pub fn to_graphml(doc: &Document) -> String {
+	let mut keys = vec![
+		Key { id: "label".to_string(), domain: "node", name: "label".to_string(), kind: "string" },
+		Key { id: "type".to_string(), domain: "node", name: "type".to_string(), kind: "string" },
+		Key { id: "url".to_string(), domain: "node", name: "url".to_string(), kind: "string" },
+		Key { id: "text".to_string(), domain: "node", name: "text".to_string(), kind: "string" },
+		Key { id: "sentiment".to_string(), domain: "node", name: "sentiment".to_string(), kind: "string" },
+		Key { id: "rlabel".to_string(), domain: "edge", name: "label".to_string(), kind: "string" },
+		Key { id: "rtype".to_string(), domain: "edge", name: "type".to_string(), kind: "string" },
+		Key { id: "rurl".to_string(), domain: "edge", name: "url".to_string(), kind: "string" },
+		Key { id: "prob".to_string(), domain: "edge", name: "prob".to_string(), kind: "double" },
+	];
+	let mut attributes: Vec<&str> = Vec::new();
+	for a in doc.entities.iter().flat_map(|e| &e.attributes) {
+		if !attributes.contains(&a.lab.as_str()) {
+			attributes.push(&a.lab);
+			keys.push(Key {
+				id: format!("a{}", attributes.len()),
+				domain: "node",
+				name: a.lab.clone(),
+				kind: "string",
+			});
+		}
+	}
+
+	let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+	for k in &keys {
+		out.push_str(&format!(
+			"  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+			k.id,
+			k.domain,
+			escape_xml(&k.name),
+			k.kind
+		));
+	}
+	out.push_str(&format!("  <graph id=\"doc{}\" edgedefault=\"directed\">\n", doc.id));
+
+	for e in &doc.entities {
+		out.push_str(&format!("    <node id=\"e{}\">\n", e.id));
+		data(&mut out, "label", &e.label);
+		data(&mut out, "type", &e.etype);
+		data(&mut out, "url", &e.url);
+		data(&mut out, "text", doc.entity_text(e).unwrap_or(""));
+		data(&mut out, "sentiment", &e.sentiment);
+		for a in &e.attributes {
+			let i = attributes.iter().position(|&lab| lab == a.lab).unwrap_or_default();
+			data(&mut out, &format!("a{}", i + 1), &a.val);
+		}
+		out.push_str("    </node>\n");
+	}
+
+	for t in &doc.triples {
+		let (from, to) = match (t.from_entity, t.to_entity) {
+			(Some(from), Some(to)) => (from, to),
+			_ => continue,
+		};
+		if !doc.entities.iter().any(|e| e.id == from) || !doc.entities.iter().any(|e| e.id == to) {
+			continue;
+		}
+		let directed = if t.directional == Some(false) { " directed=\"false\"" } else { "" };
+		out.push_str(&format!("    <edge id=\"t{}\" source=\"e{}\" target=\"e{}\"{}>\n", t.id, from, to, directed));
+		if let Some(r) = t.rel.and_then(|id| doc.relations.iter().find(|r| r.id == id)) {
+			data(&mut out, "rlabel", &r.label);
+			data(&mut out, "rtype", &r.rtype);
+			data(&mut out, "rurl", &r.url);
+		}
+		if let Some(p) = t.prob {
+			data(&mut out, "prob", &p.get().to_string());
+		}
+		out.push_str("    </edge>\n");
+	}
+
+	out.push_str("  </graph>\n</graphml>\n");
+	out
+}
@@ -0,0 +1,223 @@
+//! [gRPC](https://grpc.io/) services exchanging [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) annotations,
+//! built with [tonic](https://docs.rs/tonic) and defined in `proto/jsonnlp_service.proto` with the messages of the
+//! [proto](crate::proto) module.
+//!
+//! The clients and servers of the services `AnnotateStore`, `Validate`, and `Convert` are generated by tonic-build
+//! into the modules `annotate_store_client`, `annotate_store_server`, etc. MemoryStore, Validator, and Converter
+//! implement the services, and serve() runs all three on an address. Messages that do not convert to the JSON-NLP
+//! model are rejected with `INVALID_ARGUMENT`.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{PoisonError, RwLock};
+
+use tonic::codegen::tokio_stream::{self, Stream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::convert::{read, write, Format};
+use crate::{proto, Document, JsonNlpError, ParseOptions, Result, JSONNLP};
+
+mod services;
+
+pub use services::*;
+
+/// This struct is the response of `AnnotateStore.Put` with the number of stored documents.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PutResponse {
+	#[prost(uint64, tag = "1")]
+	pub count: u64,
+}
+
+/// This struct is the request of `AnnotateStore.Get` and `AnnotateStore.Delete`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DocumentRequest {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+}
+
+/// This struct is the request of `AnnotateStore.List`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListRequest {}
+
+/// This struct is the response of `AnnotateStore.Delete`, telling whether the document was stored.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteResponse {
+	#[prost(bool, tag = "1")]
+	pub deleted: bool,
+}
+
+/// This struct is the Protocol Buffers message of [ValidationIssue](crate::ValidationIssue), with the description
+/// of the problem as message.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidationIssue {
+	#[prost(string, tag = "1")]
+	pub pointer: String,
+	#[prost(string, tag = "2")]
+	pub message: String,
+}
+
+/// This struct is the Protocol Buffers message of [ValidationReport](crate::ValidationReport).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValidationReport {
+	#[prost(bool, tag = "1")]
+	pub valid: bool,
+	#[prost(message, repeated, tag = "2")]
+	pub issues: Vec<ValidationIssue>,
+}
+
+/// This struct is the request of `Convert.Convert`, with the formats named as by the `server` feature.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConvertRequest {
+	#[prost(string, tag = "1")]
+	pub from: String,
+	#[prost(string, tag = "2")]
+	pub to: String,
+	#[prost(bytes = "vec", tag = "3")]
+	pub data: Vec<u8>,
+}
+
+/// This struct is the response of `Convert.Convert`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConvertResponse {
+	#[prost(bytes = "vec", tag = "1")]
+	pub data: Vec<u8>,
+}
+
+impl From<crate::ValidationReport> for ValidationReport {
+	fn from(r: crate::ValidationReport) -> Self {
+		ValidationReport {
+			valid: r.is_valid(),
+			issues: r
+				.issues
+				.into_iter()
+				.map(|issue| ValidationIssue { message: issue.to_string(), pointer: issue.pointer })
+				.collect(),
+		}
+	}
+}
+
+/// returns the gRPC status of an error.
+fn status(e: JsonNlpError) -> Status {
+	match e {
+		JsonNlpError::LimitExceeded(_) => Status::resource_exhausted(e.to_string()),
+		JsonNlpError::Io(_) | JsonNlpError::Serialize(_) => Status::internal(e.to_string()),
+		_ => Status::invalid_argument(e.to_string()),
+	}
+}
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = std::result::Result<T, Status>> + Send>>;
+
+/// This struct implements the `AnnotateStore` service, keeping the documents in memory.
+#[derive(Default, Debug)]
+pub struct MemoryStore {
+	docs: RwLock<BTreeMap<u64, Document>>,
+}
+
+#[tonic::async_trait]
+impl annotate_store_server::AnnotateStore for MemoryStore {
+	type ListStream = ResponseStream<proto::Document>;
+
+	async fn put(
+		&self,
+		request: Request<Streaming<proto::Document>>,
+	) -> std::result::Result<Response<PutResponse>, Status> {
+		let mut stream = request.into_inner();
+		let mut count = 0;
+		while let Some(doc) = stream.message().await? {
+			let doc = Document::try_from(doc).map_err(status)?;
+			self.docs.write().unwrap_or_else(PoisonError::into_inner).insert(doc.id.into(), doc);
+			count += 1;
+		}
+		Ok(Response::new(PutResponse { count }))
+	}
+
+	async fn get(&self, request: Request<DocumentRequest>) -> std::result::Result<Response<proto::Document>, Status> {
+		let id = request.into_inner().id;
+		let docs = self.docs.read().unwrap_or_else(PoisonError::into_inner);
+		match docs.get(&id) {
+			Some(doc) => Ok(Response::new(doc.clone().into())),
+			None => Err(Status::not_found(format!("no document with ID {}", id))),
+		}
+	}
+
+	async fn list(&self, _request: Request<ListRequest>) -> std::result::Result<Response<Self::ListStream>, Status> {
+		let docs: Vec<Document> = self.docs.read().unwrap_or_else(PoisonError::into_inner).values().cloned().collect();
+		Ok(Response::new(Box::pin(tokio_stream::iter(docs.into_iter().map(|doc| Ok(doc.into()))))))
+	}
+
+	async fn delete(&self, request: Request<DocumentRequest>) -> std::result::Result<Response<DeleteResponse>, Status> {
+		let id = request.into_inner().id;
+		let deleted = self.docs.write().unwrap_or_else(PoisonError::into_inner).remove(&id).is_some();
+		Ok(Response::new(DeleteResponse { deleted }))
+	}
+}
+
+/// This struct implements the `Validate` service with JSONNLP::validate() and Document::validate().
+#[derive(Default, Debug, Clone, Copy)]
+pub struct Validator;
+
+#[tonic::async_trait]
+impl validate_server::Validate for Validator {
+	type ValidateDocumentsStream = ResponseStream<ValidationReport>;
+
+	async fn validate(
+		&self,
+		request: Request<proto::JsonNlp>,
+	) -> std::result::Result<Response<ValidationReport>, Status> {
+		let j = JSONNLP::try_from(request.into_inner()).map_err(status)?;
+		Ok(Response::new(j.validate().into()))
+	}
+
+	async fn validate_documents(
+		&self,
+		request: Request<Streaming<proto::Document>>,
+	) -> std::result::Result<Response<Self::ValidateDocumentsStream>, Status> {
+		let reports = request.into_inner().map(|doc| {
+			let doc = Document::try_from(doc?).map_err(status)?;
+			Ok(doc.validate().into())
+		});
+		Ok(Response::new(Box::pin(reports)))
+	}
+}
+
+/// This struct implements the `Convert` service, parsing JSON-NLP with its ParseOptions.
+#[derive(Default, Debug, Clone)]
+pub struct Converter {
+	pub options: ParseOptions,
+}
+
+impl Converter {
+	/// returns a converter parsing JSON-NLP with the given options, e.g. with resource limits for untrusted clients.
+	pub fn new(options: ParseOptions) -> Self {
+		Converter { options }
+	}
+}
+
+#[tonic::async_trait]
+impl convert_server::Convert for Converter {
+	async fn convert(
+		&self,
+		request: Request<ConvertRequest>,
+	) -> std::result::Result<Response<ConvertResponse>, Status> {
+		let r = request.into_inner();
+		let from: Format = r.from.parse().map_err(status)?;
+		let to: Format = r.to.parse().map_err(status)?;
+		let j = read(&r.data, from, &self.options).map_err(status)?;
+		let data = write(&j, to).map_err(status)?.into_bytes();
+		Ok(Response::new(ConvertResponse { data }))
+	}
+}
+
+/// This function serves a MemoryStore, a Validator, and a Converter on an address until the process is stopped.
+pub async fn serve(addr: SocketAddr) -> Result<()> {
+	tonic::transport::Server::builder()
+		.add_service(annotate_store_server::AnnotateStoreServer::new(MemoryStore::default()))
+		.add_service(validate_server::ValidateServer::new(Validator))
+		.add_service(convert_server::ConvertServer::new(Converter::default()))
+		.serve(addr)
+		.await
+		.map_err(|e| JsonNlpError::Io(io::Error::other(e)))
+}
@@ -0,0 +1,924 @@
+// This file is generated by tonic-build 0.14 from the services in proto/jsonnlp_service.proto, see src/grpc.rs,
+// with `TryInto` qualified for the 2018 edition. Do not edit it by hand.
+
+/// Generated client implementations.
+pub mod annotate_store_client {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::http::Uri;
+	use tonic::codegen::*;
+	/// Stores annotated documents by their ID.
+	#[derive(Debug, Clone)]
+	pub struct AnnotateStoreClient<T> {
+		inner: tonic::client::Grpc<T>,
+	}
+	impl AnnotateStoreClient<tonic::transport::Channel> {
+		/// Attempt to create a new client by connecting to a given endpoint.
+		pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+		where
+			D: std::convert::TryInto<tonic::transport::Endpoint>,
+			D::Error: Into<StdError>,
+		{
+			let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+			Ok(Self::new(conn))
+		}
+	}
+	impl<T> AnnotateStoreClient<T>
+	where
+		T: tonic::client::GrpcService<tonic::body::Body>,
+		T::Error: Into<StdError>,
+		T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+		<T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+	{
+		pub fn new(inner: T) -> Self {
+			let inner = tonic::client::Grpc::new(inner);
+			Self { inner }
+		}
+		pub fn with_origin(inner: T, origin: Uri) -> Self {
+			let inner = tonic::client::Grpc::with_origin(inner, origin);
+			Self { inner }
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> AnnotateStoreClient<InterceptedService<T, F>>
+		where
+			F: tonic::service::Interceptor,
+			T::ResponseBody: Default,
+			T: tonic::codegen::Service<
+				http::Request<tonic::body::Body>,
+				Response = http::Response<<T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody>,
+			>,
+			<T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
+				Into<StdError> + std::marker::Send + std::marker::Sync,
+		{
+			AnnotateStoreClient::new(InterceptedService::new(inner, interceptor))
+		}
+		/// Compress requests with the given encoding.
+		///
+		/// This requires the server to support it otherwise it might respond with an
+		/// error.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.send_compressed(encoding);
+			self
+		}
+		/// Enable decompressing responses.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.accept_compressed(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_decoding_message_size(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_encoding_message_size(limit);
+			self
+		}
+		/// Stores a stream of documents, replacing stored documents with the same ID.
+		pub async fn put(
+			&mut self,
+			request: impl tonic::IntoStreamingRequest<Message = crate::proto::Document>,
+		) -> std::result::Result<tonic::Response<crate::grpc::PutResponse>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.AnnotateStore/Put");
+			let mut req = request.into_streaming_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.AnnotateStore", "Put"));
+			self.inner.client_streaming(req, path, codec).await
+		}
+		/// Returns the stored document with an ID.
+		pub async fn get(
+			&mut self,
+			request: impl tonic::IntoRequest<crate::grpc::DocumentRequest>,
+		) -> std::result::Result<tonic::Response<crate::proto::Document>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.AnnotateStore/Get");
+			let mut req = request.into_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.AnnotateStore", "Get"));
+			self.inner.unary(req, path, codec).await
+		}
+		/// Streams the stored documents in the order of their IDs.
+		pub async fn list(
+			&mut self,
+			request: impl tonic::IntoRequest<crate::grpc::ListRequest>,
+		) -> std::result::Result<tonic::Response<tonic::codec::Streaming<crate::proto::Document>>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.AnnotateStore/List");
+			let mut req = request.into_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.AnnotateStore", "List"));
+			self.inner.server_streaming(req, path, codec).await
+		}
+		/// Removes the stored document with an ID.
+		pub async fn delete(
+			&mut self,
+			request: impl tonic::IntoRequest<crate::grpc::DocumentRequest>,
+		) -> std::result::Result<tonic::Response<crate::grpc::DeleteResponse>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.AnnotateStore/Delete");
+			let mut req = request.into_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.AnnotateStore", "Delete"));
+			self.inner.unary(req, path, codec).await
+		}
+	}
+}
+/// Generated server implementations.
+pub mod annotate_store_server {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::*;
+	/// Generated trait containing gRPC methods that should be implemented for use with AnnotateStoreServer.
+	#[async_trait]
+	pub trait AnnotateStore: std::marker::Send + std::marker::Sync + 'static {
+		/// Stores a stream of documents, replacing stored documents with the same ID.
+		async fn put(
+			&self,
+			request: tonic::Request<tonic::Streaming<crate::proto::Document>>,
+		) -> std::result::Result<tonic::Response<crate::grpc::PutResponse>, tonic::Status>;
+		/// Returns the stored document with an ID.
+		async fn get(
+			&self,
+			request: tonic::Request<crate::grpc::DocumentRequest>,
+		) -> std::result::Result<tonic::Response<crate::proto::Document>, tonic::Status>;
+		/// Server streaming response type for the List method.
+		type ListStream: tonic::codegen::tokio_stream::Stream<Item = std::result::Result<crate::proto::Document, tonic::Status>>
+			+ std::marker::Send
+			+ 'static;
+		/// Streams the stored documents in the order of their IDs.
+		async fn list(
+			&self,
+			request: tonic::Request<crate::grpc::ListRequest>,
+		) -> std::result::Result<tonic::Response<Self::ListStream>, tonic::Status>;
+		/// Removes the stored document with an ID.
+		async fn delete(
+			&self,
+			request: tonic::Request<crate::grpc::DocumentRequest>,
+		) -> std::result::Result<tonic::Response<crate::grpc::DeleteResponse>, tonic::Status>;
+	}
+	/// Stores annotated documents by their ID.
+	#[derive(Debug)]
+	pub struct AnnotateStoreServer<T> {
+		inner: Arc<T>,
+		accept_compression_encodings: EnabledCompressionEncodings,
+		send_compression_encodings: EnabledCompressionEncodings,
+		max_decoding_message_size: Option<usize>,
+		max_encoding_message_size: Option<usize>,
+	}
+	impl<T> AnnotateStoreServer<T> {
+		pub fn new(inner: T) -> Self {
+			Self::from_arc(Arc::new(inner))
+		}
+		pub fn from_arc(inner: Arc<T>) -> Self {
+			Self {
+				inner,
+				accept_compression_encodings: Default::default(),
+				send_compression_encodings: Default::default(),
+				max_decoding_message_size: None,
+				max_encoding_message_size: None,
+			}
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+		where
+			F: tonic::service::Interceptor,
+		{
+			InterceptedService::new(Self::new(inner), interceptor)
+		}
+		/// Enable decompressing requests with the given encoding.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.accept_compression_encodings.enable(encoding);
+			self
+		}
+		/// Compress responses with the given encoding, if the client supports it.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.send_compression_encodings.enable(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.max_decoding_message_size = Some(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.max_encoding_message_size = Some(limit);
+			self
+		}
+	}
+	impl<T, B> tonic::codegen::Service<http::Request<B>> for AnnotateStoreServer<T>
+	where
+		T: AnnotateStore,
+		B: Body + std::marker::Send + 'static,
+		B::Error: Into<StdError> + std::marker::Send + 'static,
+	{
+		type Response = http::Response<tonic::body::Body>;
+		type Error = std::convert::Infallible;
+		type Future = BoxFuture<Self::Response, Self::Error>;
+		fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+		fn call(&mut self, req: http::Request<B>) -> Self::Future {
+			match req.uri().path() {
+				"/jsonnlp.v1.AnnotateStore/Put" => {
+					#[allow(non_camel_case_types)]
+					struct PutSvc<T: AnnotateStore>(pub Arc<T>);
+					impl<T: AnnotateStore> tonic::server::ClientStreamingService<crate::proto::Document> for PutSvc<T> {
+						type Response = crate::grpc::PutResponse;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(
+							&mut self,
+							request: tonic::Request<tonic::Streaming<crate::proto::Document>>,
+						) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as AnnotateStore>::put(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = PutSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.client_streaming(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				"/jsonnlp.v1.AnnotateStore/Get" => {
+					#[allow(non_camel_case_types)]
+					struct GetSvc<T: AnnotateStore>(pub Arc<T>);
+					impl<T: AnnotateStore> tonic::server::UnaryService<crate::grpc::DocumentRequest> for GetSvc<T> {
+						type Response = crate::proto::Document;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(&mut self, request: tonic::Request<crate::grpc::DocumentRequest>) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as AnnotateStore>::get(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = GetSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.unary(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				"/jsonnlp.v1.AnnotateStore/List" => {
+					#[allow(non_camel_case_types)]
+					struct ListSvc<T: AnnotateStore>(pub Arc<T>);
+					impl<T: AnnotateStore> tonic::server::ServerStreamingService<crate::grpc::ListRequest> for ListSvc<T> {
+						type Response = crate::proto::Document;
+						type ResponseStream = T::ListStream;
+						type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+						fn call(&mut self, request: tonic::Request<crate::grpc::ListRequest>) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as AnnotateStore>::list(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = ListSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.server_streaming(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				"/jsonnlp.v1.AnnotateStore/Delete" => {
+					#[allow(non_camel_case_types)]
+					struct DeleteSvc<T: AnnotateStore>(pub Arc<T>);
+					impl<T: AnnotateStore> tonic::server::UnaryService<crate::grpc::DocumentRequest> for DeleteSvc<T> {
+						type Response = crate::grpc::DeleteResponse;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(&mut self, request: tonic::Request<crate::grpc::DocumentRequest>) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as AnnotateStore>::delete(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = DeleteSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.unary(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				_ => Box::pin(async move {
+					let mut response = http::Response::new(tonic::body::Body::default());
+					let headers = response.headers_mut();
+					headers.insert(tonic::Status::GRPC_STATUS, (tonic::Code::Unimplemented as i32).into());
+					headers.insert(http::header::CONTENT_TYPE, tonic::metadata::GRPC_CONTENT_TYPE);
+					Ok(response)
+				}),
+			}
+		}
+	}
+	impl<T> Clone for AnnotateStoreServer<T> {
+		fn clone(&self) -> Self {
+			let inner = self.inner.clone();
+			Self {
+				inner,
+				accept_compression_encodings: self.accept_compression_encodings,
+				send_compression_encodings: self.send_compression_encodings,
+				max_decoding_message_size: self.max_decoding_message_size,
+				max_encoding_message_size: self.max_encoding_message_size,
+			}
+		}
+	}
+	/// Generated gRPC service name
+	pub const SERVICE_NAME: &str = "jsonnlp.v1.AnnotateStore";
+	impl<T> tonic::server::NamedService for AnnotateStoreServer<T> {
+		const NAME: &'static str = SERVICE_NAME;
+	}
+}
+/// Generated client implementations.
+pub mod validate_client {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::http::Uri;
+	use tonic::codegen::*;
+	/// Checks the referential integrity of JSON-NLP annotations.
+	#[derive(Debug, Clone)]
+	pub struct ValidateClient<T> {
+		inner: tonic::client::Grpc<T>,
+	}
+	impl ValidateClient<tonic::transport::Channel> {
+		/// Attempt to create a new client by connecting to a given endpoint.
+		pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+		where
+			D: std::convert::TryInto<tonic::transport::Endpoint>,
+			D::Error: Into<StdError>,
+		{
+			let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+			Ok(Self::new(conn))
+		}
+	}
+	impl<T> ValidateClient<T>
+	where
+		T: tonic::client::GrpcService<tonic::body::Body>,
+		T::Error: Into<StdError>,
+		T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+		<T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+	{
+		pub fn new(inner: T) -> Self {
+			let inner = tonic::client::Grpc::new(inner);
+			Self { inner }
+		}
+		pub fn with_origin(inner: T, origin: Uri) -> Self {
+			let inner = tonic::client::Grpc::with_origin(inner, origin);
+			Self { inner }
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> ValidateClient<InterceptedService<T, F>>
+		where
+			F: tonic::service::Interceptor,
+			T::ResponseBody: Default,
+			T: tonic::codegen::Service<
+				http::Request<tonic::body::Body>,
+				Response = http::Response<<T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody>,
+			>,
+			<T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
+				Into<StdError> + std::marker::Send + std::marker::Sync,
+		{
+			ValidateClient::new(InterceptedService::new(inner, interceptor))
+		}
+		/// Compress requests with the given encoding.
+		///
+		/// This requires the server to support it otherwise it might respond with an
+		/// error.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.send_compressed(encoding);
+			self
+		}
+		/// Enable decompressing responses.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.accept_compressed(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_decoding_message_size(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_encoding_message_size(limit);
+			self
+		}
+		/// Validates a collection.
+		pub async fn validate(
+			&mut self,
+			request: impl tonic::IntoRequest<crate::proto::JsonNlp>,
+		) -> std::result::Result<tonic::Response<crate::grpc::ValidationReport>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.Validate/Validate");
+			let mut req = request.into_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.Validate", "Validate"));
+			self.inner.unary(req, path, codec).await
+		}
+		/// Validates a stream of documents, returning one report per document.
+		pub async fn validate_documents(
+			&mut self,
+			request: impl tonic::IntoStreamingRequest<Message = crate::proto::Document>,
+		) -> std::result::Result<tonic::Response<tonic::codec::Streaming<crate::grpc::ValidationReport>>, tonic::Status>
+		{
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.Validate/ValidateDocuments");
+			let mut req = request.into_streaming_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.Validate", "ValidateDocuments"));
+			self.inner.streaming(req, path, codec).await
+		}
+	}
+}
+/// Generated server implementations.
+pub mod validate_server {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::*;
+	/// Generated trait containing gRPC methods that should be implemented for use with ValidateServer.
+	#[async_trait]
+	pub trait Validate: std::marker::Send + std::marker::Sync + 'static {
+		/// Validates a collection.
+		async fn validate(
+			&self,
+			request: tonic::Request<crate::proto::JsonNlp>,
+		) -> std::result::Result<tonic::Response<crate::grpc::ValidationReport>, tonic::Status>;
+		/// Server streaming response type for the ValidateDocuments method.
+		type ValidateDocumentsStream: tonic::codegen::tokio_stream::Stream<
+				Item = std::result::Result<crate::grpc::ValidationReport, tonic::Status>,
+			> + std::marker::Send
+			+ 'static;
+		/// Validates a stream of documents, returning one report per document.
+		async fn validate_documents(
+			&self,
+			request: tonic::Request<tonic::Streaming<crate::proto::Document>>,
+		) -> std::result::Result<tonic::Response<Self::ValidateDocumentsStream>, tonic::Status>;
+	}
+	/// Checks the referential integrity of JSON-NLP annotations.
+	#[derive(Debug)]
+	pub struct ValidateServer<T> {
+		inner: Arc<T>,
+		accept_compression_encodings: EnabledCompressionEncodings,
+		send_compression_encodings: EnabledCompressionEncodings,
+		max_decoding_message_size: Option<usize>,
+		max_encoding_message_size: Option<usize>,
+	}
+	impl<T> ValidateServer<T> {
+		pub fn new(inner: T) -> Self {
+			Self::from_arc(Arc::new(inner))
+		}
+		pub fn from_arc(inner: Arc<T>) -> Self {
+			Self {
+				inner,
+				accept_compression_encodings: Default::default(),
+				send_compression_encodings: Default::default(),
+				max_decoding_message_size: None,
+				max_encoding_message_size: None,
+			}
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+		where
+			F: tonic::service::Interceptor,
+		{
+			InterceptedService::new(Self::new(inner), interceptor)
+		}
+		/// Enable decompressing requests with the given encoding.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.accept_compression_encodings.enable(encoding);
+			self
+		}
+		/// Compress responses with the given encoding, if the client supports it.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.send_compression_encodings.enable(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.max_decoding_message_size = Some(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.max_encoding_message_size = Some(limit);
+			self
+		}
+	}
+	impl<T, B> tonic::codegen::Service<http::Request<B>> for ValidateServer<T>
+	where
+		T: Validate,
+		B: Body + std::marker::Send + 'static,
+		B::Error: Into<StdError> + std::marker::Send + 'static,
+	{
+		type Response = http::Response<tonic::body::Body>;
+		type Error = std::convert::Infallible;
+		type Future = BoxFuture<Self::Response, Self::Error>;
+		fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+		fn call(&mut self, req: http::Request<B>) -> Self::Future {
+			match req.uri().path() {
+				"/jsonnlp.v1.Validate/Validate" => {
+					#[allow(non_camel_case_types)]
+					struct ValidateSvc<T: Validate>(pub Arc<T>);
+					impl<T: Validate> tonic::server::UnaryService<crate::proto::JsonNlp> for ValidateSvc<T> {
+						type Response = crate::grpc::ValidationReport;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(&mut self, request: tonic::Request<crate::proto::JsonNlp>) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as Validate>::validate(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = ValidateSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.unary(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				"/jsonnlp.v1.Validate/ValidateDocuments" => {
+					#[allow(non_camel_case_types)]
+					struct ValidateDocumentsSvc<T: Validate>(pub Arc<T>);
+					impl<T: Validate> tonic::server::StreamingService<crate::proto::Document> for ValidateDocumentsSvc<T> {
+						type Response = crate::grpc::ValidationReport;
+						type ResponseStream = T::ValidateDocumentsStream;
+						type Future = BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+						fn call(
+							&mut self,
+							request: tonic::Request<tonic::Streaming<crate::proto::Document>>,
+						) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as Validate>::validate_documents(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = ValidateDocumentsSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.streaming(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				_ => Box::pin(async move {
+					let mut response = http::Response::new(tonic::body::Body::default());
+					let headers = response.headers_mut();
+					headers.insert(tonic::Status::GRPC_STATUS, (tonic::Code::Unimplemented as i32).into());
+					headers.insert(http::header::CONTENT_TYPE, tonic::metadata::GRPC_CONTENT_TYPE);
+					Ok(response)
+				}),
+			}
+		}
+	}
+	impl<T> Clone for ValidateServer<T> {
+		fn clone(&self) -> Self {
+			let inner = self.inner.clone();
+			Self {
+				inner,
+				accept_compression_encodings: self.accept_compression_encodings,
+				send_compression_encodings: self.send_compression_encodings,
+				max_decoding_message_size: self.max_decoding_message_size,
+				max_encoding_message_size: self.max_encoding_message_size,
+			}
+		}
+	}
+	/// Generated gRPC service name
+	pub const SERVICE_NAME: &str = "jsonnlp.v1.Validate";
+	impl<T> tonic::server::NamedService for ValidateServer<T> {
+		const NAME: &'static str = SERVICE_NAME;
+	}
+}
+/// Generated client implementations.
+pub mod convert_client {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::http::Uri;
+	use tonic::codegen::*;
+	/// Converts between JSON-NLP and other annotation formats.
+	#[derive(Debug, Clone)]
+	pub struct ConvertClient<T> {
+		inner: tonic::client::Grpc<T>,
+	}
+	impl ConvertClient<tonic::transport::Channel> {
+		/// Attempt to create a new client by connecting to a given endpoint.
+		pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+		where
+			D: std::convert::TryInto<tonic::transport::Endpoint>,
+			D::Error: Into<StdError>,
+		{
+			let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+			Ok(Self::new(conn))
+		}
+	}
+	impl<T> ConvertClient<T>
+	where
+		T: tonic::client::GrpcService<tonic::body::Body>,
+		T::Error: Into<StdError>,
+		T::ResponseBody: Body<Data = Bytes> + std::marker::Send + 'static,
+		<T::ResponseBody as Body>::Error: Into<StdError> + std::marker::Send,
+	{
+		pub fn new(inner: T) -> Self {
+			let inner = tonic::client::Grpc::new(inner);
+			Self { inner }
+		}
+		pub fn with_origin(inner: T, origin: Uri) -> Self {
+			let inner = tonic::client::Grpc::with_origin(inner, origin);
+			Self { inner }
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> ConvertClient<InterceptedService<T, F>>
+		where
+			F: tonic::service::Interceptor,
+			T::ResponseBody: Default,
+			T: tonic::codegen::Service<
+				http::Request<tonic::body::Body>,
+				Response = http::Response<<T as tonic::client::GrpcService<tonic::body::Body>>::ResponseBody>,
+			>,
+			<T as tonic::codegen::Service<http::Request<tonic::body::Body>>>::Error:
+				Into<StdError> + std::marker::Send + std::marker::Sync,
+		{
+			ConvertClient::new(InterceptedService::new(inner, interceptor))
+		}
+		/// Compress requests with the given encoding.
+		///
+		/// This requires the server to support it otherwise it might respond with an
+		/// error.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.send_compressed(encoding);
+			self
+		}
+		/// Enable decompressing responses.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.inner = self.inner.accept_compressed(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_decoding_message_size(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.inner = self.inner.max_encoding_message_size(limit);
+			self
+		}
+		/// Converts the data of a request from one format to another.
+		pub async fn convert(
+			&mut self,
+			request: impl tonic::IntoRequest<crate::grpc::ConvertRequest>,
+		) -> std::result::Result<tonic::Response<crate::grpc::ConvertResponse>, tonic::Status> {
+			self.inner
+				.ready()
+				.await
+				.map_err(|e| tonic::Status::unknown(format!("Service was not ready: {}", e.into())))?;
+			let codec = tonic_prost::ProstCodec::default();
+			let path = http::uri::PathAndQuery::from_static("/jsonnlp.v1.Convert/Convert");
+			let mut req = request.into_request();
+			req.extensions_mut().insert(GrpcMethod::new("jsonnlp.v1.Convert", "Convert"));
+			self.inner.unary(req, path, codec).await
+		}
+	}
+}
+/// Generated server implementations.
+pub mod convert_server {
+	#![allow(unused_variables, dead_code, missing_docs, clippy::wildcard_imports, clippy::let_unit_value)]
+	use tonic::codegen::*;
+	/// Generated trait containing gRPC methods that should be implemented for use with ConvertServer.
+	#[async_trait]
+	pub trait Convert: std::marker::Send + std::marker::Sync + 'static {
+		/// Converts the data of a request from one format to another.
+		async fn convert(
+			&self,
+			request: tonic::Request<crate::grpc::ConvertRequest>,
+		) -> std::result::Result<tonic::Response<crate::grpc::ConvertResponse>, tonic::Status>;
+	}
+	/// Converts between JSON-NLP and other annotation formats.
+	#[derive(Debug)]
+	pub struct ConvertServer<T> {
+		inner: Arc<T>,
+		accept_compression_encodings: EnabledCompressionEncodings,
+		send_compression_encodings: EnabledCompressionEncodings,
+		max_decoding_message_size: Option<usize>,
+		max_encoding_message_size: Option<usize>,
+	}
+	impl<T> ConvertServer<T> {
+		pub fn new(inner: T) -> Self {
+			Self::from_arc(Arc::new(inner))
+		}
+		pub fn from_arc(inner: Arc<T>) -> Self {
+			Self {
+				inner,
+				accept_compression_encodings: Default::default(),
+				send_compression_encodings: Default::default(),
+				max_decoding_message_size: None,
+				max_encoding_message_size: None,
+			}
+		}
+		pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+		where
+			F: tonic::service::Interceptor,
+		{
+			InterceptedService::new(Self::new(inner), interceptor)
+		}
+		/// Enable decompressing requests with the given encoding.
+		#[must_use]
+		pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.accept_compression_encodings.enable(encoding);
+			self
+		}
+		/// Compress responses with the given encoding, if the client supports it.
+		#[must_use]
+		pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+			self.send_compression_encodings.enable(encoding);
+			self
+		}
+		/// Limits the maximum size of a decoded message.
+		///
+		/// Default: `4MB`
+		#[must_use]
+		pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+			self.max_decoding_message_size = Some(limit);
+			self
+		}
+		/// Limits the maximum size of an encoded message.
+		///
+		/// Default: `usize::MAX`
+		#[must_use]
+		pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+			self.max_encoding_message_size = Some(limit);
+			self
+		}
+	}
+	impl<T, B> tonic::codegen::Service<http::Request<B>> for ConvertServer<T>
+	where
+		T: Convert,
+		B: Body + std::marker::Send + 'static,
+		B::Error: Into<StdError> + std::marker::Send + 'static,
+	{
+		type Response = http::Response<tonic::body::Body>;
+		type Error = std::convert::Infallible;
+		type Future = BoxFuture<Self::Response, Self::Error>;
+		fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+			Poll::Ready(Ok(()))
+		}
+		fn call(&mut self, req: http::Request<B>) -> Self::Future {
+			match req.uri().path() {
+				"/jsonnlp.v1.Convert/Convert" => {
+					#[allow(non_camel_case_types)]
+					struct ConvertSvc<T: Convert>(pub Arc<T>);
+					impl<T: Convert> tonic::server::UnaryService<crate::grpc::ConvertRequest> for ConvertSvc<T> {
+						type Response = crate::grpc::ConvertResponse;
+						type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+						fn call(&mut self, request: tonic::Request<crate::grpc::ConvertRequest>) -> Self::Future {
+							let inner = Arc::clone(&self.0);
+							let fut = async move { <T as Convert>::convert(&inner, request).await };
+							Box::pin(fut)
+						}
+					}
+					let accept_compression_encodings = self.accept_compression_encodings;
+					let send_compression_encodings = self.send_compression_encodings;
+					let max_decoding_message_size = self.max_decoding_message_size;
+					let max_encoding_message_size = self.max_encoding_message_size;
+					let inner = self.inner.clone();
+					let fut = async move {
+						let method = ConvertSvc(inner);
+						let codec = tonic_prost::ProstCodec::default();
+						let mut grpc = tonic::server::Grpc::new(codec)
+							.apply_compression_config(accept_compression_encodings, send_compression_encodings)
+							.apply_max_message_size_config(max_decoding_message_size, max_encoding_message_size);
+						let res = grpc.unary(method, req).await;
+						Ok(res)
+					};
+					Box::pin(fut)
+				}
+				_ => Box::pin(async move {
+					let mut response = http::Response::new(tonic::body::Body::default());
+					let headers = response.headers_mut();
+					headers.insert(tonic::Status::GRPC_STATUS, (tonic::Code::Unimplemented as i32).into());
+					headers.insert(http::header::CONTENT_TYPE, tonic::metadata::GRPC_CONTENT_TYPE);
+					Ok(response)
+				}),
+			}
+		}
+	}
+	impl<T> Clone for ConvertServer<T> {
+		fn clone(&self) -> Self {
+			let inner = self.inner.clone();
+			Self {
+				inner,
+				accept_compression_encodings: self.accept_compression_encodings,
+				send_compression_encodings: self.send_compression_encodings,
+				max_decoding_message_size: self.max_decoding_message_size,
+				max_encoding_message_size: self.max_encoding_message_size,
+			}
+		}
+	}
+	/// Generated gRPC service name
+	pub const SERVICE_NAME: &str = "jsonnlp.v1.Convert";
+	impl<T> tonic::server::NamedService for ConvertServer<T> {
+		const NAME: &'static str = SERVICE_NAME;
+	}
+}
@@ -0,0 +1,167 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents as self-contained HTML pages for
+//! reviewing annotations in a browser, without scripts or external resources.
+
+use std::collections::HashMap;
+
+use crate::conllu::feats;
+use crate::tei::{escape_xml, space_after};
+use crate::{Document, Token, TokenId};
+
+const STYLE: &str = "body { font-family: sans-serif; line-height: 2; max-width: 60em; margin: 2em auto; }
+.t:hover { outline: 1px solid #888; cursor: help; }
+mark { padding: 0.1em 0.2em; border-radius: 0.3em; }
+mark sub { font-size: 0.6em; font-weight: bold; margin-left: 0.3em; }
+table { border-collapse: collapse; line-height: 1.4; margin: 0.5em 0 1em; }
+th, td { border: 1px solid #ccc; padding: 0.1em 0.5em; text-align: left; }
+summary { cursor: pointer; }";
+
+/// returns the background color of an entity type, the same for every page.
+fn color(etype: &str) -> String {
+	let hue = etype.bytes().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b.into())) % 360;
+	format!("hsl({}, 70%, 85%)", hue)
+}
+
+/// returns the tooltip of a token with its annotations.
+fn tooltip(t: &Token) -> String {
+	let mut lines = vec![format!("token {}", t.id)];
+	for (name, value) in &[("lemma", &t.lemma), ("upos", &t.upos), ("xpos", &t.xpos), ("entity", &t.entity_iob)] {
+		if !value.is_empty() {
+			lines.push(format!("{}: {}", name, value));
+		}
+	}
+	let feats = feats(t.features());
+	if feats != "_" {
+		lines.push(format!("feats: {}", feats));
+	}
+	if let (Some(begin), Some(end)) = (t.char_offset_begin, t.char_offset_end) {
+		lines.push(format!("offsets: {}-{}", begin, end));
+	}
+	escape_xml(&lines.join("\n"))
+}
+
+/// This function converts a Document to an HTML page showing its text with the tokens of every sentence, whose
+/// lemma, POS tags, features, and offsets are shown when hovering over them, and the entities highlighted in a
+/// color per type. The entities that do not overlap an entity highlighted before are highlighted. Below the text,
+/// the entities are listed in a table, and every dependency tree is a collapsible table of its dependencies with
+/// links to the tokens.
+pub fn to_html(doc: &Document) -> String {
+	let title = match (doc.meta.title.as_str(), doc.meta.identifier.as_str()) {
+		("", "") => format!("Document {}", doc.id),
+		("", identifier) => identifier.to_string(),
+		(title, _) => title.to_string(),
+	};
+	let language = match doc.meta.language.as_str() {
+		"" => String::new(),
+		language => format!(" lang=\"{}\"", escape_xml(language)),
+	};
+	let mut html = format!("<!DOCTYPE html>\n<html{}>\n<head>\n<meta charset=\"utf-8\">\n", language);
+	html.push_str(&format!("<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n", escape_xml(&title), STYLE));
+	html.push_str(&format!("<h1>{}</h1>\n<p>\n", escape_xml(&title)));
+
+	let groups = doc.sentence_groups();
+	for (sentence_id, _, tokens) in &groups {
+		// the entity starting at a token position, with its last position, type, and label
+		let mut names: HashMap<usize, (usize, &str, &str)> = HashMap::new();
+		let mut covered = vec![false; tokens.len()];
+		for e in &doc.entities {
+			let covers: Vec<usize> =
+				tokens.iter().enumerate().filter(|(_, t)| e.covers(t.id)).map(|(i, _)| i).collect();
+			if let (Some(first), Some(last)) = (covers.first(), covers.last()) {
+				if !covered[*first..=*last].iter().any(|c| *c) {
+					covered[*first..=*last].iter_mut().for_each(|c| *c = true);
+					names.insert(*first, (*last, e.etype.as_str(), e.label.as_str()));
+				}
+			}
+		}
+
+		html.push_str(&format!("<span class=\"s\" id=\"s{}\">", sentence_id));
+		let mut name_end = None;
+		for (i, t) in tokens.iter().enumerate() {
+			if let Some((last, etype, label)) = names.get(&i) {
+				let title = if label.is_empty() { etype.to_string() } else { format!("{}: {}", etype, label) };
+				html.push_str(&format!(
+					"<mark style=\"background: {}\" title=\"{}\">",
+					color(etype),
+					escape_xml(&title)
+				));
+				name_end = Some((*last, *etype));
+			}
+			html.push_str(&format!(
+				"<span class=\"t\" id=\"t{}\" title=\"{}\">{}</span>",
+				t.id,
+				tooltip(t),
+				escape_xml(&t.text)
+			));
+			if let Some((last, etype)) = name_end {
+				if last == i {
+					if !etype.is_empty() {
+						html.push_str(&format!("<sub>{}</sub>", escape_xml(etype)));
+					}
+					html.push_str("</mark>");
+					name_end = None;
+				}
+			}
+			if tokens.get(i + 1).is_some_and(|next| space_after(t, next)) {
+				html.push(' ');
+			}
+		}
+		html.push_str("</span>\n");
+	}
+	html.push_str("</p>\n");
+
+	if !doc.entities.is_empty() {
+		html.push_str(
+			"<h2>Entities</h2>\n<table>\n<tr><th>ID</th><th>Text</th><th>Type</th><th>Label</th><th>URL</th></tr>\n",
+		);
+		for e in &doc.entities {
+			// only web URLs are links, so that the page runs no scripts of the document
+			let url = if e.url.starts_with("http://") || e.url.starts_with("https://") {
+				format!("<a href=\"{}\">{}</a>", escape_xml(&e.url), escape_xml(&e.url))
+			} else {
+				escape_xml(&e.url)
+			};
+			html.push_str(&format!(
+				"<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+				e.id,
+				escape_xml(doc.entity_text(e).unwrap_or("")),
+				escape_xml(&e.etype),
+				escape_xml(&e.label),
+				url
+			));
+		}
+		html.push_str("</table>\n");
+	}
+
+	if !doc.dependency_trees.is_empty() {
+		let tokens: HashMap<TokenId, &Token> = doc.token_list.iter().map(|t| (t.id, t)).collect();
+		let link = |id: TokenId| match tokens.get(&id) {
+			Some(t) => format!("<a href=\"#t{}\">{}</a>", id, escape_xml(&t.text)),
+			None if id == TokenId::default() => "ROOT".to_string(),
+			None => id.to_string(),
+		};
+		html.push_str("<h2>Dependencies</h2>\n");
+		for (i, tree) in doc.dependency_trees.iter().enumerate() {
+			let summary = match tree.sentence_id.and_then(|id| groups.iter().find(|g| g.0 == id)) {
+				Some((id, "", tokens)) => {
+					format!("Sentence {}: {}", id, tokens.iter().map(|t| t.text.as_str()).collect::<Vec<_>>().join(" "))
+				}
+				Some((id, text, _)) => format!("Sentence {}: {}", id, text),
+				None => format!("Tree {}", i + 1),
+			};
+			html.push_str(&format!("<details>\n<summary>{}</summary>\n<table>\n", escape_xml(&summary)));
+			html.push_str("<tr><th>Dependent</th><th>Label</th><th>Governor</th></tr>\n");
+			for d in &tree.dependencies {
+				html.push_str(&format!(
+					"<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+					link(d.dep),
+					escape_xml(&d.lab),
+					link(d.gov)
+				));
+			}
+			html.push_str("</table>\n</details>\n");
+		}
+	}
+
+	html.push_str("</body>\n</html>\n");
+	html
+}
@@ -0,0 +1,114 @@
+//! Merging of [Hugging Face](https://huggingface.co/) token classification predictions into
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{Document, Entity, EntityId, TokenId};
+
+/// This struct contains one prediction of a Hugging Face `token-classification` pipeline.
+/// Aggregated predictions carry an `entity_group`, and predictions without aggregation an
+/// IOB tagged `entity` like `B-PER`, which is accepted as well.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct TokenClassification {
+	#[serde(default)]
+	pub word: String,
+	#[serde(alias = "entity")]
+	pub entity_group: String,
+	#[serde(default)]
+	pub score: f64,
+	pub start: u64,
+	pub end: u64,
+}
+
+impl Document {
+	/// merges token classification predictions into the document and returns the number of entities added.
+	/// Every prediction becomes an Entity of the tokens its character span overlaps, with the score kept in
+	/// the entity's `extra` map, and these tokens get the entity type as `entity` and IOB2 `entity_iob` tags.
+	/// Predictions tagged `I-` that directly follow a prediction of the same type extend its entity, so that
+	/// sub-word predictions merge into words. Predictions that overlap no token with character offsets are skipped.
+	pub fn merge_token_classification(&mut self, predictions: &[TokenClassification]) -> usize {
+		// merged spans with type, start, end, and the lowest score
+		let mut spans: Vec<(&str, u64, u64, f64)> = Vec::new();
+		for p in predictions {
+			let (continues, etype) = match p.entity_group.split_once('-') {
+				Some(("B", etype)) => (false, etype),
+				Some(("I", etype)) => (true, etype),
+				_ => (false, p.entity_group.as_str()),
+			};
+			match spans.last_mut() {
+				Some(last) if continues && last.0 == etype && p.start <= last.2 + 1 => {
+					last.2 = last.2.max(p.end);
+					last.3 = last.3.min(p.score);
+				}
+				_ => spans.push((etype, p.start, p.end, p.score)),
+			}
+		}
+
+		let mut next_id = self.entities.iter().map(|e| e.id.0).max().unwrap_or(0) + 1;
+		let mut added = 0;
+		for (etype, start, end, score) in spans {
+			let mut tokens: Vec<TokenId> = Vec::new();
+			for t in self.token_list.iter_mut() {
+				let overlaps = match (t.char_offset_begin, t.char_offset_end) {
+					(Some(b), Some(e)) => b < end && e > start,
+					_ => false,
+				};
+				if overlaps {
+					t.entity_iob = if tokens.is_empty() { "B" } else { "I" }.to_string();
+					t.entity = etype.to_string();
+					tokens.push(t.id);
+				}
+			}
+			if tokens.is_empty() {
+				continue;
+			}
+			let mut entity = Entity {
+				id: EntityId(next_id),
+				label: self.span_text(start, end).unwrap_or_default().to_string(),
+				etype: etype.to_string(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			};
+			entity.extra.insert("score".to_string(), Value::from(score));
+			self.entities.push(entity);
+			next_id += 1;
+			added += 1;
+		}
+		added
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn predictions_are_merged() {
+		let mut doc: Document = serde_json::from_str(r#"{"meta":{},"id":1,"text":"Angela Merkel spoke","tokenList":[
+			{"id":1,"sentence_id":1,"text":"Angela","lemma":"Angela","characterOffsetBegin":0,"characterOffsetEnd":6},
+			{"id":2,"sentence_id":1,"text":"Merkel","lemma":"Merkel","characterOffsetBegin":7,"characterOffsetEnd":13},
+			{"id":3,"sentence_id":1,"text":"spoke","lemma":"speak"}],"entities":[{"id":4,"type":"X","tokens":[3]}]}"#)
+		.unwrap();
+		let predictions: Vec<TokenClassification> = serde_json::from_str(r###"[
+			{"entity":"B-PER","score":0.9,"word":"Ang","start":0,"end":3},
+			{"entity":"I-PER","score":0.8,"word":"##ela","start":3,"end":6},
+			{"entity":"I-PER","score":0.95,"word":"Merkel","start":7,"end":13},
+			{"entity_group":"MISC","score":0.5,"word":"spoke","start":14,"end":19}]"###)
+		.unwrap();
+		assert_eq!(doc.merge_token_classification(&predictions), 1);
+		let e = &doc.entities[1];
+		assert_eq!((e.id, e.etype.as_str(), e.label.as_str()), (EntityId(5), "PER", "Angela Merkel"));
+		assert_eq!((e.tokens.as_slice(), &e.extra["score"]), (&[TokenId(1), TokenId(2)][..], &Value::from(0.8)));
+		let tags: Vec<_> = doc.token_list.iter().map(|t| (t.entity_iob.as_str(), t.entity.as_str())).collect();
+		assert_eq!(tags, [("B", "PER"), ("I", "PER"), ("", "")]);
+	}
+
+	#[test]
+	fn invalid_predictions() {
+		assert!(serde_json::from_str::<TokenClassification>(r#"{"entity":"B-PER","start":0}"#).is_err());
+		assert!(serde_json::from_str::<TokenClassification>(r#"{"start":0,"end":3}"#).is_err());
+	}
+}
@@ -0,0 +1,94 @@
+//! Typed IDs for the elements of the different annotation layers.
+
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// The integer type of the IDs, u64 by default and u32 with the `compact` feature.
+#[cfg(not(feature = "compact"))]
+pub type RawId = u64;
+/// The integer type of the IDs, u64 by default and u32 with the `compact` feature.
+#[cfg(feature = "compact")]
+pub type RawId = u32;
+
+macro_rules! id_type {
+	($(#[$doc:meta])* $name:ident) => {
+		$(#[$doc])*
+		#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+		#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+		#[serde(transparent)]
+		pub struct $name(pub RawId);
+
+		impl $name {
+			/// returns the ID of a u64, or None if it does not fit into RawId.
+			#[allow(dead_code)]
+			pub(crate) fn from_u64(id: u64) -> Option<Self> {
+				RawId::try_from(id).ok().map($name)
+			}
+		}
+
+		impl From<RawId> for $name {
+			fn from(id: RawId) -> Self {
+				$name(id)
+			}
+		}
+
+		impl From<$name> for u64 {
+			fn from(id: $name) -> Self {
+				id.0.into()
+			}
+		}
+
+		impl fmt::Display for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "{}", self.0)
+			}
+		}
+	};
+}
+
+id_type!(
+	/// identifies a document.
+	DocumentId
+);
+id_type!(
+	/// identifies a token in the token list. Dependency governor 0 denotes the root.
+	TokenId
+);
+id_type!(
+	/// identifies a sentence.
+	SentenceId
+);
+id_type!(
+	/// identifies a clause.
+	ClauseId
+);
+id_type!(
+	/// identifies a paragraph.
+	ParagraphId
+);
+id_type!(
+	/// identifies a coreference chain.
+	CoreferenceId
+);
+id_type!(
+	/// identifies an expression.
+	ExpressionId
+);
+id_type!(
+	/// identifies an entity.
+	EntityId
+);
+id_type!(
+	/// identifies a relation.
+	RelationId
+);
+id_type!(
+	/// identifies a triple.
+	TripleId
+);
+id_type!(
+	/// identifies a predicate of the semantic role layer.
+	PredicateId
+);
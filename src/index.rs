@@ -0,0 +1,299 @@
+//! Lookup of the tokens, sentences, clauses, and entities of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP)
+//! documents by their IDs in constant time, instead of scanning their layers, and iteration over sentences with
+//! their tokens, dependency trees, and clauses.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{Clause, ClauseId, DependencyTree, Document, Entity, EntityId, Sentence, SentenceId, Token, TokenId};
+
+/// returns the positions of the elements of a layer by their ID, with the first element of every ID.
+fn positions<T, I: Copy + Eq + Hash>(layer: &[T], id: impl Fn(&T) -> I) -> HashMap<I, usize> {
+	let mut positions = HashMap::with_capacity(layer.len());
+	for (i, element) in layer.iter().enumerate() {
+		positions.entry(id(element)).or_insert(i);
+	}
+	positions
+}
+
+/// This struct maps the IDs of the tokens, sentences, clauses, and entities of a Document to their positions in
+/// their layers. It borrows the document, so that it cannot change while the index is in use. If IDs are not
+/// unique, the first element with the ID is found.
+#[derive(Debug, Clone)]
+pub struct DocumentIndex<'a> {
+	doc: &'a Document,
+	tokens: HashMap<TokenId, usize>,
+	sentences: HashMap<SentenceId, usize>,
+	clauses: HashMap<ClauseId, usize>,
+	entities: HashMap<EntityId, usize>,
+}
+
+impl<'a> DocumentIndex<'a> {
+	/// returns the indexed document.
+	pub fn document(&self) -> &'a Document {
+		self.doc
+	}
+
+	/// returns the position of a token in the token list.
+	pub fn token_position(&self, id: &TokenId) -> Option<usize> {
+		self.tokens.get(id).copied()
+	}
+
+	/// returns the token with the ID.
+	pub fn token(&self, id: &TokenId) -> Option<&'a Token> {
+		self.token_position(id).map(|i| &self.doc.token_list[i])
+	}
+
+	/// returns the position of a sentence in the sentence layer.
+	pub fn sentence_position(&self, id: &SentenceId) -> Option<usize> {
+		self.sentences.get(id).copied()
+	}
+
+	/// returns the sentence with the ID.
+	pub fn sentence(&self, id: &SentenceId) -> Option<&'a Sentence> {
+		self.sentence_position(id).map(|i| &self.doc.sentences[i])
+	}
+
+	/// returns the position of a clause in the clause layer.
+	pub fn clause_position(&self, id: &ClauseId) -> Option<usize> {
+		self.clauses.get(id).copied()
+	}
+
+	/// returns the clause with the ID.
+	pub fn clause(&self, id: &ClauseId) -> Option<&'a Clause> {
+		self.clause_position(id).map(|i| &self.doc.clauses[i])
+	}
+
+	/// returns the position of an entity in the entity layer.
+	pub fn entity_position(&self, id: &EntityId) -> Option<usize> {
+		self.entities.get(id).copied()
+	}
+
+	/// returns the entity with the ID.
+	pub fn entity(&self, id: &EntityId) -> Option<&'a Entity> {
+		self.entity_position(id).map(|i| &self.doc.entities[i])
+	}
+}
+
+impl Document {
+	/// returns an index of the tokens, sentences, clauses, and entities of the document by their IDs, which takes
+	/// linear time to build and then finds every element in constant time.
+	pub fn index(&self) -> DocumentIndex<'_> {
+		DocumentIndex {
+			doc: self,
+			tokens: positions(&self.token_list, |t| t.id),
+			sentences: positions(&self.sentences, |s| s.id),
+			clauses: positions(&self.clauses, |c| c.id),
+			entities: positions(&self.entities, |e| e.id),
+		}
+	}
+}
+
+/// This struct is an iterator over the tokens of a sentence, see Sentence::tokens_in().
+#[derive(Debug, Clone)]
+pub struct SentenceTokens<'a> {
+	doc: &'a Document,
+	index: Option<&'a DocumentIndex<'a>>,
+	sentence: &'a Sentence,
+	/// the position of the next ID in the sentence's token list, or of the next token in the document's token list.
+	next: usize,
+	/// the position in the document's token list after the token found last by its ID.
+	cursor: usize,
+}
+
+impl<'a> SentenceTokens<'a> {
+	/// returns the token with the ID, searched from the cursor on and then from the start, which is fast for tokens
+	/// in the order of the token list.
+	fn find(&mut self, id: TokenId) -> Option<&'a Token> {
+		if let Some(index) = self.index {
+			return index.token(&id);
+		}
+		let tokens = &self.doc.token_list;
+		let i = tokens[self.cursor..]
+			.iter()
+			.position(|t| t.id == id)
+			.map(|i| i + self.cursor)
+			.or_else(|| tokens[..self.cursor].iter().position(|t| t.id == id))?;
+		self.cursor = i + 1;
+		Some(&tokens[i])
+	}
+}
+
+impl<'a> Iterator for SentenceTokens<'a> {
+	type Item = &'a Token;
+
+	fn next(&mut self) -> Option<&'a Token> {
+		let s = self.sentence;
+		if !s.tokens.is_empty() {
+			while let Some(id) = s.tokens.get(self.next) {
+				self.next += 1;
+				if let Some(t) = self.find(*id) {
+					return Some(t);
+				}
+			}
+			return None;
+		}
+		while let Some(t) = self.doc.token_list.get(self.next) {
+			self.next += 1;
+			let belongs = match (s.token_from, s.token_to) {
+				(Some(from), Some(to)) => t.id >= from && t.id <= to,
+				_ => t.sentence_id == s.id,
+			};
+			if belongs {
+				return Some(t);
+			}
+		}
+		None
+	}
+}
+
+impl Sentence {
+	/// returns an iterator over the tokens of the sentence in a document, given by the sentence's token list, or
+	/// else by its `tokenFrom`/`tokenTo` span or the tokens' `sentence_id`, in the order of the token list.
+	/// IDs of tokens that are not in the document are skipped. Finding tokens by ID takes time linear in the length
+	/// of the token list, but little more if the tokens are in its order.
+	pub fn tokens_in<'a>(&'a self, doc: &'a Document) -> SentenceTokens<'a> {
+		SentenceTokens { doc, index: None, sentence: self, next: 0, cursor: 0 }
+	}
+
+	/// returns an iterator over the tokens of the sentence like Sentence::tokens_in(), finding them by ID with the
+	/// index of the document.
+	pub fn tokens_in_index<'a>(&'a self, index: &'a DocumentIndex<'a>) -> SentenceTokens<'a> {
+		SentenceTokens { doc: index.doc, index: Some(index), sentence: self, next: 0, cursor: 0 }
+	}
+}
+
+/// This struct bundles a sentence with its tokens, its dependency tree, and its clauses, see Document::sentences().
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceView<'a> {
+	pub sentence: &'a Sentence,
+	pub tokens: Vec<&'a Token>,
+	pub dependency_tree: Option<&'a DependencyTree>,
+	pub clauses: Vec<&'a Clause>,
+}
+
+/// This struct is an iterator over the sentences of a document, see Document::sentences().
+#[derive(Debug, Clone)]
+pub struct Sentences<'a> {
+	index: DocumentIndex<'a>,
+	trees: HashMap<SentenceId, &'a DependencyTree>,
+	clauses: HashMap<SentenceId, Vec<&'a Clause>>,
+	next: usize,
+}
+
+impl<'a> Iterator for Sentences<'a> {
+	type Item = SentenceView<'a>;
+
+	fn next(&mut self) -> Option<SentenceView<'a>> {
+		let doc = self.index.doc;
+		let sentence = doc.sentences.get(self.next)?;
+		self.next += 1;
+		let tokens = if sentence.tokens.is_empty() {
+			sentence.tokens_in(doc).collect()
+		} else {
+			sentence.tokens.iter().filter_map(|id| self.index.token(id)).collect()
+		};
+		let clauses = if sentence.clauses.is_empty() {
+			self.clauses.get(&sentence.id).cloned().unwrap_or_default()
+		} else {
+			sentence.clauses.iter().filter_map(|id| self.index.clause(id)).collect()
+		};
+		Some(SentenceView { sentence, tokens, dependency_tree: self.trees.get(&sentence.id).copied(), clauses })
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let n = self.index.doc.sentences.len() - self.next;
+		(n, Some(n))
+	}
+}
+
+impl ExactSizeIterator for Sentences<'_> {}
+
+impl Document {
+	/// returns an iterator over the sentences of the document with their tokens like Sentence::tokens_in(), the
+	/// first dependency tree of the sentence, and its clauses, given by the sentence's clause list or else by the
+	/// clauses' `sentenceId`. It indexes the document once, so that iterating over all sentences takes linear time
+	/// for sentences that list their tokens.
+	pub fn sentences(&self) -> Sentences<'_> {
+		let mut trees = HashMap::new();
+		for tree in &self.dependency_trees {
+			if let Some(id) = tree.sentence_id {
+				trees.entry(id).or_insert(tree);
+			}
+		}
+		let mut clauses: HashMap<SentenceId, Vec<&Clause>> = HashMap::new();
+		for clause in &self.clauses {
+			if let Some(id) = clause.sentence_id {
+				clauses.entry(id).or_default().push(clause);
+			}
+		}
+		Sentences { index: self.index(), trees, clauses, next: 0 }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// returns a document with tokens out of order, a duplicate token ID, and sentences given by their token list,
+	/// their span, and the tokens' sentence IDs.
+	fn doc() -> Document {
+		serde_json::from_str(r#"{"meta":{},"id":1,"tokenList":[
+			{"id":2,"sentence_id":1,"text":"b","lemma":"b"},{"id":1,"sentence_id":1,"text":"a","lemma":"a"},
+			{"id":3,"sentence_id":2,"text":"c","lemma":"c"},{"id":4,"sentence_id":2,"text":"d","lemma":"d"},
+			{"id":4,"sentence_id":2,"text":"duplicate","lemma":"duplicate"},
+			{"id":5,"sentence_id":3,"text":"e","lemma":"e"}],
+			"sentences":[{"id":1,"tokens":[1,2,9]},{"id":2,"tokenFrom":3,"tokenTo":4},{"id":3}],
+			"clauses":[{"id":1,"sentenceId":2,"tokens":[3]}],"entities":[{"id":7,"type":"X","tokens":[5]}]}"#)
+		.unwrap()
+	}
+
+	#[test]
+	fn elements_are_found_by_id() {
+		let doc = doc();
+		let index = doc.index();
+		assert!(std::ptr::eq(index.document(), &doc));
+		assert_eq!(index.token_position(&TokenId(1)), Some(1));
+		assert_eq!(index.token(&TokenId(4)).map(|t| t.text.as_str()), Some("d"));
+		assert_eq!(index.token(&TokenId(9)), None);
+		assert_eq!((index.sentence_position(&SentenceId(3)), index.sentence(&SentenceId(4))), (Some(2), None));
+		assert_eq!(index.clause(&ClauseId(1)).and_then(|c| c.sentence_id), Some(SentenceId(2)));
+		assert_eq!((index.entity_position(&EntityId(7)), index.entity(&EntityId(1))), (Some(0), None));
+	}
+
+	#[test]
+	fn tokens_of_sentences() {
+		fn texts(tokens: SentenceTokens<'_>) -> Vec<&str> {
+			tokens.map(|t| t.text.as_str()).collect()
+		}
+		let doc = doc();
+		let index = doc.index();
+		let expected = [vec!["a", "b"], vec!["c", "d", "duplicate"], vec!["e"]];
+		for (s, expected) in doc.sentences.iter().zip(&expected) {
+			assert_eq!(&texts(s.tokens_in(&doc)), expected);
+			assert_eq!(&texts(s.tokens_in_index(&index)), expected);
+		}
+	}
+
+	#[test]
+	fn sentence_views() {
+		let mut doc = doc();
+		doc.dependency_trees = serde_json::from_str(r#"[{"sentenceId":3,"dependencies":[]},{"sentenceId":3}]"#).unwrap();
+		doc.clauses.push(Clause { id: ClauseId(2), ..Default::default() });
+		doc.sentences[0].clauses = vec![ClauseId(2), ClauseId(3)];
+		let views = doc.sentences();
+		assert_eq!(views.len(), 3);
+		let views: Vec<_> = views
+			.map(|v| {
+				let tokens: Vec<_> = v.tokens.iter().map(|t| t.text.as_str()).collect();
+				let clauses: Vec<_> = v.clauses.iter().map(|c| c.id.0).collect();
+				(v.sentence.id.0, tokens, v.dependency_tree.map(|t| std::ptr::eq(t, &doc.dependency_trees[0])), clauses)
+			})
+			.collect();
+		assert_eq!(views, [
+			(1, vec!["a", "b"], None, vec![2]),
+			(2, vec!["c", "d", "duplicate"], None, vec![1]),
+			(3, vec!["e"], Some(true), vec![]),
+		]);
+	}
+}
@@ -0,0 +1,254 @@
+//! Interning of the strings that repeat throughout corpora, like lemmas, POS tags, and dependency labels, so that
+//! every distinct string is stored once and tokens refer to it by a symbol.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::stream::{stream_tokens, StreamEvent, StreamOptions};
+use crate::{Dependency, Document, Meta, Prob, Result, SentenceId, Token, TokenId, JSONNLP};
+
+/// This struct identifies a string of an Interner. The symbol of the empty string is the default.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+/// This struct stores every distinct string once and maps it to a symbol.
+#[derive(Debug, Clone)]
+pub struct Interner {
+	strings: Vec<Arc<str>>,
+	symbols: HashMap<Arc<str>, Symbol>,
+}
+
+impl Default for Interner {
+	fn default() -> Self {
+		let empty: Arc<str> = Arc::from("");
+		Interner { strings: vec![empty.clone()], symbols: vec![(empty, Symbol(0))].into_iter().collect() }
+	}
+}
+
+/// This struct contains a token with its repeating strings interned. The properties that are not interned, other than
+/// IDs and offsets, are kept in a token with the interned strings left empty, if any is set.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InternedToken {
+	pub id: TokenId,
+	pub sentence_id: SentenceId,
+	pub text: Symbol,
+	pub lemma: Symbol,
+	pub xpos: Symbol,
+	pub upos: Symbol,
+	pub entity_iob: Symbol,
+	pub entity: Symbol,
+	pub char_offset_begin: Option<u64>,
+	pub char_offset_end: Option<u64>,
+	pub rest: Option<Box<Token>>,
+}
+
+/// This struct contains a dependency with its label interned.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InternedDependency {
+	pub lab: Symbol,
+	pub gov: TokenId,
+	pub dep: TokenId,
+	pub prob: Option<Prob>,
+	pub extra: Option<Box<Map<String, Value>>>,
+}
+
+/// This struct contains a document with the tokens and dependencies interned. The document keeps all other
+/// properties, with an empty token list and dependency trees without dependencies.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct InternedDocument {
+	pub document: Document,
+	pub tokens: Vec<InternedToken>,
+	/// the dependencies of every dependency tree of the document.
+	pub dependencies: Vec<Vec<InternedDependency>>,
+}
+
+impl Interner {
+	/// returns a new interner that only contains the empty string.
+	pub fn new() -> Self {
+		Interner::default()
+	}
+
+	/// returns the symbol of a string, adding the string if it is new.
+	pub fn intern(&mut self, s: &str) -> Symbol {
+		if let Some(symbol) = self.symbols.get(s) {
+			return *symbol;
+		}
+		let symbol = Symbol(self.strings.len() as u32);
+		let s: Arc<str> = Arc::from(s);
+		self.strings.push(s.clone());
+		self.symbols.insert(s, symbol);
+		symbol
+	}
+
+	/// returns the symbol of a string, or None if it is not interned.
+	pub fn get(&self, s: &str) -> Option<Symbol> {
+		self.symbols.get(s).copied()
+	}
+
+	/// returns the string of a symbol.
+	///
+	/// # Panics
+	///
+	/// Panics if the symbol is not of this interner.
+	pub fn resolve(&self, symbol: Symbol) -> &str {
+		&self.strings[symbol.0 as usize]
+	}
+
+	/// returns the number of distinct strings, including the empty string.
+	pub fn len(&self) -> usize {
+		self.strings.len()
+	}
+
+	/// checks whether only the empty string is interned.
+	pub fn is_empty(&self) -> bool {
+		self.strings.len() == 1
+	}
+
+	/// returns the token with its repeating strings interned.
+	pub fn intern_token(&mut self, mut token: Token) -> InternedToken {
+		let mut interned = InternedToken {
+			id: token.id,
+			sentence_id: token.sentence_id,
+			text: self.intern(&std::mem::take(&mut token.text)),
+			lemma: self.intern(&std::mem::take(&mut token.lemma)),
+			xpos: self.intern(&std::mem::take(&mut token.xpos)),
+			upos: self.intern(&std::mem::take(&mut token.upos)),
+			entity_iob: self.intern(&std::mem::take(&mut token.entity_iob)),
+			entity: self.intern(&std::mem::take(&mut token.entity)),
+			char_offset_begin: token.char_offset_begin.take(),
+			char_offset_end: token.char_offset_end.take(),
+			rest: None,
+		};
+		token.id = TokenId::default();
+		token.sentence_id = SentenceId::default();
+		if token != Token::default() {
+			interned.rest = Some(Box::new(token));
+		}
+		interned
+	}
+
+	/// returns a token with the strings of its symbols.
+	pub fn token(&self, t: &InternedToken) -> Token {
+		let mut token = t.rest.as_deref().cloned().unwrap_or_default();
+		token.id = t.id;
+		token.sentence_id = t.sentence_id;
+		token.text = self.resolve(t.text).to_string();
+		token.lemma = self.resolve(t.lemma).to_string();
+		token.xpos = self.resolve(t.xpos).to_string();
+		token.upos = self.resolve(t.upos).to_string();
+		token.entity_iob = self.resolve(t.entity_iob).to_string();
+		token.entity = self.resolve(t.entity).to_string();
+		token.char_offset_begin = t.char_offset_begin;
+		token.char_offset_end = t.char_offset_end;
+		token
+	}
+
+	/// returns the document with its tokens and dependencies interned.
+	pub fn intern_document(&mut self, mut document: Document) -> InternedDocument {
+		let tokens = std::mem::take(&mut document.token_list).into_iter().map(|t| self.intern_token(t)).collect();
+		let dependencies = document.dependency_trees.iter_mut().map(|tree| {
+			std::mem::take(&mut tree.dependencies).into_iter().map(|d| InternedDependency {
+				lab: self.intern(&d.lab),
+				gov: d.gov,
+				dep: d.dep,
+				prob: d.prob,
+				extra: if d.extra.is_empty() { None } else { Some(Box::new(d.extra)) },
+			}).collect()
+		}).collect();
+		InternedDocument { document, tokens, dependencies }
+	}
+
+	/// returns a document with the strings of the symbols of its tokens and dependencies.
+	pub fn document(&self, d: &InternedDocument) -> Document {
+		let mut document = d.document.clone();
+		document.token_list = d.tokens.iter().map(|t| self.token(t)).collect();
+		for (tree, dependencies) in document.dependency_trees.iter_mut().zip(&d.dependencies) {
+			tree.dependencies = dependencies.iter().map(|d| Dependency {
+				lab: self.resolve(d.lab).to_string(),
+				gov: d.gov,
+				dep: d.dep,
+				prob: d.prob,
+				extra: d.extra.as_deref().cloned().unwrap_or_default(),
+			}).collect();
+		}
+		document
+	}
+}
+
+/// This struct contains a collection of documents with their strings interned by one interner.
+/// It serializes to JSON-NLP, resolving one document at a time.
+#[derive(Default, Debug, Clone)]
+pub struct InternedCorpus {
+	pub meta: Meta,
+	pub interner: Interner,
+	pub docs: Vec<InternedDocument>,
+	pub extra: Map<String, Value>,
+}
+
+impl InternedCorpus {
+	/// reads [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) from a reader, interning the tokens while they are
+	/// parsed, so that the token lists are never materialized with their strings.
+	pub fn from_reader<R: Read>(reader: R) -> Result<InternedCorpus> {
+		let mut corpus = InternedCorpus::default();
+		let mut tokens = Vec::new();
+		let j = stream_tokens(reader, &StreamOptions::default(), |event| {
+			match event {
+				StreamEvent::Token(t) => tokens.push(corpus.interner.intern_token(t)),
+				StreamEvent::Document(d) => {
+					let mut d = corpus.interner.intern_document(d);
+					d.tokens = std::mem::take(&mut tokens);
+					corpus.docs.push(d);
+				}
+				StreamEvent::SentenceStart(_) => {}
+			}
+			Ok(())
+		})?;
+		corpus.meta = j.meta;
+		corpus.extra = j.extra;
+		Ok(corpus)
+	}
+
+	/// returns the collection with the strings of all symbols.
+	pub fn to_jsonnlp(&self) -> JSONNLP {
+		JSONNLP {
+			meta: self.meta.clone(),
+			docs: self.docs.iter().map(|d| self.interner.document(d)).collect(),
+			extra: self.extra.clone(),
+		}
+	}
+}
+
+impl From<JSONNLP> for InternedCorpus {
+	fn from(j: JSONNLP) -> Self {
+		let mut interner = Interner::new();
+		let docs = j.docs.into_iter().map(|d| interner.intern_document(d)).collect();
+		InternedCorpus { meta: j.meta, interner, docs, extra: j.extra }
+	}
+}
+
+impl Serialize for InternedCorpus {
+	fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+		/// This struct serializes the documents one at a time.
+		struct Docs<'a>(&'a InternedCorpus);
+
+		impl Serialize for Docs<'_> {
+			fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+				serializer.collect_seq(self.0.docs.iter().map(|d| self.0.interner.document(d)))
+			}
+		}
+
+		#[derive(Serialize)]
+		struct Collection<'a> {
+			meta: &'a Meta,
+			docs: Docs<'a>,
+			#[serde(flatten)]
+			extra: &'a Map<String, Value>,
+		}
+
+		Collection { meta: &self.meta, docs: Docs(self), extra: &self.extra }.serialize(serializer)
+	}
+}
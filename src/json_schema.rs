@@ -0,0 +1,31 @@
+//! The [JSON Schema](https://json-schema.org/) of the model types, derived with [schemars](https://graham.cool/schemars/).
+//! Unlike the schema of the JSON-NLP specification used by validate_schema(), it describes exactly the JSON that this
+//! crate reads and writes, e.g. with the optional properties it adds and the range of probabilities.
+
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+use serde_json::Value;
+
+use crate::{Prob, JSONNLP};
+
+impl JsonSchema for Prob {
+	fn schema_name() -> Cow<'static, str> {
+		"Prob".into()
+	}
+
+	fn json_schema(_: &mut SchemaGenerator) -> Schema {
+		json_schema!({
+			"type": "number",
+			"minimum": 0.0,
+			"maximum": 1.0,
+		})
+	}
+}
+
+impl JSONNLP {
+	/// returns the JSON Schema of JSON-NLP collections as accepted and emitted by this crate.
+	pub fn json_schema() -> Value {
+		schemars::schema_for!(JSONNLP).to_value()
+	}
+}
@@ -0,0 +1,106 @@
+//! Export of the entity and triple layers of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents
+//! as a [JSON-LD](https://www.w3.org/TR/json-ld11/) graph.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Map, Value};
+
+use crate::{Document, EntityId};
+
+const RDFS_LABEL: &str = "http://www.w3.org/2000/01/rdf-schema#label";
+
+/// This struct contains the options for the JSON-LD export.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct JsonLdOptions {
+	/// the IRI that the IDs of entities without URL are appended to, e.g. `http://example.org/doc1#`,
+	/// or blank nodes if empty.
+	pub base: String,
+	/// the IRI that entity and relation types are appended to, e.g. `http://example.org/vocab#`,
+	/// or empty to keep them as terms to be defined by the context.
+	pub vocab: String,
+	/// the `@context` of the graph, or None for a context declaring the base and vocabulary IRIs.
+	pub context: Option<Value>,
+}
+
+impl JsonLdOptions {
+	/// returns the IRI of an entity or relation type, kept as given if it is an IRI or compact IRI already.
+	fn type_iri(&self, name: &str) -> String {
+		if name.contains(':') || self.vocab.is_empty() {
+			name.to_string()
+		} else {
+			format!("{}{}", self.vocab, name.split_whitespace().collect::<Vec<_>>().join("_"))
+		}
+	}
+}
+
+/// This function converts the entities and triples of a Document to a JSON-LD graph. Every entity becomes a node
+/// identified by its URL, or else by `entity-1` etc. appended to the base IRI, typed by its type, and labeled with
+/// its label as `rdfs:label`. Every triple with both entities and a relation links the nodes by a property
+/// identified by the relation's URL, or else its type or label appended to the vocabulary IRI, and triples that
+/// are not directional link both ways.
+pub fn to_jsonld(doc: &Document, options: &JsonLdOptions) -> Value {
+	let node_id = |id: EntityId| match doc.entities.iter().find(|e| e.id == id) {
+		Some(e) if !e.url.is_empty() => e.url.clone(),
+		_ if options.base.is_empty() => format!("_:entity-{}", id),
+		_ => format!("{}entity-{}", options.base, id),
+	};
+
+	let mut nodes: Vec<Map<String, Value>> = Vec::new();
+	let mut index: HashMap<EntityId, usize> = HashMap::new();
+	for e in &doc.entities {
+		let mut node = Map::new();
+		node.insert("@id".to_string(), json!(node_id(e.id)));
+		if !e.etype.is_empty() {
+			node.insert("@type".to_string(), json!(options.type_iri(&e.etype)));
+		}
+		if !e.label.is_empty() {
+			node.insert(RDFS_LABEL.to_string(), json!(e.label));
+		}
+		index.insert(e.id, nodes.len());
+		nodes.push(node);
+	}
+
+	for t in &doc.triples {
+		let relation = t.rel.and_then(|id| doc.relations.iter().find(|r| r.id == id));
+		let (relation, from, to) = match (relation, t.from_entity, t.to_entity) {
+			(Some(r), Some(from), Some(to)) if index.contains_key(&from) && index.contains_key(&to) => (r, from, to),
+			_ => continue,
+		};
+		let property = match (relation.url.as_str(), relation.rtype.as_str()) {
+			("", "") => options.type_iri(&relation.label),
+			("", rtype) => options.type_iri(rtype),
+			(url, _) => url.to_string(),
+		};
+		if property.is_empty() {
+			continue;
+		}
+		let mut link = |from: EntityId, to: EntityId| {
+			let values = nodes[index[&from]].entry(property.clone()).or_insert_with(|| Value::Array(Vec::new()));
+			if let Value::Array(values) = values {
+				let target = json!({ "@id": node_id(to) });
+				if !values.contains(&target) {
+					values.push(target);
+				}
+			}
+		};
+		link(from, to);
+		if t.directional == Some(false) {
+			link(to, from);
+		}
+	}
+
+	let context = options.context.clone().unwrap_or_else(|| {
+		let mut context = Map::new();
+		if !options.base.is_empty() {
+			context.insert("@base".to_string(), json!(options.base));
+		}
+		if !options.vocab.is_empty() {
+			context.insert("@vocab".to_string(), json!(options.vocab));
+		}
+		Value::Object(context)
+	});
+	json!({
+		"@context": context,
+		"@graph": nodes,
+	})
+}
@@ -0,0 +1,87 @@
+//! BCP-47 language tags for `DC.language` and token languages.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::JsonNlpError;
+
+/// This struct contains a BCP-47 language tag such as `en`, `en-US`, or `zh-Hant-TW`.
+/// With the `bcp47` feature, tags are checked against the IANA subtag registry when they are
+/// created or deserialized, so codes like `english` or `EN_us` are rejected.
+#[derive(Serialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct LanguageTag(String);
+
+impl LanguageTag {
+	/// returns the language tag, or a validation error if it is not a valid BCP-47 tag.
+	pub fn new(tag: &str) -> Result<Self, JsonNlpError> {
+		check(tag).map_err(JsonNlpError::Validation)?;
+		Ok(LanguageTag(tag.to_string()))
+	}
+
+	/// returns the tag as a string.
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+
+	/// returns true if no language is set.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// returns the primary language subtag, e.g. `zh` for `zh-Hant-TW`.
+	pub fn primary_language(&self) -> &str {
+		self.0.split('-').next().unwrap_or("")
+	}
+
+	/// returns the script subtag, e.g. `Hant` for `zh-Hant-TW`.
+	pub fn script(&self) -> Option<&str> {
+		self.0.split('-')
+			.skip(1)
+			// extended language subtags have three letters and precede the script
+			.find(|s| !(s.len() == 3 && s.chars().all(|c| c.is_ascii_alphabetic())))
+			.filter(|s| s.len() == 4 && s.chars().all(|c| c.is_ascii_alphabetic()))
+	}
+}
+
+#[cfg(feature = "bcp47")]
+fn check(tag: &str) -> Result<(), String> {
+	language_tags::LanguageTag::parse(tag)
+		.map_err(|e| e.to_string())
+		.and_then(|t| t.validate().map_err(|e| e.to_string()))
+		.map_err(|e| format!("{:?} is not a BCP-47 language tag: {}", tag, e))
+}
+
+#[cfg(not(feature = "bcp47"))]
+fn check(_tag: &str) -> Result<(), String> {
+	Ok(())
+}
+
+impl FromStr for LanguageTag {
+	type Err = JsonNlpError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		LanguageTag::new(s)
+	}
+}
+
+impl fmt::Display for LanguageTag {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for LanguageTag {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		if s.is_empty() {
+			return Ok(LanguageTag::default());
+		}
+		check(&s).map_err(D::Error::custom)?;
+		Ok(LanguageTag(s))
+	}
+}
@@ -0,0 +1,109 @@
+//! Export of the dependency trees and constituent trees of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP)
+//! documents as LaTeX, for the [tikz-dependency](https://ctan.org/pkg/tikz-dependency) and
+//! [forest](https://ctan.org/pkg/forest) packages, e.g. for figures in papers.
+
+use std::collections::HashMap;
+
+use crate::{ConstituentTree, DependencyTree, Document, TokenId};
+
+/// returns a text with the special characters of LaTeX escaped.
+fn escape_latex(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'\\' => out.push_str("\\textbackslash{}"),
+			'~' => out.push_str("\\textasciitilde{}"),
+			'^' => out.push_str("\\textasciicircum{}"),
+			'&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+				out.push('\\');
+				out.push(c);
+			}
+			_ => out.push(c),
+		}
+	}
+	out
+}
+
+/// returns a text escaped for a cell of a `deptext`, where `\&` separates the cells, so that ampersands are written
+/// as characters.
+fn escape_cell(s: &str) -> String {
+	s.split('&').map(escape_latex).collect::<Vec<_>>().join("{\\char`\\&}")
+}
+
+/// This function converts a dependency tree of a Document to a `dependency` environment of tikz-dependency. The
+/// tokens are those of the tree's sentence, or else those of its dependencies, in the order of the token list, with
+/// a second row of their UPOS, or else XPOS, tags if any token has one. Every dependency is a `\depedge` from the
+/// governor to the dependent, and the dependents of the root are marked by `\deproot`. Dependencies of tokens that
+/// are not in the token list are skipped.
+pub fn to_tikz_dependency(doc: &Document, tree: &DependencyTree) -> String {
+	let tokens = doc.dependency_tree_tokens(tree);
+	// tikz-dependency counts the words from 1
+	let position: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i + 1)).collect();
+
+	let mut out = String::from("\\begin{dependency}\n\\begin{deptext}\n");
+	let words: Vec<String> = tokens.iter().map(|t| escape_cell(&t.text)).collect();
+	out.push_str(&format!("{} \\\\\n", words.join(" \\& ")));
+	let tags: Vec<String> =
+		tokens.iter().map(|t| escape_cell(if t.upos.is_empty() { &t.xpos } else { &t.upos })).collect();
+	if tags.iter().any(|t| !t.is_empty()) {
+		out.push_str(&format!("{} \\\\\n", tags.join(" \\& ")));
+	}
+	out.push_str("\\end{deptext}\n");
+
+	for d in &tree.dependencies {
+		let dep = match position.get(&d.dep) {
+			Some(dep) => dep,
+			None => continue,
+		};
+		if d.gov == TokenId::default() {
+			out.push_str(&format!("\\deproot{{{}}}{{{}}}\n", dep, escape_latex(&d.lab)));
+		} else if let Some(gov) = position.get(&d.gov) {
+			out.push_str(&format!("\\depedge{{{}}}{{{}}}{{{}}}\n", gov, dep, escape_latex(&d.lab)));
+		}
+	}
+	out.push_str("\\end{dependency}\n");
+	out
+}
+
+impl ConstituentTree {
+	/// returns the tree as a `forest` environment of the forest package, with every node in brackets, e.g.
+	/// `[S [NP [DT [The]] [NN [dog]]] [VP [VBZ [barks]]]]`.
+	pub fn to_forest(&self) -> String {
+		let mut out = String::from("\\begin{forest}\n");
+		self.write_forest(&mut out);
+		out.push_str("\n\\end{forest}\n");
+		out
+	}
+
+	fn write_forest(&self, out: &mut String) {
+		let label = escape_latex(&self.label);
+		out.push('[');
+		// brackets, commas, and equal signs would be read as forest syntax
+		if label.contains(['[', ']', ',', '=']) || label.is_empty() {
+			out.push_str(&format!("{{{}}}", label));
+		} else {
+			out.push_str(&label);
+		}
+		for c in &self.children {
+			out.push(' ');
+			c.write_forest(out);
+		}
+		out.push(']');
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::from_conllu;
+
+	#[test]
+	fn ampersands_in_deptext() {
+		let doc = &from_conllu("1\tA&B\tA&B\tPROPN\t_\t_\t2\tnsubj\t_\t_\n2\twon\twin\tVERB\t_\t_\t0\troot\t_\t_\n\n")
+			.expect("valid CoNLL-U")
+			.docs[0];
+		let tikz = to_tikz_dependency(doc, &doc.dependency_trees[0]);
+		assert!(tikz.contains("A{\\char`\\&}B \\& won \\\\\n"));
+		assert!(tikz.contains("\\depedge{2}{1}{nsubj}\n"));
+	}
+}
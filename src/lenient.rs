@@ -0,0 +1,285 @@
+//! Tolerant parsing that repairs common problems of real-world JSON-NLP and reports them as warnings.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+
+use serde_json::{Map, Value};
+
+use crate::prob::{self, ProbPolicy};
+use crate::{Result, JSONNLP};
+
+/// This struct describes one repair made by tolerant parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseWarning {
+	/// the JSON pointer of the repaired element.
+	pub pointer: String,
+	/// a description of the problem and the repair.
+	pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}: {}", self.pointer, self.message)
+	}
+}
+
+const NUMBER_KEYS: &[&str] = &[
+	"id", "sentence_id", "sentenceId", "tokenFrom", "tokenTo", "head", "gov", "dep",
+	"characterOffsetBegin", "characterOffsetEnd", "frameID", "wordNetID", "verbNetID", "number",
+	"person", "tripleID", "count", "fromEntity", "toEntity", "rel", "eventID", "tempSeq",
+];
+
+const ID_LIST_KEYS: &[&str] = &["tokens", "clauses", "sentences", "gov", "dep", "terminals", "clauseID", "sentenceID"];
+
+const PROB_KEYS: &[&str] = &[
+	"xpos_prob", "upos_prob", "propIDProbability", "frameIDProb", "wordNetIDProb", "verbNetIDProb",
+	"sentimentProb", "prob",
+];
+
+const BOOL_KEYS: &[&str] = &[
+	"overt", "stop", "alpha", "perfect", "continuous", "progressive", "human", "animate", "negated",
+	"countable", "factive", "counterfactive", "irregular", "phrasalVerb", "foreign", "spaceAfter",
+	"main", "neg", "directional", "syntactic", "implied", "presupposed",
+];
+
+const STRING_KEYS: &[&str] = &[
+	"text", "lemma", "xpos", "upos", "entity_iob", "propID", "lang", "shape", "entity", "gender",
+	"tense", "case", "mood", "aspect", "voice", "sentiment", "type", "style", "lab", "val", "label",
+	"url", "labeledBracketing", "dependency", "frame", "concept", "role", "top",
+];
+
+struct Repair {
+	warnings: Vec<ParseWarning>,
+}
+
+impl Repair {
+	fn warn(&mut self, pointer: &str, message: String) {
+		self.warnings.push(ParseWarning { pointer: pointer.to_string(), message });
+	}
+
+	/// fixes the types of the known properties of an object and its children.
+	fn object(&mut self, obj: &mut Map<String, Value>, path: &str) {
+		let keys: Vec<String> = obj.keys().cloned().collect();
+		for key in keys {
+			let pointer = format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"));
+			let value = obj.get_mut(&key).expect("key was just listed");
+			if value.is_null() {
+				obj.remove(&key);
+				self.warn(&pointer, "removed null value".to_string());
+				continue;
+			}
+			let k = key.as_str();
+			if ID_LIST_KEYS.contains(&k) && value.is_array() {
+				if let Value::Array(items) = value {
+					for (i, item) in items.iter_mut().enumerate() {
+						// the document layers "sentences" and "clauses" share their names with ID lists
+						if item.is_object() {
+							self.value(item, &format!("{}/{}", pointer, i));
+						} else {
+							self.number(item, &format!("{}/{}", pointer, i));
+						}
+					}
+				}
+			} else if NUMBER_KEYS.contains(&k) {
+				self.number(value, &pointer);
+			} else if PROB_KEYS.contains(&k) {
+				self.prob(value, &pointer);
+			} else if BOOL_KEYS.contains(&k) {
+				self.boolean(value, &pointer);
+			} else if STRING_KEYS.contains(&k) {
+				self.string(value, &pointer);
+			} else {
+				self.value(value, &pointer);
+			}
+		}
+	}
+
+	fn value(&mut self, value: &mut Value, path: &str) {
+		match value {
+			Value::Object(obj) => self.object(obj, path),
+			Value::Array(items) => {
+				for (i, item) in items.iter_mut().enumerate() {
+					self.value(item, &format!("{}/{}", path, i));
+				}
+			}
+			_ => {}
+		}
+	}
+
+	fn number(&mut self, value: &mut Value, pointer: &str) {
+		let repaired = match value {
+			Value::String(s) => s.trim().parse::<u64>().ok()
+				.or_else(|| s.trim().parse::<f64>().ok().filter(|f| *f >= 0.0 && f.fract() == 0.0).map(|f| f as u64)),
+			Value::Number(n) if !n.is_u64() => n.as_f64().filter(|f| *f >= 0.0 && f.fract() == 0.0).map(|f| f as u64),
+			_ => return,
+		};
+		if let Some(n) = repaired {
+			self.warn(pointer, format!("converted {} to the integer {}", value, n));
+			*value = Value::from(n);
+		}
+	}
+
+	fn prob(&mut self, value: &mut Value, pointer: &str) {
+		let p = match value {
+			Value::String(s) => match s.trim().parse::<f64>() {
+				Ok(p) => {
+					self.warn(pointer, format!("converted {} to the number {}", value, p));
+					p
+				}
+				Err(_) => return,
+			},
+			Value::Number(n) => n.as_f64().unwrap_or(0.0),
+			_ => return,
+		};
+		if !(0.0..=1.0).contains(&p) {
+			let clamped = p.clamp(0.0, 1.0);
+			self.warn(pointer, format!("clamped probability {} to {}", p, clamped));
+			*value = Value::from(clamped);
+		} else if !value.is_number() {
+			*value = Value::from(p);
+		}
+	}
+
+	fn boolean(&mut self, value: &mut Value, pointer: &str) {
+		let b = match value {
+			Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+				"true" | "yes" | "1" => true,
+				"false" | "no" | "0" | "" => false,
+				_ => return,
+			},
+			Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+			_ => return,
+		};
+		self.warn(pointer, format!("converted {} to the boolean {}", value, b));
+		*value = Value::Bool(b);
+	}
+
+	fn string(&mut self, value: &mut Value, pointer: &str) {
+		if let Value::Number(_) | Value::Bool(_) = value {
+			let s = value.to_string();
+			self.warn(pointer, format!("converted {} to a string", value));
+			*value = Value::String(s);
+		}
+	}
+
+	fn default_missing(&mut self, obj: &mut Map<String, Value>, key: &str, default: Value, path: &str) {
+		if !obj.contains_key(key) {
+			self.warn(&format!("{}/{}", path, key), format!("added missing property with {}", default));
+			obj.insert(key.to_string(), default);
+		}
+	}
+
+	/// adds the required properties that are missing in a document.
+	fn document(&mut self, doc: &mut Map<String, Value>, index: usize, path: &str) {
+		self.default_missing(doc, "meta", Value::Object(Map::new()), path);
+		self.default_missing(doc, "id", Value::from(index as u64 + 1), path);
+
+		// sentence of each token, as listed by the sentence layer
+		let mut sentence_of = HashMap::new();
+		let mut spans = Vec::new();
+		if let Some(Value::Array(sentences)) = doc.get("sentences") {
+			for s in sentences {
+				let id = match s.get("id").and_then(Value::as_u64) {
+					Some(id) => id,
+					None => continue,
+				};
+				if let Some(Value::Array(tokens)) = s.get("tokens") {
+					for t in tokens.iter().filter_map(Value::as_u64) {
+						sentence_of.insert(t, id);
+					}
+				}
+				if let (Some(from), Some(to)) = (s.get("tokenFrom").and_then(Value::as_u64), s.get("tokenTo").and_then(Value::as_u64)) {
+					spans.push((from, to, id));
+				}
+			}
+		}
+
+		if let Some(Value::Array(tokens)) = doc.get_mut("tokenList") {
+			for (i, t) in tokens.iter_mut().enumerate() {
+				let p = format!("{}/tokenList/{}", path, i);
+				if let Value::Object(t) = t {
+					let sentence = t.get("id").and_then(Value::as_u64)
+						.and_then(|id| sentence_of.get(&id).copied()
+							.or_else(|| spans.iter().find(|(from, to, _)| id >= *from && id <= *to).map(|s| s.2)))
+						.unwrap_or(0);
+					self.default_missing(t, "sentence_id", Value::from(sentence), &p);
+					let lemma = t.get("text").cloned().unwrap_or_else(|| Value::String(String::new()));
+					self.default_missing(t, "text", Value::String(String::new()), &p);
+					self.default_missing(t, "lemma", lemma, &p);
+				}
+			}
+		}
+	}
+}
+
+/// This function converts untyped JSON to a JSONNLP struct, repairing what it can:
+/// numbers, booleans, and strings given with the wrong type are converted, null values removed,
+/// probabilities clamped, and missing required properties such as a token's `sentence_id`
+/// (taken from the sentence layer) or `lemma` (taken from the text) filled in.
+/// All repairs are returned as warnings together with the document.
+pub fn from_value_lenient(mut value: Value) -> Result<(JSONNLP, Vec<ParseWarning>)> {
+	let mut repair = Repair { warnings: Vec::new() };
+	repair.value(&mut value, "");
+	if let Value::Object(top) = &mut value {
+		repair.default_missing(top, "meta", Value::Object(Map::new()), "");
+		if let Some(Value::Array(docs)) = top.get_mut("docs") {
+			for (i, doc) in docs.iter_mut().enumerate() {
+				if let Value::Object(doc) = doc {
+					repair.document(doc, i, &format!("/docs/{}", i));
+				}
+			}
+		}
+	}
+	let j = prob::with_policy(ProbPolicy::Clamp, || JSONNLP::try_from(value))?;
+	Ok((j, repair.warnings))
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) with tolerant parsing,
+/// see from_value_lenient().
+pub fn from_string_lenient(json: &str) -> Result<(JSONNLP, Vec<ParseWarning>)> {
+	let value: Value = serde_json::from_str(json)?;
+	from_value_lenient(value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{SentenceId, TokenId};
+
+	#[test]
+	fn repairs_are_reported() {
+		let json = r#"{"docs":[{"tokenList":[
+			{"id":"1","text":"Dogs","upos_prob":"1.5","features":{"spaceAfter":"no"},"entity":null},
+			{"id":2.0,"text":"bark","lemma":"bark","sentence_id":1}],
+			"sentences":[{"id":1,"tokens":[1,"2"]}]}]}"#;
+		let (j, warnings) = from_string_lenient(json).expect("repairable JSON-NLP");
+		let t = &j.docs[0].token_list[0];
+		assert_eq!((t.id, t.sentence_id, t.lemma.as_str()), (TokenId(1), SentenceId(1), "Dogs"));
+		assert_eq!(t.features().spaceafter, Some(false));
+		assert_eq!(j.docs[0].token_list[1].id, TokenId(2));
+		assert_eq!(j.docs[0].sentences[0].tokens, [TokenId(1), TokenId(2)]);
+		let warnings: Vec<String> = warnings.iter().map(ToString::to_string).collect();
+		assert_eq!(warnings, [
+			"/docs/0/sentences/0/tokens/1: converted \"2\" to the integer 2",
+			"/docs/0/tokenList/0/entity: removed null value",
+			"/docs/0/tokenList/0/features/spaceAfter: converted \"no\" to the boolean false",
+			"/docs/0/tokenList/0/id: converted \"1\" to the integer 1",
+			"/docs/0/tokenList/0/upos_prob: converted \"1.5\" to the number 1.5",
+			"/docs/0/tokenList/0/upos_prob: clamped probability 1.5 to 1",
+			"/docs/0/tokenList/1/id: converted 2.0 to the integer 2",
+			"/meta: added missing property with {}",
+			"/docs/0/meta: added missing property with {}",
+			"/docs/0/id: added missing property with 1",
+			"/docs/0/tokenList/0/sentence_id: added missing property with 1",
+			"/docs/0/tokenList/0/lemma: added missing property with \"Dogs\"",
+		]);
+	}
+
+	#[test]
+	fn unrepairable_input() {
+		assert!(from_string_lenient(r#"{"docs":[{"tokenList":[{"id":"one"}]}]}"#).is_err());
+		assert!(from_string_lenient(r#"{"docs":"#).is_err());
+		assert!(from_string_lenient("[]").is_err());
+	}
+}
@@ -9,6 +9,9 @@
 //!
 //! Version 0.0.5
 //!
+//! Properties that are not modeled by the structs are kept in their `extra` maps,
+//! so documents from other JSON-NLP implementations survive a round-trip unchanged.
+//!
 //! See for more details:
 //!
 //! - [GitHub repo](https://github.com/SemiringInc/RustJSONNLP)
@@ -16,536 +19,1045 @@
 
 // use serde_derive;
 
-use serde;
-use serde_json;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-use std::error::Error;
-use std::fs::File;
-use std::io::BufReader;
+use std::io::{Read, Write};
 use std::path::Path;
 
+#[cfg(feature = "arena")]
+mod arena;
+#[cfg(feature = "arrow")]
+mod arrow;
+#[cfg(feature = "async")]
+mod asyncio;
+#[cfg(feature = "avro")]
+mod avro;
+mod azure;
+#[cfg(feature = "bson")]
+mod bson;
+mod borrowed;
+mod brat;
+mod builder;
+mod canonical;
+mod columnar;
+mod comprehend;
+mod compression;
+mod conll2003;
+mod conll2012;
+mod conllu;
+mod constituent;
+#[cfg(any(feature = "server", feature = "grpc", feature = "node"))]
+mod convert;
+mod coref;
+mod corenlp;
+#[cfg(feature = "chrono")]
+mod datetime;
+mod dependency;
+mod elasticsearch;
+mod error;
+mod features;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "xml")]
+mod gate;
+mod google;
+mod graphml;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+mod html;
+mod huggingface;
+mod ids;
+mod index;
+mod intern;
+#[cfg(feature = "schemars")]
+mod json_schema;
+mod jsonld;
+mod language;
+mod latex;
+mod lenient;
+mod limits;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod ndjson;
+mod neo4j;
+mod nif;
+#[cfg(feature = "node")]
+pub mod node;
+mod offsets;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod penman;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+#[cfg(feature = "polars")]
+mod polars;
+mod prob;
+mod query;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+#[cfg(feature = "python")]
+mod python;
+mod relex;
+mod report;
+#[cfg(feature = "schema")]
+mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "simd")]
+mod simd;
+mod size;
+mod spacy;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod stanza;
+mod stream;
+mod strict;
+mod svg;
+mod table;
+mod tcf;
+mod tei;
+mod text;
+#[cfg(feature = "typescript")]
+mod typescript;
+mod validate;
+mod value;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod watson;
+mod webannotation;
+mod writer;
+mod xml;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "arena")]
+pub use arena::{document_from_str_in, ArenaDependency, ArenaDependencyTree, ArenaDocument, ArenaToken};
+#[cfg(feature = "arrow")]
+pub use arrow::{dependency_batch, entity_batch, to_parquet, token_batch, write_parquet};
+#[cfg(feature = "async")]
+pub use asyncio::{from_file_async, from_reader_async, to_file_async, to_writer_async};
+#[cfg(feature = "avro")]
+pub use avro::{from_avro, from_avro_datum, to_avro, to_avro_datum, AvroReader, AvroWriter, AVRO_SCHEMA};
+pub use azure::from_azure_language_json;
+#[cfg(feature = "bson")]
+pub use bson::{from_bson, to_bson};
+pub use borrowed::{from_slice_borrowed, from_str_borrowed, DependencyRef, DependencyTreeRef, DocumentRef, JSONNLPRef, TokenRef};
+pub use brat::{from_brat, to_brat};
+pub use builder::{DocumentBuilder, MetaBuilder, TokenBuilder, CONFORMS_TO};
+pub use canonical::to_canonical_json;
+pub use columnar::ColumnarDocument;
+pub use comprehend::from_comprehend_json;
+#[cfg(feature = "gzip")]
+pub use compression::{from_gzip_file, to_gzip_file};
+#[cfg(feature = "zstd")]
+pub use compression::{from_zstd_file, from_zstd_reader, to_zstd_file, to_zstd_writer};
+pub use conll2003::{from_conll2003, to_conll2003};
+pub use conll2012::from_conll2012;
+pub use conllu::{from_conllu, from_conllx, to_conllu, to_conllx};
+pub use constituent::{ConstituentPreorder, ConstituentTree};
+pub use coref::CoreferenceMention;
+pub use corenlp::{from_corenlp_json, to_corenlp_json};
+#[cfg(feature = "chrono")]
+pub use datetime::parse_iso8601;
+pub use dependency::{DependencyPath, PathStep};
+pub use elasticsearch::{to_elasticsearch_bulk, ElasticsearchOptions, TokenMapping};
+pub use error::{JsonNlpError, Limit, LimitError, ParseError, Result};
+#[cfg(feature = "xml")]
+pub use gate::from_gate_xml;
+pub use google::from_google_nl_json;
+pub use graphml::to_graphml;
+pub use html::to_html;
+pub use huggingface::TokenClassification;
+pub use ids::{
+	ClauseId, CoreferenceId, DocumentId, EntityId, ExpressionId, ParagraphId, PredicateId, RawId, RelationId, SentenceId,
+	TokenId, TripleId,
+};
+pub use index::{DocumentIndex, SentenceTokens, SentenceView, Sentences};
+pub use intern::{InternedCorpus, InternedDependency, InternedDocument, InternedToken, Interner, Symbol};
+pub use jsonld::{to_jsonld, JsonLdOptions};
+pub use language::LanguageTag;
+pub use latex::to_tikz_dependency;
+pub use lenient::{from_string_lenient, from_value_lenient, ParseWarning};
+pub use limits::Limits;
+#[cfg(feature = "mmap")]
+pub use mmap::{from_mmap, MappedFile};
+#[cfg(feature = "msgpack")]
+pub use msgpack::{from_msgpack, to_msgpack};
+pub use ndjson::{read_ndjson, NdjsonReader, NdjsonWriter};
+pub use neo4j::{to_cypher, to_neo4j_csv, CypherStatement, Neo4jCsv};
+pub use nif::to_nif;
+pub use offsets::{Normalization, OffsetOptions, OffsetUnit};
+#[cfg(feature = "rayon")]
+pub use parallel::{from_slice_parallel, from_string_parallel, from_string_parallel_with_options};
+#[cfg(feature = "petgraph")]
+pub use petgraph::{DependencyGraph, EntityGraph, TripleEdge};
+#[cfg(feature = "polars")]
+pub use polars::{from_dataframes, to_dataframes, DocumentFrames};
+pub use prob::{Prob, ProbPolicy};
+pub use query::{TokenMatch, TokenQuery};
+#[cfg(feature = "protobuf")]
+pub use proto::{from_protobuf, to_protobuf};
+#[cfg(feature = "python")]
+pub use python::{PyDocument, PyJsonNlp, PyToken};
+pub use relex::{from_docred, from_tacred, to_docred, to_tacred};
+pub use report::to_markdown_report;
+#[cfg(feature = "schema")]
+pub use schema::{validate_schema, SchemaViolation, SCHEMA};
+pub use size::DocumentSize;
+pub use spacy::{from_spacy_json, to_spacy_training};
+#[cfg(feature = "sqlite")]
+pub use sqlite::to_sqlite;
+pub use stanza::from_stanza_json;
+pub use stream::{stream_tokens, StreamEvent, StreamOptions};
+pub use svg::{to_dependency_svg, SvgOptions};
+pub use table::{to_corpus_token_table, to_token_table, TableOptions, TokenColumn};
+#[cfg(feature = "xml")]
+pub use tcf::from_tcf;
+pub use tcf::to_tcf;
+pub use tei::{to_tei, TeiOptions};
+pub use text::TextOptions;
+#[cfg(feature = "typescript")]
+pub use typescript::{to_typescript, to_typescript_file};
+pub use validate::{IssueKind, ValidationIssue, ValidationReport};
+#[cfg(feature = "wasm")]
+pub use wasm::WasmJsonNlp;
+pub use watson::from_watson_nlu_json;
+pub use webannotation::to_web_annotations;
+pub use writer::{CollectionWriter, DocumentWriter};
+#[cfg(feature = "xml")]
+pub use xml::from_xml;
+pub use xml::to_xml;
+#[cfg(feature = "yaml")]
+pub use yaml::{from_yaml, to_yaml};
+
+use strict::UnknownFields;
+
 /// contains the metadata for the [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) and individual documents.
 /// The metadata is using Dublin Core (DC) terms.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Meta {
 	#[serde(default,
 		rename = "DC.conformsTo",
 		skip_serializing_if = "String::is_empty")]
-	conforms_to: String, // String,
+	pub conforms_to: String, // String,
 	#[serde(default,
 		rename = "DC.author",
 		skip_serializing_if = "String::is_empty")]
-	author: String,
+	pub author: String,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty",
 		rename = "DC.created")]
-	created: String,
+	#[cfg_attr(feature = "chrono", serde(deserialize_with = "datetime::deserialize"))]
+	#[cfg_attr(feature = "chrono", serde(serialize_with = "datetime::serialize"))]
+	pub created: String,
 	#[serde(default,
 		rename = "DC.date",
 		skip_serializing_if = "String::is_empty")]
-	date: String,
+	#[cfg_attr(feature = "chrono", serde(deserialize_with = "datetime::deserialize"))]
+	#[cfg_attr(feature = "chrono", serde(serialize_with = "datetime::serialize"))]
+	pub date: String,
 	#[serde(default,
 		rename = "DC.source",
 		skip_serializing_if = "String::is_empty")]
-	source: String,
+	pub source: String,
 	#[serde(default,
 		rename = "DC.language",
-		skip_serializing_if = "String::is_empty")]
-	language: String,
+		skip_serializing_if = "LanguageTag::is_empty")]
+	pub language: LanguageTag,
 	#[serde(default,
 		rename = "DC.creator",
 		skip_serializing_if = "String::is_empty")]
-	creator: String,
+	pub creator: String,
 	#[serde(default,
 		rename = "DC.publisher",
 		skip_serializing_if = "String::is_empty")]
-	publisher: String,
+	pub publisher: String,
 	#[serde(default,
 		rename = "DC.title",
 		skip_serializing_if = "String::is_empty")]
-	title: String,
+	pub title: String,
 	#[serde(default,
 		rename = "DC.description",
 		skip_serializing_if = "String::is_empty")]
-	description: String,
+	pub description: String,
 	#[serde(default,
 		rename = "DC.identifier",
 		skip_serializing_if = "String::is_empty")]
-	identifier: String,
+	pub identifier: String,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 ///  contains different morpho-syntactic, semantic, or orthographic token features.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TokenFeatures {
-	#[serde(default)]
-	overt: bool,
-	#[serde(default)]
-	stop: bool,
-	#[serde(default)]
-	alpha: bool,
-	#[serde(default)]
-	number: u8,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub overt: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub stop: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub alpha: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub number: Option<u8>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	gender: String,
-	#[serde(default)]
-	person: u8,
+	pub gender: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub person: Option<u8>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	tense: String,
-	#[serde(default)]
-	perfect: bool,
-	#[serde(default)]
-	continuous: bool,
-	#[serde(default)]
-	progressive: bool,
+	pub tense: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub perfect: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub continuous: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub progressive: Option<bool>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	case: String,
-	#[serde(default)]
-	human: bool,
-	#[serde(default)]
-	animate: bool,
-	#[serde(default)]
-	negated: bool,
-	#[serde(default)]
-	countable: bool,
-	#[serde(default)]
-	factive: bool,
-	#[serde(default)]
-	counterfactive: bool,
-	#[serde(default)]
-	irregular: bool,
+	pub case: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub human: Option<bool>,
 	#[serde(default,
-		rename = "phrasalVerb")]
-	phrasalverb: bool,
+		skip_serializing_if = "Option::is_none")]
+	pub animate: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub negated: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub countable: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub factive: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub counterfactive: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub irregular: Option<bool>,
+	#[serde(default,
+		rename = "phrasalVerb",
+		skip_serializing_if = "Option::is_none")]
+	pub phrasalverb: Option<bool>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	mood: String,
-	#[serde(default)]
-	foreign: bool,
+	pub mood: String,
 	#[serde(default,
-		rename = "spaceAfter")]
-	spaceafter: bool,
+		skip_serializing_if = "Option::is_none")]
+	pub foreign: Option<bool>,
+	#[serde(default,
+		rename = "spaceAfter",
+		skip_serializing_if = "Option::is_none")]
+	pub spaceafter: Option<bool>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl TokenFeatures {
+	/// returns the features for the `features` field of a token, which is None if they are empty.
+	pub fn boxed(self) -> Option<Box<TokenFeatures>> {
+		if &self == features::empty() {
+			None
+		} else {
+			Some(Box::new(self))
+		}
+	}
 }
 
 /// contains the token information.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Token {
-	id: u64,
-	sentence_id: u64,
-	text: String,
-	lemma: String,
+	pub id: TokenId,
+	pub sentence_id: SentenceId,
+	pub text: String,
+	pub lemma: String,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	xpos: String,
-	#[serde(default)]
-	xpos_prob: f64,
+	pub xpos: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub xpos_prob: Option<Prob>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	upos: String,
-	#[serde(default)]
-	upos_prob: f64,
+	pub upos: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub upos_prob: Option<Prob>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty")]
-	entity_iob: String,
+	pub entity_iob: String,
 	#[serde(default,
-		rename = "characterOffsetBegin")]
-	char_offset_begin: u64,
+		rename = "characterOffsetBegin",
+		skip_serializing_if = "Option::is_none")]
+	pub char_offset_begin: Option<u64>,
 	#[serde(default,
-		rename = "characterOffsetEnd")]
-	char_offset_end: u64,
+		rename = "characterOffsetEnd",
+		skip_serializing_if = "Option::is_none")]
+	pub char_offset_end: Option<u64>,
 	#[serde(default,
 		skip_serializing_if = "String::is_empty",
 		rename = "propID")]
-	prop_id: String,
+	pub prop_id: String,
 	#[serde(rename = "propIDProbability",
-		default)]
-	prop_id_prob: f64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub prop_id_prob: Option<Prob>,
 	#[serde(rename = "frameID",
-		default)]
-	frame_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub frame_id: Option<u64>,
 	#[serde(rename = "frameIDProb",
-		default)]
-	frame_id_prob: f64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub frame_id_prob: Option<Prob>,
 	#[serde(rename = "wordNetID",
-		default)]
-	wordnet_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub wordnet_id: Option<u64>,
 	#[serde(rename = "wordNetIDProb",
-		default)]
-	wordnet_id_prob: f64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub wordnet_id_prob: Option<Prob>,
 	#[serde(rename = "verbNetID",
-		default)]
-	verbnet_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub verbnet_id: Option<u64>,
 	#[serde(rename = "verbNetIDProb",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub verbnet_id_prob: Option<Prob>,
+	#[serde(skip_serializing_if = "LanguageTag::is_empty",
 		default)]
-	verbnet_id_prob: f64,
-	#[serde(skip_serializing_if = "String::is_empty",
-		default)]
-	lang: String,
-	// #[serde(default)]
-	features: TokenFeatures,
+	pub lang: LanguageTag,
+	#[serde(default,
+		with = "features")]
+	#[cfg_attr(feature = "schemars", schemars(with = "TokenFeatures"))]
+	pub features: Option<Box<TokenFeatures>>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	shape: String,
+	pub shape: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	entity: String,
+	pub entity: String,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl Token {
+	/// returns a TokenBuilder with the required token properties set.
+	pub fn builder(id: TokenId, sentence_id: SentenceId, text: &str, lemma: &str) -> TokenBuilder {
+		TokenBuilder::new(id, sentence_id, text, lemma)
+	}
+
+	/// returns the features of the token, which are empty if none are set.
+	pub fn features(&self) -> &TokenFeatures {
+		self.features.as_deref().unwrap_or(features::empty())
+	}
+
+	/// returns the features of the token for modification, allocating them if none are set.
+	pub fn features_mut(&mut self) -> &mut TokenFeatures {
+		self.features.get_or_insert_with(Default::default)
+	}
 }
 
 /// contains sentence information.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Sentence {
-	id: u64,
+	pub id: SentenceId,
+	#[serde(default,
+		skip_serializing_if = "String::is_empty")]
+	pub text: String,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	tokens: Vec<u64>,
+	pub tokens: Vec<TokenId>,
 	#[serde(default)]
-	clauses: Vec<u64>,
+	pub clauses: Vec<ClauseId>,
 	#[serde(rename = "type",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	stype: String,
+	pub stype: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	sentiment: String,
+	pub sentiment: String,
 	#[serde(rename = "sentimentProb",
-		default)]
-	sentiment_prob: f64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentiment_prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// contains clause information, assuming that sentences contain one or more clauses.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Clause {
-	id: u64,
+	pub id: ClauseId,
 	#[serde(rename = "sentenceId",
-		default)]
-	sentence_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentence_id: Option<SentenceId>,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
-	#[serde(default)]
-	tokens: Vec<u64>,
-	#[serde(default)]
-	main: bool,
-	#[serde(default)]
-	gov: u64,
-	#[serde(default)]
-	head: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	neg: bool,
+	pub tokens: Vec<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub main: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub gov: Option<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub head: Option<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub neg: Option<bool>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	tense: String,
+	pub tense: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	mood: String,
-	#[serde(default)]
-	perfect: bool,
-	#[serde(default)]
-	continuous: bool,
+	pub mood: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub perfect: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub continuous: Option<bool>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	aspect: String,
+	pub aspect: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	voice: String,
+	pub voice: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	sentiment: String,
+	pub sentiment: String,
 	#[serde(rename = "sentimentProb",
-		default)]
-	sentiment_prob: f64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentiment_prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// contains dependency information as part of dependency trees.
 /// A dependency is a tuple that contains a governor token ID, a dependent token ID, and a dependency label.
 /// In addition, each dependency can provide probability information about the confidence or another likelihood property.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Dependency {
-	lab: String,
-	gov: u64,
-	dep: u64,
-	#[serde(default)]
-	prob: f64,
+	pub lab: String,
+	pub gov: TokenId,
+	pub dep: TokenId,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about a dependency tree.
 /// A dependency tree is a set of dependency triples.
 /// In addition a tree provides the possibility to encode a probability score for the dependency tree.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DependencyTree {
 	#[serde(rename = "sentenceId",
-		default)]
-	sentence_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentence_id: Option<SentenceId>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	style: String,
-	#[serde(default)]
-	dependencies: Vec<Dependency>,
+	pub style: String,
 	#[serde(default)]
-	prob: f64,
+	pub dependencies: Vec<Dependency>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about a representative phrase or token for coreference.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CoreferenceRepresentantive {
-	tokens: Vec<u64>,
-	head: u64,
+	pub tokens: Vec<TokenId>,
+	pub head: TokenId,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about a referent or anaphoric expression that refers to some referent.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct CoreferenceReferents {
-	tokens: Vec<u64>,
-	head: u64,
-	#[serde(default)]
-	prob: f64,
+	pub tokens: Vec<TokenId>,
+	pub head: TokenId,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about a coreference relation between one referent and a list of refering expressions.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Coreference {
-	id: u64,
-	representative: CoreferenceRepresentantive,
-	referents: Vec<CoreferenceReferents>,
+	pub id: CoreferenceId,
+	pub representative: CoreferenceRepresentantive,
+	pub referents: Vec<CoreferenceReferents>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about scope relations between tokens or phrases in a sentence.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Scope {
-	id: u64,
-	gov: Vec<u64>,
-	dep: Vec<u64>,
-	terminals: Vec<u64>,
+	pub id: u64,
+	pub gov: Vec<TokenId>,
+	pub dep: Vec<TokenId>,
+	pub terminals: Vec<TokenId>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about the constituent parse tree for a sentence.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ConstituentParse {
 	#[serde(rename = "sentenceId")]
-	sentence_id: u64,
+	pub sentence_id: SentenceId,
 	#[serde(rename = "type",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	ctype: String,
+	pub ctype: String,
 	#[serde(rename = "labeledBracketing",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	labeled_bracketing: String,
-	#[serde(default)]
-	prob: f64,
+	pub labeled_bracketing: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
 	#[serde(default)]
-	scopes: Vec<Scope>,
+	pub scopes: Vec<Scope>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct provides information about expressions or chunks in the text.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Expression {
-	id: u64,
+	pub id: ExpressionId,
 	#[serde(rename = "type",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	etype: String,
-	#[serde(default)]
-	head: u64,
+	pub etype: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub head: Option<TokenId>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	dependency: String,
+	pub dependency: String,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
-	#[serde(default)]
-	tokens: Vec<u64>,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	prob: f64,
+	pub tokens: Vec<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains information about paragraph properties in the text.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Paragraph {
-	id: u64,
+	pub id: ParagraphId,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	tokens: Vec<u64>,
+	pub tokens: Vec<TokenId>,
 	#[serde(default)]
-	sentences: Vec<u64>,
+	pub sentences: Vec<SentenceId>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct encodes generic attribute value tuples for Attribute Value Matrix (AVM) based encoding of properties.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Attribute {
-	lab: String,
-	val: String,
+	pub lab: String,
+	pub val: String,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct encodes entity properties.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Entity {
-	id: u64,
+	pub id: EntityId,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	label: String,
+	pub label: String,
 	#[serde(rename = "type",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	etype: String,
+	pub etype: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	url: String,
-	#[serde(default)]
-	head: u64,
+	pub url: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub head: Option<TokenId>,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	tokens: Vec<u64>,
+	pub tokens: Vec<TokenId>,
 	#[serde(rename = "tripleID",
-		default)]
-	triple_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub triple_id: Option<TripleId>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	sentiment: String,
+	pub sentiment: String,
 	#[serde(rename = "sentimentProb",
-		default)]
-	sentiment_prob: f64,
-	#[serde(default)]
-	count: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentiment_prob: Option<Prob>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub count: Option<u64>,
 	#[serde(default)]
-	attributes: Vec<Attribute>,
+	pub attributes: Vec<Attribute>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct encodes relations and properties in a graph for entity, cocept, or knowledge graphs.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Relation {
-	id: u64,
+	pub id: RelationId,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	label: String,
+	pub label: String,
 	#[serde(rename = "type",
 		default,
 		skip_serializing_if = "String::is_empty")]
-	rtype: String,
+	pub rtype: String,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	url: String,
-	#[serde(default)]
-	head: u64,
+	pub url: String,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub head: Option<TokenId>,
 	#[serde(rename = "tokenFrom",
-		default)]
-	token_from: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
 	#[serde(rename = "tokenTo",
-		default)]
-	token_to: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	tokens: Vec<u64>,
+	pub tokens: Vec<TokenId>,
 	#[serde(skip_serializing_if = "String::is_empty",
 		default)]
-	sentiment: String,
+	pub sentiment: String,
 	#[serde(rename = "sentimentProb",
-		default)]
-	sentiment_prob: f64,
-	#[serde(default)]
-	count: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentiment_prob: Option<Prob>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub count: Option<u64>,
 	#[serde(default)]
-	attributes: Vec<Attribute>,
+	pub attributes: Vec<Attribute>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct encodes triples for RDF, JSON-LD, or general Knowledge Graph encoding.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Triple {
-	id: u64,
+	pub id: TripleId,
 	#[serde(rename = "fromEntity",
-		default)]
-	from_entity: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub from_entity: Option<EntityId>,
 	#[serde(rename = "toEntity",
-		default)]
-	to_entity: u64,
-	#[serde(default)]
-	rel: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub to_entity: Option<EntityId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub rel: Option<RelationId>,
 	#[serde(rename = "clauseID",
 		default)]
-	clause_id: Vec<u64>,
+	pub clause_id: Vec<ClauseId>,
 	#[serde(rename = "sentenceID",
 		default)]
-	sentence_id: Vec<u64>,
-	#[serde(default)]
-	directional: bool,
+	pub sentence_id: Vec<SentenceId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub directional: Option<bool>,
 	#[serde(rename = "eventID",
-		default)]
-	event_id: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub event_id: Option<u64>,
 	#[serde(rename = "tempSeq",
-		default)]
-	temp_seq: u64,
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub temp_seq: Option<u64>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub syntactic: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub implied: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub presupposed: Option<bool>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub count: Option<u64>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes a semantic argument of a predicate, e.g. `ARG0` or `ARGM-TMP`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Argument {
+	pub label: String,
+	#[serde(rename = "tokenFrom",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_from: Option<TokenId>,
+	#[serde(rename = "tokenTo",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub token_to: Option<TokenId>,
 	#[serde(default)]
-	prob: f64,
+	pub tokens: Vec<TokenId>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes a predicate-argument structure from semantic role labeling (SRL).
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct Predicate {
+	pub id: PredicateId,
+	#[serde(rename = "sentenceId",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentence_id: Option<SentenceId>,
+	pub head: TokenId,
+	#[serde(default,
+		skip_serializing_if = "String::is_empty")]
+	pub lemma: String,
+	/// the frame or roleset of the predicate, e.g. a PropBank frameset like `run.01`.
+	#[serde(default,
+		skip_serializing_if = "String::is_empty")]
+	pub frame: String,
 	#[serde(default)]
-	syntactic: bool,
+	pub arguments: Vec<Argument>,
+	#[serde(default,
+		skip_serializing_if = "Option::is_none")]
+	pub prob: Option<Prob>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes a concept node of an abstract meaning representation (AMR) graph.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AmrNode {
+	/// the variable of the node, e.g. `b`.
+	pub id: String,
+	/// the concept of the node, e.g. `boy` or a PropBank frameset like `want-01`.
+	pub concept: String,
+	/// the tokens the concept is aligned to.
+	#[serde(default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub tokens: Vec<TokenId>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes a role between two nodes of an AMR graph, e.g. `ARG0`. Inverse roles like `ARG0-of`
+/// are kept as written, with the node they are written on as the source.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AmrEdge {
+	pub source: String,
+	pub role: String,
+	pub target: String,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes a constant value of a node of an AMR graph, e.g. `:polarity -` or `:op1 "Obama"`.
+/// String constants keep their quotes.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AmrAttribute {
+	pub source: String,
+	pub role: String,
+	pub value: String,
+	/// the tokens the constant is aligned to.
+	#[serde(default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub tokens: Vec<TokenId>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+/// This struct encodes the abstract meaning representation (AMR) graph of a sentence.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct AmrGraph {
+	#[serde(rename = "sentenceId",
+		default,
+		skip_serializing_if = "Option::is_none")]
+	pub sentence_id: Option<SentenceId>,
+	/// the variable of the top node.
+	pub top: String,
 	#[serde(default)]
-	implied: bool,
+	pub nodes: Vec<AmrNode>,
 	#[serde(default)]
-	presupposed: bool,
+	pub edges: Vec<AmrEdge>,
 	#[serde(default)]
-	count: u64,
+	pub attributes: Vec<AmrAttribute>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /// This struct contains all the information for one particular document.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Document {
-	meta: Meta,
-	id: u64,
+	pub meta: Meta,
+	pub id: DocumentId,
+	#[serde(default,
+		skip_serializing_if = "String::is_empty")]
+	pub text: String,
 	#[serde(rename = "tokenList",
 		default)]
-	token_list: Vec<Token>,
+	pub token_list: Vec<Token>,
 	#[serde(default)]
-	clauses: Vec<Clause>,
+	pub clauses: Vec<Clause>,
 	#[serde(default)]
-	sentences: Vec<Sentence>,
+	pub sentences: Vec<Sentence>,
 	#[serde(default)]
-	paragraphs: Vec<Paragraph>,
+	pub paragraphs: Vec<Paragraph>,
 	#[serde(rename = "dependencyTrees",
 		default)]
-	dependency_trees: Vec<DependencyTree>,
+	pub dependency_trees: Vec<DependencyTree>,
 	#[serde(default)]
-	coreferences: Vec<Coreference>,
+	pub coreferences: Vec<Coreference>,
 	#[serde(default)]
-	constituents: Vec<ConstituentParse>,
+	pub constituents: Vec<ConstituentParse>,
 	#[serde(default)]
-	expressions: Vec<Expression>,
+	pub expressions: Vec<Expression>,
 	#[serde(default)]
-	entities: Vec<Entity>,
+	pub entities: Vec<Entity>,
 	#[serde(default)]
-	relations: Vec<Relation>,
+	pub relations: Vec<Relation>,
 	#[serde(default)]
-	triples: Vec<Triple>,
+	pub triples: Vec<Triple>,
+	#[serde(rename = "semanticRoles",
+		default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub semantic_roles: Vec<Predicate>,
+	#[serde(rename = "amrGraphs",
+		default,
+		skip_serializing_if = "Vec::is_empty")]
+	pub amr_graphs: Vec<AmrGraph>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
+}
+
+impl Document {
+	/// returns a DocumentBuilder for a document with the given ID.
+	pub fn builder(id: DocumentId) -> DocumentBuilder {
+		DocumentBuilder::new(id)
+	}
 }
 
 /// This struct contains general elements of a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document.
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct JSONNLP {
-	meta: Meta,
+	pub meta: Meta,
 	#[serde(default)]
-	docs: Vec<Document>,
+	pub docs: Vec<Document>,
+	#[serde(flatten)]
+	pub extra: Map<String, Value>,
 }
 
 /*
@@ -559,21 +1071,191 @@ where
 */
 
 /// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), returning a JSONNLP struct.
-pub fn from_string(json: &str) -> Result<JSONNLP, Box<dyn Error>> {
-	let r = serde_json::from_str::<JSONNLP>(json).unwrap();
-	Ok(r)
+/// With the `simd` feature, it is parsed by simd-json from a copy of the string.
+pub fn from_string(json: &str) -> Result<JSONNLP> {
+	#[cfg(feature = "simd")]
+	{
+		simd::from_buffer(json.as_bytes().to_vec())
+	}
+	#[cfg(not(feature = "simd"))]
+	{
+		let mut de = serde_json::Deserializer::from_str(json);
+		let r = serde_path_to_error::deserialize(&mut de)?;
+		de.end()?;
+		Ok(r)
+	}
+}
+
+/// This struct contains the options for parsing JSON-NLP.
+/// By default, unknown properties are accepted and kept in the `extra` maps.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ParseOptions {
+	/// reject documents containing properties that are not part of the JSON-NLP model.
+	pub strict: bool,
+	/// how probabilities outside of 0.0..=1.0 are handled.
+	pub prob_policy: ProbPolicy,
+	/// the resource limits for untrusted input, none by default.
+	pub limits: Limits,
+}
+
+impl ParseOptions {
+	/// returns options for strict parsing.
+	pub fn strict() -> Self {
+		ParseOptions { strict: true, ..Default::default() }
+	}
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) using the given options.
+pub fn from_string_with_options(json: &str, options: &ParseOptions) -> Result<JSONNLP> {
+	options.limits.check(json.as_bytes())?;
+	let r = prob::with_policy(options.prob_policy, || from_string(json))?;
+	check_options(r, options)
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP),
+/// returning an error if it contains properties that are not part of the JSON-NLP model.
+pub fn from_string_strict(json: &str) -> Result<JSONNLP> {
+	from_string_with_options(json, &ParseOptions::strict())
+}
+
+pub(crate) fn check_options(j: JSONNLP, options: &ParseOptions) -> Result<JSONNLP> {
+	if options.strict {
+		let mut unknown = Vec::new();
+		j.unknown_fields("", &mut unknown);
+		if !unknown.is_empty() {
+			return Err(JsonNlpError::UnknownFields(unknown));
+		}
+	}
+	Ok(j)
 }
 
 /// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from a file and returns a JSONNLP struct.
-pub fn from_file<P: AsRef<Path>>(path: P) -> Result<JSONNLP, Box<dyn Error>> {
-	let file = File::open(path)?;
-	let reader = BufReader::new(file);
-	let u = serde_json::from_reader(reader)?;
-	Ok(u)
+/// With the `gzip` and `zstd` features, gzip- and zstd-compressed files are detected and decompressed.
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<JSONNLP> {
+	from_reader(compression::open(path)?)
+}
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from a file using the given options.
+/// The limits apply to the decompressed content.
+pub fn from_file_with_options<P: AsRef<Path>>(path: P, options: &ParseOptions) -> Result<JSONNLP> {
+	from_reader_with_options(compression::open(path)?, options)
+}
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from a reader and returns a JSONNLP struct.
+/// The reader is not buffered, wrap it in a BufReader if it performs a system call per read.
+pub fn from_reader<R: Read>(reader: R) -> Result<JSONNLP> {
+	let mut de = serde_json::Deserializer::from_reader(reader);
+	let r = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+	Ok(r)
+}
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) document from a reader using the given options.
+/// The limits are checked while the input is read.
+pub fn from_reader_with_options<R: Read>(reader: R, options: &ParseOptions) -> Result<JSONNLP> {
+	let r = if options.limits.is_unlimited() {
+		prob::with_policy(options.prob_policy, || from_reader(reader))?
+	} else {
+		let mut reader = limits::LimitedReader::new(reader, &options.limits);
+		let r = prob::with_policy(options.prob_policy, || from_reader(&mut reader));
+		match reader.error {
+			Some(e) => return Err(JsonNlpError::LimitExceeded(e)),
+			None => r?,
+		}
+	};
+	check_options(r, options)
+}
+
+/// This function converts a byte slice containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), returning a JSONNLP struct.
+/// With the `simd` feature, it is parsed by simd-json from a copy of the slice.
+pub fn from_slice(json: &[u8]) -> Result<JSONNLP> {
+	#[cfg(feature = "simd")]
+	{
+		simd::from_buffer(json.to_vec())
+	}
+	#[cfg(not(feature = "simd"))]
+	{
+		let mut de = serde_json::Deserializer::from_slice(json);
+		let r = serde_path_to_error::deserialize(&mut de)?;
+		de.end()?;
+		Ok(r)
+	}
+}
+
+/// This function converts a byte slice containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) using the given options.
+pub fn from_slice_with_options(json: &[u8], options: &ParseOptions) -> Result<JSONNLP> {
+	options.limits.check(json)?;
+	let r = prob::with_policy(options.prob_policy, || from_slice(json))?;
+	check_options(r, options)
 }
 
 /// This function returns a string representation of a JSONNLP struct/object.
-pub fn get_json(j: &JSONNLP) -> Result<String, Box<dyn Error>> {
-	let r = serde_json::to_string(j).unwrap();
+pub fn get_json(j: &JSONNLP) -> Result<String> {
+	let r = serde_json::to_string(j).map_err(JsonNlpError::Serialize)?;
 	Ok(r)
 }
+
+/// This struct contains the options for serializing JSON-NLP.
+/// The default options produce compact JSON.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct WriteOptions {
+	/// the indentation used for pretty-printed output, or None for compact output.
+	pub indent: Option<String>,
+}
+
+impl WriteOptions {
+	/// returns options for compact output without any whitespace.
+	pub fn compact() -> Self {
+		WriteOptions { indent: None }
+	}
+
+	/// returns options for pretty-printed output using the given indentation string.
+	pub fn pretty(indent: &str) -> Self {
+		WriteOptions { indent: Some(indent.to_string()) }
+	}
+}
+
+/// This function returns a pretty-printed string representation of a JSONNLP struct/object,
+/// indented with two spaces.
+pub fn to_string_pretty(j: &JSONNLP) -> Result<String> {
+	to_string_with_options(j, &WriteOptions::pretty("  "))
+}
+
+/// This function returns a string representation of a JSONNLP struct/object using the given options.
+pub fn to_string_with_options(j: &JSONNLP, options: &WriteOptions) -> Result<String> {
+	let mut buf = Vec::new();
+	to_writer_with_options(j, &mut buf, options)?;
+	// serde_json only emits valid UTF-8
+	Ok(String::from_utf8(buf).expect("serde_json produced invalid UTF-8"))
+}
+
+/// This function writes a JSONNLP struct/object as JSON to a writer.
+pub fn to_writer<W: Write>(j: &JSONNLP, writer: W) -> Result<()> {
+	to_writer_with_options(j, writer, &WriteOptions::default())
+}
+
+/// This function writes a JSONNLP struct/object as JSON to a writer using the given options.
+pub fn to_writer_with_options<W: Write>(j: &JSONNLP, writer: W, options: &WriteOptions) -> Result<()> {
+	match &options.indent {
+		Some(indent) => {
+			let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+			let mut ser = serde_json::Serializer::with_formatter(writer, formatter);
+			j.serialize(&mut ser).map_err(JsonNlpError::Serialize)?;
+		}
+		None => serde_json::to_writer(writer, j).map_err(JsonNlpError::Serialize)?,
+	}
+	Ok(())
+}
+
+/// This function writes a JSONNLP struct/object as JSON to a file, replacing an existing file.
+pub fn to_file<P: AsRef<Path>>(j: &JSONNLP, path: P) -> Result<()> {
+	to_file_with_options(j, path, &WriteOptions::default())
+}
+
+/// This function writes a JSONNLP struct/object as JSON to a file using the given options.
+/// With the `gzip` and `zstd` features, files with the `.gz` and `.zst` extensions are compressed.
+pub fn to_file_with_options<P: AsRef<Path>>(j: &JSONNLP, path: P, options: &WriteOptions) -> Result<()> {
+	let mut output = compression::Output::create(path)?;
+	to_writer_with_options(j, &mut output, options)?;
+	output.finish()
+}
@@ -0,0 +1,446 @@
+//! Resource limits for parsing untrusted [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) input.
+//! The input is checked by a scanner that looks at every byte before it is deserialized, so that
+//! oversized input is rejected before the memory for it is allocated.
+
+use std::io::{self, Read};
+
+use crate::{JsonNlpError, Limit, LimitError, Result};
+
+/// This struct contains the limits for parsing JSON-NLP. A limit of None is not enforced, which is the default.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+	/// the maximum number of documents of a collection.
+	pub max_docs: Option<usize>,
+	/// the maximum number of tokens of all documents of a collection.
+	pub max_tokens: Option<usize>,
+	/// the maximum length of a string, including property names, in bytes as encoded in the input.
+	pub max_string_length: Option<usize>,
+	/// the maximum nesting depth of objects and arrays, with the collection object at depth 1.
+	pub max_depth: Option<usize>,
+}
+
+impl Limits {
+	/// checks whether no limit is set.
+	pub fn is_unlimited(&self) -> bool {
+		*self == Limits::default()
+	}
+
+	/// checks the JSON input against the limits.
+	pub(crate) fn check(&self, json: &[u8]) -> Result<()> {
+		if self.is_unlimited() {
+			return Ok(());
+		}
+		Scanner::new(self.clone()).scan(json).map_err(JsonNlpError::LimitExceeded)
+	}
+
+	/// checks newline-delimited JSON documents against the limits as the documents of one collection.
+	#[allow(dead_code)]
+	pub(crate) fn check_ndjson(&self, ndjson: &[u8]) -> Result<()> {
+		if self.is_unlimited() {
+			return Ok(());
+		}
+		// the lines are scanned as the items of a `docs` array, with a comma in place of every newline so that the
+		// offsets in the input are kept
+		const PREFIX: &[u8] = b"{\"docs\":[";
+		let mut scanner = Scanner::new(self.clone());
+		let scanned = scanner.scan(PREFIX).and_then(|_| {
+			ndjson.split(|b| *b == b'\n').try_for_each(|line| scanner.scan(line).and_then(|_| scanner.scan(b",")))
+		});
+		scanned.map_err(|e| JsonNlpError::LimitExceeded(LimitError { offset: e.offset - PREFIX.len(), ..e }))
+	}
+
+	/// checks line-based input like CoNLL-U against the limits before it is parsed, with `kind` telling the kind of
+	/// every line. Documents are counted at the lines that start them and at the first token outside of a document,
+	/// and the columns of the tokens and the texts are checked as strings. The depth is not limited.
+	#[allow(dead_code)]
+	pub(crate) fn check_lines<'a>(&self, input: &'a str, kind: impl Fn(&'a str) -> Line<'a>) -> Result<()> {
+		if self.is_unlimited() {
+			return Ok(());
+		}
+		let exceeded = |limit, max: Option<usize>, value: usize, offset: usize| match max {
+			Some(max) if value > max => Err(JsonNlpError::LimitExceeded(LimitError { limit, max, offset })),
+			_ => Ok(()),
+		};
+		let (mut docs, mut tokens, mut in_document) = (0, 0, false);
+		let mut offset = 0;
+		for line in input.split_inclusive('\n') {
+			let strings = match kind(line.trim_end_matches(['\n', '\r'])) {
+				Line::Document => {
+					docs += 1;
+					in_document = true;
+					Vec::new()
+				}
+				Line::Token(columns) => {
+					if !in_document {
+						docs += 1;
+						in_document = true;
+					}
+					tokens += 1;
+					exceeded(Limit::Tokens, self.max_tokens, tokens, offset)?;
+					columns
+				}
+				Line::Text(text) => vec![text],
+				Line::Other => Vec::new(),
+			};
+			exceeded(Limit::Docs, self.max_docs, docs, offset)?;
+			for s in strings {
+				// the offset is where the string exceeds the limit, as for JSON
+				let start = offset + (s.as_ptr() as usize - line.as_ptr() as usize);
+				let max = self.max_string_length;
+				exceeded(Limit::StringLength, max, s.len(), start + max.unwrap_or(0))?;
+			}
+			offset += line.len();
+		}
+		Ok(())
+	}
+}
+
+/// This enum contains the kinds of the lines of line-based formats, see Limits::check_lines().
+#[allow(dead_code)]
+pub(crate) enum Line<'a> {
+	/// a line that starts a document.
+	Document,
+	/// a token with its columns.
+	Token(Vec<&'a str>),
+	/// a line with a text that is kept, e.g. the text of a sentence.
+	Text(&'a str),
+	/// a blank line or a line without strings that are kept.
+	Other,
+}
+
+/// the number of bytes of a string kept to recognize the property names `docs` and `tokenList`, also when every
+/// character is written as a `\uXXXX` escape.
+const MAX_NAME_LENGTH: usize = 64;
+
+/// the kinds of containers that the scanner distinguishes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Container {
+	Collection,
+	Docs,
+	Document,
+	TokenList,
+	Object,
+	Array,
+}
+
+/// the property names that open the counted arrays.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Key {
+	Docs,
+	TokenList,
+	Other,
+}
+
+struct Frame {
+	container: Container,
+	object: bool,
+	// whether the next string of an object is a property name
+	expect_key: bool,
+	key: Key,
+}
+
+/// This struct tracks the structure of JSON input incrementally, one chunk at a time. It does not validate the
+/// JSON, which is left to the parser.
+pub(crate) struct Scanner {
+	limits: Limits,
+	stack: Vec<Frame>,
+	in_string: bool,
+	escape: bool,
+	string_length: usize,
+	name: Vec<u8>,
+	docs: usize,
+	tokens: usize,
+	offset: usize,
+}
+
+impl Scanner {
+	pub(crate) fn new(limits: Limits) -> Self {
+		Scanner {
+			limits,
+			stack: Vec::new(),
+			in_string: false,
+			escape: false,
+			string_length: 0,
+			name: Vec::new(),
+			docs: 0,
+			tokens: 0,
+			offset: 0,
+		}
+	}
+
+	fn exceeded(&self, limit: Limit, max: Option<usize>, value: usize) -> std::result::Result<(), LimitError> {
+		match max {
+			Some(max) if value > max => Err(LimitError { limit, max, offset: self.offset }),
+			_ => Ok(()),
+		}
+	}
+
+	fn open(&mut self, object: bool) -> std::result::Result<(), LimitError> {
+		let parent = self.stack.last().map(|f| (f.container, f.key));
+		let container = match (object, parent) {
+			(true, None) => Container::Collection,
+			(true, Some((Container::Docs, _))) => {
+				self.docs += 1;
+				self.exceeded(Limit::Docs, self.limits.max_docs, self.docs)?;
+				Container::Document
+			}
+			(true, Some((Container::TokenList, _))) => {
+				self.tokens += 1;
+				self.exceeded(Limit::Tokens, self.limits.max_tokens, self.tokens)?;
+				Container::Object
+			}
+			(true, _) => Container::Object,
+			(false, Some((Container::Collection, Key::Docs))) => Container::Docs,
+			(false, Some((Container::Document, Key::TokenList))) => Container::TokenList,
+			(false, _) => Container::Array,
+		};
+		self.stack.push(Frame { container, object, expect_key: object, key: Key::Other });
+		self.exceeded(Limit::Depth, self.limits.max_depth, self.stack.len())
+	}
+
+	fn end_string(&mut self) {
+		if let Some(frame) = self.stack.last_mut() {
+			if frame.expect_key {
+				// names with escapes like `"\u0064ocs"` are decoded before they are compared
+				let unescaped = if self.name.contains(&b'\\') {
+					let mut quoted = Vec::with_capacity(self.name.len() + 2);
+					quoted.push(b'"');
+					quoted.extend_from_slice(&self.name);
+					quoted.push(b'"');
+					serde_json::from_slice::<String>(&quoted).ok()
+				} else {
+					None
+				};
+				frame.key = match unescaped.as_ref().map_or(self.name.as_slice(), |n| n.as_bytes()) {
+					b"docs" => Key::Docs,
+					b"tokenList" => Key::TokenList,
+					_ => Key::Other,
+				};
+			}
+		}
+	}
+
+	/// scans the next chunk of the input.
+	pub(crate) fn scan(&mut self, chunk: &[u8]) -> std::result::Result<(), LimitError> {
+		for &b in chunk {
+			if self.in_string {
+				if self.escape {
+					self.escape = false;
+				} else if b == b'\\' {
+					self.escape = true;
+				} else if b == b'"' {
+					self.in_string = false;
+					self.end_string();
+					self.offset += 1;
+					continue;
+				}
+				self.string_length += 1;
+				self.exceeded(Limit::StringLength, self.limits.max_string_length, self.string_length)?;
+				if self.name.len() < MAX_NAME_LENGTH {
+					self.name.push(b);
+				}
+			} else {
+				match b {
+					b'"' => {
+						self.in_string = true;
+						self.string_length = 0;
+						self.name.clear();
+					}
+					b'{' => self.open(true)?,
+					b'[' => self.open(false)?,
+					b'}' | b']' => {
+						self.stack.pop();
+					}
+					b':' => {
+						if let Some(frame) = self.stack.last_mut() {
+							frame.expect_key = false;
+						}
+					}
+					b',' => {
+						if let Some(frame) = self.stack.last_mut() {
+							frame.expect_key = frame.object;
+						}
+					}
+					_ => {}
+				}
+			}
+			self.offset += 1;
+		}
+		Ok(())
+	}
+}
+
+/// This struct scans the bytes of a reader as they are read, failing the read once a limit is exceeded.
+pub(crate) struct LimitedReader<R: Read> {
+	reader: R,
+	scanner: Scanner,
+	/// the exceeded limit, which the parser only sees as an I/O error.
+	pub(crate) error: Option<LimitError>,
+}
+
+impl<R: Read> LimitedReader<R> {
+	pub(crate) fn new(reader: R, limits: &Limits) -> Self {
+		LimitedReader { reader, scanner: Scanner::new(limits.clone()), error: None }
+	}
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		if let Some(e) = &self.error {
+			return Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string()));
+		}
+		let n = self.reader.read(buf)?;
+		if let Err(e) = self.scanner.scan(&buf[..n]) {
+			let message = e.to_string();
+			self.error = Some(e);
+			return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+		}
+		Ok(n)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const COLLECTION: &str = r#"{"meta":{},"docs":[
+		{"meta":{},"id":1,"tokenList":[
+			{"id":1,"sentence_id":1,"text":"a","lemma":"a"},{"id":2,"sentence_id":1,"text":"b","lemma":"b"}]},
+		{"meta":{},"id":2,"tokenList":[{"id":3,"sentence_id":1,"text":"c","lemma":"c"}]}]}"#;
+
+	/// returns the result of scanning the input split into chunks at every position.
+	fn scan_split(limits: &Limits, json: &str) -> Vec<std::result::Result<(), LimitError>> {
+		let bytes = json.as_bytes();
+		(0..=bytes.len())
+			.map(|i| {
+				let mut scanner = Scanner::new(limits.clone());
+				scanner.scan(&bytes[..i]).and_then(|_| scanner.scan(&bytes[i..]))
+			})
+			.collect()
+	}
+
+	#[test]
+	fn counts_documents_and_tokens() {
+		let limits = Limits { max_docs: Some(2), max_tokens: Some(3), ..Default::default() };
+		assert!(limits.check(COLLECTION.as_bytes()).is_ok());
+		let limits = Limits { max_docs: Some(1), ..Default::default() };
+		let exceeded = |limits: &Limits| match limits.check(COLLECTION.as_bytes()) {
+			Err(JsonNlpError::LimitExceeded(e)) => Some(e.limit),
+			_ => None,
+		};
+		assert_eq!(exceeded(&limits), Some(Limit::Docs));
+		let limits = Limits { max_tokens: Some(2), ..Default::default() };
+		assert_eq!(exceeded(&limits), Some(Limit::Tokens));
+	}
+
+	#[test]
+	fn limit_crosses_chunk_boundary() {
+		let json = r#"{"docs":[{"text":"abcdefgh"}]}"#;
+		let limits = Limits { max_string_length: Some(7), ..Default::default() };
+		let offset = json.find('h').expect("the input has an h");
+		for result in scan_split(&limits, json) {
+			let e = result.expect_err("the string is too long");
+			assert_eq!((e.limit, e.offset), (Limit::StringLength, offset));
+		}
+		let limits = Limits { max_tokens: Some(2), max_depth: Some(5), ..Default::default() };
+		for result in scan_split(&limits, COLLECTION) {
+			assert_eq!(result.expect_err("there are three tokens").limit, Limit::Tokens);
+		}
+		let limits = Limits { max_docs: Some(2), max_tokens: Some(3), max_depth: Some(5), ..Default::default() };
+		assert!(scan_split(&limits, COLLECTION).iter().all(|r| r.is_ok()));
+	}
+
+	#[test]
+	fn escaped_quotes_in_keys() {
+		// the escaped quotes neither end the names nor make them `docs`
+		let json = r#"{"a\"docs":[{},{}],"\"docs\"":[{},{}],"docs":[{"x\"":"\"tokenList\"","tokenList":[{}]}]}"#;
+		let limits = Limits { max_docs: Some(1), max_tokens: Some(1), ..Default::default() };
+		assert!(scan_split(&limits, json).iter().all(|r| r.is_ok()));
+		let limits = Limits { max_tokens: Some(0), ..Default::default() };
+		assert!(scan_split(&limits, json).iter().all(|r| r.as_ref().is_err_and(|e| e.limit == Limit::Tokens)));
+	}
+
+	#[test]
+	fn escaped_key_names() {
+		let json = r#"{"\u0064ocs":[{"\u0074\u006f\u006b\u0065\u006e\u004c\u0069\u0073\u0074":[{},{}]}]}"#;
+		let limits = Limits { max_tokens: Some(1), ..Default::default() };
+		assert!(scan_split(&limits, json).iter().all(|r| r.as_ref().is_err_and(|e| e.limit == Limit::Tokens)));
+	}
+
+	#[test]
+	fn nested_docs_are_not_counted() {
+		let json = r#"{"meta":{"docs":[{},{},{}]},"docs":[{"x":{"docs":[{}],"tokenList":[{},{}]},"tokenList":[]}]}"#;
+		let limits = Limits { max_docs: Some(1), max_tokens: Some(0), ..Default::default() };
+		assert!(scan_split(&limits, json).iter().all(|r| r.is_ok()));
+		let json = r#"[{"docs":[{},{}]}]"#;
+		assert!(limits.check(json.as_bytes()).is_ok());
+	}
+
+	#[test]
+	fn depth() {
+		let limits = Limits { max_depth: Some(3), ..Default::default() };
+		assert!(limits.check(br#"{"a":{"b":[1]}}"#).is_ok());
+		let e = Scanner::new(limits).scan(br#"{"a":{"b":[{}]}}"#).expect_err("the depth is 4");
+		assert_eq!((e.limit, e.offset), (Limit::Depth, 11));
+	}
+
+	#[test]
+	fn reader_error_becomes_limit_exceeded() {
+		let options = |max_tokens| crate::ParseOptions {
+			limits: Limits { max_tokens: Some(max_tokens), ..Default::default() },
+			..Default::default()
+		};
+		// a reader that returns a byte at a time, so that the limit is exceeded in the middle of the input
+		struct Bytewise<'a>(&'a [u8]);
+		impl Read for Bytewise<'_> {
+			fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+				let n = self.0.len().min(buf.len()).min(1);
+				buf[..n].copy_from_slice(&self.0[..n]);
+				self.0 = &self.0[n..];
+				Ok(n)
+			}
+		}
+		match crate::from_reader_with_options(Bytewise(COLLECTION.as_bytes()), &options(2)) {
+			Err(JsonNlpError::LimitExceeded(e)) => assert_eq!(e.limit, Limit::Tokens),
+			r => panic!("expected LimitExceeded, got {:?}", r.map(|_| ())),
+		}
+		let j = crate::from_reader_with_options(Bytewise(COLLECTION.as_bytes()), &options(3));
+		assert_eq!(j.expect("the input is within the limits").docs.len(), 2);
+	}
+
+	#[test]
+	fn ndjson_offsets() {
+		let line = r#"{"tokenList":[{},{}]}"#;
+		let ndjson = format!("{}\n{}\n", line, line);
+		let limits = Limits { max_tokens: Some(3), ..Default::default() };
+		match limits.check_ndjson(ndjson.as_bytes()) {
+			Err(JsonNlpError::LimitExceeded(e)) => {
+				// the fourth token is the second object of the second line
+				assert_eq!((e.limit, e.offset), (Limit::Tokens, line.len() + 1 + 17))
+			}
+			r => panic!("expected LimitExceeded, got {:?}", r),
+		}
+		assert!(Limits { max_docs: Some(2), max_depth: Some(4), ..limits.clone() }.check_ndjson(b"{}\n\n{}").is_ok());
+	}
+
+	#[test]
+	fn line_offsets() {
+		fn kind(line: &str) -> Line<'_> {
+			match line {
+				"" => Line::Other,
+				"doc" => Line::Document,
+				_ => Line::Token(line.split(' ').collect()),
+			}
+		}
+		let exceeded = |limits: Limits, input: &str| match limits.check_lines(input, kind) {
+			Err(JsonNlpError::LimitExceeded(e)) => Some((e.limit, e.offset)),
+			_ => None,
+		};
+		let input = "a bcd\n\ndoc\ne\n";
+		assert_eq!(exceeded(Limits { max_docs: Some(2), max_tokens: Some(2), ..Default::default() }, input), None);
+		assert_eq!(exceeded(Limits { max_docs: Some(1), ..Default::default() }, input), Some((Limit::Docs, 7)));
+		assert_eq!(exceeded(Limits { max_tokens: Some(1), ..Default::default() }, input), Some((Limit::Tokens, 11)));
+		let short = Limits { max_string_length: Some(2), ..Default::default() };
+		assert_eq!(exceeded(short, input), Some((Limit::StringLength, 4)));
+	}
+}
\ No newline at end of file
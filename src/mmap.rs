@@ -0,0 +1,50 @@
+//! Loading of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) files through memory maps, behind the `mmap` feature.
+//! The file is parsed from the pages the OS maps in, without reading it into a buffer first.
+//! The file must not be modified or truncated while it is mapped.
+
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::{from_slice_borrowed, JSONNLPRef, Result, JSONNLP};
+
+/// This struct contains a memory-mapped JSON-NLP file, from which documents can be borrowed.
+pub struct MappedFile {
+	mmap: Mmap,
+}
+
+impl MappedFile {
+	/// maps the file into memory.
+	pub fn open<P: AsRef<Path>>(path: P) -> Result<MappedFile> {
+		let file = File::open(path)?;
+		// SAFETY: the mapping is read-only, and the module requires that the file is not modified while it is mapped.
+		let mmap = unsafe { Mmap::map(&file)? };
+		Ok(MappedFile { mmap })
+	}
+
+	/// returns the content of the file.
+	pub fn as_bytes(&self) -> &[u8] {
+		&self.mmap
+	}
+
+	/// returns the collection with the strings of the text, tokens, and dependencies borrowed from the mapped file,
+	/// see from_slice_borrowed().
+	pub fn parse_borrowed(&self) -> Result<JSONNLPRef<'_>> {
+		from_slice_borrowed(&self.mmap)
+	}
+
+	/// returns the collection parsed from the mapped file.
+	pub fn parse(&self) -> Result<JSONNLP> {
+		let mut de = serde_json::Deserializer::from_slice(&self.mmap);
+		let r = serde_path_to_error::deserialize(&mut de)?;
+		de.end()?;
+		Ok(r)
+	}
+}
+
+/// This function reads a [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) file by memory-mapping it and parsing
+/// the mapped pages directly. Use MappedFile to borrow the strings from the mapping instead.
+pub fn from_mmap<P: AsRef<Path>>(path: P) -> Result<JSONNLP> {
+	MappedFile::open(path)?.parse()
+}
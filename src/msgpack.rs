@@ -0,0 +1,18 @@
+//! [MessagePack](https://msgpack.org/) representation of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), with
+//! the structure of the JSON and the property names as map keys.
+
+use crate::{JsonNlpError, Result, JSONNLP};
+
+fn error(e: impl std::fmt::Display) -> JsonNlpError {
+	JsonNlpError::Format(format!("MessagePack: {}", e))
+}
+
+/// This function returns the MessagePack encoding of a JSONNLP struct/object.
+pub fn to_msgpack(j: &JSONNLP) -> Result<Vec<u8>> {
+	rmp_serde::to_vec_named(j).map_err(error)
+}
+
+/// This function converts MessagePack-encoded JSON-NLP to a JSONNLP struct/object.
+pub fn from_msgpack(bytes: &[u8]) -> Result<JSONNLP> {
+	rmp_serde::from_slice(bytes).map_err(error)
+}
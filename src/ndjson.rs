@@ -0,0 +1,89 @@
+//! Streaming of document collections as [newline-delimited JSON](https://github.com/ndjson/ndjson-spec),
+//! one Document or [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) value per line.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{JsonNlpError, Result};
+
+/// This struct reads the values of newline-delimited JSON as an iterator, one per line, skipping empty lines.
+/// Errors name the line of the value.
+pub struct NdjsonReader<R: BufRead, T> {
+	reader: R,
+	line: String,
+	number: usize,
+	value: PhantomData<T>,
+}
+
+impl<R: BufRead, T: DeserializeOwned> NdjsonReader<R, T> {
+	/// returns a reader of the values of a buffered reader.
+	pub fn new(reader: R) -> Self {
+		NdjsonReader { reader, line: String::new(), number: 0, value: PhantomData }
+	}
+
+	fn next_value(&mut self) -> Result<Option<T>> {
+		loop {
+			self.line.clear();
+			if self.reader.read_line(&mut self.line)? == 0 {
+				return Ok(None);
+			}
+			self.number += 1;
+			let line = self.line.trim();
+			if line.is_empty() {
+				continue;
+			}
+			let mut de = serde_json::Deserializer::from_str(line);
+			let value: Result<T> = serde_path_to_error::deserialize(&mut de).map_err(JsonNlpError::from)
+				.and_then(|v| de.end().map(|_| v).map_err(JsonNlpError::from));
+			return value.map(Some).map_err(|e| JsonNlpError::Format(format!("NDJSON line {}: {}", self.number, e)));
+		}
+	}
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for NdjsonReader<R, T> {
+	type Item = Result<T>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.next_value().transpose()
+	}
+}
+
+/// This function returns an iterator over the values of newline-delimited JSON, e.g. Documents or JSONNLP
+/// collections, reading one line at a time.
+pub fn read_ndjson<T: DeserializeOwned, R: Read>(reader: R) -> NdjsonReader<BufReader<R>, T> {
+	NdjsonReader::new(BufReader::new(reader))
+}
+
+/// This struct writes values as newline-delimited JSON, one compact JSON value per line.
+pub struct NdjsonWriter<W: Write> {
+	writer: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+	/// returns a writer of values to the writer, which is not buffered.
+	pub fn new(writer: W) -> Self {
+		NdjsonWriter { writer }
+	}
+
+	/// writes a value as one line.
+	pub fn write<T: Serialize>(&mut self, value: &T) -> Result<()> {
+		serde_json::to_writer(&mut self.writer, value).map_err(JsonNlpError::Serialize)?;
+		self.writer.write_all(b"\n")?;
+		Ok(())
+	}
+
+	/// flushes the underlying writer.
+	pub fn flush(&mut self) -> Result<()> {
+		self.writer.flush()?;
+		Ok(())
+	}
+
+	/// flushes and returns the underlying writer.
+	pub fn into_inner(mut self) -> Result<W> {
+		self.flush()?;
+		Ok(self.writer)
+	}
+}
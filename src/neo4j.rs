@@ -0,0 +1,211 @@
+//! Export of the entity, relation, and triple layers of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP)
+//! documents to [Neo4j](https://neo4j.com/), as parameterized Cypher statements or as the CSV files of
+//! `neo4j-admin database import`.
+//!
+//! Entities become nodes labeled `Entity`, identified by the property `key`, which is the URL of the entity, so that
+//! the entities of several documents linked to the same resource are merged, or else the document ID and the entity
+//! ID, e.g. `3/1`. Triples become relationships between the nodes of their entities, typed by the type of their
+//! relation, or else its label, or else `RELATED_TO`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::table::field;
+use crate::{Document, Entity, EntityId, Relation};
+
+/// This struct contains a Cypher statement with its parameters, serialized as a statement of the Neo4j HTTP API.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CypherStatement {
+	pub statement: String,
+	pub parameters: Value,
+}
+
+/// This struct contains the CSV files of the nodes and relationships for `neo4j-admin database import`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Neo4jCsv {
+	/// the nodes, with the columns `key:ID`, `label`, `type`, `url`, `text`, and `:LABEL`.
+	pub nodes: String,
+	/// the relationships, with the columns `:START_ID`, `:END_ID`, `:TYPE`, `key`, `label`, `url`, `prob:double`,
+	/// and `directional:boolean`.
+	pub relationships: String,
+}
+
+/// returns the key of the node of an entity.
+fn entity_key(doc: &Document, e: &Entity) -> String {
+	if e.url.is_empty() {
+		format!("{}/{}", doc.id, e.id)
+	} else {
+		e.url.clone()
+	}
+}
+
+/// returns the type of the relationships of a relation.
+fn relationship_type(relation: Option<&Relation>) -> &str {
+	match relation {
+		Some(r) if !r.rtype.is_empty() => &r.rtype,
+		Some(r) if !r.label.is_empty() => &r.label,
+		_ => "RELATED_TO",
+	}
+}
+
+/// This struct contains a triple whose entities are both in the document.
+struct Link<'a> {
+	key: String,
+	from: String,
+	to: String,
+	relation: Option<&'a Relation>,
+	prob: Option<f64>,
+	directional: bool,
+}
+
+/// returns the triples of a document linking two of its entities.
+fn links(doc: &Document) -> Vec<Link<'_>> {
+	let keys: HashMap<EntityId, String> = doc.entities.iter().map(|e| (e.id, entity_key(doc, e))).collect();
+	doc.triples
+		.iter()
+		.filter_map(|t| {
+			let from = keys.get(&t.from_entity?)?;
+			let to = keys.get(&t.to_entity?)?;
+			Some(Link {
+				key: format!("{}/{}", doc.id, t.id),
+				from: from.clone(),
+				to: to.clone(),
+				relation: t.rel.and_then(|id| doc.relations.iter().find(|r| r.id == id)),
+				prob: t.prob.map(|p| p.get()),
+				directional: t.directional != Some(false),
+			})
+		})
+		.collect()
+}
+
+/// returns a name quoted as a Cypher identifier.
+fn identifier(name: &str) -> String {
+	format!("`{}`", name.replace('`', "``"))
+}
+
+/// This function converts the entities and triples of a Document to Cypher statements merging the nodes of the
+/// entities and the relationships of the triples, so that running the statements again changes nothing. The
+/// entities are merged by one statement with their properties `label`, `type`, `url`, and `text`, plus their
+/// attributes as properties prefixed with `attr_` like `attr_negated`, so that attributes cannot overwrite the merge
+/// key or the other properties, and the triples by one statement per relationship type with their properties
+/// `key`, `label`, `url`, `prob`, and `directional`. Relationships are directed in Neo4j, so triples that are not
+/// directional have `directional` false.
+pub fn to_cypher(doc: &Document) -> Vec<CypherStatement> {
+	let mut statements = Vec::new();
+	let entities: Vec<Value> = doc
+		.entities
+		.iter()
+		.map(|e| {
+			let mut properties = Map::new();
+			for a in &e.attributes {
+				properties.insert(format!("attr_{}", a.lab), json!(a.val));
+			}
+			for (name, value) in [
+				("label", e.label.as_str()),
+				("type", &e.etype),
+				("url", &e.url),
+				("text", doc.entity_text(e).unwrap_or("")),
+			] {
+				if !value.is_empty() {
+					properties.insert(name.to_string(), json!(value));
+				}
+			}
+			json!({ "key": entity_key(doc, e), "properties": properties })
+		})
+		.collect();
+	if !entities.is_empty() {
+		statements.push(CypherStatement {
+			statement: "UNWIND $entities AS e MERGE (n:Entity {key: e.key}) SET n += e.properties".to_string(),
+			parameters: json!({ "entities": entities }),
+		});
+	}
+
+	let mut by_type: BTreeMap<&str, Vec<Value>> = BTreeMap::new();
+	for link in links(doc) {
+		let mut properties = Map::new();
+		if let Some(r) = link.relation {
+			if !r.label.is_empty() {
+				properties.insert("label".to_string(), json!(r.label));
+			}
+			if !r.url.is_empty() {
+				properties.insert("url".to_string(), json!(r.url));
+			}
+		}
+		if let Some(p) = link.prob {
+			properties.insert("prob".to_string(), json!(p));
+		}
+		properties.insert("directional".to_string(), json!(link.directional));
+		by_type.entry(relationship_type(link.relation)).or_default().push(json!({
+			"key": link.key,
+			"from": link.from,
+			"to": link.to,
+			"properties": properties,
+		}));
+	}
+	for (rtype, triples) in by_type {
+		statements.push(CypherStatement {
+			statement: format!(
+				"UNWIND $triples AS t MATCH (a:Entity {{key: t.from}}), (b:Entity {{key: t.to}}) \
+				 MERGE (a)-[r:{} {{key: t.key}}]->(b) SET r += t.properties",
+				identifier(rtype)
+			),
+			parameters: json!({ "triples": triples }),
+		});
+	}
+	statements
+}
+
+/// This function converts the entities and triples of a Document to the CSV files of the nodes and relationships
+/// for `neo4j-admin database import`, with the same keys, labels, and types as to_cypher(). Entities with the same
+/// URL are written once.
+pub fn to_neo4j_csv(doc: &Document) -> Neo4jCsv {
+	let mut out = Neo4jCsv {
+		nodes: "key:ID,label,type,url,text,:LABEL\n".to_string(),
+		relationships: ":START_ID,:END_ID,:TYPE,key,label,url,prob:double,directional:boolean\n".to_string(),
+	};
+	let mut written = Vec::new();
+	for e in &doc.entities {
+		let key = entity_key(doc, e);
+		if written.contains(&key) {
+			continue;
+		}
+		let text = doc.entity_text(e).unwrap_or("");
+		let row = [key.as_str(), &e.label, &e.etype, &e.url, text, "Entity"];
+		out.nodes.push_str(&row.iter().map(|s| field(s, ',')).collect::<Vec<_>>().join(","));
+		out.nodes.push('\n');
+		written.push(key);
+	}
+	for link in links(doc) {
+		let (label, url) = link.relation.map_or(("", ""), |r| (r.label.as_str(), r.url.as_str()));
+		let prob = link.prob.map(|p| p.to_string()).unwrap_or_default();
+		let directional = link.directional.to_string();
+		let row = [&link.from, &link.to, relationship_type(link.relation), &link.key, label, url, &prob, &directional];
+		out.relationships.push_str(&row.iter().map(|s| field(s, ',')).collect::<Vec<_>>().join(","));
+		out.relationships.push('\n');
+	}
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Attribute, DocumentId};
+
+	#[test]
+	fn attributes_keep_the_merge_key() {
+		let mut doc = Document { id: DocumentId(1), ..Default::default() };
+		let attribute = |lab: &str| Attribute { lab: lab.to_string(), val: "x".to_string(), ..Default::default() };
+		doc.entities.push(Entity {
+			id: EntityId(1),
+			etype: "PER".to_string(),
+			attributes: vec![attribute("key"), attribute("type")],
+			..Default::default()
+		});
+		let statements = to_cypher(&doc);
+		let e = &statements[0].parameters["entities"][0];
+		assert_eq!(e["key"], "1/1");
+		assert_eq!(e["properties"], json!({ "attr_key": "x", "attr_type": "x", "type": "PER" }));
+	}
+}
@@ -0,0 +1,104 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents to the
+//! [NLP Interchange Format](https://persistence.uni-leipzig.org/nlp2rdf/) (NIF) 2.0 in Turtle.
+
+use crate::offsets::{char_boundaries, slice_chars};
+use crate::Document;
+
+const PREFIXES: &str = "@prefix nif: <http://persistence.uni-leipzig.org/nlp2rdf/ontologies/nif-core#> .
+@prefix itsrdf: <http://www.w3.org/2005/11/its/rdf#> .
+@prefix xsd: <http://www.w3.org/2001/XMLSchema#> .
+";
+
+/// returns a Turtle string literal.
+fn literal(s: &str) -> String {
+	let mut out = String::with_capacity(s.len() + 2);
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+	out
+}
+
+/// returns a Turtle IRI reference, or None if the string cannot be used as an IRI.
+fn iri(s: &str) -> Option<String> {
+	let valid = s.contains(':') && !s.chars().any(|c| c.is_whitespace() || "<>\"{}|^`\\".contains(c));
+	if valid { Some(format!("<{}>", s)) } else { None }
+}
+
+/// This function converts a Document to NIF 2.0 triples in Turtle. The document text becomes the `nif:Context`,
+/// and sentences, tokens, and entities become strings identified by their RFC 5147 character offsets appended
+/// to the base IRI, e.g. `http://example.org/doc1#char=0,5`, with `nif:beginIndex`, `nif:endIndex`, and
+/// `nif:anchorOf`. Tokens carry their `nif:posTag` and `nif:lemma`, and entities with a URL link to it with
+/// `itsrdf:taIdentRef` and to a type IRI with `itsrdf:taClassRef`, as used by GERBIL. Offsets count characters,
+/// and elements without character offsets are skipped.
+pub fn to_nif(doc: &Document, base: &str) -> String {
+	let boundaries = char_boundaries(&doc.text);
+	let length = boundaries.len() as u64 - 1;
+	let uri = |begin: u64, end: u64| format!("<{}#char={},{}>", base, begin, end);
+	let context = uri(0, length);
+	let mut nif = String::from(PREFIXES);
+
+	nif.push_str(&format!("\n{} a nif:String , nif:Context , nif:RFC5147String ;\n\tnif:isString {}^^xsd:string ;\n",
+		context, literal(&doc.text)));
+	nif.push_str(&format!("\tnif:beginIndex \"0\"^^xsd:nonNegativeInteger ;\n\tnif:endIndex \"{}\"^^xsd:nonNegativeInteger .\n", length));
+	// writes the triples shared by all strings of the context
+	let string = |nif: &mut String, class: &str, begin: u64, end: u64| {
+		let anchor = slice_chars(&doc.text, &boundaries, begin, end).unwrap_or_default();
+		nif.push_str(&format!("\n{} a nif:String , nif:{} , nif:RFC5147String ;\n\tnif:anchorOf {}^^xsd:string ;\n",
+			uri(begin, end), class, literal(anchor)));
+		nif.push_str(&format!("\tnif:beginIndex \"{}\"^^xsd:nonNegativeInteger ;\n\tnif:endIndex \"{}\"^^xsd:nonNegativeInteger ;\n",
+			begin, end));
+		nif.push_str(&format!("\tnif:referenceContext {}", context));
+	};
+	let valid = |begin: u64, end: u64| begin <= end && end <= length;
+
+	for (_, _, tokens) in doc.sentence_groups() {
+		let ids: Vec<_> = tokens.iter().map(|t| t.id).collect();
+		let sentence = match doc.char_span(|id| ids.contains(&id)) {
+			Some((begin, end)) if valid(begin, end) => (begin, end),
+			_ => continue,
+		};
+		string(&mut nif, "Sentence", sentence.0, sentence.1);
+		nif.push_str(" .\n");
+		for t in tokens {
+			let (begin, end) = match (t.char_offset_begin, t.char_offset_end) {
+				(Some(begin), Some(end)) if valid(begin, end) => (begin, end),
+				_ => continue,
+			};
+			string(&mut nif, "Word", begin, end);
+			nif.push_str(&format!(" ;\n\tnif:sentence {}", uri(sentence.0, sentence.1)));
+			if !t.xpos.is_empty() || !t.upos.is_empty() {
+				let tag = if t.xpos.is_empty() { &t.upos } else { &t.xpos };
+				nif.push_str(&format!(" ;\n\tnif:posTag {}", literal(tag)));
+			}
+			if !t.lemma.is_empty() {
+				nif.push_str(&format!(" ;\n\tnif:lemma {}", literal(&t.lemma)));
+			}
+			nif.push_str(" .\n");
+		}
+	}
+
+	for e in &doc.entities {
+		let (begin, end) = match doc.char_span(|id| e.covers(id)) {
+			Some((begin, end)) if valid(begin, end) => (begin, end),
+			_ => continue,
+		};
+		string(&mut nif, "Phrase", begin, end);
+		if let Some(url) = iri(&e.url) {
+			nif.push_str(&format!(" ;\n\titsrdf:taIdentRef {}", url));
+		}
+		if let Some(etype) = iri(&e.etype) {
+			nif.push_str(&format!(" ;\n\titsrdf:taClassRef {}", etype));
+		}
+		nif.push_str(" .\n");
+	}
+	nif
+}
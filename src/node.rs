@@ -0,0 +1,125 @@
+//! [Node.js](https://nodejs.org/) bindings built with [napi-rs](https://napi.rs/), so that JavaScript annotation
+//! tools share the model of this crate. The addon is built by the napi-rs CLI with the settings in `package.json`.
+//! A collection is parsed once into a `JsonNlp` object, whose queries return the items of a layer as plain
+//! JavaScript objects. The functions `validate()` and `convert()` work on strings, and errors are thrown as `Error`.
+
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::convert::{read, write, Format};
+use crate::{Document, ParseOptions, RawId, SentenceId, JSONNLP};
+
+fn js_error(e: impl std::fmt::Display) -> Error {
+	Error::from_reason(e.to_string())
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value> {
+	serde_json::to_value(value).map_err(js_error)
+}
+
+/// This struct contains a parsed JSON-NLP collection, exported to JavaScript as `JsonNlp`.
+#[napi(js_name = "JsonNlp")]
+pub struct NodeJsonNlp {
+	inner: JSONNLP,
+}
+
+impl NodeJsonNlp {
+	fn doc(&self, doc: u32) -> Result<&Document> {
+		self.inner.docs.get(doc as usize).ok_or_else(|| js_error(format!("no document at index {}", doc)))
+	}
+}
+
+#[napi]
+impl NodeJsonNlp {
+	/// parses a string containing JSON-NLP, throwing an error if it is invalid.
+	#[napi(factory)]
+	pub fn from_string(json: String) -> Result<NodeJsonNlp> {
+		crate::from_string(&json).map(|inner| NodeJsonNlp { inner }).map_err(js_error)
+	}
+
+	/// returns the collection as JSON.
+	#[napi]
+	pub fn to_json(&self) -> Result<String> {
+		crate::get_json(&self.inner).map_err(js_error)
+	}
+
+	/// returns the collection as an object.
+	#[napi]
+	pub fn to_object(&self) -> Result<Value> {
+		to_value(&self.inner)
+	}
+
+	/// returns the problems found by validation, or an empty array if the collection is valid.
+	#[napi]
+	pub fn validate(&self) -> Vec<String> {
+		self.inner.validate().issues.iter().map(ToString::to_string).collect()
+	}
+
+	/// returns the number of documents.
+	#[napi(getter)]
+	pub fn doc_count(&self) -> u32 {
+		self.inner.docs.len() as u32
+	}
+
+	/// returns the text of a document.
+	#[napi]
+	pub fn text(&self, doc: u32) -> Result<String> {
+		Ok(self.doc(doc)?.text.clone())
+	}
+
+	/// returns the tokens of a document.
+	#[napi]
+	pub fn tokens(&self, doc: u32) -> Result<Value> {
+		to_value(&self.doc(doc)?.token_list)
+	}
+
+	/// returns the tokens of a sentence of a document.
+	#[napi]
+	pub fn sentence_tokens(&self, doc: u32, sentence: u32) -> Result<Value> {
+		let sentence = SentenceId(RawId::from(sentence));
+		let tokens: Vec<_> = self.doc(doc)?.token_list.iter().filter(|t| t.sentence_id == sentence).collect();
+		to_value(&tokens)
+	}
+
+	/// returns the sentences of a document.
+	#[napi]
+	pub fn sentences(&self, doc: u32) -> Result<Value> {
+		to_value(&self.doc(doc)?.sentences)
+	}
+
+	/// returns the entities of a document.
+	#[napi]
+	pub fn entities(&self, doc: u32) -> Result<Value> {
+		to_value(&self.doc(doc)?.entities)
+	}
+
+	/// returns the dependency trees of a document.
+	#[napi]
+	pub fn dependency_trees(&self, doc: u32) -> Result<Value> {
+		to_value(&self.doc(doc)?.dependency_trees)
+	}
+
+	/// returns the document as CoNLL-U.
+	#[napi]
+	pub fn to_conllu(&self, doc: u32) -> Result<String> {
+		Ok(crate::to_conllu(self.doc(doc)?))
+	}
+}
+
+/// returns the problems found by validating a string containing JSON-NLP, throwing an error if it cannot be parsed.
+#[napi]
+pub fn validate(json: String) -> Result<Vec<String>> {
+	let j = crate::from_string(&json).map_err(js_error)?;
+	Ok(j.validate().issues.iter().map(ToString::to_string).collect())
+}
+
+/// converts a string between the formats `json`, `ndjson`, `conllu`, `conllx`, `conll2003`, and `xml`.
+#[napi]
+pub fn convert(input: String, from: String, to: String) -> Result<String> {
+	let from: Format = from.parse().map_err(js_error)?;
+	let to: Format = to.parse().map_err(js_error)?;
+	let j = read(input.as_bytes(), from, &ParseOptions::default()).map_err(js_error)?;
+	write(&j, to).map_err(js_error)
+}
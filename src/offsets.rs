@@ -0,0 +1,228 @@
+//! Consistency checks between token character offsets and the source text.
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::validate::{IssueKind, ValidationReport};
+use crate::{Document, Entity, Expression, Token, TokenId};
+
+/// This enum selects the Unicode normalization applied before comparing token text with the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Normalization {
+	/// compare the strings exactly.
+	#[default]
+	None,
+	/// compare the NFC normalized strings.
+	Nfc,
+	/// compare the NFKC normalized strings, e.g. to accept ligatures or full-width characters.
+	Nfkc,
+}
+
+impl Normalization {
+	fn apply(self, s: &str) -> String {
+		match self {
+			Normalization::None => s.to_string(),
+			Normalization::Nfc => s.nfc().collect(),
+			Normalization::Nfkc => s.nfkc().collect(),
+		}
+	}
+}
+
+/// This struct contains the options for checking character offsets.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct OffsetOptions {
+	pub normalization: Normalization,
+}
+
+/// This enum selects the unit in which an annotation service counts the offsets into the text it was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffsetUnit {
+	/// bytes of the UTF-8 encoding.
+	Utf8,
+	/// code units of the UTF-16 encoding, as counted by Java, JavaScript, and .NET strings.
+	Utf16,
+	/// Unicode characters, the unit of JSON-NLP character offsets.
+	#[default]
+	CodePoint,
+}
+
+/// This struct finds the character offsets of the spans reported by an annotation service in the text it was sent.
+pub(crate) struct SpanLocator<'a> {
+	text: &'a str,
+	boundaries: Vec<usize>,
+	/// the offset in the unit of the service of every character position, plus the end of the text.
+	units: Vec<u64>,
+}
+
+impl<'a> SpanLocator<'a> {
+	pub(crate) fn new(text: &'a str, unit: OffsetUnit) -> Self {
+		let mut units = Vec::with_capacity(text.len() + 1);
+		let mut offset = 0;
+		for c in text.chars() {
+			units.push(offset);
+			offset += match unit {
+				OffsetUnit::Utf8 => c.len_utf8(),
+				OffsetUnit::Utf16 => c.len_utf16(),
+				OffsetUnit::CodePoint => 1,
+			} as u64;
+		}
+		units.push(offset);
+		SpanLocator { text, boundaries: char_boundaries(text), units }
+	}
+
+	/// returns the character offsets of a span with the given content, located by its offset in the unit if that
+	/// selects the content, or else by the occurrence of the content at or after the character offset `from` that
+	/// is closest to the offset, or the first one without offset.
+	pub(crate) fn locate(&self, content: &str, offset: Option<u64>, from: u64) -> Option<(u64, u64)> {
+		let length = content.chars().count() as u64;
+		if let Some(begin) = offset.and_then(|o| self.units.binary_search(&o).ok()).map(|i| i as u64) {
+			if slice_chars(self.text, &self.boundaries, begin, begin + length) == Some(content) {
+				return Some((begin, begin + length));
+			}
+		}
+		if content.is_empty() {
+			return None;
+		}
+		let start = *self.boundaries.get(from as usize)?;
+		let mut occurrences = self.text[start..]
+			.match_indices(content)
+			.filter_map(|(byte, _)| self.boundaries.binary_search(&(start + byte)).ok())
+			.map(|begin| begin as u64);
+		let begin = match offset {
+			Some(offset) => occurrences.min_by_key(|begin| begin.abs_diff(offset))?,
+			None => occurrences.next()?,
+		};
+		Some((begin, begin + length))
+	}
+}
+
+/// returns the byte positions of all characters in the text, plus the end of the text.
+pub(crate) fn char_boundaries(text: &str) -> Vec<usize> {
+	text.char_indices().map(|(i, _)| i).chain(std::iter::once(text.len())).collect()
+}
+
+/// returns the substring between the character offsets begin and end, if they are within the text.
+pub(crate) fn slice_chars<'a>(text: &'a str, boundaries: &[usize], begin: u64, end: u64) -> Option<&'a str> {
+	if begin > end {
+		return None;
+	}
+	let b = *boundaries.get(begin as usize)?;
+	let e = *boundaries.get(end as usize)?;
+	Some(&text[b..e])
+}
+
+impl Document {
+	/// checks that the `characterOffsetBegin` and `characterOffsetEnd` of every token select
+	/// exactly the token text from the given source text. Offsets count Unicode characters.
+	/// Tokens without offsets are skipped.
+	pub fn check_offsets(&self, text: &str, options: &OffsetOptions) -> ValidationReport {
+		let mut r = ValidationReport::default();
+		let boundaries = char_boundaries(text);
+		for (i, t) in self.token_list.iter().enumerate() {
+			let (begin, end) = match (t.char_offset_begin, t.char_offset_end) {
+				(Some(b), Some(e)) => (b, e),
+				_ => continue,
+			};
+			let pointer = format!("/tokenList/{}", i);
+			match slice_chars(text, &boundaries, begin, end) {
+				None => r.push(pointer, IssueKind::OffsetOutOfRange { begin, end }),
+				Some(found) => {
+					if options.normalization.apply(found) != options.normalization.apply(&t.text) {
+						r.push(pointer, IssueKind::OffsetMismatch { expected: t.text.clone(), found: found.to_string() });
+					}
+				}
+			}
+		}
+		r
+	}
+}
+
+impl Document {
+	/// checks the token offsets against the document text, see Document::check_offsets().
+	pub fn check_text_offsets(&self, options: &OffsetOptions) -> ValidationReport {
+		self.check_offsets(&self.text, options)
+	}
+
+	/// returns the part of the document text between the character offsets begin and end.
+	pub fn span_text(&self, begin: u64, end: u64) -> Option<&str> {
+		slice_chars(&self.text, &char_boundaries(&self.text), begin, end)
+	}
+
+	/// returns the part of the document text covered by the token's character offsets.
+	pub fn token_text(&self, token: &Token) -> Option<&str> {
+		self.span_text(token.char_offset_begin?, token.char_offset_end?)
+	}
+
+	/// returns the part of the document text covered by the tokens of an entity.
+	/// The tokens are taken from the entity's token list, or its `tokenFrom`/`tokenTo` span.
+	pub fn entity_text(&self, entity: &Entity) -> Option<&str> {
+		let (begin, end) = self.char_span(|id| entity.covers(id))?;
+		self.span_text(begin, end)
+	}
+
+	/// returns the IDs of the tokens overlapping the character spans.
+	pub(crate) fn tokens_overlapping(&self, spans: &[(u64, u64)]) -> Vec<TokenId> {
+		self.token_list.iter().filter(|t| match (t.char_offset_begin, t.char_offset_end) {
+			(Some(b), Some(e)) => spans.iter().any(|(start, end)| b < *end && e > *start),
+			_ => false,
+		}).map(|t| t.id).collect()
+	}
+
+	/// returns the first begin and last end character offset of the tokens selected by their ID.
+	pub(crate) fn char_span(&self, selected: impl Fn(TokenId) -> bool) -> Option<(u64, u64)> {
+		let tokens: Vec<&Token> = self.token_list.iter().filter(|t| selected(t.id)).collect();
+		let begin = tokens.iter().filter_map(|t| t.char_offset_begin).min()?;
+		let end = tokens.iter().filter_map(|t| t.char_offset_end).max()?;
+		Some((begin, end))
+	}
+}
+
+impl Entity {
+	/// checks whether the token belongs to the entity, given by its token list or its `tokenFrom`/`tokenTo` span.
+	pub(crate) fn covers(&self, id: TokenId) -> bool {
+		match (self.token_from, self.token_to) {
+			_ if !self.tokens.is_empty() => self.tokens.contains(&id),
+			(Some(from), Some(to)) => id >= from && id <= to,
+			_ => false,
+		}
+	}
+}
+
+impl Expression {
+	/// checks whether the token belongs to the expression, given by its token list or its `tokenFrom`/`tokenTo` span.
+	pub(crate) fn covers(&self, id: TokenId) -> bool {
+		match (self.token_from, self.token_to) {
+			_ if !self.tokens.is_empty() => self.tokens.contains(&id),
+			(Some(from), Some(to)) => id >= from && id <= to,
+			_ => false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn token_offsets() {
+		let mut doc: Document = serde_json::from_str(
+			r#"{"meta":{},"id":1,"text":"Café ﬁnal","tokenList":[
+			{"id":1,"sentence_id":1,"text":"Café","lemma":"café","characterOffsetBegin":0,"characterOffsetEnd":4},
+			{"id":2,"sentence_id":1,"text":"final","lemma":"final","characterOffsetBegin":5,"characterOffsetEnd":9}]}"#,
+		)
+		.expect("valid document");
+		assert_eq!(doc.token_text(&doc.token_list[0]), Some("Café"));
+		let kinds = |doc: &Document, normalization| -> Vec<IssueKind> {
+			let options = OffsetOptions { normalization };
+			doc.check_text_offsets(&options).issues.into_iter().map(|i| i.kind).collect()
+		};
+		// the ligature is one character, which only NFKC reads as "fi"
+		let mismatch = || IssueKind::OffsetMismatch { expected: "final".to_string(), found: "ﬁnal".to_string() };
+		assert_eq!(kinds(&doc, Normalization::None), [mismatch()]);
+		assert_eq!(kinds(&doc, Normalization::Nfc), [mismatch()]);
+		assert_eq!(kinds(&doc, Normalization::Nfkc), []);
+		doc.token_list[1].char_offset_end = Some(10);
+		assert_eq!(kinds(&doc, Normalization::Nfkc), [IssueKind::OffsetOutOfRange { begin: 5, end: 10 }]);
+		doc.token_list[0].char_offset_begin = Some(5);
+		assert_eq!(kinds(&doc, Normalization::Nfkc)[0], IssueKind::OffsetOutOfRange { begin: 5, end: 4 });
+	}
+}
@@ -0,0 +1,159 @@
+//! Parallel parsing and validation of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) collections with
+//! [rayon](https://github.com/rayon-rs/rayon), behind the `rayon` feature.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use rayon::prelude::*;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use serde_json::{Map, Value};
+
+use crate::error::pointer;
+use crate::prob::{self, ProbPolicy};
+use crate::validate::ValidationReport;
+use crate::{check_options, Document, JsonNlpError, Meta, ParseError, ParseOptions, Result, Token, JSONNLP};
+
+/// This struct contains a collection with the documents left unparsed.
+struct Envelope<'a> {
+	meta: Meta,
+	docs: Vec<&'a RawValue>,
+	extra: Map<String, Value>,
+}
+
+struct EnvelopeVisitor<'a>(PhantomData<&'a ()>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for Envelope<'a> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+		deserializer.deserialize_map(EnvelopeVisitor(PhantomData))
+	}
+}
+
+impl<'de: 'a, 'a> Visitor<'de> for EnvelopeVisitor<'a> {
+	type Value = Envelope<'a>;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP object")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Envelope<'a>, A::Error> {
+		let mut meta = None;
+		let mut docs = Vec::new();
+		let mut extra = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"meta" => meta = Some(map.next_value::<Meta>()?),
+				"docs" => docs = map.next_value()?,
+				_ => {
+					extra.insert(key, map.next_value()?);
+				}
+			}
+		}
+		let meta = meta.ok_or_else(|| de::Error::missing_field("meta"))?;
+		Ok(Envelope { meta, docs, extra })
+	}
+}
+
+fn parse_document(json: &str, index: usize, policy: ProbPolicy) -> Result<Document> {
+	prob::with_policy(policy, || {
+		let mut de = serde_json::Deserializer::from_str(json);
+		serde_path_to_error::deserialize(&mut de).map_err(|e| {
+			let pointer = format!("/docs/{}{}", index, pointer(e.path()));
+			JsonNlpError::Parse(ParseError::new(pointer, e.into_inner()))
+		})
+	})
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), parsing the
+/// documents in parallel. The line and column numbers of errors in documents are relative to the start of the document.
+pub fn from_string_parallel(json: &str) -> Result<JSONNLP> {
+	from_string_parallel_with_options(json, &ParseOptions::default())
+}
+
+/// This function converts a string containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) using the given
+/// options, parsing the documents in parallel.
+pub fn from_string_parallel_with_options(json: &str, options: &ParseOptions) -> Result<JSONNLP> {
+	options.limits.check(json.as_bytes())?;
+	let mut de = serde_json::Deserializer::from_str(json);
+	let envelope: Envelope = prob::with_policy(options.prob_policy, || serde_path_to_error::deserialize(&mut de))?;
+	de.end()?;
+	let docs = envelope.docs.par_iter().enumerate()
+		.map(|(i, doc)| parse_document(doc.get(), i, options.prob_policy))
+		.collect::<Result<Vec<Document>>>()?;
+	check_options(JSONNLP { meta: envelope.meta, docs, extra: envelope.extra }, options)
+}
+
+/// This function converts a byte slice containing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), parsing the
+/// documents in parallel, see from_string_parallel().
+pub fn from_slice_parallel(json: &[u8]) -> Result<JSONNLP> {
+	let json = std::str::from_utf8(json).map_err(|e| JsonNlpError::Parse(ParseError::new(String::new(), de::Error::custom(e))))?;
+	from_string_parallel(json)
+}
+
+impl JSONNLP {
+	/// validates all documents in parallel, see Document::validate().
+	pub fn validate_parallel(&self) -> ValidationReport {
+		let reports: Vec<ValidationReport> = self.docs.par_iter().map(Document::validate).collect();
+		let mut r = ValidationReport::default();
+		for (i, report) in reports.into_iter().enumerate() {
+			r.extend_prefixed(&format!("/docs/{}", i), report);
+		}
+		r
+	}
+
+	/// returns a parallel iterator over the documents.
+	pub fn par_docs(&self) -> rayon::slice::Iter<'_, Document> {
+		self.docs.par_iter()
+	}
+
+	/// returns a parallel iterator over the documents that allows modifying them.
+	pub fn par_docs_mut(&mut self) -> rayon::slice::IterMut<'_, Document> {
+		self.docs.par_iter_mut()
+	}
+
+	/// returns a parallel iterator over the tokens of all documents.
+	pub fn par_tokens(&self) -> impl ParallelIterator<Item = &Token> {
+		self.docs.par_iter().flat_map(|d| d.token_list.par_iter())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::validate::IssueKind;
+	use crate::{from_string, from_string_with_options, Limits, Prob};
+
+	const JSON: &str = r#"{"meta":{},"docs":[
+		{"meta":{},"id":1,"tokenList":[{"id":1,"sentence_id":1,"text":"a","lemma":"a"}]},
+		{"meta":{},"id":2,"tokenList":[{"id":1,"sentence_id":1,"text":"b","lemma":"b","upos_prob":1.5}],
+			"sentences":[{"id":1,"tokens":[1,2]}]}],"x":1}"#;
+
+	#[test]
+	fn documents_are_parsed_in_parallel() {
+		let options = ParseOptions { prob_policy: ProbPolicy::Clamp, ..Default::default() };
+		let j = from_string_parallel_with_options(JSON, &options).expect("valid JSON-NLP");
+		assert_eq!(j, from_string_with_options(JSON, &options).unwrap());
+		assert_eq!(j.docs[1].token_list[0].upos_prob, Prob::new(1.0));
+		assert_eq!(j.extra["x"], 1);
+		assert_eq!(j.par_tokens().map(|t| t.text.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+
+		let report = j.validate_parallel();
+		assert_eq!(report, j.validate());
+		assert_eq!(report.issues.len(), 1);
+		assert_eq!(report.issues[0].pointer, "/docs/1/sentences/0/tokens/1");
+		assert_eq!(report.issues[0].kind, IssueKind::DanglingReference { layer: "token", id: 2 });
+
+		let json = r#"{"meta":{},"docs":[{"meta":{},"id":1}]}"#;
+		assert_eq!(from_slice_parallel(json.as_bytes()).unwrap(), from_string(json).unwrap());
+	}
+
+	#[test]
+	fn parallel_errors() {
+		let e = from_string_parallel(JSON).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Parse(ref p) if p.pointer == "/docs/1/tokenList/0/upos_prob"), "{}", e);
+		let options = ParseOptions { limits: Limits { max_docs: Some(1), ..Default::default() }, ..Default::default() };
+		assert!(matches!(from_string_parallel_with_options(JSON, &options), Err(JsonNlpError::LimitExceeded(_))));
+		assert!(from_string_parallel(r#"{"docs":[]}"#).is_err());
+		assert!(from_slice_parallel(b"{\"meta\":{},\"docs\":[]}\xff").is_err());
+	}
+}
@@ -0,0 +1,291 @@
+//! Conversion between the [PENMAN](https://penman.readthedocs.io/en/latest/notation.html) notation of abstract
+//! meaning representations and the AMR graphs of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents.
+
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::{AmrAttribute, AmrEdge, AmrGraph, AmrNode, Document, JsonNlpError, Result, SentenceId, TokenId};
+
+/// This enum contains the kinds of lexical units of the PENMAN notation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+	Open,
+	Close,
+	Slash,
+	Role,
+	Atom,
+}
+
+/// This struct contains a lexical unit with the surface alignment that follows it, e.g. `~e.2`.
+struct Lexeme<'a> {
+	kind: Kind,
+	text: &'a str,
+	alignment: Option<&'a str>,
+}
+
+/// returns the lexical units of a graph, or an error for an unterminated string.
+fn lexemes(s: &str) -> Result<Vec<Lexeme<'_>>> {
+	let delimiter = |c: char| c.is_whitespace() || "()/:\"~".contains(c);
+	let mut out: Vec<Lexeme> = Vec::new();
+	let mut chars = s.char_indices().peekable();
+	while let Some((i, c)) = chars.next() {
+		let (kind, end) = match c {
+			c if c.is_whitespace() => continue,
+			'(' => (Kind::Open, i + 1),
+			')' => (Kind::Close, i + 1),
+			'/' => (Kind::Slash, i + 1),
+			'"' => {
+				let mut escaped = false;
+				let end = chars.by_ref().find(|(_, c)| {
+					let close = *c == '"' && !escaped;
+					escaped = *c == '\\' && !escaped;
+					close
+				});
+				match end {
+					Some((j, _)) => (Kind::Atom, j + 1),
+					None => return Err(JsonNlpError::Format(format!("PENMAN: unterminated string at byte {}", i))),
+				}
+			}
+			_ => {
+				// alignments end at whitespace or brackets, and other atoms also at slashes, roles, quotes, or alignments
+				let alignment = c == '~';
+				let mut end = i + c.len_utf8();
+				while let Some((j, c)) = chars.peek().copied() {
+					if if alignment { c.is_whitespace() || c == '(' || c == ')' } else { delimiter(c) } {
+						break;
+					}
+					end = j + c.len_utf8();
+					chars.next();
+				}
+				if alignment {
+					match out.last_mut() {
+						Some(last) => last.alignment = Some(&s[i + 1..end]),
+						None => return Err(JsonNlpError::Format("PENMAN: alignment before the graph".to_string())),
+					}
+					continue;
+				}
+				(if c == ':' { Kind::Role } else { Kind::Atom }, end)
+			}
+		};
+		out.push(Lexeme { kind, text: &s[i..end], alignment: None });
+	}
+	Ok(out)
+}
+
+/// returns the tokens of an alignment like `e.2,3` or `2`, given by their indexes in the sentence's tokens.
+fn aligned(alignment: Option<&str>, tokens: &[TokenId]) -> Result<Vec<TokenId>> {
+	let alignment = match alignment {
+		Some(a) => a.rsplit('.').next().unwrap_or_default(),
+		None => return Ok(Vec::new()),
+	};
+	alignment.split(',').filter(|i| !i.is_empty()).map(|i| {
+		i.parse::<usize>().ok().and_then(|i| tokens.get(i).copied())
+			.ok_or_else(|| JsonNlpError::Format(format!("PENMAN: alignment {:?} refers to no token", i)))
+	}).collect()
+}
+
+/// This struct contains the state of parsing one graph.
+struct Parser<'a, 'b> {
+	lexemes: Vec<Lexeme<'a>>,
+	position: usize,
+	tokens: &'b [TokenId],
+	graph: AmrGraph,
+	/// the constants and references to variables, with source, role, value, and alignment
+	atoms: Vec<(String, String, &'a str, Option<&'a str>)>,
+}
+
+impl<'a> Parser<'a, '_> {
+	fn error(&self, msg: &str) -> JsonNlpError {
+		JsonNlpError::Format(format!("PENMAN: {} at {:?}", msg, self.lexemes.get(self.position).map_or("the end", |l| l.text)))
+	}
+
+	fn next(&mut self, kind: Kind) -> Result<&Lexeme<'a>> {
+		match self.lexemes.get(self.position) {
+			Some(l) if l.kind == kind => {
+				self.position += 1;
+				Ok(&self.lexemes[self.position - 1])
+			}
+			_ => Err(self.error(&format!("expected {:?}", kind))),
+		}
+	}
+
+	/// parses a node with its roles and returns its variable.
+	fn node(&mut self) -> Result<String> {
+		self.next(Kind::Open)?;
+		let id = self.next(Kind::Atom)?.text.to_string();
+		let mut node = AmrNode { id: id.clone(), ..Default::default() };
+		if self.next(Kind::Slash).is_ok() {
+			let concept = self.next(Kind::Atom)?;
+			let (concept, alignment) = (concept.text, concept.alignment);
+			node.concept = concept.to_string();
+			node.tokens = aligned(alignment, self.tokens)?;
+		}
+		self.graph.nodes.push(node);
+		while self.next(Kind::Close).is_err() {
+			let role = self.next(Kind::Role)?.text[1..].to_string();
+			match self.lexemes.get(self.position).map(|l| l.kind) {
+				Some(Kind::Open) => {
+					let target = self.node()?;
+					self.graph.edges.push(AmrEdge { source: id.clone(), role, target, ..Default::default() });
+				}
+				Some(Kind::Atom) => {
+					let atom = self.next(Kind::Atom)?;
+					let (text, alignment) = (atom.text, atom.alignment);
+					self.atoms.push((id.clone(), role, text, alignment));
+				}
+				_ => return Err(self.error("expected a node or constant")),
+			}
+		}
+		Ok(id)
+	}
+}
+
+impl AmrGraph {
+	/// returns the graph of a PENMAN string like `(w / want-01 :ARG0 (b / boy) :ARG1 (g / go-02 :ARG0 b))`.
+	/// Roles on variables become edges, and other roles become attributes with their constant value. Surface
+	/// alignments like `~e.2` refer to the given tokens of the sentence by their index and align concepts and
+	/// constants to tokens, and alignments of roles are dropped. Metadata comments like `# ::id 1 ::snt ...`
+	/// are kept in the graph's `extra` map.
+	pub fn from_penman(penman: &str, tokens: &[TokenId]) -> Result<AmrGraph> {
+		let mut graph = AmrGraph::default();
+		let mut body = String::new();
+		for line in penman.lines() {
+			match line.trim_start().strip_prefix('#') {
+				Some(comment) => {
+					for field in comment.split("::").skip(1).map(str::trim).filter(|f| !f.is_empty()) {
+						let (key, value) = field.split_once(char::is_whitespace).unwrap_or((field, ""));
+						graph.extra.insert(key.to_string(), Value::String(value.trim().to_string()));
+					}
+				}
+				None => {
+					body.push_str(line);
+					body.push('\n');
+				}
+			}
+		}
+
+		let mut parser = Parser { lexemes: lexemes(&body)?, position: 0, tokens, graph, atoms: Vec::new() };
+		parser.graph.top = parser.node()?;
+		if parser.position < parser.lexemes.len() {
+			return Err(parser.error("text after the graph"));
+		}
+		let variables: HashSet<String> = parser.graph.nodes.iter().map(|n| n.id.clone()).collect();
+		for (source, role, value, alignment) in std::mem::take(&mut parser.atoms) {
+			if variables.contains(value) {
+				parser.graph.edges.push(AmrEdge { source, role, target: value.to_string(), ..Default::default() });
+			} else {
+				let tokens = aligned(alignment, tokens)?;
+				parser.graph.attributes.push(AmrAttribute { source, role, value: value.to_string(), tokens, ..Default::default() });
+			}
+		}
+		Ok(parser.graph)
+	}
+
+	/// returns the graph in PENMAN notation, starting at the top node, with one role per line and the metadata
+	/// in the `extra` map as `# ::` comments. The edges of a node are written before its attributes, and a node
+	/// reached again is written as its variable. Alignments are written as `~e.` and the indexes of the aligned
+	/// tokens among the given tokens of the sentence.
+	pub fn to_penman(&self, tokens: &[TokenId]) -> String {
+		let mut out = String::new();
+		for (key, value) in &self.extra {
+			match value {
+				Value::String(value) if value.is_empty() => out.push_str(&format!("# ::{}\n", key)),
+				Value::String(value) => out.push_str(&format!("# ::{} {}\n", key, value)),
+				_ => {}
+			}
+		}
+		let top = match (self.top.as_str(), self.nodes.first()) {
+			("", Some(first)) => first.id.as_str(),
+			(top, _) => top,
+		};
+		let mut visited = HashSet::new();
+		self.write_node(top, 1, tokens, &mut visited, &mut out);
+		out
+	}
+
+	fn write_node<'a>(&'a self, id: &'a str, depth: usize, tokens: &[TokenId], visited: &mut HashSet<&'a str>, out: &mut String) {
+		let alignment = |aligned: &[TokenId]| {
+			let indexes: Vec<String> = aligned.iter().filter_map(|t| tokens.iter().position(|i| i == t)).map(|i| i.to_string()).collect();
+			if indexes.is_empty() { String::new() } else { format!("~e.{}", indexes.join(",")) }
+		};
+		visited.insert(id);
+		out.push('(');
+		out.push_str(id);
+		if let Some(node) = self.nodes.iter().find(|n| n.id == id).filter(|n| !n.concept.is_empty()) {
+			out.push_str(&format!(" / {}{}", node.concept, alignment(&node.tokens)));
+		}
+		let indent = " ".repeat(6 * depth);
+		for e in self.edges.iter().filter(|e| e.source == id) {
+			out.push_str(&format!("\n{}:{} ", indent, e.role));
+			if visited.contains(e.target.as_str()) || !self.nodes.iter().any(|n| n.id == e.target) {
+				out.push_str(&e.target);
+			} else {
+				self.write_node(&e.target, depth + 1, tokens, visited, out);
+			}
+		}
+		for a in self.attributes.iter().filter(|a| a.source == id) {
+			out.push_str(&format!("\n{}:{} {}{}", indent, a.role, a.value, alignment(&a.tokens)));
+		}
+		out.push(')');
+	}
+}
+
+impl Document {
+	/// returns the IDs of the tokens of a sentence, in order.
+	fn sentence_tokens(&self, sentence_id: Option<SentenceId>) -> Vec<TokenId> {
+		self.sentence_groups().into_iter()
+			.find(|g| Some(g.0) == sentence_id)
+			.map(|g| g.2.iter().map(|t| t.id).collect())
+			.unwrap_or_default()
+	}
+
+	/// adds the AMR graph of a sentence in PENMAN notation, with its alignments referring to the sentence's tokens,
+	/// see AmrGraph::from_penman().
+	pub fn add_penman(&mut self, sentence_id: SentenceId, penman: &str) -> Result<()> {
+		let mut graph = AmrGraph::from_penman(penman, &self.sentence_tokens(Some(sentence_id)))?;
+		graph.sentence_id = Some(sentence_id);
+		self.amr_graphs.push(graph);
+		Ok(())
+	}
+
+	/// returns an AMR graph in PENMAN notation, with its alignments referring to the tokens of its sentence,
+	/// see AmrGraph::to_penman().
+	pub fn penman(&self, graph: &AmrGraph) -> String {
+		graph.to_penman(&self.sentence_tokens(graph.sentence_id))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn penman_round_trip() {
+		let tokens = [TokenId(5), TokenId(6), TokenId(7)];
+		let penman = "# ::id 1\n(w / want-01~e.1\n      :ARG0 (b / boy~e.0)\n      :ARG1 (g / go-02~e.2\n\
+			\x20           :ARG0 b\n            :polarity -))";
+		let graph = AmrGraph::from_penman(penman, &tokens).expect("valid PENMAN");
+		assert_eq!(graph.top, "w");
+		assert_eq!(graph.nodes.len(), 3);
+		assert_eq!(graph.edges.len(), 3);
+		assert_eq!(graph.attributes.len(), 1);
+		assert_eq!(graph.nodes.iter().find(|n| n.id == "b").map(|n| n.tokens.clone()), Some(vec![TokenId(5)]));
+		assert_eq!(graph.to_penman(&tokens), penman);
+		assert_eq!(AmrGraph::from_penman(&graph.to_penman(&tokens), &tokens).expect("valid PENMAN"), graph);
+	}
+
+	#[test]
+	fn penman_single_line() {
+		let graph = AmrGraph::from_penman(r#"(n / name :op1 "Barack" :op2 "Obama")"#, &[]).expect("valid PENMAN");
+		let values: Vec<&str> = graph.attributes.iter().map(|a| a.value.as_str()).collect();
+		assert_eq!(values, [r#""Barack""#, r#""Obama""#]);
+		assert_eq!(AmrGraph::from_penman(&graph.to_penman(&[]), &[]).expect("valid PENMAN"), graph);
+	}
+
+	#[test]
+	fn penman_errors() {
+		assert!(AmrGraph::from_penman("(w / want-01", &[]).is_err());
+		assert!(AmrGraph::from_penman("(w / want-01) (b / boy)", &[]).is_err());
+	}
+}
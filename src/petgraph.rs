@@ -0,0 +1,100 @@
+//! Dependency trees and entity graphs of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents as
+//! [petgraph](https://docs.rs/petgraph) graphs, so that the algorithms of petgraph, e.g. shortest paths,
+//! centrality, or connected components, apply to them. The graphs borrow their tokens, entities, and
+//! dependencies from the document.
+
+use std::collections::HashMap;
+
+use petgraph::graph::{DiGraph, NodeIndex};
+
+use crate::{Dependency, DependencyTree, Document, Entity, EntityId, Relation, Token, TokenId, Triple};
+
+/// This struct contains the graph of a dependency tree with a node per token and an edge from the governor to the
+/// dependent of every dependency.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph<'a> {
+	pub graph: DiGraph<&'a Token, &'a Dependency>,
+	/// the nodes of the tokens.
+	pub nodes: HashMap<TokenId, NodeIndex>,
+	/// the nodes of the dependents of the root.
+	pub roots: Vec<NodeIndex>,
+}
+
+impl DependencyGraph<'_> {
+	/// returns the node of a token, or None if the token is not in the graph.
+	pub fn node(&self, id: TokenId) -> Option<NodeIndex> {
+		self.nodes.get(&id).copied()
+	}
+}
+
+/// This struct is the edge of a triple, with its relation if the document has it.
+#[derive(Debug, Clone, Copy)]
+pub struct TripleEdge<'a> {
+	pub triple: &'a Triple,
+	pub relation: Option<&'a Relation>,
+}
+
+/// This struct contains the graph of the entities and triples of a document, with a node per entity and an edge
+/// per triple between two of them. Triples that are not directional have an edge in both directions.
+#[derive(Debug, Clone)]
+pub struct EntityGraph<'a> {
+	pub graph: DiGraph<&'a Entity, TripleEdge<'a>>,
+	/// the nodes of the entities.
+	pub nodes: HashMap<EntityId, NodeIndex>,
+}
+
+impl EntityGraph<'_> {
+	/// returns the node of an entity, or None if the entity is not in the graph.
+	pub fn node(&self, id: EntityId) -> Option<NodeIndex> {
+		self.nodes.get(&id).copied()
+	}
+}
+
+impl Document {
+	/// returns the graph of a dependency tree of the document, with the tokens of its dependencies as nodes.
+	/// Dependencies of tokens that are not in the token list are skipped.
+	pub fn dependency_graph<'a>(&'a self, tree: &'a DependencyTree) -> DependencyGraph<'a> {
+		let tokens: HashMap<TokenId, &Token> = self.token_list.iter().map(|t| (t.id, t)).collect();
+		let mut g = DependencyGraph { graph: DiGraph::new(), nodes: HashMap::new(), roots: Vec::new() };
+		let node = |g: &mut DependencyGraph<'a>, id: TokenId| -> Option<NodeIndex> {
+			let token = *tokens.get(&id)?;
+			let DependencyGraph { graph, nodes, .. } = g;
+			Some(*nodes.entry(id).or_insert_with(|| graph.add_node(token)))
+		};
+		for d in &tree.dependencies {
+			let dep = match node(&mut g, d.dep) {
+				Some(dep) => dep,
+				None => continue,
+			};
+			if d.gov == TokenId::default() {
+				g.roots.push(dep);
+			} else if let Some(gov) = node(&mut g, d.gov) {
+				g.graph.add_edge(gov, dep, d);
+			}
+		}
+		g
+	}
+
+	/// returns the graph of the entities and triples of the document. Triples without two entities of the
+	/// document are skipped.
+	pub fn entity_graph(&self) -> EntityGraph<'_> {
+		let mut g = EntityGraph { graph: DiGraph::new(), nodes: HashMap::new() };
+		for e in &self.entities {
+			let i = g.graph.add_node(e);
+			g.nodes.insert(e.id, i);
+		}
+		for t in &self.triples {
+			let (from, to) = match (t.from_entity.and_then(|id| g.node(id)), t.to_entity.and_then(|id| g.node(id))) {
+				(Some(from), Some(to)) => (from, to),
+				_ => continue,
+			};
+			let edge =
+				TripleEdge { triple: t, relation: t.rel.and_then(|id| self.relations.iter().find(|r| r.id == id)) };
+			g.graph.add_edge(from, to, edge);
+			if t.directional == Some(false) {
+				g.graph.add_edge(to, from, edge);
+			}
+		}
+		g
+	}
+}
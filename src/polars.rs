@@ -0,0 +1,394 @@
+//! Conversion of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents to [Polars](https://pola.rs/)
+//! DataFrames with one row per token, sentence, entity, and dependency, and back. The columns are named after the
+//! JSON-NLP properties, so that e.g. the entities are joined with their tokens by exploding `tokens` and joining
+//! it with `id` of the tokens.
+//!
+//! The properties without a column, e.g. the features of tokens, are kept as JSON in the column `rest` of every
+//! row, null if there are none, so that a document converted to DataFrames and back is unchanged.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use polars::prelude::{
+	Column, DataFrame, Float64Chunked, IntoColumn, ListChunked, NamedFrom, PolarsError, Series, StringChunked,
+	UInt64Chunked,
+};
+
+use crate::{Dependency, Document, Entity, EntityId, JsonNlpError, Prob, Result, Sentence, SentenceId, Token, TokenId};
+
+/// This struct contains the DataFrames of the layers of a document. The document keeps all other properties, with
+/// empty token, sentence, and entity lists and dependency trees without dependencies.
+#[derive(Default, Debug, Clone)]
+pub struct DocumentFrames {
+	pub document: Document,
+	/// the tokens, with the columns `id`, `sentence_id`, `text`, `lemma`, `xpos`, `xpos_prob`, `upos`,
+	/// `upos_prob`, `entity_iob`, `entity`, `shape`, `characterOffsetBegin`, `characterOffsetEnd`, and `rest`.
+	pub tokens: DataFrame,
+	/// the sentences, with the columns `id`, `text`, `tokenFrom`, `tokenTo`, `tokens`, `type`, `sentiment`,
+	/// `sentimentProb`, and `rest`.
+	pub sentences: DataFrame,
+	/// the entities, with the columns `id`, `label`, `type`, `url`, `head`, `tokenFrom`, `tokenTo`, `tokens`,
+	/// `sentiment`, `sentimentProb`, and `rest`.
+	pub entities: DataFrame,
+	/// the dependencies, with the index of their tree in the dependency trees of the document in `tree`, the
+	/// sentence and style of the tree in `sentence_id` and `style`, and the columns `lab`, `gov`, `dep`, `prob`, and
+	/// `rest`.
+	pub dependencies: DataFrame,
+}
+
+fn error(e: PolarsError) -> JsonNlpError {
+	JsonNlpError::Format(format!("Polars: {}", e))
+}
+
+fn prob(p: Option<Prob>) -> Option<f64> {
+	p.map(Prob::get)
+}
+
+/// returns the item as JSON, or None if it is the default, i.e. if all its properties have columns.
+fn rest<T: Serialize + Default + PartialEq>(item: &T) -> Result<Option<String>> {
+	if *item == T::default() {
+		Ok(None)
+	} else {
+		Ok(Some(serde_json::to_string(item)?))
+	}
+}
+
+/// returns the item kept as JSON in a row, or the default if there is none.
+fn from_rest<T: DeserializeOwned + Default>(rest: Option<&str>) -> Result<T> {
+	match rest {
+		Some(json) => Ok(serde_json::from_str(json)?),
+		None => Ok(T::default()),
+	}
+}
+
+fn ids<I: Into<u64> + Copy>(ids: &[I]) -> Series {
+	Series::new("".into(), ids.iter().map(|&id| id.into()).collect::<Vec<u64>>())
+}
+
+fn id_list(name: &str, lists: Vec<Series>) -> Column {
+	let mut column: ListChunked = lists.into_iter().map(Some).collect();
+	column.rename(name.into());
+	column.into_column()
+}
+
+fn invalid(column: &str, value: impl std::fmt::Display) -> JsonNlpError {
+	JsonNlpError::Format(format!("Polars: invalid value {} in column {}", value, column))
+}
+
+fn frame(columns: Vec<Column>) -> Result<DataFrame> {
+	DataFrame::new(columns).map_err(error)
+}
+
+/// This function converts the tokens, sentences, entities, and dependencies of a Document to DataFrames.
+pub fn to_dataframes(doc: &Document) -> Result<DocumentFrames> {
+	let mut document = doc.clone();
+	let token_list = std::mem::take(&mut document.token_list);
+	let sentences = std::mem::take(&mut document.sentences);
+	let entities = std::mem::take(&mut document.entities);
+	let mut dependencies = Vec::new();
+	for (i, tree) in document.dependency_trees.iter_mut().enumerate() {
+		for dep in std::mem::take(&mut tree.dependencies) {
+			dependencies.push((i as u64, tree.sentence_id, tree.style.clone(), dep));
+		}
+	}
+
+	let mut token_rest = Vec::new();
+	for t in &token_list {
+		token_rest.push(rest(&Token {
+			id: TokenId::default(),
+			sentence_id: SentenceId::default(),
+			text: String::new(),
+			lemma: String::new(),
+			xpos: String::new(),
+			xpos_prob: None,
+			upos: String::new(),
+			upos_prob: None,
+			entity_iob: String::new(),
+			entity: String::new(),
+			shape: String::new(),
+			char_offset_begin: None,
+			char_offset_end: None,
+			..t.clone()
+		})?);
+	}
+	let tokens = frame(vec![
+		Column::new("id".into(), token_list.iter().map(|t| t.id.into()).collect::<Vec<u64>>()),
+		Column::new("sentence_id".into(), token_list.iter().map(|t| t.sentence_id.into()).collect::<Vec<u64>>()),
+		Column::new("text".into(), token_list.iter().map(|t| t.text.as_str()).collect::<Vec<_>>()),
+		Column::new("lemma".into(), token_list.iter().map(|t| t.lemma.as_str()).collect::<Vec<_>>()),
+		Column::new("xpos".into(), token_list.iter().map(|t| t.xpos.as_str()).collect::<Vec<_>>()),
+		Column::new("xpos_prob".into(), token_list.iter().map(|t| prob(t.xpos_prob)).collect::<Vec<_>>()),
+		Column::new("upos".into(), token_list.iter().map(|t| t.upos.as_str()).collect::<Vec<_>>()),
+		Column::new("upos_prob".into(), token_list.iter().map(|t| prob(t.upos_prob)).collect::<Vec<_>>()),
+		Column::new("entity_iob".into(), token_list.iter().map(|t| t.entity_iob.as_str()).collect::<Vec<_>>()),
+		Column::new("entity".into(), token_list.iter().map(|t| t.entity.as_str()).collect::<Vec<_>>()),
+		Column::new("shape".into(), token_list.iter().map(|t| t.shape.as_str()).collect::<Vec<_>>()),
+		Column::new("characterOffsetBegin".into(), token_list.iter().map(|t| t.char_offset_begin).collect::<Vec<_>>()),
+		Column::new("characterOffsetEnd".into(), token_list.iter().map(|t| t.char_offset_end).collect::<Vec<_>>()),
+		Column::new("rest".into(), token_rest),
+	])?;
+
+	let mut sentence_rest = Vec::new();
+	for s in &sentences {
+		sentence_rest.push(rest(&Sentence {
+			id: SentenceId::default(),
+			text: String::new(),
+			token_from: None,
+			token_to: None,
+			tokens: Vec::new(),
+			stype: String::new(),
+			sentiment: String::new(),
+			sentiment_prob: None,
+			..s.clone()
+		})?);
+	}
+	let sentences = frame(vec![
+		Column::new("id".into(), sentences.iter().map(|s| s.id.into()).collect::<Vec<u64>>()),
+		Column::new("text".into(), sentences.iter().map(|s| s.text.as_str()).collect::<Vec<_>>()),
+		Column::new("tokenFrom".into(), sentences.iter().map(|s| s.token_from.map(u64::from)).collect::<Vec<_>>()),
+		Column::new("tokenTo".into(), sentences.iter().map(|s| s.token_to.map(u64::from)).collect::<Vec<_>>()),
+		id_list("tokens", sentences.iter().map(|s| ids(&s.tokens)).collect()),
+		Column::new("type".into(), sentences.iter().map(|s| s.stype.as_str()).collect::<Vec<_>>()),
+		Column::new("sentiment".into(), sentences.iter().map(|s| s.sentiment.as_str()).collect::<Vec<_>>()),
+		Column::new("sentimentProb".into(), sentences.iter().map(|s| prob(s.sentiment_prob)).collect::<Vec<_>>()),
+		Column::new("rest".into(), sentence_rest),
+	])?;
+
+	let mut entity_rest = Vec::new();
+	for e in &entities {
+		entity_rest.push(rest(&Entity {
+			id: EntityId::default(),
+			label: String::new(),
+			etype: String::new(),
+			url: String::new(),
+			head: None,
+			token_from: None,
+			token_to: None,
+			tokens: Vec::new(),
+			sentiment: String::new(),
+			sentiment_prob: None,
+			..e.clone()
+		})?);
+	}
+	let entities = frame(vec![
+		Column::new("id".into(), entities.iter().map(|e| e.id.into()).collect::<Vec<u64>>()),
+		Column::new("label".into(), entities.iter().map(|e| e.label.as_str()).collect::<Vec<_>>()),
+		Column::new("type".into(), entities.iter().map(|e| e.etype.as_str()).collect::<Vec<_>>()),
+		Column::new("url".into(), entities.iter().map(|e| e.url.as_str()).collect::<Vec<_>>()),
+		Column::new("head".into(), entities.iter().map(|e| e.head.map(u64::from)).collect::<Vec<_>>()),
+		Column::new("tokenFrom".into(), entities.iter().map(|e| e.token_from.map(u64::from)).collect::<Vec<_>>()),
+		Column::new("tokenTo".into(), entities.iter().map(|e| e.token_to.map(u64::from)).collect::<Vec<_>>()),
+		id_list("tokens", entities.iter().map(|e| ids(&e.tokens)).collect()),
+		Column::new("sentiment".into(), entities.iter().map(|e| e.sentiment.as_str()).collect::<Vec<_>>()),
+		Column::new("sentimentProb".into(), entities.iter().map(|e| prob(e.sentiment_prob)).collect::<Vec<_>>()),
+		Column::new("rest".into(), entity_rest),
+	])?;
+
+	let mut dependency_rest = Vec::new();
+	for (_, _, _, d) in &dependencies {
+		dependency_rest.push(rest(&Dependency {
+			lab: String::new(),
+			gov: TokenId::default(),
+			dep: TokenId::default(),
+			prob: None,
+			..d.clone()
+		})?);
+	}
+	let dependencies = frame(vec![
+		Column::new("tree".into(), dependencies.iter().map(|(i, _, _, _)| *i).collect::<Vec<_>>()),
+		Column::new("sentence_id".into(), dependencies.iter().map(|(_, s, _, _)| s.map(u64::from)).collect::<Vec<_>>()),
+		Column::new("style".into(), dependencies.iter().map(|(_, _, style, _)| style.as_str()).collect::<Vec<_>>()),
+		Column::new("lab".into(), dependencies.iter().map(|(_, _, _, d)| d.lab.as_str()).collect::<Vec<_>>()),
+		Column::new("gov".into(), dependencies.iter().map(|(_, _, _, d)| d.gov.into()).collect::<Vec<u64>>()),
+		Column::new("dep".into(), dependencies.iter().map(|(_, _, _, d)| d.dep.into()).collect::<Vec<u64>>()),
+		Column::new("prob".into(), dependencies.iter().map(|(_, _, _, d)| prob(d.prob)).collect::<Vec<_>>()),
+		Column::new("rest".into(), dependency_rest),
+	])?;
+
+	Ok(DocumentFrames { document, tokens, sentences, entities, dependencies })
+}
+
+/// This struct reads the columns of a DataFrame by name.
+struct Columns<'a>(&'a DataFrame);
+
+impl<'a> Columns<'a> {
+	fn column(&self, name: &str) -> Result<&'a Column> {
+		self.0.column(name).map_err(error)
+	}
+
+	fn u64(&self, name: &str) -> Result<&'a UInt64Chunked> {
+		self.column(name)?.u64().map_err(error)
+	}
+
+	fn f64(&self, name: &str) -> Result<&'a Float64Chunked> {
+		self.column(name)?.f64().map_err(error)
+	}
+
+	fn str(&self, name: &str) -> Result<&'a StringChunked> {
+		self.column(name)?.str().map_err(error)
+	}
+
+	fn list(&self, name: &str) -> Result<&'a ListChunked> {
+		self.column(name)?.list().map_err(error)
+	}
+
+	/// returns the string values of a column, empty for nulls.
+	fn strings(&self, name: &str) -> Result<Vec<String>> {
+		Ok(self.str(name)?.into_iter().map(|s| s.unwrap_or_default().to_string()).collect())
+	}
+
+	/// returns the probabilities of a column.
+	fn probs(&self, name: &str) -> Result<Vec<Option<Prob>>> {
+		self.f64(name)?
+			.into_iter()
+			.map(|p| p.map(|p| Prob::new(p).ok_or_else(|| invalid(name, p))).transpose())
+			.collect()
+	}
+
+	/// returns the IDs or offsets of a column, with nulls as None.
+	fn optional_ids<I>(&self, name: &str, id: fn(u64) -> Option<I>) -> Result<Vec<Option<I>>> {
+		self.u64(name)?.into_iter().map(|v| v.map(|v| id(v).ok_or_else(|| invalid(name, v))).transpose()).collect()
+	}
+
+	/// returns the IDs of a column, failing on nulls.
+	fn ids<I>(&self, name: &str, id: fn(u64) -> Option<I>) -> Result<Vec<I>> {
+		self.optional_ids(name, id)?
+			.into_iter()
+			.map(|id| id.ok_or_else(|| JsonNlpError::Format(format!("Polars: null in column {}", name))))
+			.collect()
+	}
+
+	/// returns the ID lists of a list column.
+	fn id_lists<I>(&self, name: &str, id: fn(u64) -> Option<I>) -> Result<Vec<Vec<I>>> {
+		let mut lists = Vec::new();
+		for list in self.list(name)?.into_iter() {
+			let mut ids = Vec::new();
+			if let Some(list) = list {
+				for v in list.u64().map_err(error)?.into_iter().flatten() {
+					ids.push(id(v).ok_or_else(|| invalid(name, v))?);
+				}
+			}
+			lists.push(ids);
+		}
+		Ok(lists)
+	}
+
+	/// returns the items kept as JSON in the column `rest`.
+	fn rest<T: DeserializeOwned + Default>(&self) -> Result<Vec<T>> {
+		self.str("rest")?.into_iter().map(from_rest).collect()
+	}
+}
+
+/// This function converts DataFrames with the columns of to_dataframes() back to a Document. The columns may be in
+/// any order, and further columns, e.g. ones added by an analysis, are ignored.
+pub fn from_dataframes(frames: &DocumentFrames) -> Result<Document> {
+	let mut doc = frames.document.clone();
+
+	let c = Columns(&frames.tokens);
+	let tokens = c.rest::<Token>()?;
+	let mut id = c.ids("id", TokenId::from_u64)?.into_iter();
+	let mut sentence_id = c.ids("sentence_id", SentenceId::from_u64)?.into_iter();
+	let mut text = c.strings("text")?.into_iter();
+	let mut lemma = c.strings("lemma")?.into_iter();
+	let mut xpos = c.strings("xpos")?.into_iter();
+	let mut xpos_prob = c.probs("xpos_prob")?.into_iter();
+	let mut upos = c.strings("upos")?.into_iter();
+	let mut upos_prob = c.probs("upos_prob")?.into_iter();
+	let mut entity_iob = c.strings("entity_iob")?.into_iter();
+	let mut entity = c.strings("entity")?.into_iter();
+	let mut shape = c.strings("shape")?.into_iter();
+	let mut begin = c.optional_ids("characterOffsetBegin", Some)?.into_iter();
+	let mut end = c.optional_ids("characterOffsetEnd", Some)?.into_iter();
+	for t in tokens {
+		doc.token_list.push(Token {
+			id: id.next().unwrap_or_default(),
+			sentence_id: sentence_id.next().unwrap_or_default(),
+			text: text.next().unwrap_or_default(),
+			lemma: lemma.next().unwrap_or_default(),
+			xpos: xpos.next().unwrap_or_default(),
+			xpos_prob: xpos_prob.next().flatten(),
+			upos: upos.next().unwrap_or_default(),
+			upos_prob: upos_prob.next().flatten(),
+			entity_iob: entity_iob.next().unwrap_or_default(),
+			entity: entity.next().unwrap_or_default(),
+			shape: shape.next().unwrap_or_default(),
+			char_offset_begin: begin.next().flatten(),
+			char_offset_end: end.next().flatten(),
+			..t
+		});
+	}
+
+	let c = Columns(&frames.sentences);
+	let sentences = c.rest::<Sentence>()?;
+	let mut id = c.ids("id", SentenceId::from_u64)?.into_iter();
+	let mut text = c.strings("text")?.into_iter();
+	let mut token_from = c.optional_ids("tokenFrom", TokenId::from_u64)?.into_iter();
+	let mut token_to = c.optional_ids("tokenTo", TokenId::from_u64)?.into_iter();
+	let mut tokens = c.id_lists("tokens", TokenId::from_u64)?.into_iter();
+	let mut stype = c.strings("type")?.into_iter();
+	let mut sentiment = c.strings("sentiment")?.into_iter();
+	let mut sentiment_prob = c.probs("sentimentProb")?.into_iter();
+	for s in sentences {
+		doc.sentences.push(Sentence {
+			id: id.next().unwrap_or_default(),
+			text: text.next().unwrap_or_default(),
+			token_from: token_from.next().flatten(),
+			token_to: token_to.next().flatten(),
+			tokens: tokens.next().unwrap_or_default(),
+			stype: stype.next().unwrap_or_default(),
+			sentiment: sentiment.next().unwrap_or_default(),
+			sentiment_prob: sentiment_prob.next().flatten(),
+			..s
+		});
+	}
+
+	let c = Columns(&frames.entities);
+	let entities = c.rest::<Entity>()?;
+	let mut id = c.ids("id", EntityId::from_u64)?.into_iter();
+	let mut label = c.strings("label")?.into_iter();
+	let mut etype = c.strings("type")?.into_iter();
+	let mut url = c.strings("url")?.into_iter();
+	let mut head = c.optional_ids("head", TokenId::from_u64)?.into_iter();
+	let mut token_from = c.optional_ids("tokenFrom", TokenId::from_u64)?.into_iter();
+	let mut token_to = c.optional_ids("tokenTo", TokenId::from_u64)?.into_iter();
+	let mut tokens = c.id_lists("tokens", TokenId::from_u64)?.into_iter();
+	let mut sentiment = c.strings("sentiment")?.into_iter();
+	let mut sentiment_prob = c.probs("sentimentProb")?.into_iter();
+	for e in entities {
+		doc.entities.push(Entity {
+			id: id.next().unwrap_or_default(),
+			label: label.next().unwrap_or_default(),
+			etype: etype.next().unwrap_or_default(),
+			url: url.next().unwrap_or_default(),
+			head: head.next().flatten(),
+			token_from: token_from.next().flatten(),
+			token_to: token_to.next().flatten(),
+			tokens: tokens.next().unwrap_or_default(),
+			sentiment: sentiment.next().unwrap_or_default(),
+			sentiment_prob: sentiment_prob.next().flatten(),
+			..e
+		});
+	}
+
+	let c = Columns(&frames.dependencies);
+	let dependencies = c.rest::<Dependency>()?;
+	let mut tree = c.ids("tree", Some)?.into_iter();
+	let mut lab = c.strings("lab")?.into_iter();
+	let mut gov = c.ids("gov", TokenId::from_u64)?.into_iter();
+	let mut dep = c.ids("dep", TokenId::from_u64)?.into_iter();
+	let mut prob = c.probs("prob")?.into_iter();
+	for d in dependencies {
+		let i = tree.next().unwrap_or_default() as usize;
+		let tree = doc.dependency_trees.get_mut(i).ok_or_else(|| {
+			JsonNlpError::Format(format!("Polars: dependency of tree {}, which the document does not have", i))
+		})?;
+		tree.dependencies.push(Dependency {
+			lab: lab.next().unwrap_or_default(),
+			gov: gov.next().unwrap_or_default(),
+			dep: dep.next().unwrap_or_default(),
+			prob: prob.next().flatten(),
+			..d
+		});
+	}
+	Ok(doc)
+}
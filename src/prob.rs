@@ -0,0 +1,91 @@
+//! Probability values restricted to the range 0.0 to 1.0.
+
+use std::cell::Cell;
+use std::fmt;
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// This enum selects how out-of-range probabilities are handled during deserialization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProbPolicy {
+	/// reject probabilities outside of 0.0..=1.0 and NaN.
+	#[default]
+	Error,
+	/// clamp probabilities into 0.0..=1.0, reading NaN as 0.0.
+	Clamp,
+}
+
+thread_local! {
+	static POLICY: Cell<ProbPolicy> = const { Cell::new(ProbPolicy::Error) };
+}
+
+/// runs f with the given policy for all probabilities deserialized on this thread.
+pub(crate) fn with_policy<T, F: FnOnce() -> T>(policy: ProbPolicy, f: F) -> T {
+	let previous = POLICY.with(|p| p.replace(policy));
+	let r = f();
+	POLICY.with(|p| p.set(previous));
+	r
+}
+
+/// The floating-point type of probabilities, f64 by default and f32 with the `compact` feature.
+#[cfg(not(feature = "compact"))]
+type RawProb = f64;
+/// The floating-point type of probabilities, f64 by default and f32 with the `compact` feature.
+#[cfg(feature = "compact")]
+type RawProb = f32;
+
+/// This struct contains a probability, guaranteed to be within 0.0..=1.0.
+/// With the `compact` feature it is stored and serialized as f32.
+#[derive(Serialize, Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(transparent)]
+pub struct Prob(RawProb);
+
+impl Prob {
+	/// returns the probability, or None if the value is outside of 0.0..=1.0 or NaN.
+	pub fn new(p: f64) -> Option<Prob> {
+		if (0.0..=1.0).contains(&p) {
+			Some(Prob(p as RawProb))
+		} else {
+			None
+		}
+	}
+
+	/// returns the probability clamped into 0.0..=1.0, with NaN mapped to 0.0.
+	pub fn clamped(p: f64) -> Prob {
+		if p.is_nan() {
+			Prob(0.0)
+		} else {
+			Prob(p.clamp(0.0, 1.0) as RawProb)
+		}
+	}
+
+	/// returns the probability as f64.
+	// the conversion is only needed with the `compact` feature
+	#[allow(clippy::useless_conversion)]
+	pub fn get(self) -> f64 {
+		self.0.into()
+	}
+}
+
+impl From<Prob> for f64 {
+	fn from(p: Prob) -> Self {
+		p.get()
+	}
+}
+
+impl fmt::Display for Prob {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.0)
+	}
+}
+
+impl<'de> Deserialize<'de> for Prob {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let p = f64::deserialize(deserializer)?;
+		match POLICY.with(|policy| policy.get()) {
+			ProbPolicy::Error => Prob::new(p).ok_or_else(|| D::Error::custom(format!("probability {} is outside of 0.0..=1.0", p))),
+			ProbPolicy::Clamp => Ok(Prob::clamped(p)),
+		}
+	}
+}
@@ -0,0 +1,1516 @@
+//! [Protocol Buffers](https://protobuf.dev/) messages of the [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) model,
+//! compatible with [prost](https://docs.rs/prost) and defined in `proto/jsonnlp.proto`, for exchanging annotations
+//! with gRPC services. The messages convert from the JSON-NLP structs with `From`, and back with `TryFrom`, which
+//! checks probabilities and language tags. Properties in the `extra` maps are kept as JSON strings.
+
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+
+use prost::Message;
+use serde_json::{Map, Value};
+
+use crate::{JsonNlpError, LanguageTag, Prob, RawId, Result};
+
+/// This struct is the Protocol Buffers message of [Meta](crate::Meta).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Meta {
+	#[prost(string, tag = "1")]
+	pub conforms_to: String,
+	#[prost(string, tag = "2")]
+	pub author: String,
+	#[prost(string, tag = "3")]
+	pub created: String,
+	#[prost(string, tag = "4")]
+	pub date: String,
+	#[prost(string, tag = "5")]
+	pub source: String,
+	#[prost(string, tag = "6")]
+	pub language: String,
+	#[prost(string, tag = "7")]
+	pub creator: String,
+	#[prost(string, tag = "8")]
+	pub publisher: String,
+	#[prost(string, tag = "9")]
+	pub title: String,
+	#[prost(string, tag = "10")]
+	pub description: String,
+	#[prost(string, tag = "11")]
+	pub identifier: String,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [TokenFeatures](crate::TokenFeatures).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TokenFeatures {
+	#[prost(bool, optional, tag = "1")]
+	pub overt: Option<bool>,
+	#[prost(bool, optional, tag = "2")]
+	pub stop: Option<bool>,
+	#[prost(bool, optional, tag = "3")]
+	pub alpha: Option<bool>,
+	#[prost(uint32, optional, tag = "4")]
+	pub number: Option<u32>,
+	#[prost(string, tag = "5")]
+	pub gender: String,
+	#[prost(uint32, optional, tag = "6")]
+	pub person: Option<u32>,
+	#[prost(string, tag = "7")]
+	pub tense: String,
+	#[prost(bool, optional, tag = "8")]
+	pub perfect: Option<bool>,
+	#[prost(bool, optional, tag = "9")]
+	pub continuous: Option<bool>,
+	#[prost(bool, optional, tag = "10")]
+	pub progressive: Option<bool>,
+	#[prost(string, tag = "11")]
+	pub case: String,
+	#[prost(bool, optional, tag = "12")]
+	pub human: Option<bool>,
+	#[prost(bool, optional, tag = "13")]
+	pub animate: Option<bool>,
+	#[prost(bool, optional, tag = "14")]
+	pub negated: Option<bool>,
+	#[prost(bool, optional, tag = "15")]
+	pub countable: Option<bool>,
+	#[prost(bool, optional, tag = "16")]
+	pub factive: Option<bool>,
+	#[prost(bool, optional, tag = "17")]
+	pub counterfactive: Option<bool>,
+	#[prost(bool, optional, tag = "18")]
+	pub irregular: Option<bool>,
+	#[prost(bool, optional, tag = "19")]
+	pub phrasal_verb: Option<bool>,
+	#[prost(string, tag = "20")]
+	pub mood: String,
+	#[prost(bool, optional, tag = "21")]
+	pub foreign: Option<bool>,
+	#[prost(bool, optional, tag = "22")]
+	pub space_after: Option<bool>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Token](crate::Token).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Token {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, tag = "2")]
+	pub sentence_id: u64,
+	#[prost(string, tag = "3")]
+	pub text: String,
+	#[prost(string, tag = "4")]
+	pub lemma: String,
+	#[prost(string, tag = "5")]
+	pub xpos: String,
+	#[prost(double, optional, tag = "6")]
+	pub xpos_prob: Option<f64>,
+	#[prost(string, tag = "7")]
+	pub upos: String,
+	#[prost(double, optional, tag = "8")]
+	pub upos_prob: Option<f64>,
+	#[prost(string, tag = "9")]
+	pub entity_iob: String,
+	#[prost(uint64, optional, tag = "10")]
+	pub character_offset_begin: Option<u64>,
+	#[prost(uint64, optional, tag = "11")]
+	pub character_offset_end: Option<u64>,
+	#[prost(string, tag = "12")]
+	pub prop_id: String,
+	#[prost(double, optional, tag = "13")]
+	pub prop_id_prob: Option<f64>,
+	#[prost(uint64, optional, tag = "14")]
+	pub frame_id: Option<u64>,
+	#[prost(double, optional, tag = "15")]
+	pub frame_id_prob: Option<f64>,
+	#[prost(uint64, optional, tag = "16")]
+	pub word_net_id: Option<u64>,
+	#[prost(double, optional, tag = "17")]
+	pub word_net_id_prob: Option<f64>,
+	#[prost(uint64, optional, tag = "18")]
+	pub verb_net_id: Option<u64>,
+	#[prost(double, optional, tag = "19")]
+	pub verb_net_id_prob: Option<f64>,
+	#[prost(string, tag = "20")]
+	pub lang: String,
+	#[prost(message, optional, tag = "21")]
+	pub features: Option<TokenFeatures>,
+	#[prost(string, tag = "22")]
+	pub shape: String,
+	#[prost(string, tag = "23")]
+	pub entity: String,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Sentence](crate::Sentence).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Sentence {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(string, tag = "2")]
+	pub text: String,
+	#[prost(uint64, optional, tag = "3")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "4")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "5")]
+	pub tokens: Vec<u64>,
+	#[prost(uint64, repeated, tag = "6")]
+	pub clauses: Vec<u64>,
+	#[prost(string, tag = "7")]
+	pub r#type: String,
+	#[prost(string, tag = "8")]
+	pub sentiment: String,
+	#[prost(double, optional, tag = "9")]
+	pub sentiment_prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Clause](crate::Clause).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Clause {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, optional, tag = "2")]
+	pub sentence_id: Option<u64>,
+	#[prost(uint64, optional, tag = "3")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "4")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "5")]
+	pub tokens: Vec<u64>,
+	#[prost(bool, optional, tag = "6")]
+	pub main: Option<bool>,
+	#[prost(uint64, optional, tag = "7")]
+	pub gov: Option<u64>,
+	#[prost(uint64, optional, tag = "8")]
+	pub head: Option<u64>,
+	#[prost(bool, optional, tag = "9")]
+	pub neg: Option<bool>,
+	#[prost(string, tag = "10")]
+	pub tense: String,
+	#[prost(string, tag = "11")]
+	pub mood: String,
+	#[prost(bool, optional, tag = "12")]
+	pub perfect: Option<bool>,
+	#[prost(bool, optional, tag = "13")]
+	pub continuous: Option<bool>,
+	#[prost(string, tag = "14")]
+	pub aspect: String,
+	#[prost(string, tag = "15")]
+	pub voice: String,
+	#[prost(string, tag = "16")]
+	pub sentiment: String,
+	#[prost(double, optional, tag = "17")]
+	pub sentiment_prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Dependency](crate::Dependency).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Dependency {
+	#[prost(string, tag = "1")]
+	pub lab: String,
+	#[prost(uint64, tag = "2")]
+	pub gov: u64,
+	#[prost(uint64, tag = "3")]
+	pub dep: u64,
+	#[prost(double, optional, tag = "4")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [DependencyTree](crate::DependencyTree).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DependencyTree {
+	#[prost(uint64, optional, tag = "1")]
+	pub sentence_id: Option<u64>,
+	#[prost(string, tag = "2")]
+	pub style: String,
+	#[prost(message, repeated, tag = "3")]
+	pub dependencies: Vec<Dependency>,
+	#[prost(double, optional, tag = "4")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [CoreferenceRepresentantive](crate::CoreferenceRepresentantive).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CoreferenceRepresentative {
+	#[prost(uint64, repeated, tag = "1")]
+	pub tokens: Vec<u64>,
+	#[prost(uint64, tag = "2")]
+	pub head: u64,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [CoreferenceReferents](crate::CoreferenceReferents).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CoreferenceReferent {
+	#[prost(uint64, repeated, tag = "1")]
+	pub tokens: Vec<u64>,
+	#[prost(uint64, tag = "2")]
+	pub head: u64,
+	#[prost(double, optional, tag = "3")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Coreference](crate::Coreference).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Coreference {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(message, optional, tag = "2")]
+	pub representative: Option<CoreferenceRepresentative>,
+	#[prost(message, repeated, tag = "3")]
+	pub referents: Vec<CoreferenceReferent>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Scope](crate::Scope).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Scope {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, repeated, tag = "2")]
+	pub gov: Vec<u64>,
+	#[prost(uint64, repeated, tag = "3")]
+	pub dep: Vec<u64>,
+	#[prost(uint64, repeated, tag = "4")]
+	pub terminals: Vec<u64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [ConstituentParse](crate::ConstituentParse).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConstituentParse {
+	#[prost(uint64, tag = "1")]
+	pub sentence_id: u64,
+	#[prost(string, tag = "2")]
+	pub r#type: String,
+	#[prost(string, tag = "3")]
+	pub labeled_bracketing: String,
+	#[prost(double, optional, tag = "4")]
+	pub prob: Option<f64>,
+	#[prost(message, repeated, tag = "5")]
+	pub scopes: Vec<Scope>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Expression](crate::Expression).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Expression {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(string, tag = "2")]
+	pub r#type: String,
+	#[prost(uint64, optional, tag = "3")]
+	pub head: Option<u64>,
+	#[prost(string, tag = "4")]
+	pub dependency: String,
+	#[prost(uint64, optional, tag = "5")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "6")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "7")]
+	pub tokens: Vec<u64>,
+	#[prost(double, optional, tag = "8")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Paragraph](crate::Paragraph).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Paragraph {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, optional, tag = "2")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "3")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "4")]
+	pub tokens: Vec<u64>,
+	#[prost(uint64, repeated, tag = "5")]
+	pub sentences: Vec<u64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Attribute](crate::Attribute).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Attribute {
+	#[prost(string, tag = "1")]
+	pub lab: String,
+	#[prost(string, tag = "2")]
+	pub val: String,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Entity](crate::Entity).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Entity {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(string, tag = "2")]
+	pub label: String,
+	#[prost(string, tag = "3")]
+	pub r#type: String,
+	#[prost(string, tag = "4")]
+	pub url: String,
+	#[prost(uint64, optional, tag = "5")]
+	pub head: Option<u64>,
+	#[prost(uint64, optional, tag = "6")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "7")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "8")]
+	pub tokens: Vec<u64>,
+	#[prost(uint64, optional, tag = "9")]
+	pub triple_id: Option<u64>,
+	#[prost(string, tag = "10")]
+	pub sentiment: String,
+	#[prost(double, optional, tag = "11")]
+	pub sentiment_prob: Option<f64>,
+	#[prost(uint64, optional, tag = "12")]
+	pub count: Option<u64>,
+	#[prost(message, repeated, tag = "13")]
+	pub attributes: Vec<Attribute>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Relation](crate::Relation).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Relation {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(string, tag = "2")]
+	pub label: String,
+	#[prost(string, tag = "3")]
+	pub r#type: String,
+	#[prost(string, tag = "4")]
+	pub url: String,
+	#[prost(uint64, optional, tag = "5")]
+	pub head: Option<u64>,
+	#[prost(uint64, optional, tag = "6")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "7")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "8")]
+	pub tokens: Vec<u64>,
+	#[prost(string, tag = "9")]
+	pub sentiment: String,
+	#[prost(double, optional, tag = "10")]
+	pub sentiment_prob: Option<f64>,
+	#[prost(uint64, optional, tag = "11")]
+	pub count: Option<u64>,
+	#[prost(message, repeated, tag = "12")]
+	pub attributes: Vec<Attribute>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Triple](crate::Triple).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Triple {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, optional, tag = "2")]
+	pub from_entity: Option<u64>,
+	#[prost(uint64, optional, tag = "3")]
+	pub to_entity: Option<u64>,
+	#[prost(uint64, optional, tag = "4")]
+	pub rel: Option<u64>,
+	#[prost(uint64, repeated, tag = "5")]
+	pub clause_id: Vec<u64>,
+	#[prost(uint64, repeated, tag = "6")]
+	pub sentence_id: Vec<u64>,
+	#[prost(bool, optional, tag = "7")]
+	pub directional: Option<bool>,
+	#[prost(uint64, optional, tag = "8")]
+	pub event_id: Option<u64>,
+	#[prost(uint64, optional, tag = "9")]
+	pub temp_seq: Option<u64>,
+	#[prost(double, optional, tag = "10")]
+	pub prob: Option<f64>,
+	#[prost(bool, optional, tag = "11")]
+	pub syntactic: Option<bool>,
+	#[prost(bool, optional, tag = "12")]
+	pub implied: Option<bool>,
+	#[prost(bool, optional, tag = "13")]
+	pub presupposed: Option<bool>,
+	#[prost(uint64, optional, tag = "14")]
+	pub count: Option<u64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Argument](crate::Argument).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Argument {
+	#[prost(string, tag = "1")]
+	pub label: String,
+	#[prost(uint64, optional, tag = "2")]
+	pub token_from: Option<u64>,
+	#[prost(uint64, optional, tag = "3")]
+	pub token_to: Option<u64>,
+	#[prost(uint64, repeated, tag = "4")]
+	pub tokens: Vec<u64>,
+	#[prost(double, optional, tag = "5")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Predicate](crate::Predicate).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Predicate {
+	#[prost(uint64, tag = "1")]
+	pub id: u64,
+	#[prost(uint64, optional, tag = "2")]
+	pub sentence_id: Option<u64>,
+	#[prost(uint64, tag = "3")]
+	pub head: u64,
+	#[prost(string, tag = "4")]
+	pub lemma: String,
+	#[prost(string, tag = "5")]
+	pub frame: String,
+	#[prost(message, repeated, tag = "6")]
+	pub arguments: Vec<Argument>,
+	#[prost(double, optional, tag = "7")]
+	pub prob: Option<f64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [AmrNode](crate::AmrNode).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AmrNode {
+	#[prost(string, tag = "1")]
+	pub id: String,
+	#[prost(string, tag = "2")]
+	pub concept: String,
+	#[prost(uint64, repeated, tag = "3")]
+	pub tokens: Vec<u64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [AmrEdge](crate::AmrEdge).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AmrEdge {
+	#[prost(string, tag = "1")]
+	pub source: String,
+	#[prost(string, tag = "2")]
+	pub role: String,
+	#[prost(string, tag = "3")]
+	pub target: String,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [AmrAttribute](crate::AmrAttribute).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AmrAttribute {
+	#[prost(string, tag = "1")]
+	pub source: String,
+	#[prost(string, tag = "2")]
+	pub role: String,
+	#[prost(string, tag = "3")]
+	pub value: String,
+	#[prost(uint64, repeated, tag = "4")]
+	pub tokens: Vec<u64>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [AmrGraph](crate::AmrGraph).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AmrGraph {
+	#[prost(uint64, optional, tag = "1")]
+	pub sentence_id: Option<u64>,
+	#[prost(string, tag = "2")]
+	pub top: String,
+	#[prost(message, repeated, tag = "3")]
+	pub nodes: Vec<AmrNode>,
+	#[prost(message, repeated, tag = "4")]
+	pub edges: Vec<AmrEdge>,
+	#[prost(message, repeated, tag = "5")]
+	pub attributes: Vec<AmrAttribute>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [Document](crate::Document).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Document {
+	#[prost(message, optional, tag = "1")]
+	pub meta: Option<Meta>,
+	#[prost(uint64, tag = "2")]
+	pub id: u64,
+	#[prost(string, tag = "3")]
+	pub text: String,
+	#[prost(message, repeated, tag = "4")]
+	pub token_list: Vec<Token>,
+	#[prost(message, repeated, tag = "5")]
+	pub clauses: Vec<Clause>,
+	#[prost(message, repeated, tag = "6")]
+	pub sentences: Vec<Sentence>,
+	#[prost(message, repeated, tag = "7")]
+	pub paragraphs: Vec<Paragraph>,
+	#[prost(message, repeated, tag = "8")]
+	pub dependency_trees: Vec<DependencyTree>,
+	#[prost(message, repeated, tag = "9")]
+	pub coreferences: Vec<Coreference>,
+	#[prost(message, repeated, tag = "10")]
+	pub constituents: Vec<ConstituentParse>,
+	#[prost(message, repeated, tag = "11")]
+	pub expressions: Vec<Expression>,
+	#[prost(message, repeated, tag = "12")]
+	pub entities: Vec<Entity>,
+	#[prost(message, repeated, tag = "13")]
+	pub relations: Vec<Relation>,
+	#[prost(message, repeated, tag = "14")]
+	pub triples: Vec<Triple>,
+	#[prost(message, repeated, tag = "15")]
+	pub semantic_roles: Vec<Predicate>,
+	#[prost(message, repeated, tag = "16")]
+	pub amr_graphs: Vec<AmrGraph>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// This struct is the Protocol Buffers message of [JSONNLP](crate::JSONNLP).
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct JsonNlp {
+	#[prost(message, optional, tag = "1")]
+	pub meta: Option<Meta>,
+	#[prost(message, repeated, tag = "2")]
+	pub docs: Vec<Document>,
+	#[prost(map = "string, string", tag = "99")]
+	pub extra: HashMap<String, String>,
+}
+
+/// returns the `extra` map with every value encoded as JSON.
+fn extra_to_proto(extra: Map<String, Value>) -> HashMap<String, String> {
+	extra.into_iter().map(|(k, v)| (k, v.to_string())).collect()
+}
+
+/// returns the `extra` map with every value decoded from JSON, or kept as a string if it is no JSON.
+fn extra_from_proto(extra: HashMap<String, String>) -> Map<String, Value> {
+	extra.into_iter().map(|(k, v)| {
+		let value = serde_json::from_str(&v).unwrap_or(Value::String(v));
+		(k, value)
+	}).collect()
+}
+
+/// returns the probability, or a validation error if it is outside of 0.0..=1.0.
+fn prob(p: Option<f64>) -> Result<Option<Prob>> {
+	p.map(|p| Prob::new(p).ok_or_else(|| JsonNlpError::Validation(format!("probability {} is outside of 0.0..=1.0", p)))).transpose()
+}
+
+fn language(tag: String) -> Result<LanguageTag> {
+	if tag.is_empty() {
+		Ok(LanguageTag::default())
+	} else {
+		LanguageTag::new(&tag)
+	}
+}
+
+/// returns the ID of a u64, or a validation error if it does not fit into RawId.
+fn id<T: From<RawId>>(id: u64) -> Result<T> {
+	RawId::try_from(id).map(T::from).map_err(|_| JsonNlpError::Validation(format!("ID {} is out of range", id)))
+}
+
+fn ids<T: From<RawId>>(ids: Vec<u64>) -> Result<Vec<T>> {
+	ids.into_iter().map(id).collect()
+}
+
+fn ids_to_proto<T: Into<u64>>(ids: Vec<T>) -> Vec<u64> {
+	ids.into_iter().map(Into::into).collect()
+}
+
+/// converts all messages of a list.
+fn all<A, B: TryFrom<A, Error = JsonNlpError>>(messages: Vec<A>) -> Result<Vec<B>> {
+	messages.into_iter().map(B::try_from).collect()
+}
+
+impl From<crate::Meta> for Meta {
+	fn from(m: crate::Meta) -> Self {
+		Meta {
+			conforms_to: m.conforms_to,
+			author: m.author,
+			created: m.created,
+			date: m.date,
+			source: m.source,
+			language: m.language.as_str().to_string(),
+			creator: m.creator,
+			publisher: m.publisher,
+			title: m.title,
+			description: m.description,
+			identifier: m.identifier,
+			extra: extra_to_proto(m.extra),
+		}
+	}
+}
+
+impl TryFrom<Meta> for crate::Meta {
+	type Error = JsonNlpError;
+
+	fn try_from(m: Meta) -> Result<Self> {
+		Ok(crate::Meta {
+			conforms_to: m.conforms_to,
+			author: m.author,
+			created: m.created,
+			date: m.date,
+			source: m.source,
+			language: language(m.language)?,
+			creator: m.creator,
+			publisher: m.publisher,
+			title: m.title,
+			description: m.description,
+			identifier: m.identifier,
+			extra: extra_from_proto(m.extra),
+		})
+	}
+}
+
+impl From<crate::TokenFeatures> for TokenFeatures {
+	fn from(f: crate::TokenFeatures) -> Self {
+		TokenFeatures {
+			overt: f.overt,
+			stop: f.stop,
+			alpha: f.alpha,
+			number: f.number.map(u32::from),
+			gender: f.gender,
+			person: f.person.map(u32::from),
+			tense: f.tense,
+			perfect: f.perfect,
+			continuous: f.continuous,
+			progressive: f.progressive,
+			case: f.case,
+			human: f.human,
+			animate: f.animate,
+			negated: f.negated,
+			countable: f.countable,
+			factive: f.factive,
+			counterfactive: f.counterfactive,
+			irregular: f.irregular,
+			phrasal_verb: f.phrasalverb,
+			mood: f.mood,
+			foreign: f.foreign,
+			space_after: f.spaceafter,
+			extra: extra_to_proto(f.extra),
+		}
+	}
+}
+
+impl TryFrom<TokenFeatures> for crate::TokenFeatures {
+	type Error = JsonNlpError;
+
+	fn try_from(f: TokenFeatures) -> Result<Self> {
+		let small = |n: Option<u32>, name: &str| {
+			n.map(|n| u8::try_from(n).map_err(|_| JsonNlpError::Validation(format!("token feature {} {} is out of range", name, n)))).transpose()
+		};
+		Ok(crate::TokenFeatures {
+			overt: f.overt,
+			stop: f.stop,
+			alpha: f.alpha,
+			number: small(f.number, "number")?,
+			gender: f.gender,
+			person: small(f.person, "person")?,
+			tense: f.tense,
+			perfect: f.perfect,
+			continuous: f.continuous,
+			progressive: f.progressive,
+			case: f.case,
+			human: f.human,
+			animate: f.animate,
+			negated: f.negated,
+			countable: f.countable,
+			factive: f.factive,
+			counterfactive: f.counterfactive,
+			irregular: f.irregular,
+			phrasalverb: f.phrasal_verb,
+			mood: f.mood,
+			foreign: f.foreign,
+			spaceafter: f.space_after,
+			extra: extra_from_proto(f.extra),
+		})
+	}
+}
+
+impl From<crate::Token> for Token {
+	fn from(t: crate::Token) -> Self {
+		Token {
+			id: t.id.into(),
+			sentence_id: t.sentence_id.into(),
+			text: t.text,
+			lemma: t.lemma,
+			xpos: t.xpos,
+			xpos_prob: t.xpos_prob.map(Prob::get),
+			upos: t.upos,
+			upos_prob: t.upos_prob.map(Prob::get),
+			entity_iob: t.entity_iob,
+			character_offset_begin: t.char_offset_begin,
+			character_offset_end: t.char_offset_end,
+			prop_id: t.prop_id,
+			prop_id_prob: t.prop_id_prob.map(Prob::get),
+			frame_id: t.frame_id,
+			frame_id_prob: t.frame_id_prob.map(Prob::get),
+			word_net_id: t.wordnet_id,
+			word_net_id_prob: t.wordnet_id_prob.map(Prob::get),
+			verb_net_id: t.verbnet_id,
+			verb_net_id_prob: t.verbnet_id_prob.map(Prob::get),
+			lang: t.lang.as_str().to_string(),
+			features: t.features.map(|f| (*f).into()),
+			shape: t.shape,
+			entity: t.entity,
+			extra: extra_to_proto(t.extra),
+		}
+	}
+}
+
+impl TryFrom<Token> for crate::Token {
+	type Error = JsonNlpError;
+
+	fn try_from(t: Token) -> Result<Self> {
+		Ok(crate::Token {
+			id: id(t.id)?,
+			sentence_id: id(t.sentence_id)?,
+			text: t.text,
+			lemma: t.lemma,
+			xpos: t.xpos,
+			xpos_prob: prob(t.xpos_prob)?,
+			upos: t.upos,
+			upos_prob: prob(t.upos_prob)?,
+			entity_iob: t.entity_iob,
+			char_offset_begin: t.character_offset_begin,
+			char_offset_end: t.character_offset_end,
+			prop_id: t.prop_id,
+			prop_id_prob: prob(t.prop_id_prob)?,
+			frame_id: t.frame_id,
+			frame_id_prob: prob(t.frame_id_prob)?,
+			wordnet_id: t.word_net_id,
+			wordnet_id_prob: prob(t.word_net_id_prob)?,
+			verbnet_id: t.verb_net_id,
+			verbnet_id_prob: prob(t.verb_net_id_prob)?,
+			lang: language(t.lang)?,
+			features: t.features.map(crate::TokenFeatures::try_from).transpose()?.and_then(crate::TokenFeatures::boxed),
+			shape: t.shape,
+			entity: t.entity,
+			extra: extra_from_proto(t.extra),
+		})
+	}
+}
+
+impl From<crate::Sentence> for Sentence {
+	fn from(s: crate::Sentence) -> Self {
+		Sentence {
+			id: s.id.into(),
+			text: s.text,
+			token_from: s.token_from.map(u64::from),
+			token_to: s.token_to.map(u64::from),
+			tokens: ids_to_proto(s.tokens),
+			clauses: ids_to_proto(s.clauses),
+			r#type: s.stype,
+			sentiment: s.sentiment,
+			sentiment_prob: s.sentiment_prob.map(Prob::get),
+			extra: extra_to_proto(s.extra),
+		}
+	}
+}
+
+impl TryFrom<Sentence> for crate::Sentence {
+	type Error = JsonNlpError;
+
+	fn try_from(s: Sentence) -> Result<Self> {
+		Ok(crate::Sentence {
+			id: id(s.id)?,
+			text: s.text,
+			token_from: s.token_from.map(id).transpose()?,
+			token_to: s.token_to.map(id).transpose()?,
+			tokens: ids(s.tokens)?,
+			clauses: ids(s.clauses)?,
+			stype: s.r#type,
+			sentiment: s.sentiment,
+			sentiment_prob: prob(s.sentiment_prob)?,
+			extra: extra_from_proto(s.extra),
+		})
+	}
+}
+
+impl From<crate::Clause> for Clause {
+	fn from(c: crate::Clause) -> Self {
+		Clause {
+			id: c.id.into(),
+			sentence_id: c.sentence_id.map(u64::from),
+			token_from: c.token_from.map(u64::from),
+			token_to: c.token_to.map(u64::from),
+			tokens: ids_to_proto(c.tokens),
+			main: c.main,
+			gov: c.gov.map(u64::from),
+			head: c.head.map(u64::from),
+			neg: c.neg,
+			tense: c.tense,
+			mood: c.mood,
+			perfect: c.perfect,
+			continuous: c.continuous,
+			aspect: c.aspect,
+			voice: c.voice,
+			sentiment: c.sentiment,
+			sentiment_prob: c.sentiment_prob.map(Prob::get),
+			extra: extra_to_proto(c.extra),
+		}
+	}
+}
+
+impl TryFrom<Clause> for crate::Clause {
+	type Error = JsonNlpError;
+
+	fn try_from(c: Clause) -> Result<Self> {
+		Ok(crate::Clause {
+			id: id(c.id)?,
+			sentence_id: c.sentence_id.map(id).transpose()?,
+			token_from: c.token_from.map(id).transpose()?,
+			token_to: c.token_to.map(id).transpose()?,
+			tokens: ids(c.tokens)?,
+			main: c.main,
+			gov: c.gov.map(id).transpose()?,
+			head: c.head.map(id).transpose()?,
+			neg: c.neg,
+			tense: c.tense,
+			mood: c.mood,
+			perfect: c.perfect,
+			continuous: c.continuous,
+			aspect: c.aspect,
+			voice: c.voice,
+			sentiment: c.sentiment,
+			sentiment_prob: prob(c.sentiment_prob)?,
+			extra: extra_from_proto(c.extra),
+		})
+	}
+}
+
+impl From<crate::Dependency> for Dependency {
+	fn from(d: crate::Dependency) -> Self {
+		Dependency {
+			lab: d.lab,
+			gov: d.gov.into(),
+			dep: d.dep.into(),
+			prob: d.prob.map(Prob::get),
+			extra: extra_to_proto(d.extra),
+		}
+	}
+}
+
+impl TryFrom<Dependency> for crate::Dependency {
+	type Error = JsonNlpError;
+
+	fn try_from(d: Dependency) -> Result<Self> {
+		Ok(crate::Dependency {
+			lab: d.lab,
+			gov: id(d.gov)?,
+			dep: id(d.dep)?,
+			prob: prob(d.prob)?,
+			extra: extra_from_proto(d.extra),
+		})
+	}
+}
+
+impl From<crate::DependencyTree> for DependencyTree {
+	fn from(d: crate::DependencyTree) -> Self {
+		DependencyTree {
+			sentence_id: d.sentence_id.map(u64::from),
+			style: d.style,
+			dependencies: d.dependencies.into_iter().map(Into::into).collect(),
+			prob: d.prob.map(Prob::get),
+			extra: extra_to_proto(d.extra),
+		}
+	}
+}
+
+impl TryFrom<DependencyTree> for crate::DependencyTree {
+	type Error = JsonNlpError;
+
+	fn try_from(d: DependencyTree) -> Result<Self> {
+		Ok(crate::DependencyTree {
+			sentence_id: d.sentence_id.map(id).transpose()?,
+			style: d.style,
+			dependencies: all(d.dependencies)?,
+			prob: prob(d.prob)?,
+			extra: extra_from_proto(d.extra),
+		})
+	}
+}
+
+impl From<crate::CoreferenceRepresentantive> for CoreferenceRepresentative {
+	fn from(r: crate::CoreferenceRepresentantive) -> Self {
+		CoreferenceRepresentative {
+			tokens: ids_to_proto(r.tokens),
+			head: r.head.into(),
+			extra: extra_to_proto(r.extra),
+		}
+	}
+}
+
+impl TryFrom<CoreferenceRepresentative> for crate::CoreferenceRepresentantive {
+	type Error = JsonNlpError;
+
+	fn try_from(r: CoreferenceRepresentative) -> Result<Self> {
+		Ok(crate::CoreferenceRepresentantive {
+			tokens: ids(r.tokens)?,
+			head: id(r.head)?,
+			extra: extra_from_proto(r.extra),
+		})
+	}
+}
+
+impl From<crate::CoreferenceReferents> for CoreferenceReferent {
+	fn from(r: crate::CoreferenceReferents) -> Self {
+		CoreferenceReferent {
+			tokens: ids_to_proto(r.tokens),
+			head: r.head.into(),
+			prob: r.prob.map(Prob::get),
+			extra: extra_to_proto(r.extra),
+		}
+	}
+}
+
+impl TryFrom<CoreferenceReferent> for crate::CoreferenceReferents {
+	type Error = JsonNlpError;
+
+	fn try_from(r: CoreferenceReferent) -> Result<Self> {
+		Ok(crate::CoreferenceReferents {
+			tokens: ids(r.tokens)?,
+			head: id(r.head)?,
+			prob: prob(r.prob)?,
+			extra: extra_from_proto(r.extra),
+		})
+	}
+}
+
+impl From<crate::Coreference> for Coreference {
+	fn from(c: crate::Coreference) -> Self {
+		Coreference {
+			id: c.id.into(),
+			representative: Some(c.representative.into()),
+			referents: c.referents.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(c.extra),
+		}
+	}
+}
+
+impl TryFrom<Coreference> for crate::Coreference {
+	type Error = JsonNlpError;
+
+	fn try_from(c: Coreference) -> Result<Self> {
+		Ok(crate::Coreference {
+			id: id(c.id)?,
+			representative: c.representative.map(TryInto::try_into).transpose()?.unwrap_or_default(),
+			referents: all(c.referents)?,
+			extra: extra_from_proto(c.extra),
+		})
+	}
+}
+
+impl From<crate::Scope> for Scope {
+	fn from(s: crate::Scope) -> Self {
+		Scope {
+			id: s.id,
+			gov: ids_to_proto(s.gov),
+			dep: ids_to_proto(s.dep),
+			terminals: ids_to_proto(s.terminals),
+			extra: extra_to_proto(s.extra),
+		}
+	}
+}
+
+impl TryFrom<Scope> for crate::Scope {
+	type Error = JsonNlpError;
+
+	fn try_from(s: Scope) -> Result<Self> {
+		Ok(crate::Scope {
+			id: s.id,
+			gov: ids(s.gov)?,
+			dep: ids(s.dep)?,
+			terminals: ids(s.terminals)?,
+			extra: extra_from_proto(s.extra),
+		})
+	}
+}
+
+impl From<crate::ConstituentParse> for ConstituentParse {
+	fn from(c: crate::ConstituentParse) -> Self {
+		ConstituentParse {
+			sentence_id: c.sentence_id.into(),
+			r#type: c.ctype,
+			labeled_bracketing: c.labeled_bracketing,
+			prob: c.prob.map(Prob::get),
+			scopes: c.scopes.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(c.extra),
+		}
+	}
+}
+
+impl TryFrom<ConstituentParse> for crate::ConstituentParse {
+	type Error = JsonNlpError;
+
+	fn try_from(c: ConstituentParse) -> Result<Self> {
+		Ok(crate::ConstituentParse {
+			sentence_id: id(c.sentence_id)?,
+			ctype: c.r#type,
+			labeled_bracketing: c.labeled_bracketing,
+			prob: prob(c.prob)?,
+			scopes: all(c.scopes)?,
+			extra: extra_from_proto(c.extra),
+		})
+	}
+}
+
+impl From<crate::Expression> for Expression {
+	fn from(e: crate::Expression) -> Self {
+		Expression {
+			id: e.id.into(),
+			r#type: e.etype,
+			head: e.head.map(u64::from),
+			dependency: e.dependency,
+			token_from: e.token_from.map(u64::from),
+			token_to: e.token_to.map(u64::from),
+			tokens: ids_to_proto(e.tokens),
+			prob: e.prob.map(Prob::get),
+			extra: extra_to_proto(e.extra),
+		}
+	}
+}
+
+impl TryFrom<Expression> for crate::Expression {
+	type Error = JsonNlpError;
+
+	fn try_from(e: Expression) -> Result<Self> {
+		Ok(crate::Expression {
+			id: id(e.id)?,
+			etype: e.r#type,
+			head: e.head.map(id).transpose()?,
+			dependency: e.dependency,
+			token_from: e.token_from.map(id).transpose()?,
+			token_to: e.token_to.map(id).transpose()?,
+			tokens: ids(e.tokens)?,
+			prob: prob(e.prob)?,
+			extra: extra_from_proto(e.extra),
+		})
+	}
+}
+
+impl From<crate::Paragraph> for Paragraph {
+	fn from(p: crate::Paragraph) -> Self {
+		Paragraph {
+			id: p.id.into(),
+			token_from: p.token_from.map(u64::from),
+			token_to: p.token_to.map(u64::from),
+			tokens: ids_to_proto(p.tokens),
+			sentences: ids_to_proto(p.sentences),
+			extra: extra_to_proto(p.extra),
+		}
+	}
+}
+
+impl TryFrom<Paragraph> for crate::Paragraph {
+	type Error = JsonNlpError;
+
+	fn try_from(p: Paragraph) -> Result<Self> {
+		Ok(crate::Paragraph {
+			id: id(p.id)?,
+			token_from: p.token_from.map(id).transpose()?,
+			token_to: p.token_to.map(id).transpose()?,
+			tokens: ids(p.tokens)?,
+			sentences: ids(p.sentences)?,
+			extra: extra_from_proto(p.extra),
+		})
+	}
+}
+
+impl From<crate::Attribute> for Attribute {
+	fn from(a: crate::Attribute) -> Self {
+		Attribute { lab: a.lab, val: a.val, extra: extra_to_proto(a.extra) }
+	}
+}
+
+impl TryFrom<Attribute> for crate::Attribute {
+	type Error = JsonNlpError;
+
+	fn try_from(a: Attribute) -> Result<Self> {
+		Ok(crate::Attribute { lab: a.lab, val: a.val, extra: extra_from_proto(a.extra) })
+	}
+}
+
+impl From<crate::Entity> for Entity {
+	fn from(e: crate::Entity) -> Self {
+		Entity {
+			id: e.id.into(),
+			label: e.label,
+			r#type: e.etype,
+			url: e.url,
+			head: e.head.map(u64::from),
+			token_from: e.token_from.map(u64::from),
+			token_to: e.token_to.map(u64::from),
+			tokens: ids_to_proto(e.tokens),
+			triple_id: e.triple_id.map(u64::from),
+			sentiment: e.sentiment,
+			sentiment_prob: e.sentiment_prob.map(Prob::get),
+			count: e.count,
+			attributes: e.attributes.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(e.extra),
+		}
+	}
+}
+
+impl TryFrom<Entity> for crate::Entity {
+	type Error = JsonNlpError;
+
+	fn try_from(e: Entity) -> Result<Self> {
+		Ok(crate::Entity {
+			id: id(e.id)?,
+			label: e.label,
+			etype: e.r#type,
+			url: e.url,
+			head: e.head.map(id).transpose()?,
+			token_from: e.token_from.map(id).transpose()?,
+			token_to: e.token_to.map(id).transpose()?,
+			tokens: ids(e.tokens)?,
+			triple_id: e.triple_id.map(id).transpose()?,
+			sentiment: e.sentiment,
+			sentiment_prob: prob(e.sentiment_prob)?,
+			count: e.count,
+			attributes: all(e.attributes)?,
+			extra: extra_from_proto(e.extra),
+		})
+	}
+}
+
+impl From<crate::Relation> for Relation {
+	fn from(r: crate::Relation) -> Self {
+		Relation {
+			id: r.id.into(),
+			label: r.label,
+			r#type: r.rtype,
+			url: r.url,
+			head: r.head.map(u64::from),
+			token_from: r.token_from.map(u64::from),
+			token_to: r.token_to.map(u64::from),
+			tokens: ids_to_proto(r.tokens),
+			sentiment: r.sentiment,
+			sentiment_prob: r.sentiment_prob.map(Prob::get),
+			count: r.count,
+			attributes: r.attributes.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(r.extra),
+		}
+	}
+}
+
+impl TryFrom<Relation> for crate::Relation {
+	type Error = JsonNlpError;
+
+	fn try_from(r: Relation) -> Result<Self> {
+		Ok(crate::Relation {
+			id: id(r.id)?,
+			label: r.label,
+			rtype: r.r#type,
+			url: r.url,
+			head: r.head.map(id).transpose()?,
+			token_from: r.token_from.map(id).transpose()?,
+			token_to: r.token_to.map(id).transpose()?,
+			tokens: ids(r.tokens)?,
+			sentiment: r.sentiment,
+			sentiment_prob: prob(r.sentiment_prob)?,
+			count: r.count,
+			attributes: all(r.attributes)?,
+			extra: extra_from_proto(r.extra),
+		})
+	}
+}
+
+impl From<crate::Triple> for Triple {
+	fn from(t: crate::Triple) -> Self {
+		Triple {
+			id: t.id.into(),
+			from_entity: t.from_entity.map(u64::from),
+			to_entity: t.to_entity.map(u64::from),
+			rel: t.rel.map(u64::from),
+			clause_id: ids_to_proto(t.clause_id),
+			sentence_id: ids_to_proto(t.sentence_id),
+			directional: t.directional,
+			event_id: t.event_id,
+			temp_seq: t.temp_seq,
+			prob: t.prob.map(Prob::get),
+			syntactic: t.syntactic,
+			implied: t.implied,
+			presupposed: t.presupposed,
+			count: t.count,
+			extra: extra_to_proto(t.extra),
+		}
+	}
+}
+
+impl TryFrom<Triple> for crate::Triple {
+	type Error = JsonNlpError;
+
+	fn try_from(t: Triple) -> Result<Self> {
+		Ok(crate::Triple {
+			id: id(t.id)?,
+			from_entity: t.from_entity.map(id).transpose()?,
+			to_entity: t.to_entity.map(id).transpose()?,
+			rel: t.rel.map(id).transpose()?,
+			clause_id: ids(t.clause_id)?,
+			sentence_id: ids(t.sentence_id)?,
+			directional: t.directional,
+			event_id: t.event_id,
+			temp_seq: t.temp_seq,
+			prob: prob(t.prob)?,
+			syntactic: t.syntactic,
+			implied: t.implied,
+			presupposed: t.presupposed,
+			count: t.count,
+			extra: extra_from_proto(t.extra),
+		})
+	}
+}
+
+impl From<crate::Argument> for Argument {
+	fn from(a: crate::Argument) -> Self {
+		Argument {
+			label: a.label,
+			token_from: a.token_from.map(u64::from),
+			token_to: a.token_to.map(u64::from),
+			tokens: ids_to_proto(a.tokens),
+			prob: a.prob.map(Prob::get),
+			extra: extra_to_proto(a.extra),
+		}
+	}
+}
+
+impl TryFrom<Argument> for crate::Argument {
+	type Error = JsonNlpError;
+
+	fn try_from(a: Argument) -> Result<Self> {
+		Ok(crate::Argument {
+			label: a.label,
+			token_from: a.token_from.map(id).transpose()?,
+			token_to: a.token_to.map(id).transpose()?,
+			tokens: ids(a.tokens)?,
+			prob: prob(a.prob)?,
+			extra: extra_from_proto(a.extra),
+		})
+	}
+}
+
+impl From<crate::Predicate> for Predicate {
+	fn from(p: crate::Predicate) -> Self {
+		Predicate {
+			id: p.id.into(),
+			sentence_id: p.sentence_id.map(u64::from),
+			head: p.head.into(),
+			lemma: p.lemma,
+			frame: p.frame,
+			arguments: p.arguments.into_iter().map(Into::into).collect(),
+			prob: p.prob.map(Prob::get),
+			extra: extra_to_proto(p.extra),
+		}
+	}
+}
+
+impl TryFrom<Predicate> for crate::Predicate {
+	type Error = JsonNlpError;
+
+	fn try_from(p: Predicate) -> Result<Self> {
+		Ok(crate::Predicate {
+			id: id(p.id)?,
+			sentence_id: p.sentence_id.map(id).transpose()?,
+			head: id(p.head)?,
+			lemma: p.lemma,
+			frame: p.frame,
+			arguments: all(p.arguments)?,
+			prob: prob(p.prob)?,
+			extra: extra_from_proto(p.extra),
+		})
+	}
+}
+
+impl From<crate::AmrNode> for AmrNode {
+	fn from(n: crate::AmrNode) -> Self {
+		AmrNode { id: n.id, concept: n.concept, tokens: ids_to_proto(n.tokens), extra: extra_to_proto(n.extra) }
+	}
+}
+
+impl TryFrom<AmrNode> for crate::AmrNode {
+	type Error = JsonNlpError;
+
+	fn try_from(n: AmrNode) -> Result<Self> {
+		Ok(crate::AmrNode { id: n.id, concept: n.concept, tokens: ids(n.tokens)?, extra: extra_from_proto(n.extra) })
+	}
+}
+
+impl From<crate::AmrEdge> for AmrEdge {
+	fn from(e: crate::AmrEdge) -> Self {
+		AmrEdge { source: e.source, role: e.role, target: e.target, extra: extra_to_proto(e.extra) }
+	}
+}
+
+impl TryFrom<AmrEdge> for crate::AmrEdge {
+	type Error = JsonNlpError;
+
+	fn try_from(e: AmrEdge) -> Result<Self> {
+		Ok(crate::AmrEdge { source: e.source, role: e.role, target: e.target, extra: extra_from_proto(e.extra) })
+	}
+}
+
+impl From<crate::AmrAttribute> for AmrAttribute {
+	fn from(a: crate::AmrAttribute) -> Self {
+		AmrAttribute {
+			source: a.source,
+			role: a.role,
+			value: a.value,
+			tokens: ids_to_proto(a.tokens),
+			extra: extra_to_proto(a.extra),
+		}
+	}
+}
+
+impl TryFrom<AmrAttribute> for crate::AmrAttribute {
+	type Error = JsonNlpError;
+
+	fn try_from(a: AmrAttribute) -> Result<Self> {
+		Ok(crate::AmrAttribute {
+			source: a.source,
+			role: a.role,
+			value: a.value,
+			tokens: ids(a.tokens)?,
+			extra: extra_from_proto(a.extra),
+		})
+	}
+}
+
+impl From<crate::AmrGraph> for AmrGraph {
+	fn from(g: crate::AmrGraph) -> Self {
+		AmrGraph {
+			sentence_id: g.sentence_id.map(u64::from),
+			top: g.top,
+			nodes: g.nodes.into_iter().map(Into::into).collect(),
+			edges: g.edges.into_iter().map(Into::into).collect(),
+			attributes: g.attributes.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(g.extra),
+		}
+	}
+}
+
+impl TryFrom<AmrGraph> for crate::AmrGraph {
+	type Error = JsonNlpError;
+
+	fn try_from(g: AmrGraph) -> Result<Self> {
+		Ok(crate::AmrGraph {
+			sentence_id: g.sentence_id.map(id).transpose()?,
+			top: g.top,
+			nodes: all(g.nodes)?,
+			edges: all(g.edges)?,
+			attributes: all(g.attributes)?,
+			extra: extra_from_proto(g.extra),
+		})
+	}
+}
+
+impl From<crate::Document> for Document {
+	fn from(d: crate::Document) -> Self {
+		Document {
+			meta: Some(d.meta.into()),
+			id: d.id.into(),
+			text: d.text,
+			token_list: d.token_list.into_iter().map(Into::into).collect(),
+			clauses: d.clauses.into_iter().map(Into::into).collect(),
+			sentences: d.sentences.into_iter().map(Into::into).collect(),
+			paragraphs: d.paragraphs.into_iter().map(Into::into).collect(),
+			dependency_trees: d.dependency_trees.into_iter().map(Into::into).collect(),
+			coreferences: d.coreferences.into_iter().map(Into::into).collect(),
+			constituents: d.constituents.into_iter().map(Into::into).collect(),
+			expressions: d.expressions.into_iter().map(Into::into).collect(),
+			entities: d.entities.into_iter().map(Into::into).collect(),
+			relations: d.relations.into_iter().map(Into::into).collect(),
+			triples: d.triples.into_iter().map(Into::into).collect(),
+			semantic_roles: d.semantic_roles.into_iter().map(Into::into).collect(),
+			amr_graphs: d.amr_graphs.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(d.extra),
+		}
+	}
+}
+
+impl TryFrom<Document> for crate::Document {
+	type Error = JsonNlpError;
+
+	fn try_from(d: Document) -> Result<Self> {
+		Ok(crate::Document {
+			meta: d.meta.map(TryInto::try_into).transpose()?.unwrap_or_default(),
+			id: id(d.id)?,
+			text: d.text,
+			token_list: all(d.token_list)?,
+			clauses: all(d.clauses)?,
+			sentences: all(d.sentences)?,
+			paragraphs: all(d.paragraphs)?,
+			dependency_trees: all(d.dependency_trees)?,
+			coreferences: all(d.coreferences)?,
+			constituents: all(d.constituents)?,
+			expressions: all(d.expressions)?,
+			entities: all(d.entities)?,
+			relations: all(d.relations)?,
+			triples: all(d.triples)?,
+			semantic_roles: all(d.semantic_roles)?,
+			amr_graphs: all(d.amr_graphs)?,
+			extra: extra_from_proto(d.extra),
+		})
+	}
+}
+
+impl From<crate::JSONNLP> for JsonNlp {
+	fn from(j: crate::JSONNLP) -> Self {
+		JsonNlp {
+			meta: Some(j.meta.into()),
+			docs: j.docs.into_iter().map(Into::into).collect(),
+			extra: extra_to_proto(j.extra),
+		}
+	}
+}
+
+impl TryFrom<JsonNlp> for crate::JSONNLP {
+	type Error = JsonNlpError;
+
+	fn try_from(j: JsonNlp) -> Result<Self> {
+		Ok(crate::JSONNLP {
+			meta: j.meta.map(TryInto::try_into).transpose()?.unwrap_or_default(),
+			docs: all(j.docs)?,
+			extra: extra_from_proto(j.extra),
+		})
+	}
+}
+
+/// This function encodes JSON-NLP as a Protocol Buffers `jsonnlp.v1.JsonNlp` message.
+pub fn to_protobuf(j: &crate::JSONNLP) -> Vec<u8> {
+	JsonNlp::from(j.clone()).encode_to_vec()
+}
+
+/// This function decodes JSON-NLP from a Protocol Buffers `jsonnlp.v1.JsonNlp` message.
+pub fn from_protobuf(bytes: &[u8]) -> Result<crate::JSONNLP> {
+	let message = JsonNlp::decode(bytes).map_err(|e| JsonNlpError::Format(format!("Protocol Buffers: {}", e)))?;
+	message.try_into()
+}
@@ -0,0 +1,333 @@
+//! Python bindings built with [PyO3](https://pyo3.rs/), for an extension module `jsonnlp` with the classes
+//! `JSONNLP`, `Document`, and `Token`, validation, and the converters of common NLP tools. The extension is
+//! built by [maturin](https://www.maturin.rs/) with the settings in `pyproject.toml`. Layers without a class are
+//! returned as lists of dicts, and errors are raised as `jsonnlp.JsonNlpError`.
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use pyo3::IntoPyObjectExt;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{Document, Token, JSONNLP};
+
+create_exception!(jsonnlp, JsonNlpError, PyException, "An error raised by JSON-NLP parsing, conversion, or I/O.");
+
+fn error(e: crate::JsonNlpError) -> PyErr {
+	JsonNlpError::new_err(e.to_string())
+}
+
+/// returns the Python object of a JSON value.
+fn to_py<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+	match value {
+		Value::Null => Ok(py.None().into_bound(py)),
+		Value::Bool(b) => b.into_bound_py_any(py),
+		Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+			(Some(u), _) => u.into_bound_py_any(py),
+			(None, Some(i)) => i.into_bound_py_any(py),
+			_ => n.as_f64().unwrap_or(f64::NAN).into_bound_py_any(py),
+		},
+		Value::String(s) => s.into_bound_py_any(py),
+		Value::Array(items) => {
+			let list = PyList::empty(py);
+			for item in items {
+				list.append(to_py(py, item)?)?;
+			}
+			Ok(list.into_any())
+		}
+		Value::Object(obj) => {
+			let dict = PyDict::new(py);
+			for (key, item) in obj {
+				dict.set_item(key, to_py(py, item)?)?;
+			}
+			Ok(dict.into_any())
+		}
+	}
+}
+
+/// returns a value as a Python dict or list of dicts.
+fn to_dict<'py, T: Serialize + ?Sized>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+	let value = serde_json::to_value(value).map_err(|e| error(crate::JsonNlpError::Serialize(e)))?;
+	to_py(py, &value)
+}
+
+/// returns the JSON of a value, pretty-printed with the given number of spaces.
+fn to_json<T: Serialize + ?Sized>(value: &T, indent: Option<usize>) -> PyResult<String> {
+	let json = match indent {
+		Some(n) => {
+			let indent = " ".repeat(n);
+			let mut buf = Vec::new();
+			let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+			let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+			value.serialize(&mut ser).map(|_| String::from_utf8(buf).expect("serde_json produced invalid UTF-8"))
+		}
+		None => serde_json::to_string(value),
+	};
+	json.map_err(|e| error(crate::JsonNlpError::Serialize(e)))
+}
+
+/// This struct contains a JSON-NLP collection, exported to Python as `JSONNLP`.
+#[pyclass(name = "JSONNLP", module = "jsonnlp")]
+#[derive(Default, Clone)]
+pub struct PyJsonNlp {
+	pub inner: JSONNLP,
+}
+
+#[pymethods]
+impl PyJsonNlp {
+	/// returns an empty collection.
+	#[new]
+	fn new() -> Self {
+		PyJsonNlp::default()
+	}
+
+	/// returns the metadata as a dict.
+	#[getter]
+	fn meta<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner.meta)
+	}
+
+	/// returns copies of the documents.
+	#[getter]
+	fn docs(&self) -> Vec<PyDocument> {
+		self.inner.docs.iter().cloned().map(|inner| PyDocument { inner }).collect()
+	}
+
+	/// appends a copy of a document.
+	fn append(&mut self, doc: &PyDocument) {
+		self.inner.docs.push(doc.inner.clone());
+	}
+
+	/// returns the problems found by validation, or an empty list if the collection is valid.
+	fn validate(&self) -> Vec<String> {
+		self.inner.validate().issues.iter().map(ToString::to_string).collect()
+	}
+
+	/// returns the collection as JSON, pretty-printed if an indentation is given.
+	#[pyo3(signature = (indent = None))]
+	fn to_json(&self, indent: Option<usize>) -> PyResult<String> {
+		to_json(&self.inner, indent)
+	}
+
+	/// returns the collection as a dict.
+	fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner)
+	}
+
+	fn __len__(&self) -> usize {
+		self.inner.docs.len()
+	}
+
+	fn __repr__(&self) -> String {
+		format!("JSONNLP(docs={})", self.inner.docs.len())
+	}
+}
+
+/// This struct contains a document, exported to Python as `Document`.
+#[pyclass(name = "Document", module = "jsonnlp")]
+#[derive(Default, Clone)]
+pub struct PyDocument {
+	pub inner: Document,
+}
+
+#[pymethods]
+impl PyDocument {
+	#[getter]
+	fn id(&self) -> u64 {
+		self.inner.id.into()
+	}
+
+	#[getter]
+	fn text(&self) -> &str {
+		&self.inner.text
+	}
+
+	/// returns the metadata as a dict.
+	#[getter]
+	fn meta<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner.meta)
+	}
+
+	/// returns copies of the tokens.
+	#[getter]
+	fn tokens(&self) -> Vec<PyToken> {
+		self.inner.token_list.iter().cloned().map(|inner| PyToken { inner }).collect()
+	}
+
+	/// returns the sentences as a list of dicts.
+	#[getter]
+	fn sentences<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner.sentences)
+	}
+
+	/// returns the entities as a list of dicts.
+	#[getter]
+	fn entities<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner.entities)
+	}
+
+	/// returns the dependency trees as a list of dicts.
+	#[getter]
+	fn dependency_trees<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner.dependency_trees)
+	}
+
+	/// returns the problems found by validation, or an empty list if the document is valid.
+	fn validate(&self) -> Vec<String> {
+		self.inner.validate().issues.iter().map(ToString::to_string).collect()
+	}
+
+	/// returns the document as CoNLL-U.
+	fn to_conllu(&self) -> String {
+		crate::to_conllu(&self.inner)
+	}
+
+	/// returns the document as JSON, pretty-printed if an indentation is given.
+	#[pyo3(signature = (indent = None))]
+	fn to_json(&self, indent: Option<usize>) -> PyResult<String> {
+		to_json(&self.inner, indent)
+	}
+
+	/// returns the document as a dict.
+	fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner)
+	}
+
+	fn __len__(&self) -> usize {
+		self.inner.token_list.len()
+	}
+
+	fn __repr__(&self) -> String {
+		format!("Document(id={}, tokens={})", self.inner.id, self.inner.token_list.len())
+	}
+}
+
+/// This struct contains a token, exported to Python as `Token`.
+#[pyclass(name = "Token", module = "jsonnlp")]
+#[derive(Default, Clone)]
+pub struct PyToken {
+	pub inner: Token,
+}
+
+#[pymethods]
+impl PyToken {
+	#[getter]
+	fn id(&self) -> u64 {
+		self.inner.id.into()
+	}
+
+	#[getter]
+	fn sentence_id(&self) -> u64 {
+		self.inner.sentence_id.into()
+	}
+
+	#[getter]
+	fn text(&self) -> &str {
+		&self.inner.text
+	}
+
+	#[getter]
+	fn lemma(&self) -> &str {
+		&self.inner.lemma
+	}
+
+	#[getter]
+	fn xpos(&self) -> &str {
+		&self.inner.xpos
+	}
+
+	#[getter]
+	fn upos(&self) -> &str {
+		&self.inner.upos
+	}
+
+	#[getter]
+	fn entity_iob(&self) -> &str {
+		&self.inner.entity_iob
+	}
+
+	#[getter]
+	fn entity(&self) -> &str {
+		&self.inner.entity
+	}
+
+	#[getter]
+	fn char_offset_begin(&self) -> Option<u64> {
+		self.inner.char_offset_begin
+	}
+
+	#[getter]
+	fn char_offset_end(&self) -> Option<u64> {
+		self.inner.char_offset_end
+	}
+
+	/// returns the features as a dict.
+	#[getter]
+	fn features<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, self.inner.features())
+	}
+
+	/// returns the token as a dict.
+	fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+		to_dict(py, &self.inner)
+	}
+
+	fn __repr__(&self) -> String {
+		format!("Token(id={}, text={:?})", self.inner.id, self.inner.text)
+	}
+}
+
+/// parses a string containing JSON-NLP.
+#[pyfunction]
+fn from_string(json: &str) -> PyResult<PyJsonNlp> {
+	crate::from_string(json).map(|inner| PyJsonNlp { inner }).map_err(error)
+}
+
+/// reads a JSON-NLP file.
+#[pyfunction]
+fn from_file(path: &str) -> PyResult<PyJsonNlp> {
+	crate::from_file(path).map(|inner| PyJsonNlp { inner }).map_err(error)
+}
+
+/// converts CoNLL-U to a collection with one document.
+#[pyfunction]
+fn from_conllu(input: &str) -> PyResult<PyJsonNlp> {
+	crate::from_conllu(input).map(|inner| PyJsonNlp { inner }).map_err(error)
+}
+
+/// converts the JSON of a spaCy Doc to a document.
+#[pyfunction]
+fn from_spacy(json: &str) -> PyResult<PyDocument> {
+	crate::from_spacy_json(json).map(|inner| PyDocument { inner }).map_err(error)
+}
+
+/// converts the JSON of a Stanza Document to a document.
+#[pyfunction]
+fn from_stanza(json: &str) -> PyResult<PyDocument> {
+	crate::from_stanza_json(json).map(|inner| PyDocument { inner }).map_err(error)
+}
+
+/// converts the JSON output of Stanford CoreNLP to a document.
+#[pyfunction]
+fn from_corenlp(json: &str) -> PyResult<PyDocument> {
+	crate::from_corenlp_json(json).map(|inner| PyDocument { inner }).map_err(error)
+}
+
+/// The `jsonnlp` Python module.
+#[pymodule]
+#[pyo3(name = "jsonnlp")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add("JsonNlpError", m.py().get_type::<JsonNlpError>())?;
+	m.add_class::<PyJsonNlp>()?;
+	m.add_class::<PyDocument>()?;
+	m.add_class::<PyToken>()?;
+	m.add_function(wrap_pyfunction!(from_string, m)?)?;
+	m.add_function(wrap_pyfunction!(from_file, m)?)?;
+	m.add_function(wrap_pyfunction!(from_conllu, m)?)?;
+	m.add_function(wrap_pyfunction!(from_spacy, m)?)?;
+	m.add_function(wrap_pyfunction!(from_stanza, m)?)?;
+	m.add_function(wrap_pyfunction!(from_corenlp, m)?)?;
+	Ok(())
+}
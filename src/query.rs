@@ -0,0 +1,228 @@
+//! Search for the tokens of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents by their annotations,
+//! with the sentences they occur in. Regular expressions over the token text need the `regex` feature.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::{Document, Sentence, Token, TokenFeatures, TokenId};
+
+/// This struct is a token that matches a query, with its sentence and the tokens of the sentence as context.
+/// Tokens of documents without a sentence layer have no sentence and all tokens as context.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenMatch<'a> {
+	pub token: &'a Token,
+	pub sentence: Option<&'a Sentence>,
+	/// the position of the token in the tokens of its sentence.
+	pub position: usize,
+	pub sentence_tokens: Rc<[&'a Token]>,
+}
+
+impl<'a> TokenMatch<'a> {
+	/// returns up to `n` tokens of the sentence before and after the token, e.g. for keyword-in-context lists.
+	pub fn context(&self, n: usize) -> (&[&'a Token], &[&'a Token]) {
+		let tokens = &self.sentence_tokens[..];
+		let end = (self.position + 1 + n).min(tokens.len());
+		(&tokens[self.position.saturating_sub(n)..self.position], &tokens[self.position + 1..end])
+	}
+}
+
+/// This type is a condition on tokens added to a query.
+type TokenFilter<'a> = Box<dyn Fn(&Token) -> bool + 'a>;
+
+/// This struct is a query for the tokens of a document with all of the given annotations, see
+/// Document::find_tokens(). Strings are compared exactly.
+pub struct TokenQuery<'a> {
+	doc: &'a Document,
+	lemma: Option<String>,
+	upos: Option<String>,
+	xpos: Option<String>,
+	entity: Option<String>,
+	#[cfg(feature = "regex")]
+	text: Option<regex::Regex>,
+	filters: Vec<TokenFilter<'a>>,
+}
+
+impl fmt::Debug for TokenQuery<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut d = f.debug_struct("TokenQuery");
+		d.field("lemma", &self.lemma).field("upos", &self.upos).field("xpos", &self.xpos).field("entity", &self.entity);
+		#[cfg(feature = "regex")]
+		d.field("text", &self.text);
+		d.field("filters", &self.filters.len()).finish()
+	}
+}
+
+impl<'a> TokenQuery<'a> {
+	/// sets the lemma of the tokens.
+	pub fn lemma(mut self, lemma: &str) -> Self {
+		self.lemma = Some(lemma.to_string());
+		self
+	}
+
+	/// sets the UPOS tag of the tokens.
+	pub fn upos(mut self, upos: &str) -> Self {
+		self.upos = Some(upos.to_string());
+		self
+	}
+
+	/// sets the XPOS tag of the tokens.
+	pub fn xpos(mut self, xpos: &str) -> Self {
+		self.xpos = Some(xpos.to_string());
+		self
+	}
+
+	/// sets the entity type of the tokens, given by their `entity` tag or by an entity of the type covering them.
+	pub fn entity(mut self, etype: &str) -> Self {
+		self.entity = Some(etype.to_string());
+		self
+	}
+
+	/// sets a regular expression that must match the token text; anchor it with `^` and `$` to match all of it.
+	#[cfg(feature = "regex")]
+	pub fn text(mut self, pattern: regex::Regex) -> Self {
+		self.text = Some(pattern);
+		self
+	}
+
+	/// adds a condition on the token features, e.g. `|f| f.negated == Some(true)`. Tokens without features are
+	/// checked with empty features.
+	pub fn features(self, condition: impl Fn(&TokenFeatures) -> bool + 'a) -> Self {
+		self.filter(move |t| condition(t.features()))
+	}
+
+	/// adds a condition on the tokens.
+	pub fn filter(mut self, condition: impl Fn(&Token) -> bool + 'a) -> Self {
+		self.filters.push(Box::new(condition));
+		self
+	}
+
+	/// returns the IDs of the tokens covered by entities of the queried type.
+	fn entity_tokens(&self) -> HashSet<TokenId> {
+		let etype = match &self.entity {
+			Some(etype) => etype,
+			None => return HashSet::new(),
+		};
+		let entities: Vec<_> = self.doc.entities.iter().filter(|e| &e.etype == etype).collect();
+		self.doc.token_list.iter().filter(|t| entities.iter().any(|e| e.covers(t.id))).map(|t| t.id).collect()
+	}
+
+	/// checks whether the token has all queried annotations.
+	fn accepts(&self, t: &Token, entity_tokens: &HashSet<TokenId>) -> bool {
+		let equal = |query: &Option<String>, value: &str| query.as_ref().is_none_or(|q| q == value);
+		#[cfg(feature = "regex")]
+		let text = self.text.as_ref().is_none_or(|r| r.is_match(&t.text));
+		#[cfg(not(feature = "regex"))]
+		let text = true;
+		text && equal(&self.lemma, &t.lemma)
+			&& equal(&self.upos, &t.upos)
+			&& equal(&self.xpos, &t.xpos)
+			&& self.entity.as_ref().is_none_or(|e| &t.entity == e || entity_tokens.contains(&t.id))
+			&& self.filters.iter().all(|f| f(t))
+	}
+
+	/// returns an iterator over the matching tokens in the order of the sentences, and their tokens like
+	/// Sentence::tokens_in().
+	pub fn iter(&self) -> impl Iterator<Item = TokenMatch<'a>> + '_ {
+		let doc = self.doc;
+		let entity_tokens = self.entity_tokens();
+		let without_sentences =
+			if doc.sentences.is_empty() { Some((None, doc.token_list.iter().collect())) } else { None };
+		doc.sentences().map(|v| (Some(v.sentence), v.tokens)).chain(without_sentences).flat_map(
+			move |(sentence, tokens): (Option<&'a Sentence>, Vec<&'a Token>)| {
+				let positions: Vec<usize> =
+					(0..tokens.len()).filter(|i| self.accepts(tokens[*i], &entity_tokens)).collect();
+				let tokens: Rc<[&'a Token]> = tokens.into();
+				positions.into_iter().map(move |position| TokenMatch {
+					token: tokens[position],
+					sentence,
+					position,
+					sentence_tokens: Rc::clone(&tokens),
+				})
+			},
+		)
+	}
+}
+
+impl Document {
+	/// returns a query for the tokens of the document, which matches all tokens until conditions are added, e.g.
+	/// `doc.find_tokens().lemma("be").upos("AUX").iter()`.
+	pub fn find_tokens(&self) -> TokenQuery<'_> {
+		TokenQuery {
+			doc: self,
+			lemma: None,
+			upos: None,
+			xpos: None,
+			entity: None,
+			#[cfg(feature = "regex")]
+			text: None,
+			filters: Vec::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn doc() -> Document {
+		serde_json::from_str(r#"{"meta":{},"id":1,"tokenList":[
+			{"id":1,"sentence_id":1,"text":"Mary","lemma":"Mary","upos":"PROPN","xpos":"NNP"},
+			{"id":2,"sentence_id":1,"text":"Ann","lemma":"Ann","upos":"PROPN","xpos":"NNP"},
+			{"id":3,"sentence_id":1,"text":"was","lemma":"be","upos":"AUX","xpos":"VBD"},
+			{"id":4,"sentence_id":1,"text":"not","lemma":"not","upos":"PART","features":{"negated":true}},
+			{"id":5,"sentence_id":2,"text":"Paris","lemma":"Paris","upos":"PROPN","entity":"LOC"},
+			{"id":6,"sentence_id":2,"text":"is","lemma":"be","upos":"AUX","xpos":"VBZ"}],
+			"sentences":[{"id":1,"tokenFrom":1,"tokenTo":4},{"id":2,"tokenFrom":5,"tokenTo":6}],
+			"entities":[{"id":1,"type":"PER","tokens":[1,2]}]}"#)
+		.unwrap()
+	}
+
+	fn texts<'a>(matches: impl Iterator<Item = TokenMatch<'a>>) -> Vec<&'a str> {
+		matches.map(|m| m.token.text.as_str()).collect()
+	}
+
+	fn texts_of<'a>(tokens: &[&'a Token]) -> Vec<&'a str> {
+		tokens.iter().map(|t| t.text.as_str()).collect()
+	}
+
+	#[test]
+	fn tokens_are_found() {
+		let doc = doc();
+		assert_eq!(texts(doc.find_tokens().iter()).len(), 6);
+		assert_eq!(texts(doc.find_tokens().lemma("be").upos("AUX").iter()), ["was", "is"]);
+		assert_eq!(texts(doc.find_tokens().lemma("be").xpos("VBZ").iter()), ["is"]);
+		assert_eq!(texts(doc.find_tokens().entity("PER").iter()), ["Mary", "Ann"]);
+		assert_eq!(texts(doc.find_tokens().entity("LOC").iter()), ["Paris"]);
+		assert_eq!(texts(doc.find_tokens().features(|f| f.negated == Some(true)).iter()), ["not"]);
+		assert_eq!(texts(doc.find_tokens().filter(|t| t.text.len() == 2).iter()), ["is"]);
+		assert!(doc.find_tokens().upos("PROPN").entity("ORG").iter().next().is_none());
+	}
+
+	#[test]
+	fn matches_have_context() {
+		let doc = doc();
+		let query = doc.find_tokens().lemma("be");
+		let matches: Vec<_> = query.iter().collect();
+		assert_eq!((matches[0].position, matches[0].sentence.map(|s| s.id.0)), (2, Some(1)));
+		let (before, after) = matches[0].context(1);
+		assert_eq!((texts_of(before), texts_of(after)), (vec!["Ann"], vec!["not"]));
+		let (before, after) = matches[1].context(5);
+		assert_eq!((texts_of(before), texts_of(after)), (vec!["Paris"], vec![]));
+
+		let mut doc = doc.clone();
+		doc.sentences.clear();
+		let m = doc.find_tokens().lemma("not").iter().next().unwrap();
+		assert_eq!((m.sentence, m.position, m.sentence_tokens.len()), (None, 3, 6));
+	}
+
+	#[cfg(feature = "regex")]
+	#[test]
+	fn text_is_matched() {
+		let doc = doc();
+		let pattern = regex::Regex::new("^[A-Z][a-z]+$").unwrap();
+		assert_eq!(texts(doc.find_tokens().text(pattern).iter()), ["Mary", "Ann", "Paris"]);
+		let pattern = regex::Regex::new("s").unwrap();
+		assert_eq!(texts(doc.find_tokens().text(pattern).upos("AUX").iter()), ["was", "is"]);
+	}
+}
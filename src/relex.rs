@@ -0,0 +1,462 @@
+//! Conversion between the entity and triple layers of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents
+//! and the relation extraction datasets [TACRED](https://nlp.stanford.edu/projects/tacred/) and
+//! [DocRED](https://github.com/thunlp/DocRED).
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+	Dependency, DependencyTree, Document, DocumentId, Entity, EntityId, JsonNlpError, Meta, RawId, Relation, RelationId,
+	Result, Sentence, SentenceId, Token, TokenId, Triple, TripleId, JSONNLP,
+};
+
+/// This struct contains one sentence of TACRED with a subject, an object, and their relation.
+#[derive(Deserialize)]
+struct TacredInstance {
+	#[serde(default)]
+	id: String,
+	#[serde(default)]
+	docid: String,
+	relation: String,
+	token: Vec<String>,
+	subj_start: usize,
+	subj_end: usize,
+	obj_start: usize,
+	obj_end: usize,
+	#[serde(default)]
+	subj_type: String,
+	#[serde(default)]
+	obj_type: String,
+	#[serde(default)]
+	stanford_pos: Vec<String>,
+	#[serde(default)]
+	stanford_ner: Vec<String>,
+	#[serde(default)]
+	stanford_head: Vec<u64>,
+	#[serde(default)]
+	stanford_deprel: Vec<String>,
+}
+
+/// This enum accepts a single DocRED document or a list of them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DocredInput {
+	One(DocredDocument),
+	Many(Vec<DocredDocument>),
+}
+
+/// This struct contains a DocRED document with its entities, each a list of mentions, and relation labels.
+#[derive(Deserialize)]
+struct DocredDocument {
+	#[serde(default)]
+	title: String,
+	sents: Vec<Vec<String>>,
+	#[serde(rename = "vertexSet")]
+	vertex_set: Vec<Vec<DocredMention>>,
+	#[serde(default)]
+	labels: Vec<DocredLabel>,
+}
+
+/// This struct contains a mention with its token span in its sentence, the end exclusive.
+#[derive(Deserialize)]
+struct DocredMention {
+	#[serde(default)]
+	name: String,
+	pos: [usize; 2],
+	sent_id: usize,
+	#[serde(rename = "type",
+		default)]
+	mtype: String,
+}
+
+/// This struct contains a relation between two entities, given by their index, with its evidence sentences.
+#[derive(Deserialize)]
+struct DocredLabel {
+	h: usize,
+	t: usize,
+	r: String,
+	#[serde(default)]
+	evidence: Vec<usize>,
+}
+
+impl Document {
+	/// adds a sentence of words separated by spaces to the text and returns the IDs of its new tokens.
+	fn push_words(&mut self, words: &[String]) -> Vec<TokenId> {
+		let id = SentenceId(self.sentences.len() as RawId + 1);
+		if !self.text.is_empty() {
+			self.text.push(' ');
+		}
+		let mut offset = self.text.chars().count() as u64;
+		let first = self.text.len();
+		let mut ids = Vec::new();
+		for (i, w) in words.iter().enumerate() {
+			if i > 0 {
+				self.text.push(' ');
+				offset += 1;
+			}
+			self.text.push_str(w);
+			let length = w.chars().count() as u64;
+			let token_id = TokenId(self.token_list.len() as RawId + 1);
+			self.token_list.push(Token {
+				id: token_id,
+				sentence_id: id,
+				text: w.clone(),
+				char_offset_begin: Some(offset),
+				char_offset_end: Some(offset + length),
+				..Default::default()
+			});
+			offset += length;
+			ids.push(token_id);
+		}
+		self.sentences.push(Sentence {
+			id,
+			text: self.text[first..].to_string(),
+			token_from: ids.first().copied(),
+			token_to: ids.last().copied(),
+			tokens: ids.clone(),
+			..Default::default()
+		});
+		ids
+	}
+
+	/// returns the relation of the given type, added if the document has none yet.
+	pub(crate) fn relation_of_type(&mut self, rtype: &str) -> RelationId {
+		if let Some(r) = self.relations.iter().find(|r| r.rtype == rtype) {
+			return r.id;
+		}
+		let id = RelationId(self.relations.len() as RawId + 1);
+		self.relations.push(Relation { id, rtype: rtype.to_string(), ..Default::default() });
+		id
+	}
+
+	/// returns the mentions of an entity as sentence index and token span in the sentence, the end exclusive.
+	/// The mentions are the `tokenFrom`/`tokenTo` pairs in the entity's `extra` map as `mentions`, or else the
+	/// runs of adjacent tokens of the entity.
+	fn mentions(&self, entity: &Entity, groups: &[(SentenceId, &str, Vec<&Token>)]) -> Vec<(usize, usize, usize)> {
+		let position: HashMap<TokenId, (usize, usize)> = groups.iter().enumerate()
+			.flat_map(|(s, g)| g.2.iter().enumerate().map(move |(i, t)| (t.id, (s, i))))
+			.collect();
+		let kept: Vec<(TokenId, TokenId)> = entity.extra.get("mentions").and_then(Value::as_array).into_iter().flatten()
+			.filter_map(|m| Some((TokenId::from_u64(m.get(0)?.as_u64()?)?, TokenId::from_u64(m.get(1)?.as_u64()?)?)))
+			.collect();
+		let mut mentions: Vec<(usize, usize, usize)> = Vec::new();
+		if !kept.is_empty() {
+			for (from, to) in kept {
+				if let (Some(from), Some(to)) = (position.get(&from), position.get(&to)) {
+					if from.0 == to.0 && from.1 <= to.1 {
+						mentions.push((from.0, from.1, to.1 + 1));
+					}
+				}
+			}
+			return mentions;
+		}
+		let mut tokens: Vec<(usize, usize)> = self.token_list.iter().filter(|t| entity.covers(t.id)).filter_map(|t| position.get(&t.id).copied()).collect();
+		tokens.sort_unstable();
+		for (s, i) in tokens {
+			match mentions.last_mut() {
+				Some(last) if last.0 == s && last.2 == i => last.2 = i + 1,
+				_ => mentions.push((s, i, i + 1)),
+			}
+		}
+		mentions
+	}
+}
+
+/// This function converts a TACRED dataset, a JSON list of sentences, to JSON-NLP with one Document per sentence.
+/// The subject and object become the entities 1 and 2, typed by `subj_type` and `obj_type`, and their relation a
+/// triple, also for `no_relation`, linking them by a relation of that type. The Stanford annotations become the
+/// tokens' XPOS and `entity` tags and a dependency tree, the `docid` becomes the document identifier, and the
+/// instance ID is kept in the triple's `extra` map as `tacredId`.
+pub fn from_tacred(json: &str) -> Result<JSONNLP> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let instances: Vec<TacredInstance> = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let mut docs = Vec::new();
+	for (n, instance) in instances.into_iter().enumerate() {
+		let mut doc = Document { id: DocumentId(n as RawId + 1), ..Default::default() };
+		doc.meta.identifier = instance.docid.clone();
+		let ids = doc.push_words(&instance.token);
+		let span = |start: usize, end: usize| -> Result<Vec<TokenId>> {
+			ids.get(start..=end).map(<[TokenId]>::to_vec).ok_or_else(|| {
+				JsonNlpError::Format(format!("TACRED instance {:?}: span {}..={} outside of the sentence", instance.id, start, end))
+			})
+		};
+		let subject = span(instance.subj_start, instance.subj_end)?;
+		let object = span(instance.obj_start, instance.obj_end)?;
+
+		let mut previous = "O";
+		for (i, t) in doc.token_list.iter_mut().enumerate() {
+			t.xpos = instance.stanford_pos.get(i).cloned().unwrap_or_default();
+			if let Some(ner) = instance.stanford_ner.get(i) {
+				t.entity_iob = if ner == "O" { "O" } else if ner == previous { "I" } else { "B" }.to_string();
+				t.entity = if ner == "O" { String::new() } else { ner.clone() };
+				previous = ner;
+			}
+		}
+		let mut tree = DependencyTree { sentence_id: Some(SentenceId(1)), ..Default::default() };
+		for (i, (head, dep)) in instance.stanford_head.iter().zip(&ids).enumerate() {
+			let gov = match *head as usize {
+				0 => TokenId(0),
+				h => *ids.get(h - 1).ok_or_else(|| JsonNlpError::Format(format!("TACRED instance {:?}: head {} outside of the sentence", instance.id, h)))?,
+			};
+			tree.dependencies.push(Dependency {
+				lab: instance.stanford_deprel.get(i).cloned().unwrap_or_default(),
+				gov,
+				dep: *dep,
+				..Default::default()
+			});
+		}
+		if !tree.dependencies.is_empty() {
+			doc.dependency_trees.push(tree);
+		}
+
+		for (i, (tokens, etype)) in vec![(subject, &instance.subj_type), (object, &instance.obj_type)].into_iter().enumerate() {
+			let label = tokens.iter().map(|t| doc.token_list[t.0 as usize - 1].text.as_str()).collect::<Vec<_>>().join(" ");
+			doc.entities.push(Entity {
+				id: EntityId(i as RawId + 1),
+				label,
+				etype: etype.clone(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			});
+		}
+		let rel = doc.relation_of_type(&instance.relation);
+		let mut triple = Triple {
+			id: TripleId(1),
+			from_entity: Some(EntityId(1)),
+			to_entity: Some(EntityId(2)),
+			rel: Some(rel),
+			sentence_id: vec![SentenceId(1)],
+			directional: Some(true),
+			..Default::default()
+		};
+		if !instance.id.is_empty() {
+			triple.extra.insert("tacredId".to_string(), Value::String(instance.id));
+		}
+		doc.triples.push(triple);
+		docs.push(doc);
+	}
+	Ok(JSONNLP { meta: Meta::new(), docs, ..Default::default() })
+}
+
+/// This function converts the triples of a Document to TACRED instances, one per triple whose subject and object
+/// entities have a mention in the same sentence, using the first such mentions. The relation is the type, or else
+/// the label, of the triple's relation, the tokens of the sentence carry their XPOS and `entity` tags as
+/// `stanford_pos` and `stanford_ner` and their dependencies from the sentence's first dependency tree as
+/// `stanford_head` and `stanford_deprel`, and the instance ID is the triple's `tacredId` or else made of the
+/// document and triple IDs.
+pub fn to_tacred(doc: &Document) -> Vec<Value> {
+	let groups = doc.sentence_groups();
+	let entity = |id: Option<EntityId>| id.and_then(|id| doc.entities.iter().find(|e| e.id == id));
+	let mut instances = Vec::new();
+	for t in &doc.triples {
+		let relation = t.rel.and_then(|id| doc.relations.iter().find(|r| r.id == id));
+		let (relation, subject, object) = match (relation, entity(t.from_entity), entity(t.to_entity)) {
+			(Some(r), Some(s), Some(o)) => (if r.rtype.is_empty() { &r.label } else { &r.rtype }, s, o),
+			_ => continue,
+		};
+		let objects = doc.mentions(object, &groups);
+		let pair = doc.mentions(subject, &groups).into_iter()
+			.find_map(|s| objects.iter().find(|o| o.0 == s.0).map(|o| (s, *o)));
+		let (s, o) = match pair {
+			Some(pair) => pair,
+			None => continue,
+		};
+		let (sentence_id, _, tokens) = &groups[s.0];
+		let position: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+		let tree = doc.dependency_trees.iter().find(|d| d.sentence_id == Some(*sentence_id))
+			.or_else(|| doc.dependency_trees.iter().find(|d| d.sentence_id.is_none()));
+		let dependency = |id: TokenId| tree.and_then(|tree| tree.dependencies.iter().find(|d| d.dep == id));
+		let id = match t.extra.get("tacredId").and_then(Value::as_str) {
+			Some(id) => id.to_string(),
+			None => format!("{}-{}", doc.id, t.id),
+		};
+		instances.push(json!({
+			"id": id,
+			"docid": doc.meta.identifier,
+			"relation": relation,
+			"token": tokens.iter().map(|t| t.text.as_str()).collect::<Vec<_>>(),
+			"subj_start": s.1,
+			"subj_end": s.2 - 1,
+			"obj_start": o.1,
+			"obj_end": o.2 - 1,
+			"subj_type": subject.etype,
+			"obj_type": object.etype,
+			"stanford_pos": tokens.iter().map(|t| t.xpos.as_str()).collect::<Vec<_>>(),
+			"stanford_ner": tokens.iter().map(|t| if t.entity.is_empty() { "O" } else { t.entity.as_str() }).collect::<Vec<_>>(),
+			"stanford_head": tokens.iter().map(|t| match dependency(t.id) {
+				Some(d) if d.is_root() => 0,
+				Some(d) => position.get(&d.gov).map_or(0, |p| p + 1),
+				None => 0,
+			}).collect::<Vec<_>>(),
+			"stanford_deprel": tokens.iter().map(|t| dependency(t.id).map_or("", |d| d.lab.as_str())).collect::<Vec<_>>(),
+		}));
+	}
+	instances
+}
+
+/// This function converts DocRED, a single document or a JSON list of documents, to JSON-NLP. Every entity of the
+/// `vertexSet` becomes an Entity of the tokens of all its mentions, with the name and type of its first mention, and
+/// the mentions kept in its `extra` map as `mentions`, a list of `tokenFrom`/`tokenTo` pairs. Every label becomes a
+/// triple linking the entities by a relation of its type, e.g. `P17`, with its evidence sentences as sentence IDs.
+/// The title becomes the document title.
+pub fn from_docred(json: &str) -> Result<JSONNLP> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let input: DocredInput = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+	let documents = match input {
+		DocredInput::One(d) => vec![d],
+		DocredInput::Many(d) => d,
+	};
+
+	let mut docs = Vec::new();
+	for (n, d) in documents.into_iter().enumerate() {
+		let error = |msg: String| JsonNlpError::Format(format!("DocRED document {}: {}", n + 1, msg));
+		let mut doc = Document { id: DocumentId(n as RawId + 1), ..Default::default() };
+		doc.meta.title = d.title.clone();
+		let sentences: Vec<Vec<TokenId>> = d.sents.iter().map(|words| doc.push_words(words)).collect();
+
+		for (i, vertex) in d.vertex_set.iter().enumerate() {
+			let mut entity = Entity { id: EntityId(i as RawId + 1), ..Default::default() };
+			let mut mentions = Vec::new();
+			for m in vertex {
+				let tokens = sentences.get(m.sent_id).and_then(|s| s.get(m.pos[0]..m.pos[1])).filter(|t| !t.is_empty())
+					.ok_or_else(|| error(format!("mention {:?} outside of sentence {}", m.name, m.sent_id)))?;
+				mentions.push(json!([tokens[0], tokens[tokens.len() - 1]]));
+				entity.tokens.extend_from_slice(tokens);
+			}
+			if let Some(first) = vertex.first() {
+				entity.label = first.name.clone();
+				entity.etype = first.mtype.clone();
+			}
+			entity.tokens.sort_unstable();
+			entity.tokens.dedup();
+			entity.token_from = entity.tokens.first().copied();
+			entity.token_to = entity.tokens.last().copied();
+			entity.extra.insert("mentions".to_string(), Value::Array(mentions));
+			doc.entities.push(entity);
+		}
+
+		for (i, l) in d.labels.iter().enumerate() {
+			if l.h >= doc.entities.len() || l.t >= doc.entities.len() {
+				return Err(error(format!("label {} refers to a missing entity", i)));
+			}
+			let rel = doc.relation_of_type(&l.r);
+			doc.triples.push(Triple {
+				id: TripleId(i as RawId + 1),
+				from_entity: Some(EntityId(l.h as RawId + 1)),
+				to_entity: Some(EntityId(l.t as RawId + 1)),
+				rel: Some(rel),
+				sentence_id: l.evidence.iter().map(|e| SentenceId(*e as RawId + 1)).collect(),
+				directional: Some(true),
+				..Default::default()
+			});
+		}
+		docs.push(doc);
+	}
+	Ok(JSONNLP { meta: Meta::new(), docs, ..Default::default() })
+}
+
+/// This function converts a Document to a DocRED document. The sentences become `sents`, every entity with a mention
+/// becomes an entity of the `vertexSet` with its mentions, and every triple between two of these entities with a
+/// relation becomes a label with the type, or else the label, of its relation and its sentences as evidence.
+/// The mentions are those kept by from_docred(), or else the runs of adjacent tokens of the entity.
+pub fn to_docred(doc: &Document) -> Value {
+	let groups = doc.sentence_groups();
+	let sentence_index: HashMap<SentenceId, usize> = groups.iter().enumerate().map(|(i, g)| (g.0, i)).collect();
+	let mut vertices = Vec::new();
+	let mut index: HashMap<EntityId, usize> = HashMap::new();
+	for e in &doc.entities {
+		let mentions: Vec<Value> = doc.mentions(e, &groups).into_iter().map(|(s, start, end)| {
+			let name = groups[s].2[start..end].iter().map(|t| t.text.as_str()).collect::<Vec<_>>().join(" ");
+			json!({ "name": name, "pos": [start, end], "sent_id": s, "type": e.etype })
+		}).collect();
+		if !mentions.is_empty() {
+			index.insert(e.id, vertices.len());
+			vertices.push(mentions);
+		}
+	}
+
+	let labels: Vec<Value> = doc.triples.iter().filter_map(|t| {
+		let relation = doc.relations.iter().find(|r| Some(r.id) == t.rel)?;
+		let h = index.get(&t.from_entity?)?;
+		let t_ = index.get(&t.to_entity?)?;
+		let evidence: Vec<usize> = t.sentence_id.iter().filter_map(|s| sentence_index.get(s).copied()).collect();
+		Some(json!({
+			"h": h,
+			"t": t_,
+			"r": if relation.rtype.is_empty() { &relation.label } else { &relation.rtype },
+			"evidence": evidence,
+		}))
+	}).collect();
+
+	json!({
+		"title": if doc.meta.title.is_empty() { &doc.meta.identifier } else { &doc.meta.title },
+		"sents": groups.iter().map(|g| g.2.iter().map(|t| t.text.as_str()).collect::<Vec<_>>()).collect::<Vec<_>>(),
+		"vertexSet": vertices,
+		"labels": labels,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TACRED: &str = r#"[{"id":"e1","docid":"d1","relation":"per:employee_of",
+		"token":["Bill","Gates","founded","Microsoft"],"subj_start":0,"subj_end":1,"obj_start":3,"obj_end":3,
+		"subj_type":"PERSON","obj_type":"ORGANIZATION","stanford_pos":["NNP","NNP","VBD","NNP"],
+		"stanford_ner":["PERSON","PERSON","O","ORGANIZATION"],
+		"stanford_head":[2,3,0,3],"stanford_deprel":["compound","nsubj","ROOT","dobj"]}]"#;
+
+	const DOCRED: &str = r#"{"title":"Bern","sents":[["Bern","is","in","Switzerland","."],
+		["It","is","the","capital","."]],"vertexSet":[[{"name":"Bern","pos":[0,1],"sent_id":0,"type":"LOC"},
+		{"name":"It","pos":[0,1],"sent_id":1,"type":"LOC"}],[{"name":"Switzerland","pos":[3,4],"sent_id":0,
+		"type":"LOC"}]],"labels":[{"h":0,"t":1,"r":"P17","evidence":[0]}]}"#;
+
+	#[test]
+	fn tacred_round_trip() {
+		let j = from_tacred(TACRED).expect("valid TACRED");
+		let doc = &j.docs[0];
+		assert_eq!((doc.text.as_str(), doc.meta.identifier.as_str()), ("Bill Gates founded Microsoft", "d1"));
+		assert_eq!((doc.entities[0].label.as_str(), doc.entities[1].etype.as_str()), ("Bill Gates", "ORGANIZATION"));
+		assert_eq!(doc.relations[0].rtype, "per:employee_of");
+		assert_eq!((doc.triples[0].from_entity, doc.triples[0].to_entity), (Some(EntityId(1)), Some(EntityId(2))));
+		let iob: Vec<_> = doc.token_list.iter().map(|t| t.entity_iob.as_str()).collect();
+		assert_eq!(iob, ["B", "I", "O", "B"]);
+		assert_eq!(doc.dependency_trees[0].dependencies[0].gov, TokenId(2));
+		assert_eq!(Value::Array(to_tacred(doc)), serde_json::from_str::<Value>(TACRED).unwrap());
+	}
+
+	#[test]
+	fn docred_round_trip() {
+		let j = from_docred(DOCRED).expect("valid DocRED");
+		let doc = &j.docs[0];
+		assert_eq!(doc.text, "Bern is in Switzerland . It is the capital .");
+		assert_eq!(doc.entities[0].tokens, [TokenId(1), TokenId(6)]);
+		assert_eq!(doc.entities[0].extra["mentions"], json!([[1, 1], [6, 6]]));
+		assert_eq!(doc.triples[0].sentence_id, [SentenceId(1)]);
+		assert_eq!(doc.relations[0].rtype, "P17");
+		assert_eq!(to_docred(doc), serde_json::from_str::<Value>(DOCRED).unwrap());
+		assert_eq!(from_docred(&format!("[{},{}]", DOCRED, DOCRED)).unwrap().docs.len(), 2);
+	}
+
+	#[test]
+	fn relation_extraction_errors() {
+		let e = from_tacred(&TACRED.replace(r#""obj_end":3"#, r#""obj_end":4"#)).unwrap_err();
+		let message = "TACRED instance \"e1\": span 3..=4 outside of the sentence";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		assert!(from_tacred(&TACRED.replace("[2,3,0,3]", "[2,3,0,5]")).is_err());
+		assert!(from_tacred(&TACRED.replace(r#""relation":"per:employee_of","#, "")).is_err());
+
+		let e = from_docred(&DOCRED.replace("[3,4]", "[3,6]")).unwrap_err();
+		let message = "DocRED document 1: mention \"Switzerland\" outside of sentence 0";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		assert!(from_docred(&DOCRED.replace(r#""t":1"#, r#""t":2"#)).is_err());
+		assert!(from_docred(r#"{"sents":[]}"#).is_err());
+	}
+}
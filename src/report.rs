@@ -0,0 +1,159 @@
+//! Markdown reports summarizing [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) corpora, e.g. for dataset
+//! cards: the number of documents, their languages, the annotation layers they contain, the entity types, and the
+//! problems found by validation.
+
+use std::collections::HashMap;
+
+use crate::{Document, JSONNLP};
+
+/// the number of validation issues listed in a report, the remaining ones are only counted.
+const MAX_ISSUES: usize = 50;
+
+/// returns a text with the characters escaped that would end a table cell or start Markdown formatting.
+fn escape_markdown(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'|' | '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' => {
+				out.push('\\');
+				out.push(c);
+			}
+			'\n' | '\r' => out.push(' '),
+			_ => out.push(c),
+		}
+	}
+	out
+}
+
+/// returns the counts of the values sorted by descending count, then by value.
+fn sorted_counts(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+	let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+	counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+	counts
+}
+
+/// returns the share of a count as percentage.
+fn percent(count: usize, total: usize) -> String {
+	if total == 0 {
+		"0.0%".to_string()
+	} else {
+		format!("{:.1}%", count as f64 * 100.0 / total as f64)
+	}
+}
+
+/// the layers of a document by their JSON-NLP names, in the order of layer_sizes().
+const LAYERS: [&str; 14] = [
+	"text",
+	"tokenList",
+	"sentences",
+	"paragraphs",
+	"clauses",
+	"dependencyTrees",
+	"coreferences",
+	"constituents",
+	"expressions",
+	"entities",
+	"relations",
+	"triples",
+	"semanticRoles",
+	"amrGraphs",
+];
+
+/// returns the number of elements of every layer of a document.
+fn layer_sizes(doc: &Document) -> [usize; 14] {
+	[
+		usize::from(!doc.text.is_empty()),
+		doc.token_list.len(),
+		doc.sentences.len(),
+		doc.paragraphs.len(),
+		doc.clauses.len(),
+		doc.dependency_trees.len(),
+		doc.coreferences.len(),
+		doc.constituents.len(),
+		doc.expressions.len(),
+		doc.entities.len(),
+		doc.relations.len(),
+		doc.triples.len(),
+		doc.semantic_roles.len(),
+		doc.amr_graphs.len(),
+	]
+}
+
+/// This function returns a Markdown report of a collection with an overview of its size, the distribution of the
+/// document languages, the number of documents containing each layer, the frequencies of the entity types, and the
+/// problems found by JSONNLP::validate(), of which the first 50 are listed. Documents without a language have the
+/// language of the collection, or else count as `unknown`.
+pub fn to_markdown_report(j: &JSONNLP) -> String {
+	let title = if j.meta.title.is_empty() { "Corpus report".to_string() } else { escape_markdown(&j.meta.title) };
+	let mut out = format!("# {}\n\n", title);
+	if !j.meta.description.is_empty() {
+		out.push_str(&format!("{}\n\n", escape_markdown(&j.meta.description)));
+	}
+
+	let docs = j.docs.len();
+	let tokens: usize = j.docs.iter().map(|d| d.token_list.len()).sum();
+	let sentences: usize = j.docs.iter().map(|d| d.sentences.len()).sum();
+	let entities: usize = j.docs.iter().map(|d| d.entities.len()).sum();
+	out.push_str("## Overview\n\n| | Count |\n|---|---:|\n");
+	out.push_str(&format!("| Documents | {} |\n| Tokens | {} |\n", docs, tokens));
+	out.push_str(&format!("| Sentences | {} |\n| Entities | {} |\n\n", sentences, entities));
+
+	let mut languages: HashMap<String, usize> = HashMap::new();
+	for doc in &j.docs {
+		let language = match (doc.meta.language.as_str(), j.meta.language.as_str()) {
+			("", "") => "unknown",
+			("", language) | (language, _) => language,
+		};
+		*languages.entry(language.to_string()).or_default() += 1;
+	}
+	out.push_str("## Languages\n\n| Language | Documents | Share |\n|---|---:|---:|\n");
+	for (language, count) in sorted_counts(languages) {
+		out.push_str(&format!("| {} | {} | {} |\n", escape_markdown(&language), count, percent(count, docs)));
+	}
+	out.push('\n');
+
+	// the number of documents with the layer and its number of elements
+	let mut coverage = [(0, 0); 14];
+	for doc in &j.docs {
+		for (c, size) in coverage.iter_mut().zip(layer_sizes(doc)) {
+			c.0 += usize::from(size > 0);
+			c.1 += size;
+		}
+	}
+	out.push_str("## Layers\n\n| Layer | Documents | Coverage | Elements |\n|---|---:|---:|---:|\n");
+	for (name, (count, elements)) in LAYERS.iter().zip(coverage) {
+		out.push_str(&format!("| {} | {} | {} | {} |\n", name, count, percent(count, docs), elements));
+	}
+	out.push('\n');
+
+	let mut types: HashMap<String, usize> = HashMap::new();
+	for e in j.docs.iter().flat_map(|d| &d.entities) {
+		let etype = if e.etype.is_empty() { "(none)" } else { e.etype.as_str() };
+		*types.entry(etype.to_string()).or_default() += 1;
+	}
+	out.push_str("## Entity types\n\n");
+	if types.is_empty() {
+		out.push_str("The corpus has no entities.\n\n");
+	} else {
+		out.push_str("| Type | Entities | Share |\n|---|---:|---:|\n");
+		for (etype, count) in sorted_counts(types) {
+			out.push_str(&format!("| {} | {} | {} |\n", escape_markdown(&etype), count, percent(count, entities)));
+		}
+		out.push('\n');
+	}
+
+	let report = j.validate();
+	out.push_str("## Validation\n\n");
+	if report.is_valid() {
+		out.push_str("No issues found.\n");
+	} else {
+		out.push_str(&format!("{} issues found.\n\n", report.issues.len()));
+		for issue in report.issues.iter().take(MAX_ISSUES) {
+			out.push_str(&format!("- {}\n", escape_markdown(&issue.to_string())));
+		}
+		if report.issues.len() > MAX_ISSUES {
+			out.push_str(&format!("- and {} more\n", report.issues.len() - MAX_ISSUES));
+		}
+	}
+	out
+}
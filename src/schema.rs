@@ -0,0 +1,36 @@
+//! Validation of untyped JSON against the [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) JSON Schema.
+
+use std::sync::OnceLock;
+
+use serde_json::Value;
+
+/// The JSON-NLP JSON Schema bundled with this crate.
+pub const SCHEMA: &str = include_str!("../schema/jsonnlp.schema.json");
+
+/// This struct describes one violation of the JSON-NLP schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+	/// the JSON pointer of the offending element in the validated document.
+	pub pointer: String,
+	/// a description of the violated constraint.
+	pub message: String,
+}
+
+fn validator() -> &'static jsonschema::Validator {
+	static VALIDATOR: OnceLock<jsonschema::Validator> = OnceLock::new();
+	VALIDATOR.get_or_init(|| {
+		let schema: Value = serde_json::from_str(SCHEMA).expect("bundled schema is valid JSON");
+		jsonschema::validator_for(&schema).expect("bundled schema is a valid JSON Schema")
+	})
+}
+
+/// This function checks a JSON document against the JSON-NLP schema and returns all violations.
+/// An empty list means that the document conforms to the schema.
+pub fn validate_schema(json: &Value) -> Vec<SchemaViolation> {
+	validator().iter_errors(json)
+		.map(|e| SchemaViolation {
+			pointer: e.instance_path().to_string(),
+			message: e.to_string(),
+		})
+		.collect()
+}
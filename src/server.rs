@@ -0,0 +1,255 @@
+//! An embeddable HTTP service built with [axum](https://github.com/tokio-rs/axum) for validating, converting, and
+//! merging [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), e.g. as a sidecar of annotation pipelines.
+//!
+//! - `POST /validate` takes a JSON-NLP collection and returns `{"valid": bool, "issues": [...]}` with the problems
+//!   found by JSONNLP::validate().
+//! - `POST /convert?from=conllu&to=json` converts the body between `json`, `ndjson`, `conllu`, `conllx`,
+//!   `conll2003`, and `xml`, each defaulting to `json`. Reading `xml` requires the `xml` feature.
+//! - `POST /merge` takes a JSON array of collections and returns one collection with all of their documents and the
+//!   metadata of the first one. Documents whose ID is already taken are given the next free ID. The limits of
+//!   documents and tokens apply to the merged collection.
+//!
+//! Malformed input is answered with status 400 and `{"error": message}`, and input exceeding the limits of the
+//! ParseOptions with status 413. The limits apply to all formats but XML, which is answered with status 400 if
+//! limits are set. Requests are parsed on the threads for blocking work of the tokio runtime.
+//! The router can be extended with further routes and layers, e.g. to raise the default body limit of axum.
+
+use std::collections::HashSet;
+use std::io;
+
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use serde_json::value::RawValue;
+use tokio::net::{TcpListener, ToSocketAddrs};
+
+use crate::convert::{read, write, Format};
+use crate::{
+	from_slice_with_options, from_string_with_options, DocumentId, JsonNlpError, Limits, ParseOptions, Result, JSONNLP,
+};
+
+/// This struct wraps the errors of the handlers as HTTP responses.
+struct ServerError(JsonNlpError);
+
+impl From<JsonNlpError> for ServerError {
+	fn from(e: JsonNlpError) -> Self {
+		ServerError(e)
+	}
+}
+
+impl IntoResponse for ServerError {
+	fn into_response(self) -> Response {
+		let status = match self.0 {
+			JsonNlpError::LimitExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
+			JsonNlpError::Io(_) | JsonNlpError::Serialize(_) => StatusCode::INTERNAL_SERVER_ERROR,
+			_ => StatusCode::BAD_REQUEST,
+		};
+		(status, Json(json!({ "error": self.0.to_string() }))).into_response()
+	}
+}
+
+type Reply<T> = std::result::Result<T, ServerError>;
+
+#[derive(Deserialize)]
+struct ConvertQuery {
+	#[serde(default)]
+	from: Format,
+	#[serde(default)]
+	to: Format,
+}
+
+#[derive(Serialize)]
+struct Issue {
+	pointer: String,
+	message: String,
+}
+
+#[derive(Serialize)]
+struct ValidateResponse {
+	valid: bool,
+	issues: Vec<Issue>,
+}
+
+/// returns the result of a function run on a thread for blocking work, so that parsing large bodies does not block
+/// the worker threads of the runtime.
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T> + Send + 'static) -> Reply<T> {
+	match tokio::task::spawn_blocking(f).await {
+		Ok(r) => Ok(r?),
+		Err(e) => Err(JsonNlpError::Io(io::Error::other(e)).into()),
+	}
+}
+
+async fn validate(State(options): State<ParseOptions>, body: Bytes) -> Reply<Json<ValidateResponse>> {
+	let issues = blocking(move || {
+		let j = from_slice_with_options(&body, &options)?;
+		Ok(j.validate().issues)
+	})
+	.await?;
+	let issues: Vec<Issue> =
+		issues.into_iter().map(|issue| Issue { message: issue.to_string(), pointer: issue.pointer }).collect();
+	Ok(Json(ValidateResponse { valid: issues.is_empty(), issues }))
+}
+
+async fn convert(State(options): State<ParseOptions>, Query(q): Query<ConvertQuery>, body: Bytes) -> Reply<Response> {
+	let content_type = q.to.content_type();
+	let out = blocking(move || write(&read(&body, q.from, &options)?, q.to)).await?;
+	Ok(([(header::CONTENT_TYPE, content_type)], out).into_response())
+}
+
+/// returns the remaining budget of a limit after `used` elements.
+fn remaining(max: Option<usize>, used: usize) -> Option<usize> {
+	max.map(|max| max.saturating_sub(used))
+}
+
+async fn merge(State(options): State<ParseOptions>, body: Bytes) -> Reply<Json<JSONNLP>> {
+	Ok(Json(blocking(move || merge_parts(&body, &options)).await?))
+}
+
+/// returns the collections of a JSON array merged into one. The limits of documents and tokens apply to the merged
+/// collection, and the other limits to every collection.
+fn merge_parts(body: &[u8], options: &ParseOptions) -> Result<JSONNLP> {
+	// the documents and tokens are only counted in the collections, one level deeper than the array
+	let array_limits = Limits {
+		max_docs: None,
+		max_tokens: None,
+		max_depth: options.limits.max_depth.map(|d| d + 1),
+		..options.limits.clone()
+	};
+	array_limits.check(body)?;
+	let parts: Vec<&RawValue> = serde_json::from_slice(body)
+		.map_err(|e| JsonNlpError::Format(format!("expected an array of JSON-NLP collections: {}", e)))?;
+	let mut merged: Option<JSONNLP> = None;
+	let mut ids = HashSet::new();
+	let mut next = 1;
+	let mut tokens = 0;
+	for part in parts {
+		let mut part_options = options.clone();
+		part_options.limits.max_docs = remaining(options.limits.max_docs, ids.len());
+		part_options.limits.max_tokens = remaining(options.limits.max_tokens, tokens);
+		let j = from_string_with_options(part.get(), &part_options).map_err(|e| match e {
+			JsonNlpError::LimitExceeded(mut e) => {
+				// the offset within the part becomes the offset within the body
+				e.offset += part.get().as_ptr() as usize - body.as_ptr() as usize;
+				JsonNlpError::LimitExceeded(e)
+			}
+			e => e,
+		})?;
+		tokens += j.docs.iter().map(|d| d.token_list.len()).sum::<usize>();
+		let target = merged.get_or_insert_with(|| JSONNLP {
+			meta: j.meta.clone(),
+			extra: j.extra.clone(),
+			..Default::default()
+		});
+		for mut doc in j.docs {
+			if ids.contains(&doc.id) {
+				while ids.contains(&DocumentId(next)) {
+					next += 1;
+				}
+				doc.id = DocumentId(next);
+			}
+			ids.insert(doc.id);
+			target.docs.push(doc);
+		}
+	}
+	Ok(merged.unwrap_or_default())
+}
+
+/// This function returns the router of the service, parsing the requests with the default ParseOptions.
+pub fn router() -> Router {
+	router_with_options(ParseOptions::default())
+}
+
+/// This function returns the router of the service, parsing the requests with the given options, e.g. with
+/// resource limits for untrusted clients.
+pub fn router_with_options(options: ParseOptions) -> Router {
+	Router::new()
+		.route("/validate", post(validate))
+		.route("/convert", post(convert))
+		.route("/merge", post(merge))
+		.with_state(options)
+}
+
+/// This function serves the router() on an address, e.g. `"127.0.0.1:8080"`, until the process is stopped.
+pub async fn serve<A: ToSocketAddrs>(addr: A) -> Result<()> {
+	let listener = TcpListener::bind(addr).await?;
+	axum::serve(listener, router()).await?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Limit;
+
+	const PART: &str =
+		r#"{"meta":{},"docs":[{"meta":{},"id":1,"tokenList":[{"id":1,"sentence_id":1,"text":"a","lemma":"a"}]}]}"#;
+
+	fn merge_with(limits: Limits, parts: usize) -> Result<JSONNLP> {
+		let body = format!("[{}]", vec![PART; parts].join(","));
+		merge_parts(body.as_bytes(), &ParseOptions { limits, ..Default::default() })
+	}
+
+	#[test]
+	fn merge_renumbers_documents() {
+		let j = merge_with(Limits::default(), 3).expect("valid collections");
+		assert_eq!(j.docs.iter().map(|d| d.id).collect::<Vec<_>>(), [DocumentId(1), DocumentId(2), DocumentId(3)]);
+	}
+
+	#[test]
+	fn merge_limits_totals() {
+		assert!(merge_with(Limits { max_docs: Some(2), max_tokens: Some(2), ..Default::default() }, 2).is_ok());
+		match merge_with(Limits { max_docs: Some(2), ..Default::default() }, 3) {
+			Err(JsonNlpError::LimitExceeded(e)) => {
+				assert_eq!(e.limit, Limit::Docs);
+				// the third document starts in the third part, after the bracket and two parts with their commas
+				assert_eq!(e.offset, 1 + 2 * (PART.len() + 1) + PART.find(r#"{"meta":{},"id""#).expect("a document"));
+			}
+			r => panic!("expected LimitExceeded, got {:?}", r.map(|_| ())),
+		}
+		match merge_with(Limits { max_tokens: Some(2), ..Default::default() }, 3) {
+			Err(JsonNlpError::LimitExceeded(e)) => assert_eq!(e.limit, Limit::Tokens),
+			r => panic!("expected LimitExceeded, got {:?}", r.map(|_| ())),
+		}
+		assert!(merge_with(Limits { max_depth: Some(5), ..Default::default() }, 2).is_ok());
+		assert!(merge_with(Limits { max_depth: Some(4), ..Default::default() }, 2).is_err());
+	}
+
+	/// returns the response of the conversion of a body with the limits.
+	fn convert_with(limits: Limits, from: Format, body: &str) -> Response {
+		convert_with_options(ParseOptions { limits, ..Default::default() }, from, body)
+	}
+
+	/// returns the response of the conversion of a body with the options.
+	fn convert_with_options(options: ParseOptions, from: Format, body: &str) -> Response {
+		let runtime = tokio::runtime::Builder::new_current_thread().build().expect("a runtime");
+		let query = Query(ConvertQuery { from, to: Format::Json });
+		let body = Bytes::from(body.to_string());
+		runtime.block_on(convert(State(options), query, body)).unwrap_or_else(IntoResponse::into_response)
+	}
+
+	#[test]
+	fn convert_limits_all_formats() {
+		let one_doc = Limits { max_docs: Some(1), ..Default::default() };
+		let doc = r#"{"meta":{},"id":1,"tokenList":[{"id":1,"sentence_id":1,"text":"a","lemma":"a"}]}"#;
+		assert_eq!(convert_with(one_doc.clone(), Format::Ndjson, doc).status(), StatusCode::OK);
+		let ndjson = format!("{}\n{}\n", doc, doc);
+		assert_eq!(convert_with(Limits::default(), Format::Ndjson, &ndjson).status(), StatusCode::OK);
+		assert_eq!(convert_with(one_doc.clone(), Format::Ndjson, &ndjson).status(), StatusCode::PAYLOAD_TOO_LARGE);
+		let unknown = r#"{"meta":{},"id":1,"x":1}"#;
+		assert_eq!(convert_with(one_doc.clone(), Format::Ndjson, unknown).status(), StatusCode::OK);
+		let strict = convert_with_options(ParseOptions::strict(), Format::Ndjson, unknown);
+		assert_eq!(strict.status(), StatusCode::BAD_REQUEST);
+		let one_token = Limits { max_tokens: Some(1), ..Default::default() };
+		let conllu = "# text = a b\n1\ta\ta\tX\t_\t_\t0\troot\t_\t_\n2\tb\tb\tX\t_\t_\t1\tdep\t_\t_\n\n";
+		assert_eq!(convert_with(Limits::default(), Format::Conllu, conllu).status(), StatusCode::OK);
+		assert_eq!(convert_with(one_token, Format::Conllu, conllu).status(), StatusCode::PAYLOAD_TOO_LARGE);
+		let short = Limits { max_string_length: Some(2), ..Default::default() };
+		assert_eq!(convert_with(short, Format::Conllu, conllu).status(), StatusCode::PAYLOAD_TOO_LARGE);
+		assert_eq!(convert_with(one_doc, Format::Xml, "<jsonnlp/>").status(), StatusCode::BAD_REQUEST);
+	}
+}
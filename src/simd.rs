@@ -0,0 +1,16 @@
+//! Parsing of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) with the [simd-json](https://github.com/simd-lite/simd-json)
+//! backend, which from_string() and from_slice() use with the `simd` feature.
+
+use serde::de::Error;
+
+use crate::error::pointer;
+use crate::{JsonNlpError, ParseError, Result, JSONNLP};
+
+/// returns the collection parsed from JSON by simd-json, which rewrites the buffer while parsing.
+/// Errors are given as serde_json errors with the message of simd-json.
+pub(crate) fn from_buffer(mut json: Vec<u8>) -> Result<JSONNLP> {
+	let mut de = simd_json::Deserializer::from_slice(&mut json)
+		.map_err(|e| JsonNlpError::Parse(ParseError::new(String::new(), serde_json::Error::custom(e))))?;
+	serde_path_to_error::deserialize(&mut de)
+		.map_err(|e| JsonNlpError::Parse(ParseError::new(pointer(e.path()), serde_json::Error::custom(e.into_inner()))))
+}
@@ -0,0 +1,191 @@
+//! Estimation of the heap memory used by documents, for capacity planning and cache eviction.
+
+use std::mem::size_of;
+
+use serde_json::{Map, Value};
+
+use crate::{
+	AmrAttribute, AmrEdge, AmrGraph, AmrNode, Argument, Attribute, Clause, ClauseId, ConstituentParse, Coreference,
+	CoreferenceId, CoreferenceReferents, CoreferenceRepresentantive, Dependency, DependencyTree, Document, EntityId,
+	Entity, Expression, ExpressionId, LanguageTag, Meta, Paragraph, ParagraphId, Predicate, PredicateId, Prob, Relation,
+	RelationId, Scope, Sentence, SentenceId, Token, TokenFeatures, TokenId, Triple, TripleId,
+};
+
+/// the number of bytes of heap memory owned by a value, not counting the value itself.
+trait HeapSize {
+	fn heap_size(&self) -> usize;
+}
+
+macro_rules! no_heap {
+	($($t:ty),*) => {
+		$(impl HeapSize for $t {
+			fn heap_size(&self) -> usize {
+				0
+			}
+		})*
+	};
+}
+
+no_heap!(bool, u8, u64, Prob, TokenId, SentenceId, ClauseId, ParagraphId, EntityId, RelationId, TripleId,
+	ExpressionId, CoreferenceId, PredicateId);
+
+/// implements HeapSize for a struct as the sum of the heap sizes of the listed fields, which are those that own
+/// heap memory.
+macro_rules! heap_fields {
+	($($t:ty { $($field:ident),* })*) => {
+		$(impl HeapSize for $t {
+			fn heap_size(&self) -> usize {
+				0 $(+ self.$field.heap_size())*
+			}
+		})*
+	};
+}
+
+heap_fields! {
+	Meta { conforms_to, author, created, date, source, language, creator, publisher, title, description, identifier,
+		extra }
+	TokenFeatures { gender, tense, case, mood, extra }
+	Token { text, lemma, xpos, upos, entity_iob, prop_id, lang, features, shape, entity, extra }
+	Sentence { text, tokens, clauses, stype, sentiment, extra }
+	Clause { tokens, tense, mood, aspect, voice, sentiment, extra }
+	Dependency { lab, extra }
+	DependencyTree { style, dependencies, extra }
+	CoreferenceRepresentantive { tokens, extra }
+	CoreferenceReferents { tokens, extra }
+	Coreference { representative, referents, extra }
+	Scope { gov, dep, terminals, extra }
+	ConstituentParse { ctype, labeled_bracketing, scopes, extra }
+	Expression { etype, dependency, tokens, extra }
+	Paragraph { tokens, sentences, extra }
+	Attribute { lab, val, extra }
+	Entity { label, etype, url, tokens, sentiment, attributes, extra }
+	Relation { label, rtype, url, tokens, sentiment, attributes, extra }
+	Triple { clause_id, sentence_id, extra }
+	Argument { label, tokens, extra }
+	Predicate { lemma, frame, arguments, extra }
+	AmrNode { id, concept, tokens, extra }
+	AmrEdge { source, role, target, extra }
+	AmrAttribute { source, role, value, tokens, extra }
+	AmrGraph { top, nodes, edges, attributes, extra }
+}
+
+impl HeapSize for String {
+	fn heap_size(&self) -> usize {
+		self.capacity()
+	}
+}
+
+impl HeapSize for LanguageTag {
+	fn heap_size(&self) -> usize {
+		self.as_str().len()
+	}
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+	fn heap_size(&self) -> usize {
+		self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+	}
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+	fn heap_size(&self) -> usize {
+		self.as_ref().map_or(0, HeapSize::heap_size)
+	}
+}
+
+impl<T: HeapSize> HeapSize for Box<T> {
+	fn heap_size(&self) -> usize {
+		size_of::<T>() + (**self).heap_size()
+	}
+}
+
+impl HeapSize for Map<String, Value> {
+	fn heap_size(&self) -> usize {
+		// the entries of the tree nodes, ignoring their fill factor
+		self.iter().map(|(k, v)| size_of::<(String, Value)>() + k.heap_size() + v.heap_size()).sum()
+	}
+}
+
+impl HeapSize for Value {
+	fn heap_size(&self) -> usize {
+		match self {
+			Value::String(s) => s.heap_size(),
+			Value::Array(a) => a.heap_size(),
+			Value::Object(o) => o.heap_size(),
+			Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+		}
+	}
+}
+
+/// This struct contains the estimated heap memory of the layers of a document in bytes, see
+/// Document::estimated_size(). A layer includes the memory of its vector and of the strings, ID lists, and `extra`
+/// maps of its items. The estimate ignores allocator overhead and is approximate for the `extra` maps.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentSize {
+	/// the size of the Document struct itself, which is not part of a layer.
+	pub document: usize,
+	pub meta: usize,
+	pub text: usize,
+	pub tokens: usize,
+	pub clauses: usize,
+	pub sentences: usize,
+	pub paragraphs: usize,
+	pub dependency_trees: usize,
+	pub coreferences: usize,
+	pub constituents: usize,
+	pub expressions: usize,
+	pub entities: usize,
+	pub relations: usize,
+	pub triples: usize,
+	pub semantic_roles: usize,
+	pub amr_graphs: usize,
+	pub extra: usize,
+}
+
+impl DocumentSize {
+	/// returns the estimated size of the whole document.
+	pub fn total(&self) -> usize {
+		self.document
+			+ self.meta
+			+ self.text
+			+ self.tokens
+			+ self.clauses
+			+ self.sentences
+			+ self.paragraphs
+			+ self.dependency_trees
+			+ self.coreferences
+			+ self.constituents
+			+ self.expressions
+			+ self.entities
+			+ self.relations
+			+ self.triples
+			+ self.semantic_roles
+			+ self.amr_graphs
+			+ self.extra
+	}
+}
+
+impl Document {
+	/// returns the approximate heap memory used by the document per layer.
+	pub fn estimated_size(&self) -> DocumentSize {
+		DocumentSize {
+			document: size_of::<Document>(),
+			meta: self.meta.heap_size(),
+			text: self.text.heap_size(),
+			tokens: self.token_list.heap_size(),
+			clauses: self.clauses.heap_size(),
+			sentences: self.sentences.heap_size(),
+			paragraphs: self.paragraphs.heap_size(),
+			dependency_trees: self.dependency_trees.heap_size(),
+			coreferences: self.coreferences.heap_size(),
+			constituents: self.constituents.heap_size(),
+			expressions: self.expressions.heap_size(),
+			entities: self.entities.heap_size(),
+			relations: self.relations.heap_size(),
+			triples: self.triples.heap_size(),
+			semantic_roles: self.semantic_roles.heap_size(),
+			amr_graphs: self.amr_graphs.heap_size(),
+			extra: self.extra.heap_size(),
+		}
+	}
+}
@@ -0,0 +1,311 @@
+//! Conversion between [spaCy](https://spacy.io/) documents and [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::conllu::{feats, parse_feats};
+use crate::offsets::{char_boundaries, slice_chars};
+use crate::{
+	Dependency, DependencyTree, Document, DocumentId, Entity, EntityId, JsonNlpError, RawId, Result, Sentence,
+	SentenceId, Token, TokenId,
+};
+
+/// This struct contains the output of spaCy's `Doc.to_json()`.
+#[derive(Deserialize)]
+struct SpacyDoc {
+	text: String,
+	#[serde(default)]
+	ents: Vec<SpacySpan>,
+	#[serde(default)]
+	sents: Vec<SpacySpan>,
+	tokens: Vec<SpacyToken>,
+	#[serde(default)]
+	cats: Map<String, Value>,
+}
+
+/// This struct contains a character span of an entity or sentence.
+#[derive(Deserialize)]
+struct SpacySpan {
+	start: u64,
+	end: u64,
+	#[serde(default)]
+	label: String,
+}
+
+/// This struct contains a token with its character offsets and annotations.
+#[derive(Deserialize)]
+struct SpacyToken {
+	id: RawId,
+	start: u64,
+	end: u64,
+	#[serde(default)]
+	tag: String,
+	#[serde(default)]
+	pos: String,
+	#[serde(default)]
+	morph: String,
+	#[serde(default)]
+	lemma: String,
+	#[serde(default)]
+	dep: String,
+	#[serde(default)]
+	head: Option<RawId>,
+}
+
+/// This function converts the JSON output of spaCy's `Doc.to_json()` to a Document. Tokens keep their character
+/// offsets, tags, lemmas, and morphological features, and their IDs are the spaCy token indexes plus one.
+/// The dependency heads form one dependency tree per sentence, with spaCy's self-attached root tokens attached to
+/// the root 0, and the entity spans become entities and the tokens' `entity_iob` and `entity` tags.
+/// Without `sents` all tokens form one sentence. Text categories are kept in the document's `extra` map as `cats`.
+pub fn from_spacy_json(json: &str) -> Result<Document> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let spacy: SpacyDoc = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let boundaries = char_boundaries(&spacy.text);
+	let sentences = if spacy.sents.is_empty() {
+		vec![SpacySpan { start: 0, end: boundaries.len() as u64 - 1, label: String::new() }]
+	} else {
+		spacy.sents
+	};
+	// the sentences ordered by their start, in which the sentence of every token is looked up once
+	let mut order: Vec<usize> = (0..sentences.len()).collect();
+	order.sort_by_key(|i| sentences[*i].start);
+	let sentence_of = |start: u64| {
+		let i = *order.get(order.partition_point(|i| sentences[*i].start <= start).checked_sub(1)?)?;
+		(start < sentences[i].end).then_some(i)
+	};
+	let token_sentences: Vec<Option<usize>> = spacy.tokens.iter().map(|t| sentence_of(t.start)).collect();
+	let mut sentence_tokens: Vec<Vec<&SpacyToken>> = sentences.iter().map(|_| Vec::new()).collect();
+	for (t, s) in spacy.tokens.iter().zip(&token_sentences) {
+		if let Some(s) = s {
+			sentence_tokens[*s].push(t);
+		}
+	}
+
+	let mut doc = Document {
+		id: DocumentId(1),
+		text: spacy.text.clone(),
+		..Default::default()
+	};
+	for (i, t) in spacy.tokens.iter().enumerate() {
+		let text = slice_chars(&spacy.text, &boundaries, t.start, t.end).ok_or_else(|| {
+			JsonNlpError::Format(format!("token {} has the offsets {}..{} outside of the text", t.id, t.start, t.end))
+		})?;
+		let mut features = parse_feats(&t.morph);
+		if spacy.tokens.get(i + 1).is_some_and(|next| next.start == t.end) {
+			features.spaceafter = Some(false);
+		}
+		doc.token_list.push(Token {
+			id: TokenId(t.id + 1),
+			sentence_id: SentenceId(token_sentences[i].map_or(0, |s| s as RawId + 1)),
+			text: text.to_string(),
+			lemma: t.lemma.clone(),
+			xpos: t.tag.clone(),
+			upos: t.pos.clone(),
+			char_offset_begin: Some(t.start),
+			char_offset_end: Some(t.end),
+			features: features.boxed(),
+			..Default::default()
+		});
+	}
+
+	for (i, (s, tokens)) in sentences.iter().zip(&sentence_tokens).enumerate() {
+		let id = SentenceId(i as RawId + 1);
+		let mut tree = DependencyTree { sentence_id: Some(id), ..Default::default() };
+		for t in tokens {
+			if let Some(head) = t.head {
+				tree.dependencies.push(Dependency {
+					lab: t.dep.clone(),
+					gov: TokenId(if head == t.id { 0 } else { head + 1 }),
+					dep: TokenId(t.id + 1),
+					..Default::default()
+				});
+			}
+		}
+		if !tree.dependencies.is_empty() {
+			doc.dependency_trees.push(tree);
+		}
+		let ids: Vec<TokenId> = tokens.iter().map(|t| TokenId(t.id + 1)).collect();
+		doc.sentences.push(Sentence {
+			id,
+			text: slice_chars(&spacy.text, &boundaries, s.start, s.end).unwrap_or_default().to_string(),
+			token_from: ids.first().copied(),
+			token_to: ids.last().copied(),
+			tokens: ids,
+			..Default::default()
+		});
+	}
+
+	if !spacy.ents.is_empty() {
+		for t in doc.token_list.iter_mut() {
+			t.entity_iob = "O".to_string();
+		}
+	}
+	for e in &spacy.ents {
+		let id = EntityId(doc.entities.len() as RawId + 1);
+		let mut entity = Entity {
+			id,
+			label: slice_chars(&spacy.text, &boundaries, e.start, e.end).unwrap_or_default().to_string(),
+			etype: e.label.clone(),
+			..Default::default()
+		};
+		for t in doc.token_list.iter_mut().filter(|t| t.char_offset_begin.is_some_and(|b| b >= e.start && b < e.end)) {
+			t.entity_iob = if entity.tokens.is_empty() { "B" } else { "I" }.to_string();
+			t.entity = e.label.clone();
+			entity.tokens.push(t.id);
+		}
+		entity.token_from = entity.tokens.first().copied();
+		entity.token_to = entity.tokens.last().copied();
+		doc.entities.push(entity);
+	}
+
+	if !spacy.cats.is_empty() {
+		doc.extra.insert("cats".to_string(), Value::Object(spacy.cats));
+	}
+	Ok(doc)
+}
+
+/// This function converts a Document to spaCy's training annotation, the dictionary accepted by
+/// `Example.from_dict()` together with its `text`. Tokens are listed with `words` and `spaces`, from
+/// which the text is rebuilt, their `tags`, `pos`, `morphs`, and `lemmas`, and their absolute `heads`
+/// and `deps`, with root tokens heading themselves and `null` for tokens without a dependency. `sent_starts`
+/// marks the first token of every sentence, and `entities` lists `[start, end, label]` character spans in
+/// the rebuilt text, taken from the entity layer or else from the tokens' `entity_iob` tags.
+pub fn to_spacy_training(doc: &Document) -> Value {
+	let groups = doc.sentence_groups();
+	let tokens: Vec<&Token> = groups.iter().flat_map(|g| g.2.iter().copied()).collect();
+	let index: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+	let mut deps: HashMap<TokenId, &Dependency> = HashMap::new();
+	for tree in &doc.dependency_trees {
+		for d in &tree.dependencies {
+			deps.entry(d.dep).or_insert(d);
+		}
+	}
+
+	let mut text = String::new();
+	// the length of the text in characters
+	let mut length = 0;
+	let mut offsets = Vec::new();
+	let mut spaces = Vec::new();
+	for (i, t) in tokens.iter().enumerate() {
+		let begin = length;
+		text.push_str(&t.text);
+		length += t.text.chars().count();
+		offsets.push((begin, length));
+		let space = match (t.char_offset_end, tokens.get(i + 1).and_then(|n| n.char_offset_begin)) {
+			_ if i + 1 == tokens.len() => false,
+			(Some(end), Some(next)) => next > end,
+			_ => t.features().spaceafter != Some(false),
+		};
+		if space {
+			text.push(' ');
+			length += 1;
+		}
+		spaces.push(space);
+	}
+
+	let heads: Vec<Value> = tokens.iter().enumerate().map(|(i, t)| match deps.get(&t.id) {
+		Some(d) if d.is_root() => json!(i),
+		Some(d) => index.get(&d.gov).map_or(Value::Null, |h| json!(h)),
+		None => Value::Null,
+	}).collect();
+	let labels: Vec<Value> = tokens.iter().map(|t| deps.get(&t.id).map_or(Value::Null, |d| json!(d.lab))).collect();
+	let mut sent_starts = vec![false; tokens.len()];
+	let mut start = 0;
+	for g in &groups {
+		if let Some(s) = sent_starts.get_mut(start) {
+			*s = true;
+		}
+		start += g.2.len();
+	}
+
+	// entity spans as first and last token index
+	let mut spans: Vec<(usize, usize, &str)> = Vec::new();
+	if doc.entities.is_empty() {
+		for (i, t) in tokens.iter().enumerate() {
+			match (t.entity_iob.as_str(), spans.last_mut()) {
+				("I", Some(span)) if span.1 + 1 == i && span.2 == t.entity => span.1 = i,
+				("B", _) | ("I", _) => spans.push((i, i, t.entity.as_str())),
+				_ => {}
+			}
+		}
+	} else {
+		for e in &doc.entities {
+			let covered: Vec<usize> = tokens.iter().enumerate().filter(|(_, t)| e.covers(t.id)).map(|(i, _)| i).collect();
+			if let (Some(first), Some(last)) = (covered.first(), covered.last()) {
+				spans.push((*first, *last, e.etype.as_str()));
+			}
+		}
+	}
+	let entities: Vec<Value> = spans.iter().map(|(first, last, label)| json!([offsets[*first].0, offsets[*last].1, label])).collect();
+
+	let mut training = json!({
+		"text": text,
+		"words": tokens.iter().map(|t| t.text.as_str()).collect::<Vec<_>>(),
+		"spaces": spaces,
+		"tags": tokens.iter().map(|t| t.xpos.as_str()).collect::<Vec<_>>(),
+		"pos": tokens.iter().map(|t| t.upos.as_str()).collect::<Vec<_>>(),
+		"morphs": tokens.iter().map(|t| match feats(t.features()) {
+			f if f == "_" => String::new(),
+			f => f,
+		}).collect::<Vec<_>>(),
+		"lemmas": tokens.iter().map(|t| t.lemma.as_str()).collect::<Vec<_>>(),
+		"heads": heads,
+		"deps": labels,
+		"sent_starts": sent_starts,
+		"entities": entities,
+	});
+	if let Some(cats) = doc.extra.get("cats") {
+		training["cats"] = cats.clone();
+	}
+	training
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SPACY: &str = r#"{"text":"Zoë naps.","ents":[{"start":0,"end":3,"label":"PERSON"}],
+		"sents":[{"start":0,"end":9}],"tokens":[
+		{"id":0,"start":0,"end":3,"tag":"NNP","pos":"PROPN","lemma":"Zoë","dep":"nsubj","head":1},
+		{"id":1,"start":4,"end":8,"tag":"VBZ","pos":"VERB","morph":"Number=Sing","lemma":"nap","dep":"ROOT","head":1},
+		{"id":2,"start":8,"end":9,"tag":".","pos":"PUNCT","lemma":".","dep":"punct","head":1}]}"#;
+
+	#[test]
+	fn spacy_training() {
+		let doc = from_spacy_json(SPACY).expect("valid spaCy JSON");
+		let training = to_spacy_training(&doc);
+		assert_eq!(training["text"], "Zoë naps.");
+		assert_eq!(training["words"], json!(["Zoë", "naps", "."]));
+		assert_eq!(training["spaces"], json!([true, false, false]));
+		assert_eq!(training["heads"], json!([1, 1, 1]));
+		assert_eq!(training["deps"], json!(["nsubj", "ROOT", "punct"]));
+		assert_eq!(training["sent_starts"], json!([true, false, false]));
+		assert_eq!(training["entities"], json!([[0, 3, "PERSON"]]));
+	}
+
+	#[test]
+	fn spacy_sentences() {
+		let json = r#"{"text":"Hi. Bye","sents":[{"start":4,"end":7},{"start":0,"end":3}],"tokens":[
+			{"id":0,"start":0,"end":2,"dep":"ROOT","head":0},{"id":1,"start":2,"end":3,"dep":"punct","head":0},
+			{"id":2,"start":4,"end":7,"dep":"ROOT","head":2}]}"#;
+		let doc = from_spacy_json(json).expect("valid spaCy JSON");
+		let sentences: Vec<_> = doc.token_list.iter().map(|t| t.sentence_id).collect();
+		assert_eq!(sentences, [SentenceId(2), SentenceId(2), SentenceId(1)]);
+		assert_eq!(doc.sentences[1].tokens, [TokenId(1), TokenId(2)]);
+		assert_eq!(doc.sentences[1].text, "Hi.");
+		assert_eq!(doc.dependency_trees[1].dependencies[1].gov, TokenId(1));
+		assert_eq!(doc.token_list[1].features().spaceafter, None);
+		assert_eq!(doc.token_list[0].features().spaceafter, Some(false));
+	}
+
+	#[test]
+	fn spacy_errors() {
+		assert!(from_spacy_json(r#"{"text":"Hi","tokens":[{"id":0,"start":0,"end":3}]}"#).is_err());
+		assert!(from_spacy_json(r#"{"text":"Hi","tokens":[{"id":0,"start":0}]}"#).is_err());
+		assert!(from_spacy_json(r#"{"text":"Hi"}"#).is_err());
+	}
+}
\ No newline at end of file
@@ -0,0 +1,267 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) collections to [SQLite](https://sqlite.org/)
+//! databases with one normalized table per annotation layer, so that corpora can be queried with plain SQL, e.g.
+//!
+//! ```sql
+//! SELECT e.type, t.lemma, COUNT(*) FROM entities e
+//! JOIN entity_tokens et ON et.doc_id = e.doc_id AND et.entity_id = e.id
+//! JOIN tokens t ON t.doc_id = et.doc_id AND t.id = et.token_id
+//! GROUP BY e.type, t.lemma;
+//! ```
+//!
+//! The tables are:
+//!
+//! - `documents(id, title, author, date, source, language, text)`
+//! - `sentences(doc_id, id, text, type, sentiment)`
+//! - `tokens(doc_id, id, sentence_id, text, lemma, xpos, upos, entity_iob, begin, end)`
+//! - `dependencies(doc_id, tree, sentence_id, style, label, governor, dependent, prob)`, with the governor of roots
+//!   NULL
+//! - `entities(doc_id, id, type, label, url, text, head, sentiment)` and `entity_tokens(doc_id, entity_id, token_id)`
+//! - `coreferences(doc_id, id)`, `mentions(doc_id, coreference_id, mention, representative, head, prob)`, and
+//!   `mention_tokens(doc_id, coreference_id, mention, token_id)`, with the representative as mention 0.
+//!
+//! Items are identified by the ID of their document and their own ID, and refer to each other by foreign keys.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use rusqlite::{params, Connection, Transaction};
+
+use crate::{Document, JsonNlpError, Prob, Result, JSONNLP};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS documents (
+	id INTEGER PRIMARY KEY,
+	title TEXT,
+	author TEXT,
+	date TEXT,
+	source TEXT,
+	language TEXT,
+	text TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS sentences (
+	doc_id INTEGER NOT NULL REFERENCES documents (id),
+	id INTEGER NOT NULL,
+	text TEXT,
+	type TEXT,
+	sentiment TEXT,
+	PRIMARY KEY (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS tokens (
+	doc_id INTEGER NOT NULL REFERENCES documents (id),
+	id INTEGER NOT NULL,
+	sentence_id INTEGER NOT NULL,
+	text TEXT NOT NULL,
+	lemma TEXT,
+	xpos TEXT,
+	upos TEXT,
+	entity_iob TEXT,
+	begin INTEGER,
+	end INTEGER,
+	PRIMARY KEY (doc_id, id),
+	FOREIGN KEY (doc_id, sentence_id) REFERENCES sentences (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS dependencies (
+	doc_id INTEGER NOT NULL REFERENCES documents (id),
+	tree INTEGER NOT NULL,
+	sentence_id INTEGER,
+	style TEXT,
+	label TEXT NOT NULL,
+	governor INTEGER,
+	dependent INTEGER NOT NULL,
+	prob REAL,
+	FOREIGN KEY (doc_id, sentence_id) REFERENCES sentences (doc_id, id),
+	FOREIGN KEY (doc_id, governor) REFERENCES tokens (doc_id, id),
+	FOREIGN KEY (doc_id, dependent) REFERENCES tokens (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS entities (
+	doc_id INTEGER NOT NULL REFERENCES documents (id),
+	id INTEGER NOT NULL,
+	type TEXT,
+	label TEXT,
+	url TEXT,
+	text TEXT,
+	head INTEGER,
+	sentiment TEXT,
+	PRIMARY KEY (doc_id, id),
+	FOREIGN KEY (doc_id, head) REFERENCES tokens (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS entity_tokens (
+	doc_id INTEGER NOT NULL,
+	entity_id INTEGER NOT NULL,
+	token_id INTEGER NOT NULL,
+	PRIMARY KEY (doc_id, entity_id, token_id),
+	FOREIGN KEY (doc_id, entity_id) REFERENCES entities (doc_id, id),
+	FOREIGN KEY (doc_id, token_id) REFERENCES tokens (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS coreferences (
+	doc_id INTEGER NOT NULL REFERENCES documents (id),
+	id INTEGER NOT NULL,
+	PRIMARY KEY (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS mentions (
+	doc_id INTEGER NOT NULL,
+	coreference_id INTEGER NOT NULL,
+	mention INTEGER NOT NULL,
+	representative INTEGER NOT NULL,
+	head INTEGER NOT NULL,
+	prob REAL,
+	PRIMARY KEY (doc_id, coreference_id, mention),
+	FOREIGN KEY (doc_id, coreference_id) REFERENCES coreferences (doc_id, id),
+	FOREIGN KEY (doc_id, head) REFERENCES tokens (doc_id, id)
+);
+CREATE TABLE IF NOT EXISTS mention_tokens (
+	doc_id INTEGER NOT NULL,
+	coreference_id INTEGER NOT NULL,
+	mention INTEGER NOT NULL,
+	token_id INTEGER NOT NULL,
+	FOREIGN KEY (doc_id, coreference_id, mention) REFERENCES mentions (doc_id, coreference_id, mention),
+	FOREIGN KEY (doc_id, token_id) REFERENCES tokens (doc_id, id)
+);
+";
+
+fn error(e: rusqlite::Error) -> JsonNlpError {
+	JsonNlpError::Format(format!("SQLite: {}", e))
+}
+
+/// returns the value of an optional text column, NULL if the text is empty.
+fn text(s: &str) -> Option<&str> {
+	if s.is_empty() {
+		None
+	} else {
+		Some(s)
+	}
+}
+
+fn prob(p: Option<Prob>) -> Option<f64> {
+	p.map(Prob::get)
+}
+
+/// inserts the rows of a document into the tables.
+fn insert_document(tx: &Transaction, doc: &Document, j: &JSONNLP) -> rusqlite::Result<()> {
+	let d = u64::from(doc.id) as i64;
+	let meta = |own: &str, shared: &str| text(own).or_else(|| text(shared)).map(String::from);
+	tx.execute(
+		"INSERT INTO documents (id, title, author, date, source, language, text) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+		params![
+			d,
+			meta(&doc.meta.title, &j.meta.title),
+			meta(&doc.meta.author, &j.meta.author),
+			meta(&doc.meta.date, &j.meta.date),
+			meta(&doc.meta.source, &j.meta.source),
+			meta(doc.meta.language.as_str(), j.meta.language.as_str()),
+			doc.text,
+		],
+	)?;
+
+	// sentences referenced only by tokens are inserted without text, so that every token has its sentence
+	let mut sentences =
+		tx.prepare_cached("INSERT INTO sentences (doc_id, id, text, type, sentiment) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+	let listed: BTreeSet<u64> = doc.sentences.iter().map(|s| s.id.into()).collect();
+	for s in &doc.sentences {
+		sentences.execute(params![d, u64::from(s.id) as i64, text(&s.text), text(&s.stype), text(&s.sentiment)])?;
+	}
+	let referenced: BTreeSet<u64> = doc.token_list.iter().map(|t| t.sentence_id.into()).collect();
+	for id in referenced.difference(&listed) {
+		sentences.execute(params![d, *id as i64, None::<&str>, None::<&str>, None::<&str>])?;
+	}
+
+	let mut tokens = tx.prepare_cached(
+		"INSERT INTO tokens (doc_id, id, sentence_id, text, lemma, xpos, upos, entity_iob, begin, end) \
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+	)?;
+	for t in &doc.token_list {
+		tokens.execute(params![
+			d,
+			u64::from(t.id) as i64,
+			u64::from(t.sentence_id) as i64,
+			t.text,
+			text(&t.lemma),
+			text(&t.xpos),
+			text(&t.upos),
+			text(&t.entity_iob),
+			t.char_offset_begin.map(|o| o as i64),
+			t.char_offset_end.map(|o| o as i64),
+		])?;
+	}
+
+	let mut dependencies = tx.prepare_cached(
+		"INSERT INTO dependencies (doc_id, tree, sentence_id, style, label, governor, dependent, prob) \
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+	)?;
+	for (i, tree) in doc.dependency_trees.iter().enumerate() {
+		let sentence = tree.sentence_id.map(|s| u64::from(s) as i64);
+		for dep in &tree.dependencies {
+			let governor = u64::from(dep.gov);
+			dependencies.execute(params![
+				d,
+				i as i64,
+				sentence,
+				text(&tree.style),
+				dep.lab,
+				if governor == 0 { None } else { Some(governor as i64) },
+				u64::from(dep.dep) as i64,
+				prob(dep.prob),
+			])?;
+		}
+	}
+
+	let mut entities = tx.prepare_cached(
+		"INSERT INTO entities (doc_id, id, type, label, url, text, head, sentiment) \
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+	)?;
+	let mut entity_tokens =
+		tx.prepare_cached("INSERT OR IGNORE INTO entity_tokens (doc_id, entity_id, token_id) VALUES (?1, ?2, ?3)")?;
+	for e in &doc.entities {
+		let id = u64::from(e.id) as i64;
+		entities.execute(params![
+			d,
+			id,
+			text(&e.etype),
+			text(&e.label),
+			text(&e.url),
+			doc.entity_text(e),
+			e.head.map(|t| u64::from(t) as i64),
+			text(&e.sentiment),
+		])?;
+		for t in doc.token_list.iter().filter(|t| e.covers(t.id)) {
+			entity_tokens.execute(params![d, id, u64::from(t.id) as i64])?;
+		}
+	}
+
+	let mut coreferences = tx.prepare_cached("INSERT INTO coreferences (doc_id, id) VALUES (?1, ?2)")?;
+	let mut mentions = tx.prepare_cached(
+		"INSERT INTO mentions (doc_id, coreference_id, mention, representative, head, prob) \
+		 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+	)?;
+	let mut mention_tokens = tx.prepare_cached(
+		"INSERT INTO mention_tokens (doc_id, coreference_id, mention, token_id) VALUES (?1, ?2, ?3, ?4)",
+	)?;
+	for c in &doc.coreferences {
+		let id = u64::from(c.id) as i64;
+		coreferences.execute(params![d, id])?;
+		let representative = (&c.representative.tokens, c.representative.head, None);
+		let referents = c.referents.iter().map(|r| (&r.tokens, r.head, prob(r.prob)));
+		for (mention, (tokens, head, p)) in std::iter::once(representative).chain(referents).enumerate() {
+			mentions.execute(params![d, id, mention as i64, mention == 0, u64::from(head) as i64, p])?;
+			for t in tokens {
+				mention_tokens.execute(params![d, id, mention as i64, u64::from(*t) as i64])?;
+			}
+		}
+	}
+	Ok(())
+}
+
+/// This function writes the documents of a collection to the SQLite database at a path, creating the database and
+/// its tables if they do not exist. Several collections can be written to the same database if their document IDs
+/// differ. The documents are written in one transaction, so nothing is written if a document fails, e.g. because
+/// its ID is already taken. Foreign keys are declared but not enforced while writing, and references to missing
+/// items of invalid documents are listed by `PRAGMA foreign_key_check`.
+pub fn to_sqlite<P: AsRef<Path>>(j: &JSONNLP, path: P) -> Result<()> {
+	let mut conn = Connection::open(path).map_err(error)?;
+	conn.execute_batch(SCHEMA).map_err(error)?;
+	let tx = conn.transaction().map_err(error)?;
+	for doc in &j.docs {
+		insert_document(&tx, doc, j).map_err(error)?;
+	}
+	tx.commit().map_err(error)
+}
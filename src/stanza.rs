@@ -0,0 +1,257 @@
+//! Conversion of [Stanza](https://stanfordnlp.github.io/stanza/) documents to [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::conllu::parse_feats;
+use crate::{
+	Dependency, DependencyTree, Document, DocumentId, Entity, EntityId, Expression, ExpressionId, JsonNlpError, RawId,
+	Result, Sentence, SentenceId, Token, TokenId,
+};
+
+/// This enum contains the ID of a word, or the range of words of a multi-word token.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StanzaId {
+	Word(RawId),
+	Range(Vec<RawId>),
+}
+
+/// This struct contains a word or multi-word token of Stanza's `Document.to_dict()` output.
+#[derive(Deserialize)]
+struct StanzaWord {
+	id: StanzaId,
+	text: String,
+	#[serde(default)]
+	lemma: String,
+	#[serde(default)]
+	upos: String,
+	#[serde(default)]
+	xpos: String,
+	#[serde(default)]
+	feats: String,
+	#[serde(default)]
+	head: Option<RawId>,
+	#[serde(default)]
+	deprel: String,
+	#[serde(default)]
+	ner: String,
+	#[serde(default)]
+	misc: String,
+	#[serde(default)]
+	start_char: Option<u64>,
+	#[serde(default)]
+	end_char: Option<u64>,
+}
+
+impl StanzaWord {
+	/// returns the character offsets, given directly or in the MISC column.
+	fn offsets(&self) -> Option<(u64, u64)> {
+		let misc = |key: &str| self.misc.split('|').find_map(|m| m.strip_prefix(key)?.strip_prefix('=')?.parse().ok());
+		Some((self.start_char.or_else(|| misc("start_char"))?, self.end_char.or_else(|| misc("end_char"))?))
+	}
+
+	fn space_after(&self) -> bool {
+		!self.misc.split('|').any(|m| m == "SpaceAfter=No")
+	}
+}
+
+/// This function converts the output of Stanza's `Document.to_dict()`, a list of sentences with their words,
+/// to a Document. Words become tokens numbered through the document, with their lemma, UPOS, XPOS, and FEATS,
+/// and their heads and relations form one dependency tree per sentence. The document text is rebuilt from the
+/// character offsets, or from the words and `SpaceAfter=No` if there are none. The words of a multi-word token
+/// carry no character offsets; the multi-word token becomes an Expression of type `mwt` with its surface text in the
+/// `extra` map. BIOES entity tags are converted to IOB2 `entity_iob` tags and entities.
+pub fn from_stanza_json(json: &str) -> Result<Document> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let sentences: Vec<Vec<StanzaWord>> = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let mut doc = Document { id: DocumentId(1), ..Default::default() };
+	// the text length in characters, and whether a space must precede the next token
+	let mut length = 0;
+	let mut space = false;
+	for (i, words) in sentences.iter().enumerate() {
+		let sentence_id = SentenceId(i as RawId + 1);
+		let first = doc.token_list.len() as RawId + 1;
+		let mut tree = DependencyTree { sentence_id: Some(sentence_id), ..Default::default() };
+		// byte range of the sentence in the text
+		let mut sentence_span: Option<(usize, usize)> = None;
+		// the words of the current multi-word token still to come, and its entity tag
+		let mut mwt_words = 0;
+		let mut mwt_len = 0;
+		let mut mwt_ner = String::new();
+		for w in words {
+			let surface = match w.id {
+				StanzaId::Range(ref range) => {
+					let (from, to) = match range.as_slice() {
+						[from, to] if from <= to => (*from, *to),
+						_ => return Err(JsonNlpError::Format(format!("invalid multi-word token ID {:?} in sentence {}", range, i + 1))),
+					};
+					mwt_words = to - from + 1;
+					mwt_len = mwt_words;
+					mwt_ner = w.ner.clone();
+					doc.expressions.push(Expression {
+						id: ExpressionId(doc.expressions.len() as RawId + 1),
+						etype: "mwt".to_string(),
+						token_from: Some(TokenId(first + from - 1)),
+						token_to: Some(TokenId(first + to - 1)),
+						tokens: (from..=to).map(|id| TokenId(first + id - 1)).collect(),
+						..Default::default()
+					});
+					doc.expressions.last_mut().expect("an expression was just added")
+						.extra.insert("text".to_string(), Value::String(w.text.clone()));
+					true
+				}
+				StanzaId::Word(_) => mwt_words == 0,
+			};
+
+			let mut offsets = None;
+			if surface {
+				let (begin, end) = match w.offsets() {
+					Some((begin, end)) if begin >= length => (begin, end),
+					_ => {
+						let begin = length + space as u64;
+						(begin, begin + w.text.chars().count() as u64)
+					}
+				};
+				for _ in length..begin {
+					doc.text.push(' ');
+				}
+				let byte_begin = doc.text.len();
+				doc.text.push_str(&w.text);
+				length = begin + w.text.chars().count() as u64;
+				space = w.space_after();
+				offsets = Some((begin, end));
+				sentence_span = Some((sentence_span.map_or(byte_begin, |s| s.0), doc.text.len()));
+			}
+
+			let id = match w.id {
+				StanzaId::Range(_) => continue,
+				StanzaId::Word(id) => id,
+			};
+			let ner = if mwt_words > 0 { mwt_ner.clone() } else { w.ner.clone() };
+			// the words after the first one continue the entity of their multi-word token
+			let continues = mwt_words > 0 && mwt_words < mwt_len;
+			mwt_words = mwt_words.saturating_sub(1);
+			let mut features = parse_feats(&w.feats);
+			if surface && !w.space_after() {
+				features.spaceafter = Some(false);
+			}
+			let token_id = TokenId(first + id - 1);
+			doc.token_list.push(Token {
+				id: token_id,
+				sentence_id,
+				text: w.text.clone(),
+				lemma: w.lemma.clone(),
+				upos: w.upos.clone(),
+				xpos: w.xpos.clone(),
+				char_offset_begin: offsets.map(|o| o.0),
+				char_offset_end: offsets.map(|o| o.1),
+				entity: ner.get(2..).unwrap_or_default().to_string(),
+				entity_iob: match ner.get(..1) {
+					Some("B") | Some("S") if !continues => "B",
+					Some("B") | Some("S") => "I",
+					Some("I") | Some("E") => "I",
+					_ => "O",
+				}.to_string(),
+				features: features.boxed(),
+				..Default::default()
+			});
+			if let Some(head) = w.head {
+				tree.dependencies.push(Dependency {
+					lab: w.deprel.clone(),
+					gov: TokenId(if head == 0 { 0 } else { first + head - 1 }),
+					dep: token_id,
+					..Default::default()
+				});
+			}
+		}
+
+		let ids: Vec<TokenId> = doc.token_list[first as usize - 1..].iter().map(|t| t.id).collect();
+		doc.sentences.push(Sentence {
+			id: sentence_id,
+			text: sentence_span.map(|(b, e)| doc.text[b..e].to_string()).unwrap_or_default(),
+			token_from: ids.first().copied(),
+			token_to: ids.last().copied(),
+			tokens: ids,
+			..Default::default()
+		});
+		if !tree.dependencies.is_empty() {
+			doc.dependency_trees.push(tree);
+		}
+	}
+
+	let mut entities: Vec<Entity> = Vec::new();
+	let mut previous: Option<(SentenceId, &str)> = None;
+	for t in &doc.token_list {
+		match t.entity_iob.as_str() {
+			"I" if previous == Some((t.sentence_id, t.entity.as_str())) => {
+				let e = entities.last_mut().expect("an entity was started");
+				e.tokens.push(t.id);
+				e.token_to = Some(t.id);
+				e.label.push(' ');
+				e.label.push_str(&t.text);
+			}
+			"B" | "I" => entities.push(Entity {
+				id: EntityId(entities.len() as RawId + 1),
+				label: t.text.clone(),
+				etype: t.entity.clone(),
+				token_from: Some(t.id),
+				token_to: Some(t.id),
+				tokens: vec![t.id],
+				..Default::default()
+			}),
+			_ => {}
+		}
+		previous = if t.entity_iob == "O" { None } else { Some((t.sentence_id, t.entity.as_str())) };
+	}
+	doc.entities = entities;
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const STANZA: &str = r#"[[
+		{"id":1,"text":"Voy","lemma":"ir","upos":"VERB","head":0,"deprel":"root","ner":"O","start_char":0,"end_char":3},
+		{"id":[2,3],"text":"al","ner":"O","start_char":4,"end_char":6},
+		{"id":2,"text":"a","lemma":"a","upos":"ADP","head":4,"deprel":"case"},
+		{"id":3,"text":"el","lemma":"el","upos":"DET","feats":"Gender=Masc","head":4,"deprel":"det"},
+		{"id":4,"text":"Nueva","upos":"PROPN","head":1,"deprel":"obl","ner":"B-LOC","start_char":7,"end_char":12},
+		{"id":5,"text":"York","head":4,"deprel":"flat","ner":"E-LOC","start_char":13,"end_char":17,
+			"misc":"SpaceAfter=No"},
+		{"id":6,"text":".","head":1,"deprel":"punct","ner":"O","misc":"start_char=17|end_char=18"}]]"#;
+
+	#[test]
+	fn stanza_import() {
+		let doc = from_stanza_json(STANZA).expect("valid Stanza output");
+		assert_eq!(doc.text, "Voy al Nueva York.");
+		assert_eq!(doc.sentences[0].text, doc.text);
+		let offsets: Vec<_> = doc.token_list.iter().map(|t| (t.char_offset_begin, t.char_offset_end)).collect();
+		assert_eq!(offsets, [(Some(0), Some(3)), (None, None), (None, None), (Some(7), Some(12)), (Some(13), Some(17)),
+			(Some(17), Some(18))]);
+		assert_eq!(doc.token_list[4].features().spaceafter, Some(false));
+		assert_eq!(doc.token_list[2].features().gender, "Masc");
+
+		let mwt = &doc.expressions[0];
+		assert_eq!((mwt.etype.as_str(), mwt.tokens.as_slice()), ("mwt", &[TokenId(2), TokenId(3)][..]));
+		assert_eq!(mwt.extra["text"], "al");
+		let e = &doc.entities[0];
+		assert_eq!((e.etype.as_str(), e.label.as_str()), ("LOC", "Nueva York"));
+		assert_eq!(e.tokens, [TokenId(4), TokenId(5)]);
+		let heads: Vec<_> = doc.dependency_trees[0].dependencies.iter().map(|d| (d.gov.0, d.lab.as_str())).collect();
+		assert_eq!(heads, [(0, "root"), (4, "case"), (4, "det"), (1, "obl"), (4, "flat"), (1, "punct")]);
+	}
+
+	#[test]
+	fn stanza_errors() {
+		let e = from_stanza_json(&STANZA.replace("[2,3]", "[3,2]")).unwrap_err();
+		let message = "invalid multi-word token ID [3, 2] in sentence 1";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		let e = from_stanza_json(r#"[[{"id":"1","text":"a"}]]"#).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Parse(ref p) if p.pointer == "/0/0/id"), "{}", e);
+		assert!(from_stanza_json("[] x").is_err());
+	}
+}
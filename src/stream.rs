@@ -0,0 +1,256 @@
+//! Streaming of the tokens of very large [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents while they
+//! are parsed, without materializing the token lists.
+
+use std::fmt;
+use std::io::Read;
+
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+
+use crate::{Document, JsonNlpError, Meta, Result, SentenceId, Token, JSONNLP};
+
+/// This enum contains the events passed to the handler of stream_tokens().
+// the events are moved to the handler one at a time, so boxing the documents would not save memory
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+	/// a sentence starts with the next token, given by the sentence ID of the tokens.
+	SentenceStart(SentenceId),
+	/// a token of the token list.
+	Token(Token),
+	/// a document ended, given with the properties that are not skipped and an empty token list.
+	Document(Document),
+}
+
+/// This struct selects the properties of documents that stream_tokens() skips without materializing them.
+/// The `meta` and `id` properties are always kept, and no properties are skipped by default.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct StreamOptions {
+	/// skip all properties of documents except `meta` and `id`.
+	pub skip_all: bool,
+	/// the JSON names of the properties of documents that are skipped, e.g. `dependencyTrees` or `text`.
+	pub skip: Vec<String>,
+}
+
+impl StreamOptions {
+	/// returns options that skip everything but the tokens and the metadata.
+	pub fn tokens_only() -> Self {
+		StreamOptions { skip_all: true, ..Default::default() }
+	}
+
+	fn skips(&self, key: &str) -> bool {
+		key != "meta" && key != "id" && (self.skip_all || self.skip.iter().any(|s| s == key))
+	}
+}
+
+/// This struct contains the handler and the error it returned, which stops parsing.
+struct State<'o, F> {
+	options: &'o StreamOptions,
+	handler: F,
+	error: Option<JsonNlpError>,
+}
+
+impl<F: FnMut(StreamEvent) -> Result<()>> State<'_, F> {
+	fn emit<E: de::Error>(&mut self, event: StreamEvent) -> std::result::Result<(), E> {
+		(self.handler)(event).map_err(|e| {
+			self.error = Some(e);
+			E::custom("stopped by the handler")
+		})
+	}
+}
+
+struct CollectionSeed<'s, 'o, F>(&'s mut State<'o, F>);
+struct DocumentsSeed<'s, 'o, F>(&'s mut State<'o, F>);
+struct DocumentSeed<'s, 'o, F>(&'s mut State<'o, F>);
+struct TokensSeed<'s, 'o, F>(&'s mut State<'o, F>);
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> DeserializeSeed<'de> for CollectionSeed<'_, '_, F> {
+	type Value = JSONNLP;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<JSONNLP, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> Visitor<'de> for CollectionSeed<'_, '_, F> {
+	type Value = JSONNLP;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP object")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<JSONNLP, A::Error> {
+		let mut j = JSONNLP::default();
+		let mut meta = None;
+		while let Some(key) = map.next_key::<String>()? {
+			match key.as_str() {
+				"meta" => meta = Some(map.next_value::<Meta>()?),
+				"docs" => map.next_value_seed(DocumentsSeed(&mut *self.0))?,
+				_ => {
+					j.extra.insert(key, map.next_value()?);
+				}
+			}
+		}
+		j.meta = meta.ok_or_else(|| de::Error::missing_field("meta"))?;
+		Ok(j)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> DeserializeSeed<'de> for DocumentsSeed<'_, '_, F> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+		deserializer.deserialize_seq(self)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> Visitor<'de> for DocumentsSeed<'_, '_, F> {
+	type Value = ();
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a list of documents")
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+		while let Some(doc) = seq.next_element_seed(DocumentSeed(&mut *self.0))? {
+			self.0.emit(StreamEvent::Document(doc))?;
+		}
+		Ok(())
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> DeserializeSeed<'de> for DocumentSeed<'_, '_, F> {
+	type Value = Document;
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<Document, D::Error> {
+		deserializer.deserialize_map(self)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> Visitor<'de> for DocumentSeed<'_, '_, F> {
+	type Value = Document;
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a JSON-NLP document")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Document, A::Error> {
+		let mut kept = Map::new();
+		while let Some(key) = map.next_key::<String>()? {
+			if key == "tokenList" {
+				map.next_value_seed(TokensSeed(&mut *self.0))?;
+			} else if self.0.options.skips(&key) {
+				map.next_value::<IgnoredAny>()?;
+			} else {
+				kept.insert(key, map.next_value()?);
+			}
+		}
+		serde_json::from_value(Value::Object(kept)).map_err(de::Error::custom)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> DeserializeSeed<'de> for TokensSeed<'_, '_, F> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> std::result::Result<(), D::Error> {
+		deserializer.deserialize_seq(self)
+	}
+}
+
+impl<'de, F: FnMut(StreamEvent) -> Result<()>> Visitor<'de> for TokensSeed<'_, '_, F> {
+	type Value = ();
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("a token list")
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+		let mut sentence = None;
+		while let Some(token) = seq.next_element::<Token>()? {
+			if sentence != Some(token.sentence_id) {
+				sentence = Some(token.sentence_id);
+				self.0.emit(StreamEvent::SentenceStart(token.sentence_id))?;
+			}
+			self.0.emit(StreamEvent::Token(token))?;
+		}
+		Ok(())
+	}
+}
+
+/// This function parses [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) from a reader and passes the tokens of
+/// every document to the handler as they are parsed, with the start of every sentence, followed by the document
+/// itself with an empty token list and without the properties skipped by the options. It returns the collection
+/// with its metadata and an empty document list. An error returned by the handler stops parsing and is returned.
+/// The reader is not buffered, wrap it in a BufReader if it performs a system call per read.
+pub fn stream_tokens<R: Read, F: FnMut(StreamEvent) -> Result<()>>(reader: R, options: &StreamOptions, handler: F) -> Result<JSONNLP> {
+	let mut state = State { options, handler, error: None };
+	let mut de = serde_json::Deserializer::from_reader(reader);
+	let r = CollectionSeed(&mut state).deserialize(&mut de).and_then(|j| de.end().map(|_| j));
+	match (r, state.error) {
+		(_, Some(e)) => Err(e),
+		(r, None) => Ok(r?),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{DocumentId, TokenId};
+
+	const JSON: &str = r#"{"meta":{},"docs":[{"meta":{},"id":1,"text":"a b. c","tokenList":[
+		{"id":1,"sentence_id":1,"text":"a","lemma":"a"},{"id":2,"sentence_id":1,"text":"b","lemma":"b"},
+		{"id":3,"sentence_id":2,"text":"c","lemma":"c"}],
+		"dependencyTrees":[{"sentence_id":1,"style":"universal","dependencies":[]}]}],"x":true}"#;
+
+	#[test]
+	fn tokens_are_streamed() {
+		let mut events = Vec::new();
+		let options = StreamOptions { skip: vec!["dependencyTrees".into()], ..Default::default() };
+		let j = stream_tokens(JSON.as_bytes(), &options, |e| {
+			events.push(match e {
+				StreamEvent::SentenceStart(s) => format!("sentence {}", s.0),
+				StreamEvent::Token(t) => format!("token {} {}", t.id.0, t.text),
+				StreamEvent::Document(d) => {
+					assert_eq!((d.id, d.text.as_str()), (DocumentId(1), "a b. c"));
+					assert!(d.token_list.is_empty() && d.dependency_trees.is_empty());
+					"document".to_string()
+				}
+			});
+			Ok(())
+		})
+		.expect("valid JSON-NLP");
+		assert_eq!(events, ["sentence 1", "token 1 a", "token 2 b", "sentence 2", "token 3 c", "document"]);
+		assert!(j.docs.is_empty());
+		assert_eq!(j.extra["x"], true);
+
+		let mut text = None;
+		stream_tokens(JSON.as_bytes(), &StreamOptions::tokens_only(), |e| {
+			if let StreamEvent::Document(d) = e {
+				text = Some(d.text);
+			}
+			Ok(())
+		})
+		.unwrap();
+		assert_eq!(text.as_deref(), Some(""));
+	}
+
+	#[test]
+	fn streaming_errors() {
+		let mut tokens = Vec::new();
+		let r = stream_tokens(JSON.as_bytes(), &StreamOptions::default(), |e| match e {
+			StreamEvent::Token(t) if t.id == TokenId(2) => Err(JsonNlpError::Format("stop".into())),
+			StreamEvent::Token(t) => {
+				tokens.push(t.id);
+				Ok(())
+			}
+			_ => Ok(()),
+		});
+		assert!(matches!(r, Err(JsonNlpError::Format(m)) if m == "stop"));
+		assert_eq!(tokens, [TokenId(1)]);
+
+		let nop = |_| Ok(());
+		assert!(stream_tokens(r#"{"docs":[]}"#.as_bytes(), &StreamOptions::default(), nop).is_err());
+		assert!(stream_tokens(&JSON.as_bytes()[..100], &StreamOptions::default(), nop).is_err());
+		assert!(stream_tokens(r#"{"meta":{},"docs":[]} x"#.as_bytes(), &StreamOptions::default(), nop).is_err());
+	}
+}
@@ -0,0 +1,152 @@
+//! Detection of properties that are not part of the JSON-NLP model, used by strict parsing.
+
+use serde_json::{Map, Value};
+
+use crate::{
+	AmrAttribute, AmrEdge, AmrGraph, AmrNode, Argument, Attribute, Clause, ConstituentParse, Coreference, CoreferenceReferents, CoreferenceRepresentantive,
+	Dependency, DependencyTree, Document, Entity, Expression, Meta, Paragraph, Predicate, Relation, Scope,
+	Sentence, Token, TokenFeatures, Triple, JSONNLP,
+};
+
+/// This trait collects the JSON pointers of all unknown properties of a struct and its children.
+pub(crate) trait UnknownFields {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>);
+}
+
+fn push_extra(extra: &Map<String, Value>, path: &str, out: &mut Vec<String>) {
+	for key in extra.keys() {
+		out.push(format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1")));
+	}
+}
+
+fn walk_list<T: UnknownFields>(items: &[T], path: &str, out: &mut Vec<String>) {
+	for (i, item) in items.iter().enumerate() {
+		item.unknown_fields(&format!("{}/{}", path, i), out);
+	}
+}
+
+macro_rules! leaf_unknown_fields {
+	($($t:ty),*) => {
+		$(
+			impl UnknownFields for $t {
+				fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+					push_extra(&self.extra, path, out);
+				}
+			}
+		)*
+	};
+}
+
+leaf_unknown_fields!(Meta, TokenFeatures, Sentence, Clause, Dependency, CoreferenceRepresentantive,
+	CoreferenceReferents, Scope, Expression, Paragraph, Attribute, Triple, Argument, AmrNode, AmrEdge, AmrAttribute);
+
+impl UnknownFields for Token {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		self.features().unknown_fields(&format!("{}/features", path), out);
+	}
+}
+
+impl UnknownFields for DependencyTree {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.dependencies, &format!("{}/dependencies", path), out);
+	}
+}
+
+impl UnknownFields for Coreference {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		self.representative.unknown_fields(&format!("{}/representative", path), out);
+		walk_list(&self.referents, &format!("{}/referents", path), out);
+	}
+}
+
+impl UnknownFields for ConstituentParse {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.scopes, &format!("{}/scopes", path), out);
+	}
+}
+
+impl UnknownFields for Entity {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.attributes, &format!("{}/attributes", path), out);
+	}
+}
+
+impl UnknownFields for Relation {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.attributes, &format!("{}/attributes", path), out);
+	}
+}
+
+impl UnknownFields for Predicate {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.arguments, &format!("{}/arguments", path), out);
+	}
+}
+
+impl UnknownFields for AmrGraph {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		walk_list(&self.nodes, &format!("{}/nodes", path), out);
+		walk_list(&self.edges, &format!("{}/edges", path), out);
+		walk_list(&self.attributes, &format!("{}/attributes", path), out);
+	}
+}
+
+impl UnknownFields for Document {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		self.meta.unknown_fields(&format!("{}/meta", path), out);
+		walk_list(&self.token_list, &format!("{}/tokenList", path), out);
+		walk_list(&self.clauses, &format!("{}/clauses", path), out);
+		walk_list(&self.sentences, &format!("{}/sentences", path), out);
+		walk_list(&self.paragraphs, &format!("{}/paragraphs", path), out);
+		walk_list(&self.dependency_trees, &format!("{}/dependencyTrees", path), out);
+		walk_list(&self.coreferences, &format!("{}/coreferences", path), out);
+		walk_list(&self.constituents, &format!("{}/constituents", path), out);
+		walk_list(&self.expressions, &format!("{}/expressions", path), out);
+		walk_list(&self.entities, &format!("{}/entities", path), out);
+		walk_list(&self.relations, &format!("{}/relations", path), out);
+		walk_list(&self.triples, &format!("{}/triples", path), out);
+		walk_list(&self.semantic_roles, &format!("{}/semanticRoles", path), out);
+		walk_list(&self.amr_graphs, &format!("{}/amrGraphs", path), out);
+	}
+}
+
+impl UnknownFields for JSONNLP {
+	fn unknown_fields(&self, path: &str, out: &mut Vec<String>) {
+		push_extra(&self.extra, path, out);
+		self.meta.unknown_fields(&format!("{}/meta", path), out);
+		walk_list(&self.docs, &format!("{}/docs", path), out);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{from_string, from_string_strict, JsonNlpError};
+
+	#[test]
+	fn unknown_fields_are_rejected() {
+		let json = r#"{"meta":{"x-tool":"t"},"docs":[{"meta":{},"id":1,"tokenList":[
+			{"id":1,"sentence_id":1,"text":"a","lemma":"a","features":{"a/b":true}}],
+			"entities":[{"id":1,"type":"X","attributes":[{"lab":"l","val":"v","conf":1}]}]}]}"#;
+		assert!(from_string(json).is_ok());
+		match from_string_strict(json) {
+			Err(JsonNlpError::UnknownFields(fields)) => assert_eq!(fields, [
+				"/meta/x-tool",
+				"/docs/0/tokenList/0/features/a~1b",
+				"/docs/0/entities/0/attributes/0/conf",
+			]),
+			r => panic!("expected UnknownFields, got {:?}", r.map(|_| ())),
+		}
+		let known =
+			r#"{"meta":{},"docs":[{"meta":{},"id":1,"tokenList":[{"id":1,"sentence_id":1,"text":"a","lemma":"a"}]}]}"#;
+		assert!(from_string_strict(known).is_ok());
+	}
+}
@@ -0,0 +1,176 @@
+//! Rendering of the dependency trees of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents as
+//! [SVG](https://www.w3.org/TR/SVG2/) images, with the tokens in a row and the dependencies as labeled arcs above
+//! them, e.g. for documentation, papers, or QA reports.
+
+use std::collections::HashMap;
+
+use crate::tei::escape_xml;
+use crate::{DependencyTree, Document, Token, TokenId};
+
+/// This struct contains the options for rendering dependency trees.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgOptions {
+	/// the font size of the tokens in pixels. Labels and tags are drawn at 80% of it.
+	pub font_size: f64,
+	/// the horizontal space between tokens in pixels.
+	pub spacing: f64,
+	/// the height that arcs grow by per level of nesting, in pixels.
+	pub level_height: f64,
+	/// draw the UPOS, or else XPOS, tag below every token.
+	pub pos: bool,
+}
+
+impl Default for SvgOptions {
+	fn default() -> Self {
+		SvgOptions { font_size: 14.0, spacing: 24.0, level_height: 28.0, pos: true }
+	}
+}
+
+/// returns the estimated width of a text in pixels, since the widths of the glyphs are not known without fonts.
+fn text_width(s: &str, font_size: f64) -> f64 {
+	s.chars().count() as f64 * font_size * 0.6
+}
+
+/// returns the POS tag drawn below a token.
+fn tag(t: &Token) -> &str {
+	if t.upos.is_empty() {
+		&t.xpos
+	} else {
+		&t.upos
+	}
+}
+
+impl Document {
+	/// returns the tokens of a dependency tree's sentence, or else those of its dependencies, in the order of the
+	/// token list.
+	pub(crate) fn dependency_tree_tokens(&self, tree: &DependencyTree) -> Vec<&Token> {
+		match tree.sentence_id.and_then(|id| self.sentence_groups().into_iter().find(|g| g.0 == id)) {
+			Some((_, _, tokens)) => tokens,
+			None => self
+				.token_list
+				.iter()
+				.filter(|t| tree.dependencies.iter().any(|d| d.dep == t.id || d.gov == t.id))
+				.collect(),
+		}
+	}
+}
+
+/// This function renders a dependency tree of a Document as an SVG image. The tokens are those of the tree's
+/// sentence, or else those of its dependencies, in the order of the token list. Every dependency is an arc from the
+/// governor to the dependent with an arrowhead at the dependent, labeled with the relation, and arcs are drawn the
+/// higher the more arcs they span, so that no arcs of a projective tree cross. The root is marked by an arrow from
+/// above. Dependencies of tokens that are not in the token list are skipped.
+pub fn to_dependency_svg(doc: &Document, tree: &DependencyTree, options: &SvgOptions) -> String {
+	let tokens = doc.dependency_tree_tokens(tree);
+	let position: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+	let small = options.font_size * 0.8;
+
+	// the centers of the tokens
+	let mut centers = Vec::with_capacity(tokens.len());
+	let mut x = options.spacing / 2.0;
+	for t in &tokens {
+		let mut width = text_width(&t.text, options.font_size);
+		if options.pos {
+			width = width.max(text_width(tag(t), small));
+		}
+		centers.push(x + width / 2.0);
+		x += width + options.spacing;
+	}
+	let width = x - options.spacing / 2.0;
+
+	// the arcs with the positions of their governor and dependent and their level, and the roots
+	let mut arcs: Vec<(usize, usize, &str, usize)> = Vec::new();
+	let mut roots: Vec<(usize, &str)> = Vec::new();
+	for d in &tree.dependencies {
+		let dep = match position.get(&d.dep) {
+			Some(dep) => *dep,
+			None => continue,
+		};
+		if d.gov == TokenId::default() {
+			roots.push((dep, &d.lab));
+		} else if let Some(gov) = position.get(&d.gov) {
+			arcs.push((*gov, dep, &d.lab, 0));
+		}
+	}
+	arcs.sort_by_key(|(gov, dep, _, _)| gov.abs_diff(*dep));
+	for i in 0..arcs.len() {
+		let (gov, dep, _, _) = arcs[i];
+		let (left, right) = if gov < dep { (gov, dep) } else { (dep, gov) };
+		let inner = arcs[..i]
+			.iter()
+			.filter(|(g, d, _, _)| left <= *g.min(d) && *g.max(d) <= right)
+			.map(|(_, _, _, level)| *level)
+			.max()
+			.unwrap_or(0);
+		arcs[i].3 = inner + 1;
+	}
+	let levels = arcs.iter().map(|a| a.3).max().unwrap_or(0);
+
+	let top = small * 2.0;
+	let baseline = top + (levels as f64 + 1.0) * options.level_height;
+	let height = baseline + options.font_size + if options.pos { small * 1.5 } else { 0.0 } + options.font_size / 2.0;
+	let mut svg = format!(
+		"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.0} {:.0}\" \
+		 font-family=\"sans-serif\" font-size=\"{}\">\n",
+		width, height, width, height, options.font_size
+	);
+	svg.push_str(
+		"  <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" \
+		 markerHeight=\"6\" orient=\"auto-start-reverse\"><path d=\"M 0 0 L 10 5 L 0 10 z\"/></marker></defs>\n",
+	);
+
+	let foot = baseline - options.font_size;
+	for (gov, dep, label, level) in &arcs {
+		let (x1, x2) = (centers[*gov], centers[*dep]);
+		// the control points of the cubic curve are 4/3 as high as its apex
+		let apex = *level as f64 * options.level_height;
+		let control = foot - apex * 4.0 / 3.0;
+		svg.push_str(&format!(
+			"  <path d=\"M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}\" fill=\"none\" stroke=\"black\" \
+			 marker-end=\"url(#arrow)\"/>\n",
+			x1, foot, x1, control, x2, control, x2, foot
+		));
+		svg.push_str(&format!(
+			"  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" text-anchor=\"middle\" fill=\"#555\">{}</text>\n",
+			(x1 + x2) / 2.0,
+			foot - apex - 3.0,
+			small,
+			escape_xml(label)
+		));
+	}
+	for (dep, label) in &roots {
+		let x = centers[*dep];
+		svg.push_str(&format!(
+			"  <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"black\" \
+			 marker-end=\"url(#arrow)\"/>\n",
+			x, top, x, foot
+		));
+		svg.push_str(&format!(
+			"  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" text-anchor=\"middle\" fill=\"#555\">{}</text>\n",
+			x,
+			top - 3.0,
+			small,
+			escape_xml(label)
+		));
+	}
+
+	for (t, x) in tokens.iter().zip(&centers) {
+		svg.push_str(&format!(
+			"  <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>\n",
+			x,
+			baseline,
+			escape_xml(&t.text)
+		));
+		if options.pos && !tag(t).is_empty() {
+			svg.push_str(&format!(
+				"  <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" text-anchor=\"middle\" fill=\"#777\">{}</text>\n",
+				x,
+				baseline + small * 1.5,
+				small,
+				escape_xml(tag(t))
+			));
+		}
+	}
+	svg.push_str("</svg>\n");
+	svg
+}
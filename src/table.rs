@@ -0,0 +1,142 @@
+//! Export of the tokens of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents as CSV or TSV tables,
+//! one row per token, e.g. for reviewing annotations in a spreadsheet.
+
+use crate::{Document, Token, JSONNLP};
+
+/// This enum selects a column of the token table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenColumn {
+	Id,
+	SentenceId,
+	Text,
+	Lemma,
+	Upos,
+	Xpos,
+	/// the character offset where the token begins.
+	Begin,
+	/// the character offset where the token ends.
+	End,
+	EntityIob,
+	/// the entity of the token, or else the type of the first entity of the entities layer containing the token.
+	Entity,
+	Shape,
+}
+
+impl TokenColumn {
+	/// returns the name of the column in the header, which is the name of the JSON-NLP property.
+	pub fn name(self) -> &'static str {
+		match self {
+			TokenColumn::Id => "id",
+			TokenColumn::SentenceId => "sentence_id",
+			TokenColumn::Text => "text",
+			TokenColumn::Lemma => "lemma",
+			TokenColumn::Upos => "upos",
+			TokenColumn::Xpos => "xpos",
+			TokenColumn::Begin => "characterOffsetBegin",
+			TokenColumn::End => "characterOffsetEnd",
+			TokenColumn::EntityIob => "entity_iob",
+			TokenColumn::Entity => "entity",
+			TokenColumn::Shape => "shape",
+		}
+	}
+
+	fn value(self, doc: &Document, t: &Token) -> String {
+		match self {
+			TokenColumn::Id => t.id.to_string(),
+			TokenColumn::SentenceId => t.sentence_id.to_string(),
+			TokenColumn::Text => t.text.clone(),
+			TokenColumn::Lemma => t.lemma.clone(),
+			TokenColumn::Upos => t.upos.clone(),
+			TokenColumn::Xpos => t.xpos.clone(),
+			TokenColumn::Begin => t.char_offset_begin.map(|o| o.to_string()).unwrap_or_default(),
+			TokenColumn::End => t.char_offset_end.map(|o| o.to_string()).unwrap_or_default(),
+			TokenColumn::EntityIob => t.entity_iob.clone(),
+			TokenColumn::Entity if t.entity.is_empty() => {
+				doc.entities.iter().find(|e| e.covers(t.id)).map(|e| e.etype.clone()).unwrap_or_default()
+			}
+			TokenColumn::Entity => t.entity.clone(),
+			TokenColumn::Shape => t.shape.clone(),
+		}
+	}
+}
+
+/// This struct contains the options of the token table. By default it is CSV with a header and the columns text,
+/// lemma, UPOS, XPOS, offsets, and entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableOptions {
+	/// the delimiter of the fields, `,` for CSV and `\t` for TSV.
+	pub delimiter: char,
+	/// write the names of the columns as the first row.
+	pub header: bool,
+	/// the columns, in their order.
+	pub columns: Vec<TokenColumn>,
+}
+
+impl Default for TableOptions {
+	fn default() -> Self {
+		TableOptions {
+			delimiter: ',',
+			header: true,
+			columns: vec![
+				TokenColumn::Text,
+				TokenColumn::Lemma,
+				TokenColumn::Upos,
+				TokenColumn::Xpos,
+				TokenColumn::Begin,
+				TokenColumn::End,
+				TokenColumn::Entity,
+			],
+		}
+	}
+}
+
+impl TableOptions {
+	/// returns the default options with tabs as delimiter.
+	pub fn tsv() -> Self {
+		TableOptions { delimiter: '\t', ..Default::default() }
+	}
+}
+
+/// returns a field of a delimited table, quoted if it contains the delimiter, a quote, or a line break.
+pub(crate) fn field(s: &str, delimiter: char) -> String {
+	if s.contains([delimiter, '"', '\n', '\r']) {
+		format!("\"{}\"", s.replace('"', "\"\""))
+	} else {
+		s.to_string()
+	}
+}
+
+/// appends a row of fields to a table.
+fn row<S: AsRef<str>>(out: &mut String, fields: impl IntoIterator<Item = S>, delimiter: char) {
+	let fields: Vec<String> = fields.into_iter().map(|f| field(f.as_ref(), delimiter)).collect();
+	out.push_str(&fields.join(&delimiter.to_string()));
+	out.push('\n');
+}
+
+/// This function returns the tokens of a Document as a table with the columns of the options.
+pub fn to_token_table(doc: &Document, options: &TableOptions) -> String {
+	let mut out = String::new();
+	if options.header {
+		row(&mut out, options.columns.iter().map(|c| c.name()), options.delimiter);
+	}
+	for t in &doc.token_list {
+		row(&mut out, options.columns.iter().map(|c| c.value(doc, t)), options.delimiter);
+	}
+	out
+}
+
+/// This function returns the tokens of all documents of a collection as one table, with the ID of the document of
+/// every token in the first column `docId`, followed by the columns of the options.
+pub fn to_corpus_token_table(j: &JSONNLP, options: &TableOptions) -> String {
+	let mut out = String::new();
+	if options.header {
+		row(&mut out, std::iter::once("docId").chain(options.columns.iter().map(|c| c.name())), options.delimiter);
+	}
+	for doc in &j.docs {
+		for t in &doc.token_list {
+			let values = options.columns.iter().map(|c| c.value(doc, t));
+			row(&mut out, std::iter::once(doc.id.to_string()).chain(values), options.delimiter);
+		}
+	}
+	out
+}
@@ -0,0 +1,303 @@
+//! Conversion between the [TCF](https://weblicht.sfs.uni-tuebingen.de/weblichtwiki/index.php/The_TCF_Format)
+//! 0.4 format of WebLicht and [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+#[cfg(feature = "xml")]
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use crate::tei::escape_xml;
+use crate::{Document, TokenId};
+#[cfg(feature = "xml")]
+use crate::{
+	Dependency, DependencyTree, DocumentId, Entity, EntityId, JsonNlpError, LanguageTag, RawId, Result, Sentence,
+	SentenceId, Token,
+};
+
+#[cfg(feature = "xml")]
+const TEXTCORPUS_NS: &str = "http://www.dspin.de/data/textcorpus";
+
+/// returns the child elements of a TCF layer, if the layer exists.
+#[cfg(feature = "xml")]
+fn elements<'a, 'input>(layer: Option<roxmltree::Node<'a, 'input>>, name: &'static str) -> impl Iterator<Item = roxmltree::Node<'a, 'input>> {
+	layer.into_iter().flat_map(move |l| l.children().filter(move |c| c.has_tag_name((TEXTCORPUS_NS, name))))
+}
+
+/// returns the tagset of a TCF layer kept in the document's `extra` map.
+fn tagset<'a>(doc: &'a Document, layer: &str, default: &'a str) -> &'a str {
+	doc.extra.get("tcfTagsets").and_then(|t| t.get(layer)).and_then(Value::as_str).unwrap_or(default)
+}
+
+/// returns the TCF ID of a token.
+fn token_ref(id: TokenId) -> String {
+	format!("t_{}", id)
+}
+
+/// This function converts a Document to a TCF 0.4 document with the `text`, `tokens`, `sentences`, `lemmas`,
+/// `POStags`, `depparsing`, and `namedEntities` layers, as far as the document contains them. Tokens are identified
+/// by `t_` and their JSON-NLP ID and carry their character offsets. The part-of-speech tags are the XPOS tags, or
+/// else the UPOS tags with the `universal` tagset, and the dependency parses are those of the first dependency tree
+/// of every sentence. The tagsets read by from_tcf() are kept in the document's `extra` map as `tcfTagsets`.
+pub fn to_tcf(doc: &Document) -> String {
+	let mut tcf = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	tcf.push_str("<D-Spin xmlns=\"http://www.dspin.de/data\" version=\"0.4\">\n");
+	tcf.push_str("\t<md:MetaData xmlns:md=\"http://www.dspin.de/data/metadata\"/>\n");
+	let language = match doc.meta.language.as_str() {
+		"" => String::new(),
+		language => format!(" lang=\"{}\"", escape_xml(language)),
+	};
+	tcf.push_str(&format!("\t<tc:TextCorpus xmlns:tc=\"http://www.dspin.de/data/textcorpus\"{}>\n", language));
+	tcf.push_str(&format!("\t\t<tc:text>{}</tc:text>\n", escape_xml(&doc.text)));
+	let groups = doc.sentence_groups();
+	if doc.token_list.is_empty() {
+		tcf.push_str("\t</tc:TextCorpus>\n</D-Spin>\n");
+		return tcf;
+	}
+
+	let offsets = doc.token_list.iter().all(|t| t.char_offset_begin.is_some() && t.char_offset_end.is_some());
+	tcf.push_str(&format!("\t\t<tc:tokens{}>\n", if offsets { " charOffsets=\"true\"" } else { "" }));
+	for t in &doc.token_list {
+		let offsets = match (t.char_offset_begin, t.char_offset_end) {
+			(Some(begin), Some(end)) if offsets => format!(" start=\"{}\" end=\"{}\"", begin, end),
+			_ => String::new(),
+		};
+		tcf.push_str(&format!("\t\t\t<tc:token ID=\"{}\"{}>{}</tc:token>\n", token_ref(t.id), offsets, escape_xml(&t.text)));
+	}
+	tcf.push_str("\t\t</tc:tokens>\n");
+
+	tcf.push_str("\t\t<tc:sentences>\n");
+	for (id, _, tokens) in &groups {
+		let ids: Vec<String> = tokens.iter().map(|t| token_ref(t.id)).collect();
+		tcf.push_str(&format!("\t\t\t<tc:sentence ID=\"s_{}\" tokenIDs=\"{}\"/>\n", id, ids.join(" ")));
+	}
+	tcf.push_str("\t\t</tc:sentences>\n");
+
+	if doc.token_list.iter().any(|t| !t.lemma.is_empty()) {
+		tcf.push_str("\t\t<tc:lemmas>\n");
+		for t in doc.token_list.iter().filter(|t| !t.lemma.is_empty()) {
+			tcf.push_str(&format!("\t\t\t<tc:lemma tokenIDs=\"{}\">{}</tc:lemma>\n", token_ref(t.id), escape_xml(&t.lemma)));
+		}
+		tcf.push_str("\t\t</tc:lemmas>\n");
+	}
+
+	let xpos = doc.token_list.iter().any(|t| !t.xpos.is_empty());
+	if xpos || doc.token_list.iter().any(|t| !t.upos.is_empty()) {
+		let tags = if xpos { tagset(doc, "POStags", "unknown") } else { "universal" };
+		tcf.push_str(&format!("\t\t<tc:POStags tagset=\"{}\">\n", escape_xml(tags)));
+		for t in &doc.token_list {
+			let tag = if xpos { &t.xpos } else { &t.upos };
+			if !tag.is_empty() {
+				tcf.push_str(&format!("\t\t\t<tc:tag tokenIDs=\"{}\">{}</tc:tag>\n", token_ref(t.id), escape_xml(tag)));
+			}
+		}
+		tcf.push_str("\t\t</tc:POStags>\n");
+	}
+
+	let trees: Vec<_> = groups.iter().filter_map(|(id, _, tokens)| {
+		let tree = doc.dependency_trees.iter().find(|d| d.sentence_id == Some(*id))
+			.or_else(|| doc.dependency_trees.iter().find(|d| d.sentence_id.is_none()))?;
+		let dependencies: Vec<_> = tree.dependencies.iter().filter(|d| tokens.iter().any(|t| t.id == d.dep)).collect();
+		if dependencies.is_empty() { None } else { Some((tree, dependencies)) }
+	}).collect();
+	if let Some((first, _)) = trees.first() {
+		let style = if first.style.is_empty() { "unknown" } else { &first.style };
+		tcf.push_str(&format!("\t\t<tc:depparsing tagset=\"{}\" emptytoks=\"false\" multigovs=\"false\">\n", escape_xml(style)));
+		for (_, dependencies) in &trees {
+			tcf.push_str("\t\t\t<tc:parse>\n");
+			for d in dependencies {
+				let gov = if d.is_root() { String::new() } else { format!(" govIDs=\"{}\"", token_ref(d.gov)) };
+				tcf.push_str(&format!("\t\t\t\t<tc:dependency func=\"{}\" depIDs=\"{}\"{}/>\n", escape_xml(&d.lab), token_ref(d.dep), gov));
+			}
+			tcf.push_str("\t\t\t</tc:parse>\n");
+		}
+		tcf.push_str("\t\t</tc:depparsing>\n");
+	}
+
+	let entities: Vec<(&str, Vec<String>)> = doc.entities.iter().map(|e| {
+		(e.etype.as_str(), doc.token_list.iter().filter(|t| e.covers(t.id)).map(|t| token_ref(t.id)).collect::<Vec<_>>())
+	}).filter(|e| !e.1.is_empty()).collect();
+	if !entities.is_empty() {
+		tcf.push_str(&format!("\t\t<tc:namedEntities type=\"{}\">\n", escape_xml(tagset(doc, "namedEntities", "unknown"))));
+		for (etype, ids) in entities {
+			tcf.push_str(&format!("\t\t\t<tc:entity class=\"{}\" tokenIDs=\"{}\"/>\n", escape_xml(etype), ids.join(" ")));
+		}
+		tcf.push_str("\t\t</tc:namedEntities>\n");
+	}
+	tcf.push_str("\t</tc:TextCorpus>\n</D-Spin>\n");
+	tcf
+}
+
+/// This function converts a TCF 0.4 document to a Document, reading the `text`, `tokens`, `sentences`, `lemmas`,
+/// `POStags`, `depparsing`, and `namedEntities` layers. Tokens are numbered in their order and keep their character
+/// offsets. Part-of-speech tags become XPOS tags, or UPOS tags for the `universal` tagset, dependency parses become
+/// dependency trees with the depparsing tagset as style, and named entities become entities and the tokens'
+/// `entity` and `entity_iob` tags. The tagsets are kept in the document's `extra` map as `tcfTagsets`.
+#[cfg(feature = "xml")]
+pub fn from_tcf(xml: &str) -> Result<Document> {
+	let error = |msg: String| JsonNlpError::Format(format!("TCF: {}", msg));
+	let tree = roxmltree::Document::parse(xml).map_err(|e| error(e.to_string()))?;
+	let corpus = tree.descendants().find(|n| n.has_tag_name((TEXTCORPUS_NS, "TextCorpus")))
+		.ok_or_else(|| error("missing <tc:TextCorpus>".to_string()))?;
+	let layer = |name: &str| corpus.children().find(|c| c.has_tag_name((TEXTCORPUS_NS, name)));
+	let text = |node: roxmltree::Node| -> String { node.descendants().filter_map(|d| d.text().filter(|_| d.is_text())).collect() };
+
+	let mut doc = Document { id: DocumentId(1), ..Default::default() };
+	if let Some(language) = corpus.attribute("lang") {
+		doc.meta.language = LanguageTag::new(language)?;
+	}
+	doc.text = layer("text").map(text).unwrap_or_default();
+
+	let mut ids: HashMap<&str, TokenId> = HashMap::new();
+	for t in elements(layer("tokens"), "token") {
+		let id = TokenId(doc.token_list.len() as RawId + 1);
+		if let Some(tcf_id) = t.attribute("ID") {
+			ids.insert(tcf_id, id);
+		}
+		let offset = |name: &str| t.attribute(name).and_then(|o| o.parse().ok());
+		doc.token_list.push(Token {
+			id,
+			text: text(t),
+			char_offset_begin: offset("start"),
+			char_offset_end: offset("end"),
+			..Default::default()
+		});
+	}
+	let tokens_of = |node: roxmltree::Node, attribute: &str| -> Result<Vec<TokenId>> {
+		node.attribute(attribute).unwrap_or_default().split_whitespace().map(|r| {
+			ids.get(r).copied().ok_or_else(|| error(format!("unknown token ID {:?}", r)))
+		}).collect()
+	};
+
+	let mut tagsets = serde_json::Map::new();
+	for (name, attribute) in [("POStags", "tagset"), ("depparsing", "tagset"), ("namedEntities", "type")] {
+		if let Some(tagset) = layer(name).and_then(|l| l.attribute(attribute)) {
+			tagsets.insert(name.to_string(), Value::String(tagset.to_string()));
+		}
+	}
+
+	for (i, s) in elements(layer("sentences"), "sentence").enumerate() {
+		let id = SentenceId(i as RawId + 1);
+		let tokens = tokens_of(s, "tokenIDs")?;
+		let (begin, end) = doc.char_span(|t| tokens.contains(&t)).unwrap_or_default();
+		for t in doc.token_list.iter_mut().filter(|t| tokens.contains(&t.id)) {
+			t.sentence_id = id;
+		}
+		doc.sentences.push(Sentence {
+			id,
+			text: doc.span_text(begin, end).unwrap_or_default().to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			..Default::default()
+		});
+	}
+
+	for l in elements(layer("lemmas"), "lemma") {
+		for id in tokens_of(l, "tokenIDs")? {
+			doc.token_list[id.0 as usize - 1].lemma = text(l);
+		}
+	}
+	let universal = layer("POStags").and_then(|l| l.attribute("tagset")).is_some_and(|t| t.eq_ignore_ascii_case("universal"));
+	for tag in elements(layer("POStags"), "tag") {
+		for id in tokens_of(tag, "tokenIDs")? {
+			let token = &mut doc.token_list[id.0 as usize - 1];
+			if universal { token.upos = text(tag) } else { token.xpos = text(tag) }
+		}
+	}
+
+	let style = layer("depparsing").and_then(|l| l.attribute("tagset")).unwrap_or_default();
+	for parse in elements(layer("depparsing"), "parse") {
+		let mut tree = DependencyTree { style: style.to_string(), ..Default::default() };
+		for d in parse.children().filter(|c| c.has_tag_name((TEXTCORPUS_NS, "dependency"))) {
+			let gov = tokens_of(d, "govIDs")?.first().copied().unwrap_or(TokenId(0));
+			for dep in tokens_of(d, "depIDs")? {
+				tree.dependencies.push(Dependency {
+					lab: d.attribute("func").unwrap_or_default().to_string(),
+					gov,
+					dep,
+					..Default::default()
+				});
+			}
+		}
+		tree.sentence_id = tree.dependencies.first().map(|d| doc.token_list[d.dep.0 as usize - 1].sentence_id);
+		doc.dependency_trees.push(tree);
+	}
+
+	for e in elements(layer("namedEntities"), "entity") {
+		let tokens = tokens_of(e, "tokenIDs")?;
+		let etype = e.attribute("class").unwrap_or_default().to_string();
+		for (i, id) in tokens.iter().enumerate() {
+			let token = &mut doc.token_list[id.0 as usize - 1];
+			token.entity_iob = if i == 0 { "B" } else { "I" }.to_string();
+			token.entity = etype.clone();
+		}
+		let (begin, end) = doc.char_span(|t| tokens.contains(&t)).unwrap_or_default();
+		doc.entities.push(Entity {
+			id: EntityId(doc.entities.len() as RawId + 1),
+			label: doc.span_text(begin, end).unwrap_or_default().to_string(),
+			etype,
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			..Default::default()
+		});
+	}
+	if !doc.entities.is_empty() {
+		for t in doc.token_list.iter_mut().filter(|t| t.entity_iob.is_empty()) {
+			t.entity_iob = "O".to_string();
+		}
+	}
+	if !tagsets.is_empty() {
+		doc.extra.insert("tcfTagsets".to_string(), Value::Object(tagsets));
+	}
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn doc() -> Document {
+		serde_json::from_str(r#"{"meta":{"DC.language":"de"},"id":1,"text":"Anna lacht.","tokenList":[
+			{"id":1,"sentence_id":1,"text":"Anna","lemma":"Anna","xpos":"NE","characterOffsetBegin":0,
+				"characterOffsetEnd":4,"entity_iob":"B","entity":"PER"},
+			{"id":2,"sentence_id":1,"text":"lacht","lemma":"lachen","xpos":"VVFIN","characterOffsetBegin":5,
+				"characterOffsetEnd":10,"entity_iob":"O"},
+			{"id":3,"sentence_id":1,"text":".","lemma":".","xpos":"$.","characterOffsetBegin":10,
+				"characterOffsetEnd":11,"entity_iob":"O"}],
+			"sentences":[{"id":1,"text":"Anna lacht.","tokenFrom":1,"tokenTo":3,"tokens":[1,2,3]}],
+			"dependencyTrees":[{"sentenceId":1,"style":"universal","dependencies":[
+				{"lab":"nsubj","gov":2,"dep":1},{"lab":"root","gov":0,"dep":2},{"lab":"punct","gov":2,"dep":3}]}],
+			"entities":[{"id":1,"label":"Anna","type":"PER","tokenFrom":1,"tokenTo":1,"tokens":[1]}],
+			"tcfTagsets":{"POStags":"stts","depparsing":"universal","namedEntities":"CoNLL2002"}}"#)
+		.unwrap()
+	}
+
+	#[test]
+	fn tcf_export() {
+		let tcf = to_tcf(&doc());
+		assert!(tcf.contains("<tc:TextCorpus xmlns:tc=\"http://www.dspin.de/data/textcorpus\" lang=\"de\">"), "{}", tcf);
+		assert!(tcf.contains("<tc:token ID=\"t_1\" start=\"0\" end=\"4\">Anna</tc:token>"), "{}", tcf);
+		assert!(tcf.contains("<tc:sentence ID=\"s_1\" tokenIDs=\"t_1 t_2 t_3\"/>"), "{}", tcf);
+		assert!(tcf.contains("<tc:POStags tagset=\"stts\">"), "{}", tcf);
+		assert!(tcf.contains("<tc:dependency func=\"root\" depIDs=\"t_2\"/>"), "{}", tcf);
+		assert!(tcf.contains("<tc:dependency func=\"nsubj\" depIDs=\"t_1\" govIDs=\"t_2\"/>"), "{}", tcf);
+		assert!(tcf.contains("<tc:entity class=\"PER\" tokenIDs=\"t_1\"/>"), "{}", tcf);
+	}
+
+	#[cfg(feature = "xml")]
+	#[test]
+	fn tcf_round_trip() {
+		assert_eq!(from_tcf(&to_tcf(&doc())).expect("valid TCF"), doc());
+	}
+
+	#[cfg(feature = "xml")]
+	#[test]
+	fn tcf_errors() {
+		let tcf = to_tcf(&doc()).replace("<tc:lemma tokenIDs=\"t_2\">", "<tc:lemma tokenIDs=\"t_4\">");
+		let e = from_tcf(&tcf).unwrap_err();
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == "TCF: unknown token ID \"t_4\""), "{}", e);
+		assert!(from_tcf("<D-Spin xmlns=\"http://www.dspin.de/data\"/>").is_err());
+		assert!(from_tcf("<D-Spin>").is_err());
+	}
+}
@@ -0,0 +1,168 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents to [TEI P5](https://tei-c.org/guidelines/p5/) XML.
+
+use std::collections::HashMap;
+
+use crate::conllu::feats;
+use crate::{Document, Token, TokenId};
+
+/// This struct selects the layers written to TEI. All layers are written by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeiOptions {
+	/// write `<s>` elements for sentences.
+	pub sentences: bool,
+	/// write the `lemma` attribute of words.
+	pub lemmas: bool,
+	/// write the `pos` and `msd` attributes of words, and punctuation as `<pc>`.
+	pub pos: bool,
+	/// write `<name>` elements for entities.
+	pub entities: bool,
+	/// write a standoff `<linkGrp>` of the dependencies of every sentence.
+	pub dependencies: bool,
+}
+
+impl Default for TeiOptions {
+	fn default() -> Self {
+		TeiOptions {
+			sentences: true,
+			lemmas: true,
+			pos: true,
+			entities: true,
+			dependencies: true,
+		}
+	}
+}
+
+/// returns the text with the XML special characters escaped.
+pub(crate) fn escape_xml(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		match c {
+			'&' => out.push_str("&amp;"),
+			'<' => out.push_str("&lt;"),
+			'>' => out.push_str("&gt;"),
+			'"' => out.push_str("&quot;"),
+			c => out.push(c),
+		}
+	}
+	out
+}
+
+/// checks whether whitespace follows the token, given by the character offsets of the next token
+/// or else by the token's `SpaceAfter` feature.
+pub(crate) fn space_after(token: &Token, next: &Token) -> bool {
+	match (token.char_offset_end, next.char_offset_begin) {
+		(Some(end), Some(begin)) => begin > end,
+		_ => token.features().spaceafter != Some(false),
+	}
+}
+
+/// This function converts a Document to a TEI P5 XML document. The text becomes a paragraph with one `<s>` element
+/// per sentence, and tokens become `<w>` elements, or `<pc>` for punctuation, with the lemma as `lemma`, the UPOS
+/// or else XPOS tag as `pos`, and the morphological features as `msd`. The entities that do not overlap an
+/// entity written before are wrapped in `<name>` elements typed by the entity type, and the dependencies of the
+/// sentence's first dependency tree become a `<linkGrp type="UD-SYN">` with one `<link>` per dependency pointing
+/// to the head and dependent, or the sentence for the root, with subtypes like `nmod:poss` written as `nmod_poss`.
+/// Elements get `xml:id` attributes like `s1` and `w1` from the JSON-NLP IDs.
+pub fn to_tei(doc: &Document, options: &TeiOptions) -> String {
+	let mut tei = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<TEI xmlns=\"http://www.tei-c.org/ns/1.0\">\n");
+	let title = if doc.meta.title.is_empty() { &doc.meta.identifier } else { &doc.meta.title };
+	tei.push_str("\t<teiHeader>\n\t\t<fileDesc>\n");
+	tei.push_str(&format!("\t\t\t<titleStmt>\n\t\t\t\t<title>{}</title>\n", escape_xml(title)));
+	if !doc.meta.author.is_empty() {
+		tei.push_str(&format!("\t\t\t\t<author>{}</author>\n", escape_xml(&doc.meta.author)));
+	}
+	tei.push_str("\t\t\t</titleStmt>\n\t\t\t<publicationStmt>\n");
+	match doc.meta.publisher.as_str() {
+		"" => tei.push_str("\t\t\t\t<p>Converted from JSON-NLP</p>\n"),
+		publisher => tei.push_str(&format!("\t\t\t\t<publisher>{}</publisher>\n", escape_xml(publisher))),
+	}
+	tei.push_str("\t\t\t</publicationStmt>\n\t\t\t<sourceDesc>\n");
+	tei.push_str(&format!("\t\t\t\t<p>{}</p>\n", escape_xml(&doc.meta.source)));
+	tei.push_str("\t\t\t</sourceDesc>\n\t\t</fileDesc>\n\t</teiHeader>\n");
+	let language = match doc.meta.language.as_str() {
+		"" => String::new(),
+		language => format!(" xml:lang=\"{}\"", escape_xml(language)),
+	};
+	tei.push_str(&format!("\t<text{}>\n\t\t<body>\n\t\t\t<p>\n", language));
+
+	for (sentence_id, _, tokens) in doc.sentence_groups() {
+		let position: HashMap<TokenId, usize> = tokens.iter().enumerate().map(|(i, t)| (t.id, i)).collect();
+		// the entity starting at a token position, with its last position and type
+		let mut names: HashMap<usize, (usize, &str)> = HashMap::new();
+		if options.entities {
+			let mut covered = vec![false; tokens.len()];
+			for e in &doc.entities {
+				let covers: Vec<usize> = tokens.iter().enumerate().filter(|(_, t)| e.covers(t.id)).map(|(i, _)| i).collect();
+				if let (Some(first), Some(last)) = (covers.first(), covers.last()) {
+					if !covered[*first..=*last].iter().any(|c| *c) {
+						covered[*first..=*last].iter_mut().for_each(|c| *c = true);
+						names.insert(*first, (*last, e.etype.as_str()));
+					}
+				}
+			}
+		}
+
+		let indent = if options.sentences { "\t\t\t\t\t" } else { "\t\t\t\t" };
+		if options.sentences {
+			tei.push_str(&format!("\t\t\t\t<s xml:id=\"s{}\">\n", sentence_id));
+		}
+		let mut name_end = None;
+		for (i, t) in tokens.iter().enumerate() {
+			if let Some((last, etype)) = names.get(&i) {
+				let etype = if etype.is_empty() { String::new() } else { format!(" type=\"{}\"", escape_xml(etype)) };
+				tei.push_str(&format!("{}<name{}>\n", indent, etype));
+				name_end = Some(*last);
+			}
+			let element = if options.pos && t.upos == "PUNCT" { "pc" } else { "w" };
+			let mut attributes = format!(" xml:id=\"w{}\"", t.id);
+			if options.lemmas && !t.lemma.is_empty() && element == "w" {
+				attributes.push_str(&format!(" lemma=\"{}\"", escape_xml(&t.lemma)));
+			}
+			if options.pos {
+				let pos = if t.upos.is_empty() { &t.xpos } else { &t.upos };
+				if !pos.is_empty() {
+					attributes.push_str(&format!(" pos=\"{}\"", escape_xml(pos)));
+				}
+				let msd = feats(t.features());
+				if msd != "_" {
+					attributes.push_str(&format!(" msd=\"{}\"", escape_xml(&msd)));
+				}
+			}
+			if tokens.get(i + 1).is_some_and(|next| !space_after(t, next)) {
+				attributes.push_str(" join=\"right\"");
+			}
+			let inner = if name_end.is_some() { "\t" } else { "" };
+			tei.push_str(&format!("{}{}<{}{}>{}</{}>\n", indent, inner, element, attributes, escape_xml(&t.text), element));
+			if name_end == Some(i) {
+				tei.push_str(&format!("{}</name>\n", indent));
+				name_end = None;
+			}
+		}
+
+		let tree = doc.dependency_trees.iter().find(|d| d.sentence_id == Some(sentence_id))
+			.or_else(|| doc.dependency_trees.iter().find(|d| d.sentence_id.is_none()));
+		let dependencies: Vec<_> = tree.map(|tree| {
+			tree.dependencies.iter().filter(|d| position.contains_key(&d.dep)).collect()
+		}).unwrap_or_default();
+		if options.dependencies && !dependencies.is_empty() {
+			tei.push_str(&format!("{}<linkGrp type=\"UD-SYN\" targFunc=\"head argument\">\n", indent));
+			for d in dependencies {
+				let head = if !d.is_root() {
+					format!("#w{}", d.gov)
+				} else if options.sentences {
+					format!("#s{}", sentence_id)
+				} else {
+					format!("#w{}", d.dep)
+				};
+				let label = escape_xml(&d.lab.replace(':', "_"));
+				tei.push_str(&format!("{}\t<link ana=\"ud-syn:{}\" target=\"{} #w{}\"/>\n", indent, label, head, d.dep));
+			}
+			tei.push_str(&format!("{}</linkGrp>\n", indent));
+		}
+		if options.sentences {
+			tei.push_str("\t\t\t\t</s>\n");
+		}
+	}
+	tei.push_str("\t\t\t</p>\n\t\t</body>\n\t</text>\n</TEI>\n");
+	tei
+}
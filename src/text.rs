@@ -0,0 +1,90 @@
+//! Reconstruction of the plain text of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents from their
+//! tokens, sentences, and paragraphs, for tools that only need the text.
+
+use crate::tei::space_after;
+use crate::{Document, Paragraph, SentenceId, Token};
+
+/// This struct contains the layout options of the plain text. By default the sentences are running text separated
+/// as given by the whitespace after their tokens, and paragraphs are separated by blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextOptions {
+	/// separate paragraphs by a blank line.
+	pub paragraphs: bool,
+	/// write every sentence on a line of its own.
+	pub sentence_per_line: bool,
+	/// separate all tokens by this string, e.g. a space for tokenized text, instead of the whitespace after them.
+	pub token_separator: Option<String>,
+}
+
+impl Default for TextOptions {
+	fn default() -> Self {
+		TextOptions { paragraphs: true, sentence_per_line: false, token_separator: None }
+	}
+}
+
+/// checks whether the sentence belongs to the paragraph, given by its sentence list, or else by the first token of
+/// the sentence and the token list or `tokenFrom`/`tokenTo` span of the paragraph.
+fn contains(p: &Paragraph, sentence: SentenceId, first: Option<&Token>) -> bool {
+	if !p.sentences.is_empty() {
+		return p.sentences.contains(&sentence);
+	}
+	let first = match first {
+		Some(first) => first.id,
+		None => return false,
+	};
+	match (p.token_from, p.token_to) {
+		_ if !p.tokens.is_empty() => p.tokens.contains(&first),
+		(Some(from), Some(to)) => first >= from && first <= to,
+		_ => false,
+	}
+}
+
+impl Document {
+	/// returns the text of the document reconstructed from its sentences in their order, with the text of every
+	/// sentence joined from its tokens and the whitespace after them, given by their character offsets or else by
+	/// their `spaceAfter` feature. Sentences without tokens contribute their text. Without sentences and tokens, the
+	/// text of the document is returned unchanged. The text ends with a line break unless it is empty.
+	pub fn to_text(&self, options: &TextOptions) -> String {
+		let groups = self.sentence_groups();
+		if groups.is_empty() {
+			return self.text.clone();
+		}
+
+		let mut out = String::new();
+		let mut previous: Option<(Option<usize>, Option<&Token>)> = None;
+		for (id, text, tokens) in &groups {
+			let paragraph = self.paragraphs.iter().position(|p| contains(p, *id, tokens.first().copied()));
+			if let Some((previous_paragraph, last)) = previous {
+				if options.paragraphs && paragraph != previous_paragraph {
+					out.push_str("\n\n");
+				} else if options.sentence_per_line {
+					out.push('\n');
+				} else if let Some(separator) = &options.token_separator {
+					out.push_str(separator);
+				} else if match (last, tokens.first()) {
+					(Some(last), Some(first)) => space_after(last, first),
+					_ => true,
+				} {
+					out.push(' ');
+				}
+			}
+
+			if tokens.is_empty() {
+				out.push_str(text);
+			}
+			for (i, t) in tokens.iter().enumerate() {
+				out.push_str(&t.text);
+				match (tokens.get(i + 1), &options.token_separator) {
+					(Some(_), Some(separator)) => out.push_str(separator),
+					(Some(next), None) if space_after(t, next) => out.push(' '),
+					_ => {}
+				}
+			}
+			previous = Some((paragraph, tokens.last().copied()));
+		}
+		if !out.is_empty() {
+			out.push('\n');
+		}
+		out
+	}
+}
@@ -0,0 +1,142 @@
+//! [TypeScript](https://www.typescriptlang.org/) declarations of the model types for frontend annotation viewers,
+//! generated from the JSON Schema of JSONNLP::json_schema(), so that they follow the structs and their serde
+//! attributes. Every struct becomes an interface named after it, with the properties that may be absent marked
+//! optional and the properties of the `extra` maps allowed by an index signature.
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::{Result, JSONNLP};
+
+/// returns the property name as a TypeScript property key, quoted if it is not an identifier.
+fn key(name: &str) -> String {
+	let mut chars = name.chars();
+	let identifier = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+		&& chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+	if identifier {
+		name.to_string()
+	} else {
+		Value::String(name.to_string()).to_string()
+	}
+}
+
+/// writes the description of a schema as a doc comment.
+fn comment(out: &mut String, schema: &Value, indent: &str) {
+	if let Some(description) = schema.get("description").and_then(Value::as_str) {
+		let description = description.trim().replace("*/", "*\\/");
+		if description.contains('\n') {
+			out.push_str(&format!("{}/**\n", indent));
+			for line in description.lines() {
+				let line = format!("{} * {}", indent, line);
+				out.push_str(line.trim_end());
+				out.push('\n');
+			}
+			out.push_str(&format!("{} */\n", indent));
+		} else {
+			out.push_str(&format!("{}/** {} */\n", indent, description));
+		}
+	}
+}
+
+/// returns the type of a JSON type name, with the items of arrays and the values of maps given by the schema.
+fn simple_type(name: &str, schema: &Value, indent: &str) -> String {
+	match name {
+		"string" => "string".to_string(),
+		"integer" | "number" => "number".to_string(),
+		"boolean" => "boolean".to_string(),
+		"null" => "null".to_string(),
+		"array" => {
+			let items = schema.get("items").map_or_else(|| "unknown".to_string(), |items| ts_type(items, indent));
+			if items.contains(' ') {
+				format!("({})[]", items)
+			} else {
+				format!("{}[]", items)
+			}
+		}
+		"object" => match schema.get("properties").and_then(Value::as_object) {
+			Some(properties) => {
+				let mut out = String::from("{\n");
+				members(&mut out, schema, properties, &format!("{}\t", indent));
+				out.push_str(indent);
+				out.push('}');
+				out
+			}
+			None => match schema.get("additionalProperties") {
+				Some(values) if values.is_object() => format!("Record<string, {}>", ts_type(values, indent)),
+				_ => "Record<string, unknown>".to_string(),
+			},
+		},
+		_ => "unknown".to_string(),
+	}
+}
+
+/// returns the TypeScript type of a schema.
+fn ts_type(schema: &Value, indent: &str) -> String {
+	if let Some(name) = schema.get("$ref").and_then(Value::as_str) {
+		return name.rsplit('/').next().unwrap_or(name).to_string();
+	}
+	if let Some(variants) = schema.get("anyOf").or_else(|| schema.get("oneOf")).and_then(Value::as_array) {
+		return variants.iter().map(|v| ts_type(v, indent)).collect::<Vec<_>>().join(" | ");
+	}
+	match schema.get("type") {
+		Some(Value::String(name)) => simple_type(name, schema, indent),
+		Some(Value::Array(names)) => {
+			let types: Vec<String> =
+				names.iter().filter_map(Value::as_str).map(|name| simple_type(name, schema, indent)).collect();
+			types.join(" | ")
+		}
+		_ => "unknown".to_string(),
+	}
+}
+
+/// writes the properties of an object schema, followed by an index signature if it allows further properties.
+fn members(out: &mut String, schema: &Value, properties: &Map<String, Value>, indent: &str) {
+	let required: Vec<&str> = match schema.get("required").and_then(Value::as_array) {
+		Some(required) => required.iter().filter_map(Value::as_str).collect(),
+		None => Vec::new(),
+	};
+	for (name, property) in properties {
+		comment(out, property, indent);
+		let optional = if required.contains(&name.as_str()) { "" } else { "?" };
+		out.push_str(&format!("{}{}{}: {};\n", indent, key(name), optional, ts_type(property, indent)));
+	}
+	if schema.get("additionalProperties").is_some_and(|a| a != &Value::Bool(false)) {
+		out.push_str(&format!("{}[key: string]: unknown;\n", indent));
+	}
+}
+
+/// writes the declaration of a named schema, as an interface if it has properties and as a type alias otherwise.
+fn declaration(out: &mut String, name: &str, schema: &Value) {
+	comment(out, schema, "");
+	match schema.get("properties").and_then(Value::as_object) {
+		Some(properties) => {
+			out.push_str(&format!("export interface {} {{\n", name));
+			members(out, schema, properties, "\t");
+			out.push_str("}\n\n");
+		}
+		None => out.push_str(&format!("export type {} = {};\n\n", name, ts_type(schema, ""))),
+	}
+}
+
+/// This function returns the TypeScript declarations of JSONNLP and all types it contains, e.g. for a `.d.ts` file.
+pub fn to_typescript() -> String {
+	let schema = JSONNLP::json_schema();
+	let mut out = String::from("// TypeScript declarations of the JSON-NLP model, generated by the jsonnlp crate.\n\n");
+	declaration(&mut out, "JSONNLP", &schema);
+	if let Some(defs) = schema.get("$defs").and_then(Value::as_object) {
+		for (name, def) in defs {
+			declaration(&mut out, name, def);
+		}
+	}
+	out.truncate(out.trim_end().len());
+	out.push('\n');
+	out
+}
+
+/// This function writes the TypeScript declarations of to_typescript() to a file, e.g. `jsonnlp.d.ts`.
+pub fn to_typescript_file<P: AsRef<Path>>(path: P) -> Result<()> {
+	fs::write(path, to_typescript())?;
+	Ok(())
+}
@@ -0,0 +1,480 @@
+//! Referential integrity checks for [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{Dependency, DependencyTree, Document, SentenceId, TokenId, JSONNLP};
+
+/// This enum describes the kind of problem found by a validation check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+	/// the ID is used by more than one element of the same layer.
+	DuplicateId { id: u64 },
+	/// the ID refers to an element of the given layer that does not exist.
+	DanglingReference { layer: &'static str, id: u64 },
+	/// the span starts after it ends.
+	InvalidSpan { from: u64, to: u64 },
+	/// the dependency tree has no root.
+	MissingRoot,
+	/// the dependency tree has more than one root.
+	MultipleRoots { count: usize },
+	/// the token is the dependent of more than one dependency.
+	MultipleHeads { id: u64 },
+	/// the token is part of a cycle of dependencies.
+	Cycle { id: u64 },
+	/// the token does not belong to the sentence of the dependency tree.
+	ForeignToken { id: u64, sentence: u64 },
+	/// the dependency crosses a token that is not dominated by its governor.
+	NonProjective { gov: u64, dep: u64 },
+	/// the character offsets lie outside of the text or start after they end.
+	OffsetOutOfRange { begin: u64, end: u64 },
+	/// the character offsets select a different string than the token text.
+	OffsetMismatch { expected: String, found: String },
+}
+
+/// This struct describes one problem found in a document, located by a JSON pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+	pub pointer: String,
+	pub kind: IssueKind,
+}
+
+impl fmt::Display for ValidationIssue {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.kind {
+			IssueKind::DuplicateId { id } => write!(f, "{}: duplicate ID {}", self.pointer, id),
+			IssueKind::DanglingReference { layer, id } => write!(f, "{}: reference to missing {} ID {}", self.pointer, layer, id),
+			IssueKind::InvalidSpan { from, to } => write!(f, "{}: span {}..{} starts after it ends", self.pointer, from, to),
+			IssueKind::MissingRoot => write!(f, "{}: dependency tree has no root", self.pointer),
+			IssueKind::MultipleRoots { count } => write!(f, "{}: dependency tree has {} roots", self.pointer, count),
+			IssueKind::MultipleHeads { id } => write!(f, "{}: token {} has more than one governor", self.pointer, id),
+			IssueKind::Cycle { id } => write!(f, "{}: token {} is part of a dependency cycle", self.pointer, id),
+			IssueKind::ForeignToken { id, sentence } => write!(f, "{}: token {} does not belong to sentence {}", self.pointer, id, sentence),
+			IssueKind::NonProjective { gov, dep } => write!(f, "{}: dependency {} -> {} is non-projective", self.pointer, gov, dep),
+			IssueKind::OffsetOutOfRange { begin, end } => write!(f, "{}: offsets {}..{} are outside of the text", self.pointer, begin, end),
+			IssueKind::OffsetMismatch { expected, found } => write!(f, "{}: offsets select {:?} instead of {:?}", self.pointer, found, expected),
+		}
+	}
+}
+
+/// This struct contains all problems found by a validation run.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+	pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+	/// returns true if no problems were found.
+	pub fn is_valid(&self) -> bool {
+		self.issues.is_empty()
+	}
+
+	pub(crate) fn push(&mut self, pointer: String, kind: IssueKind) {
+		self.issues.push(ValidationIssue { pointer, kind });
+	}
+
+	pub(crate) fn extend_prefixed(&mut self, prefix: &str, other: ValidationReport) {
+		for mut issue in other.issues {
+			issue.pointer = format!("{}{}", prefix, issue.pointer);
+			self.issues.push(issue);
+		}
+	}
+}
+
+/// The sets of IDs defined by the layers of a document.
+struct Ids {
+	tokens: HashSet<u64>,
+	sentences: HashSet<u64>,
+	clauses: HashSet<u64>,
+	entities: HashSet<u64>,
+	relations: HashSet<u64>,
+	triples: HashSet<u64>,
+}
+
+fn collect_ids<T: Into<u64>, I: Iterator<Item = T>>(ids: I, path: &str, report: &mut ValidationReport) -> HashSet<u64> {
+	let mut set = HashSet::new();
+	for (i, id) in ids.enumerate() {
+		let id = id.into();
+		if !set.insert(id) {
+			report.push(format!("{}/{}/id", path, i), IssueKind::DuplicateId { id });
+		}
+	}
+	set
+}
+
+fn check_ref<T: Into<u64>>(set: &HashSet<u64>, layer: &'static str, id: T, pointer: String, report: &mut ValidationReport) {
+	let id = id.into();
+	if !set.contains(&id) {
+		report.push(pointer, IssueKind::DanglingReference { layer, id });
+	}
+}
+
+fn check_refs<T: Copy + Into<u64>>(set: &HashSet<u64>, layer: &'static str, ids: &[T], pointer: &str, report: &mut ValidationReport) {
+	for (i, id) in ids.iter().enumerate() {
+		check_ref(set, layer, *id, format!("{}/{}", pointer, i), report);
+	}
+}
+
+fn check_span(ids: &Ids, from: Option<TokenId>, to: Option<TokenId>, pointer: &str, report: &mut ValidationReport) {
+	if let Some(from) = from {
+		check_ref(&ids.tokens, "token", from, format!("{}/tokenFrom", pointer), report);
+	}
+	if let Some(to) = to {
+		check_ref(&ids.tokens, "token", to, format!("{}/tokenTo", pointer), report);
+	}
+	if let (Some(from), Some(to)) = (from, to) {
+		if from > to {
+			report.push(pointer.to_string(), IssueKind::InvalidSpan { from: from.into(), to: to.into() });
+		}
+	}
+}
+
+impl Document {
+	/// checks that all IDs are unique within their layer and that all references between layers
+	/// point to existing elements. Dependency governor 0 is accepted as the root.
+	/// Token sentence IDs are only checked if the document has a sentence layer.
+	/// Dependency trees are also checked for structural problems, see DependencyTree::validate().
+	pub fn validate(&self) -> ValidationReport {
+		let mut r = ValidationReport::default();
+		let ids = Ids {
+			tokens: collect_ids(self.token_list.iter().map(|t| t.id), "/tokenList", &mut r),
+			sentences: collect_ids(self.sentences.iter().map(|s| s.id), "/sentences", &mut r),
+			clauses: collect_ids(self.clauses.iter().map(|c| c.id), "/clauses", &mut r),
+			entities: collect_ids(self.entities.iter().map(|e| e.id), "/entities", &mut r),
+			relations: collect_ids(self.relations.iter().map(|e| e.id), "/relations", &mut r),
+			triples: collect_ids(self.triples.iter().map(|t| t.id), "/triples", &mut r),
+		};
+		collect_ids(self.paragraphs.iter().map(|p| p.id), "/paragraphs", &mut r);
+		collect_ids(self.coreferences.iter().map(|c| c.id), "/coreferences", &mut r);
+		collect_ids(self.expressions.iter().map(|e| e.id), "/expressions", &mut r);
+		collect_ids(self.semantic_roles.iter().map(|p| p.id), "/semanticRoles", &mut r);
+
+		// documents without a sentence layer still carry sentence IDs on their tokens
+		if !self.sentences.is_empty() {
+			for (i, t) in self.token_list.iter().enumerate() {
+				check_ref(&ids.sentences, "sentence", t.sentence_id, format!("/tokenList/{}/sentence_id", i), &mut r);
+			}
+		}
+		for (i, s) in self.sentences.iter().enumerate() {
+			let p = format!("/sentences/{}", i);
+			check_refs(&ids.tokens, "token", &s.tokens, &format!("{}/tokens", p), &mut r);
+			check_refs(&ids.clauses, "clause", &s.clauses, &format!("{}/clauses", p), &mut r);
+			check_span(&ids, s.token_from, s.token_to, &p, &mut r);
+		}
+		for (i, c) in self.clauses.iter().enumerate() {
+			let p = format!("/clauses/{}", i);
+			if let Some(id) = c.sentence_id {
+				check_ref(&ids.sentences, "sentence", id, format!("{}/sentenceId", p), &mut r);
+			}
+			check_refs(&ids.tokens, "token", &c.tokens, &format!("{}/tokens", p), &mut r);
+			if let Some(id) = c.gov {
+				check_ref(&ids.tokens, "token", id, format!("{}/gov", p), &mut r);
+			}
+			if let Some(id) = c.head {
+				check_ref(&ids.tokens, "token", id, format!("{}/head", p), &mut r);
+			}
+			check_span(&ids, c.token_from, c.token_to, &p, &mut r);
+		}
+		for (i, para) in self.paragraphs.iter().enumerate() {
+			let p = format!("/paragraphs/{}", i);
+			check_refs(&ids.tokens, "token", &para.tokens, &format!("{}/tokens", p), &mut r);
+			check_refs(&ids.sentences, "sentence", &para.sentences, &format!("{}/sentences", p), &mut r);
+			check_span(&ids, para.token_from, para.token_to, &p, &mut r);
+		}
+		for (i, tree) in self.dependency_trees.iter().enumerate() {
+			let p = format!("/dependencyTrees/{}", i);
+			if let Some(id) = tree.sentence_id {
+				check_ref(&ids.sentences, "sentence", id, format!("{}/sentenceId", p), &mut r);
+			}
+			for (j, d) in tree.dependencies.iter().enumerate() {
+				if d.gov.0 != 0 {
+					check_ref(&ids.tokens, "token", d.gov, format!("{}/dependencies/{}/gov", p, j), &mut r);
+				}
+				check_ref(&ids.tokens, "token", d.dep, format!("{}/dependencies/{}/dep", p, j), &mut r);
+			}
+		}
+		for (i, tree) in self.dependency_trees.iter().enumerate() {
+			r.extend_prefixed(&format!("/dependencyTrees/{}", i), tree.validate(self, false));
+		}
+		for (i, c) in self.coreferences.iter().enumerate() {
+			let p = format!("/coreferences/{}", i);
+			check_refs(&ids.tokens, "token", &c.representative.tokens, &format!("{}/representative/tokens", p), &mut r);
+			check_ref(&ids.tokens, "token", c.representative.head, format!("{}/representative/head", p), &mut r);
+			for (j, referent) in c.referents.iter().enumerate() {
+				check_refs(&ids.tokens, "token", &referent.tokens, &format!("{}/referents/{}/tokens", p, j), &mut r);
+				check_ref(&ids.tokens, "token", referent.head, format!("{}/referents/{}/head", p, j), &mut r);
+			}
+		}
+		for (i, c) in self.constituents.iter().enumerate() {
+			let p = format!("/constituents/{}", i);
+			check_ref(&ids.sentences, "sentence", c.sentence_id, format!("{}/sentenceId", p), &mut r);
+			for (j, scope) in c.scopes.iter().enumerate() {
+				check_refs(&ids.tokens, "token", &scope.gov, &format!("{}/scopes/{}/gov", p, j), &mut r);
+				check_refs(&ids.tokens, "token", &scope.dep, &format!("{}/scopes/{}/dep", p, j), &mut r);
+				check_refs(&ids.tokens, "token", &scope.terminals, &format!("{}/scopes/{}/terminals", p, j), &mut r);
+			}
+		}
+		for (i, e) in self.expressions.iter().enumerate() {
+			let p = format!("/expressions/{}", i);
+			if let Some(id) = e.head {
+				check_ref(&ids.tokens, "token", id, format!("{}/head", p), &mut r);
+			}
+			check_refs(&ids.tokens, "token", &e.tokens, &format!("{}/tokens", p), &mut r);
+			check_span(&ids, e.token_from, e.token_to, &p, &mut r);
+		}
+		for (i, e) in self.entities.iter().enumerate() {
+			let p = format!("/entities/{}", i);
+			if let Some(id) = e.head {
+				check_ref(&ids.tokens, "token", id, format!("{}/head", p), &mut r);
+			}
+			check_refs(&ids.tokens, "token", &e.tokens, &format!("{}/tokens", p), &mut r);
+			check_span(&ids, e.token_from, e.token_to, &p, &mut r);
+			if let Some(id) = e.triple_id {
+				check_ref(&ids.triples, "triple", id, format!("{}/tripleID", p), &mut r);
+			}
+		}
+		for (i, rel) in self.relations.iter().enumerate() {
+			let p = format!("/relations/{}", i);
+			if let Some(id) = rel.head {
+				check_ref(&ids.tokens, "token", id, format!("{}/head", p), &mut r);
+			}
+			check_refs(&ids.tokens, "token", &rel.tokens, &format!("{}/tokens", p), &mut r);
+			check_span(&ids, rel.token_from, rel.token_to, &p, &mut r);
+		}
+		for (i, t) in self.triples.iter().enumerate() {
+			let p = format!("/triples/{}", i);
+			if let Some(id) = t.from_entity {
+				check_ref(&ids.entities, "entity", id, format!("{}/fromEntity", p), &mut r);
+			}
+			if let Some(id) = t.to_entity {
+				check_ref(&ids.entities, "entity", id, format!("{}/toEntity", p), &mut r);
+			}
+			if let Some(id) = t.rel {
+				check_ref(&ids.relations, "relation", id, format!("{}/rel", p), &mut r);
+			}
+			check_refs(&ids.clauses, "clause", &t.clause_id, &format!("{}/clauseID", p), &mut r);
+			check_refs(&ids.sentences, "sentence", &t.sentence_id, &format!("{}/sentenceID", p), &mut r);
+		}
+		for (i, pred) in self.semantic_roles.iter().enumerate() {
+			let p = format!("/semanticRoles/{}", i);
+			if let Some(id) = pred.sentence_id {
+				check_ref(&ids.sentences, "sentence", id, format!("{}/sentenceId", p), &mut r);
+			}
+			check_ref(&ids.tokens, "token", pred.head, format!("{}/head", p), &mut r);
+			for (j, arg) in pred.arguments.iter().enumerate() {
+				let p = format!("{}/arguments/{}", p, j);
+				check_refs(&ids.tokens, "token", &arg.tokens, &format!("{}/tokens", p), &mut r);
+				check_span(&ids, arg.token_from, arg.token_to, &p, &mut r);
+			}
+		}
+		for (i, graph) in self.amr_graphs.iter().enumerate() {
+			let p = format!("/amrGraphs/{}", i);
+			if let Some(id) = graph.sentence_id {
+				check_ref(&ids.sentences, "sentence", id, format!("{}/sentenceId", p), &mut r);
+			}
+			for (j, node) in graph.nodes.iter().enumerate() {
+				check_refs(&ids.tokens, "token", &node.tokens, &format!("{}/nodes/{}/tokens", p, j), &mut r);
+			}
+			for (j, attribute) in graph.attributes.iter().enumerate() {
+				check_refs(&ids.tokens, "token", &attribute.tokens, &format!("{}/attributes/{}/tokens", p, j), &mut r);
+			}
+		}
+		r
+	}
+}
+
+impl Dependency {
+	/// returns true if this dependency attaches its dependent to the root,
+	/// i.e. the governor is 0 or the label is "root".
+	pub fn is_root(&self) -> bool {
+		self.gov.0 == 0 || self.lab.eq_ignore_ascii_case("root")
+	}
+}
+
+impl DependencyTree {
+	/// checks that the tree has exactly one root, no cycles, at most one governor per token,
+	/// and that all tokens belong to the tree's sentence in the given document.
+	/// If `projective` is set, non-projective dependencies are reported as well.
+	pub fn validate(&self, doc: &Document, projective: bool) -> ValidationReport {
+		let mut r = ValidationReport::default();
+		let roots = self.dependencies.iter().filter(|d| d.is_root()).count();
+		if roots == 0 && !self.dependencies.is_empty() {
+			r.push(String::new(), IssueKind::MissingRoot);
+		} else if roots > 1 {
+			r.push(String::new(), IssueKind::MultipleRoots { count: roots });
+		}
+
+		if let Some(sentence) = self.sentence_id {
+			let sentence_of: HashMap<TokenId, SentenceId> = doc.token_list.iter().map(|t| (t.id, t.sentence_id)).collect();
+			for (j, d) in self.dependencies.iter().enumerate() {
+				// governor 0 is the root and not a token
+				for (field, id) in [("gov", d.gov), ("dep", d.dep)].iter().filter(|(_, id)| id.0 != 0) {
+					if sentence_of.get(id).is_some_and(|s| *s != sentence) {
+						r.push(format!("/dependencies/{}/{}", j, field), IssueKind::ForeignToken { id: (*id).into(), sentence: sentence.into() });
+					}
+				}
+			}
+		}
+
+		// governor of every dependent that is not attached to the root
+		let mut heads: HashMap<TokenId, TokenId> = HashMap::new();
+		let mut seen = HashSet::new();
+		for (j, d) in self.dependencies.iter().enumerate() {
+			if !seen.insert(d.dep) {
+				r.push(format!("/dependencies/{}/dep", j), IssueKind::MultipleHeads { id: d.dep.into() });
+				continue;
+			}
+			if !d.is_root() {
+				heads.insert(d.dep, d.gov);
+			}
+		}
+
+		let mut cyclic = HashSet::new();
+		for (j, d) in self.dependencies.iter().enumerate() {
+			if cyclic.contains(&d.dep) {
+				continue;
+			}
+			let mut path = vec![d.dep];
+			let mut current = d.dep;
+			while let Some(gov) = heads.get(&current) {
+				if let Some(start) = path.iter().position(|t| t == gov) {
+					if path[start..].iter().all(|t| cyclic.insert(*t)) {
+						r.push(format!("/dependencies/{}", j), IssueKind::Cycle { id: d.dep.into() });
+					}
+					break;
+				}
+				path.push(*gov);
+				current = *gov;
+			}
+		}
+
+		if projective {
+			for (j, d) in self.dependencies.iter().enumerate() {
+				if d.is_root() || cyclic.contains(&d.dep) {
+					continue;
+				}
+				let (lo, hi) = if d.gov < d.dep { (d.gov, d.dep) } else { (d.dep, d.gov) };
+				let crossed = seen.iter()
+					.filter(|t| **t > lo && **t < hi)
+					.any(|t| !dominates(&heads, d.gov, *t));
+				if crossed {
+					r.push(format!("/dependencies/{}", j), IssueKind::NonProjective { gov: d.gov.into(), dep: d.dep.into() });
+				}
+			}
+		}
+		r
+	}
+}
+
+/// returns true if `gov` is an ancestor of `token`, following the governor map.
+fn dominates(heads: &HashMap<TokenId, TokenId>, gov: TokenId, token: TokenId) -> bool {
+	let mut current = token;
+	let mut steps = 0;
+	while let Some(h) = heads.get(&current) {
+		if *h == gov {
+			return true;
+		}
+		current = *h;
+		steps += 1;
+		if steps > heads.len() {
+			break;
+		}
+	}
+	false
+}
+
+impl JSONNLP {
+	/// validates all documents, see Document::validate().
+	pub fn validate(&self) -> ValidationReport {
+		let mut r = ValidationReport::default();
+		for (i, doc) in self.docs.iter().enumerate() {
+			r.extend_prefixed(&format!("/docs/{}", i), doc.validate());
+		}
+		r
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// returns the document of the JSON of its tokens and further layers.
+	fn doc(layers: &str) -> Document {
+		let json = format!(
+			r#"{{"meta":{{}},"id":1,"tokenList":[{{"id":1,"sentence_id":1,"text":"Dogs","lemma":"dog"}},
+			{{"id":2,"sentence_id":1,"text":"bark","lemma":"bark"}}]{}}}"#,
+			layers
+		);
+		serde_json::from_str(&json).expect("valid document")
+	}
+
+	#[test]
+	fn references_between_layers() {
+		let valid = doc(r#","sentences":[{"id":1,"tokenFrom":1,"tokenTo":2,"tokens":[1,2]}],
+			"entities":[{"id":1,"type":"ANIMAL","tokens":[1]}],"relations":[{"id":1}],
+			"triples":[{"id":1,"fromEntity":1,"toEntity":1,"rel":1}]"#);
+		assert!(valid.validate().is_valid(), "{:?}", valid.validate());
+
+		let invalid = doc(r#","sentences":[{"id":1,"tokenFrom":2,"tokenTo":1,"tokens":[1,3]}],
+			"entities":[{"id":1,"type":"ANIMAL","tokens":[1]},{"id":1,"type":"ANIMAL","tokens":[2]}],
+			"triples":[{"id":1,"fromEntity":2,"rel":1}]"#);
+		let issues: Vec<_> = invalid.validate().issues.into_iter().map(|i| (i.pointer, i.kind)).collect();
+		assert_eq!(issues, [
+			("/entities/1/id".to_string(), IssueKind::DuplicateId { id: 1 }),
+			("/sentences/0/tokens/1".to_string(), IssueKind::DanglingReference { layer: "token", id: 3 }),
+			("/sentences/0".to_string(), IssueKind::InvalidSpan { from: 2, to: 1 }),
+			("/triples/0/fromEntity".to_string(), IssueKind::DanglingReference { layer: "entity", id: 2 }),
+			("/triples/0/rel".to_string(), IssueKind::DanglingReference { layer: "relation", id: 1 }),
+		]);
+		let j = JSONNLP { docs: vec![valid, invalid], ..Default::default() };
+		let report = j.validate();
+		assert_eq!(report.issues.len(), 5);
+		assert_eq!(report.issues[0].to_string(), "/docs/1/entities/1/id: duplicate ID 1");
+	}
+
+	/// returns the tree of the dependencies given as governor, dependent, and label.
+	fn tree(dependencies: &[(u64, u64, &str)]) -> DependencyTree {
+		let dependencies = dependencies
+			.iter()
+			.map(|(gov, dep, lab)| Dependency {
+				gov: TokenId(*gov),
+				dep: TokenId(*dep),
+				lab: lab.to_string(),
+				..Default::default()
+			})
+			.collect();
+		DependencyTree { sentence_id: Some(SentenceId(1)), dependencies, ..Default::default() }
+	}
+
+	#[test]
+	fn dependency_tree_structure() {
+		let d = doc(r#","sentences":[{"id":1,"tokens":[1,2]}]"#);
+		let kinds = |t: DependencyTree, projective: bool| -> Vec<IssueKind> {
+			t.validate(&d, projective).issues.into_iter().map(|i| i.kind).collect()
+		};
+		assert_eq!(kinds(tree(&[(0, 2, "root"), (2, 1, "nsubj")]), true), []);
+		// the cycle is reported at its first dependency
+		let cycle = tree(&[(1, 2, "dep"), (2, 1, "nsubj")]);
+		assert_eq!(kinds(cycle, false), [IssueKind::MissingRoot, IssueKind::Cycle { id: 2 }]);
+		assert_eq!(kinds(tree(&[(0, 2, "root"), (0, 1, "root")]), false), [IssueKind::MultipleRoots { count: 2 }]);
+		assert_eq!(kinds(tree(&[(0, 2, "root"), (2, 1, "nsubj"), (0, 1, "dep")]), false), [
+			IssueKind::MultipleRoots { count: 2 },
+			IssueKind::MultipleHeads { id: 1 },
+		]);
+		let mut other = d.clone();
+		other.token_list[0].sentence_id = SentenceId(2);
+		let issues = tree(&[(0, 2, "root"), (2, 1, "nsubj")]).validate(&other, false).issues;
+		assert_eq!(issues[0].pointer, "/dependencies/1/dep");
+		assert_eq!(issues[0].kind, IssueKind::ForeignToken { id: 1, sentence: 1 });
+	}
+
+	#[test]
+	fn non_projective_dependencies() {
+		let d = doc("");
+		// 1 <- 3 crosses 2, which is attached to 4
+		let t = tree(&[(0, 4, "root"), (3, 1, "dep"), (4, 2, "dep"), (4, 3, "dep")]);
+		assert!(t.validate(&d, false).is_valid());
+		let issues = t.validate(&d, true).issues;
+		assert_eq!(issues.iter().map(|i| &i.kind).collect::<Vec<_>>(), [&IssueKind::NonProjective { gov: 3, dep: 1 }]);
+	}
+}
\ No newline at end of file
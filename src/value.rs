@@ -0,0 +1,36 @@
+//! Conversions between the typed model and untyped serde_json Values.
+
+use std::convert::TryFrom;
+
+use serde_json::Value;
+
+use crate::{Document, JsonNlpError, JSONNLP};
+
+impl TryFrom<Value> for JSONNLP {
+	type Error = JsonNlpError;
+
+	fn try_from(value: Value) -> Result<Self, Self::Error> {
+		Ok(serde_path_to_error::deserialize(value)?)
+	}
+}
+
+impl From<&JSONNLP> for Value {
+	fn from(j: &JSONNLP) -> Self {
+		// all maps in the model have string keys, so this cannot fail
+		serde_json::to_value(j).expect("JSONNLP is representable as a Value")
+	}
+}
+
+impl TryFrom<Value> for Document {
+	type Error = JsonNlpError;
+
+	fn try_from(value: Value) -> Result<Self, Self::Error> {
+		Ok(serde_path_to_error::deserialize(value)?)
+	}
+}
+
+impl From<&Document> for Value {
+	fn from(d: &Document) -> Self {
+		serde_json::to_value(d).expect("Document is representable as a Value")
+	}
+}
@@ -0,0 +1,94 @@
+//! [WebAssembly](https://webassembly.org/) bindings for browser-based annotation viewers, built with
+//! [wasm-bindgen](https://rustwasm.github.io/wasm-bindgen/). A collection is parsed once into a `JsonNlp` object,
+//! whose queries return the items of a layer as JSON strings, to be read with `JSON.parse`.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{Document, RawId, SentenceId, JSONNLP};
+
+fn js_error(e: impl std::fmt::Display) -> JsError {
+	JsError::new(&e.to_string())
+}
+
+fn json<T: Serialize + ?Sized>(value: &T) -> Result<String, JsError> {
+	serde_json::to_string(value).map_err(js_error)
+}
+
+/// This struct contains a parsed JSON-NLP collection, exported to JavaScript as `JsonNlp`.
+#[wasm_bindgen(js_name = JsonNlp)]
+pub struct WasmJsonNlp {
+	inner: JSONNLP,
+}
+
+impl WasmJsonNlp {
+	fn doc(&self, doc: usize) -> Result<&Document, JsError> {
+		self.inner.docs.get(doc).ok_or_else(|| js_error(format!("no document at index {}", doc)))
+	}
+}
+
+#[wasm_bindgen(js_class = JsonNlp)]
+impl WasmJsonNlp {
+	/// parses a string containing JSON-NLP, throwing an error if it is invalid.
+	#[wasm_bindgen(js_name = fromString)]
+	pub fn from_string(json: &str) -> Result<WasmJsonNlp, JsError> {
+		crate::from_string(json).map(|inner| WasmJsonNlp { inner }).map_err(js_error)
+	}
+
+	/// returns the collection as JSON.
+	#[wasm_bindgen(js_name = getJson)]
+	pub fn get_json(&self) -> Result<String, JsError> {
+		crate::get_json(&self.inner).map_err(js_error)
+	}
+
+	/// returns the problems found by validation, or an empty array if the collection is valid.
+	pub fn validate(&self) -> Vec<String> {
+		self.inner.validate().issues.iter().map(ToString::to_string).collect()
+	}
+
+	/// returns the number of documents.
+	#[wasm_bindgen(getter, js_name = docCount)]
+	pub fn doc_count(&self) -> usize {
+		self.inner.docs.len()
+	}
+
+	/// returns the text of a document.
+	pub fn text(&self, doc: usize) -> Result<String, JsError> {
+		Ok(self.doc(doc)?.text.clone())
+	}
+
+	/// returns the number of tokens of a document.
+	#[wasm_bindgen(js_name = tokenCount)]
+	pub fn token_count(&self, doc: usize) -> Result<usize, JsError> {
+		Ok(self.doc(doc)?.token_list.len())
+	}
+
+	/// returns the tokens of a document as JSON.
+	pub fn tokens(&self, doc: usize) -> Result<String, JsError> {
+		json(&self.doc(doc)?.token_list)
+	}
+
+	/// returns the tokens of a sentence of a document as JSON.
+	#[wasm_bindgen(js_name = sentenceTokens)]
+	pub fn sentence_tokens(&self, doc: usize, sentence: u32) -> Result<String, JsError> {
+		let sentence = SentenceId(RawId::from(sentence));
+		let tokens: Vec<_> = self.doc(doc)?.token_list.iter().filter(|t| t.sentence_id == sentence).collect();
+		json(&tokens)
+	}
+
+	/// returns the sentences of a document as JSON.
+	pub fn sentences(&self, doc: usize) -> Result<String, JsError> {
+		json(&self.doc(doc)?.sentences)
+	}
+
+	/// returns the entities of a document as JSON.
+	pub fn entities(&self, doc: usize) -> Result<String, JsError> {
+		json(&self.doc(doc)?.entities)
+	}
+
+	/// returns the dependency trees of a document as JSON.
+	#[wasm_bindgen(js_name = dependencyTrees)]
+	pub fn dependency_trees(&self, doc: usize) -> Result<String, JsError> {
+		json(&self.doc(doc)?.dependency_trees)
+	}
+}
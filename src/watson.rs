@@ -0,0 +1,509 @@
+//! Conversion of IBM Watson
+//! [Natural Language Understanding](https://cloud.ibm.com/apidocs/natural-language-understanding) responses to
+//! [JSON-NLP](https://github.com/SemiringInc/JSON-NLP).
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::offsets::{OffsetUnit, SpanLocator};
+use crate::{
+	Argument, Document, DocumentId, Entity, EntityId, Expression, ExpressionId, JsonNlpError, LanguageTag, Predicate,
+	PredicateId, Prob, RawId, Result, Sentence, SentenceId, Token, TokenId, Triple, TripleId,
+};
+
+/// This struct contains the response of the `analyze` method.
+#[derive(Deserialize)]
+struct WatsonResponse {
+	#[serde(default)]
+	language: String,
+	#[serde(default)]
+	analyzed_text: String,
+	#[serde(default)]
+	syntax: Option<WatsonSyntax>,
+	#[serde(default)]
+	entities: Vec<WatsonEntity>,
+	#[serde(default)]
+	keywords: Vec<WatsonKeyword>,
+	#[serde(default)]
+	relations: Vec<WatsonRelation>,
+	#[serde(default)]
+	semantic_roles: Vec<WatsonRole>,
+	#[serde(default)]
+	sentiment: Option<Value>,
+	#[serde(default)]
+	emotion: Option<Value>,
+	#[serde(default)]
+	categories: Vec<Value>,
+	#[serde(default)]
+	concepts: Vec<Value>,
+}
+
+/// This struct contains the tokens and sentences of the syntax feature.
+#[derive(Deserialize)]
+struct WatsonSyntax {
+	#[serde(default)]
+	tokens: Vec<WatsonToken>,
+	#[serde(default)]
+	sentences: Vec<WatsonSpan>,
+}
+
+/// This struct contains a token with its POS tag and lemma, if they were requested.
+#[derive(Deserialize)]
+struct WatsonToken {
+	text: String,
+	#[serde(default)]
+	location: Vec<u64>,
+	#[serde(default)]
+	part_of_speech: String,
+	#[serde(default)]
+	lemma: String,
+}
+
+/// This struct contains a text span, with its location as begin and end offset.
+#[derive(Deserialize)]
+struct WatsonSpan {
+	text: String,
+	#[serde(default)]
+	location: Vec<u64>,
+	#[serde(default)]
+	confidence: Option<f64>,
+}
+
+/// This struct contains an entity with its mentions.
+#[derive(Deserialize)]
+struct WatsonEntity {
+	#[serde(rename = "type", default)]
+	etype: String,
+	#[serde(default)]
+	text: String,
+	#[serde(default)]
+	relevance: Option<f64>,
+	#[serde(default)]
+	confidence: Option<f64>,
+	#[serde(default)]
+	mentions: Vec<WatsonSpan>,
+	#[serde(default)]
+	disambiguation: Option<WatsonDisambiguation>,
+	#[serde(default)]
+	sentiment: Option<WatsonSentiment>,
+	#[serde(default)]
+	emotion: Option<Value>,
+}
+
+/// This struct contains the disambiguation of an entity to a DBpedia resource.
+#[derive(Deserialize)]
+struct WatsonDisambiguation {
+	#[serde(default)]
+	name: String,
+	#[serde(default)]
+	dbpedia_resource: String,
+	#[serde(default)]
+	subtype: Vec<String>,
+}
+
+/// This struct contains the sentiment of an entity or keyword, with its label and a score from -1 to 1.
+#[derive(Deserialize)]
+struct WatsonSentiment {
+	#[serde(default)]
+	label: String,
+	#[serde(default)]
+	score: Option<f64>,
+}
+
+/// This struct contains a keyword, which has no location.
+#[derive(Deserialize)]
+struct WatsonKeyword {
+	text: String,
+	#[serde(default)]
+	relevance: Option<f64>,
+	#[serde(default)]
+	count: Option<u64>,
+	#[serde(default)]
+	sentiment: Option<WatsonSentiment>,
+	#[serde(default)]
+	emotion: Option<Value>,
+}
+
+/// This struct contains a relation between the entities of its two arguments.
+#[derive(Deserialize)]
+struct WatsonRelation {
+	#[serde(rename = "type")]
+	rtype: String,
+	#[serde(default)]
+	score: Option<f64>,
+	#[serde(default)]
+	arguments: Vec<WatsonArgument>,
+}
+
+/// This struct contains an argument of a relation with its entities.
+#[derive(Deserialize)]
+struct WatsonArgument {
+	text: String,
+	#[serde(default)]
+	location: Vec<u64>,
+	#[serde(default)]
+	entities: Vec<WatsonArgumentEntity>,
+}
+
+/// This struct contains an entity of a relation argument.
+#[derive(Deserialize)]
+struct WatsonArgumentEntity {
+	#[serde(rename = "type", default)]
+	etype: String,
+}
+
+/// This struct contains a subject-action-object triple of the semantic roles feature, which has no locations.
+#[derive(Deserialize)]
+struct WatsonRole {
+	sentence: String,
+	#[serde(default)]
+	subject: Option<WatsonRoleText>,
+	action: WatsonAction,
+	#[serde(default)]
+	object: Option<WatsonRoleText>,
+}
+
+/// This struct contains the subject or object of a semantic role.
+#[derive(Deserialize)]
+struct WatsonRoleText {
+	text: String,
+}
+
+/// This struct contains the action of a semantic role with its normalized text and verb.
+#[derive(Deserialize)]
+struct WatsonAction {
+	text: String,
+	#[serde(default)]
+	normalized: String,
+	#[serde(default)]
+	verb: Option<WatsonVerb>,
+}
+
+/// This struct contains the verb of an action in its base form with its tense.
+#[derive(Deserialize)]
+struct WatsonVerb {
+	text: String,
+	#[serde(default)]
+	tense: String,
+}
+
+/// returns the character offsets of a span with a location, if it is in the text.
+fn locate(locator: &SpanLocator, text: &str, location: &[u64], from: u64) -> Option<(u64, u64)> {
+	locator.locate(text, location.first().copied(), from)
+}
+
+/// sets the sentiment of an annotation to the label, and keeps the score in its `extra` map as `sentimentScore`.
+fn sentiment(s: Option<&WatsonSentiment>, target: &mut String, extra: &mut Map<String, Value>) {
+	if let Some(s) = s {
+		target.clone_from(&s.label);
+		if let Some(score) = s.score {
+			extra.insert("sentimentScore".to_string(), json!(score));
+		}
+	}
+}
+
+/// This function converts the JSON response of Watson Natural Language Understanding's `analyze` method to a
+/// Document with the text that was analyzed, or else the `analyzed_text` of the response if the text is empty.
+/// `unit` is the unit of the locations in the response; spans whose locations do not select their text are located
+/// at the nearest occurrence of their text instead.
+///
+/// The tokens and sentences of the syntax feature become tokens with their UPOS tag and lemma, and sentences.
+/// Every mention of an entity becomes an Entity with the type and text of the entity, the DBpedia resource of its
+/// disambiguation as URL, and its relevance, confidence, subtypes, emotion, and character span in the `extra` map,
+/// and its sentiment label with the score as `sentimentScore` in the `extra` map. Every relation becomes a Triple
+/// between the entities of its arguments, with a Relation per relation type and the score as probability; arguments
+/// without an entity mention of the same span become entities of their own. The subject-action-object triples of
+/// the semantic roles become predicates of the sentence with the last token of the action as head, the verb as
+/// lemma, and the `subject` and `object` arguments, which are located as the first occurrence of their text in the
+/// sentence. Keywords become expressions of type `keyword` with the tokens of the first occurrence of their text,
+/// and with their text, relevance, count, sentiment, and emotion in the `extra` map. The document sentiment,
+/// emotion, categories, and concepts are kept in the document's `extra` map.
+pub fn from_watson_nlu_json(text: &str, json: &str, unit: OffsetUnit) -> Result<Document> {
+	let mut de = serde_json::Deserializer::from_str(json);
+	let watson: WatsonResponse = serde_path_to_error::deserialize(&mut de)?;
+	de.end()?;
+
+	let text = if text.is_empty() { watson.analyzed_text.as_str() } else { text };
+	let locator = SpanLocator::new(text, unit);
+	let mut doc = Document { id: DocumentId(1), text: text.to_string(), ..Default::default() };
+	if !watson.language.is_empty() {
+		doc.meta.language = LanguageTag::new(&watson.language)?;
+	}
+
+	// the character spans of the sentences
+	let mut sentence_spans = Vec::new();
+	if let Some(syntax) = &watson.syntax {
+		let mut from = 0;
+		for (i, s) in syntax.sentences.iter().enumerate() {
+			let span = locate(&locator, &s.text, &s.location, from)
+				.ok_or_else(|| JsonNlpError::Format(format!("sentence {} {:?} is not in the text", i + 1, s.text)))?;
+			doc.sentences.push(Sentence { id: SentenceId(i as RawId + 1), text: s.text.clone(), ..Default::default() });
+			sentence_spans.push(span);
+			from = span.1;
+		}
+		let mut from = 0;
+		for (i, t) in syntax.tokens.iter().enumerate() {
+			let (begin, end) = locate(&locator, &t.text, &t.location, from)
+				.ok_or_else(|| JsonNlpError::Format(format!("token {} {:?} is not in the text", i + 1, t.text)))?;
+			if doc.token_list.last().is_some_and(|last| last.char_offset_end == Some(begin)) {
+				doc.token_list.last_mut().expect("there is a last token").features_mut().spaceafter = Some(false);
+			}
+			let sentence = sentence_spans.iter().rposition(|s| s.0 <= begin).map_or(1, |s| s as RawId + 1);
+			doc.token_list.push(Token {
+				id: TokenId(i as RawId + 1),
+				sentence_id: SentenceId(sentence),
+				text: t.text.clone(),
+				lemma: t.lemma.clone(),
+				upos: t.part_of_speech.clone(),
+				char_offset_begin: Some(begin),
+				char_offset_end: Some(end),
+				..Default::default()
+			});
+			from = end;
+		}
+		if doc.sentences.is_empty() && !doc.token_list.is_empty() {
+			doc.sentences.push(Sentence { id: SentenceId(1), text: text.to_string(), ..Default::default() });
+			sentence_spans.push((0, text.chars().count() as u64));
+		}
+		for s in doc.sentences.iter_mut() {
+			s.tokens = doc.token_list.iter().filter(|t| t.sentence_id == s.id).map(|t| t.id).collect();
+			s.token_from = s.tokens.first().copied();
+			s.token_to = s.tokens.last().copied();
+		}
+	}
+
+	for e in &watson.entities {
+		for m in &e.mentions {
+			let (begin, end) = match locate(&locator, &m.text, &m.location, 0) {
+				Some(span) => span,
+				None => continue,
+			};
+			let tokens = doc.tokens_overlapping(&[(begin, end)]);
+			let mut entity = Entity {
+				id: EntityId(doc.entities.len() as RawId + 1),
+				label: e.text.clone(),
+				etype: e.etype.clone(),
+				token_from: tokens.first().copied(),
+				token_to: tokens.last().copied(),
+				tokens,
+				..Default::default()
+			};
+			if let Some(d) = &e.disambiguation {
+				entity.url.clone_from(&d.dbpedia_resource);
+				if !d.name.is_empty() {
+					entity.extra.insert("name".to_string(), json!(d.name));
+				}
+				if !d.subtype.is_empty() {
+					entity.extra.insert("subtype".to_string(), json!(d.subtype));
+				}
+			}
+			if let Some(relevance) = e.relevance {
+				entity.extra.insert("relevance".to_string(), json!(relevance));
+			}
+			if let Some(confidence) = m.confidence.or(e.confidence) {
+				entity.extra.insert("confidence".to_string(), json!(confidence));
+			}
+			if let Some(emotion) = &e.emotion {
+				entity.extra.insert("emotion".to_string(), emotion.clone());
+			}
+			sentiment(e.sentiment.as_ref(), &mut entity.sentiment, &mut entity.extra);
+			entity.extra.insert("spans".to_string(), json!([[begin, end]]));
+			doc.entities.push(entity);
+		}
+	}
+	if !doc.entities.is_empty() {
+		for t in doc.token_list.iter_mut() {
+			t.entity_iob = "O".to_string();
+		}
+		for e in &doc.entities {
+			for (i, id) in e.tokens.iter().enumerate() {
+				if let Some(t) = doc.token_list.get_mut((id.0 - 1) as usize).filter(|t| t.entity_iob == "O") {
+					t.entity_iob = if i == 0 { "B" } else { "I" }.to_string();
+					t.entity.clone_from(&e.etype);
+				}
+			}
+		}
+	}
+
+	for r in &watson.relations {
+		// the entities of the arguments, found by their span or else added
+		let mut entities = Vec::new();
+		for a in &r.arguments {
+			let (begin, end) = match locate(&locator, &a.text, &a.location, 0) {
+				Some(span) => span,
+				None => continue,
+			};
+			let spans = json!([[begin, end]]);
+			let id = match doc.entities.iter().find(|e| e.extra.get("spans") == Some(&spans)) {
+				Some(e) => e.id,
+				None => {
+					let id = EntityId(doc.entities.len() as RawId + 1);
+					let tokens = doc.tokens_overlapping(&[(begin, end)]);
+					let mut entity = Entity {
+						id,
+						label: a.text.clone(),
+						etype: a.entities.first().map(|e| e.etype.clone()).unwrap_or_default(),
+						token_from: tokens.first().copied(),
+						token_to: tokens.last().copied(),
+						tokens,
+						..Default::default()
+					};
+					entity.extra.insert("spans".to_string(), spans);
+					doc.entities.push(entity);
+					id
+				}
+			};
+			entities.push((id, begin));
+		}
+		if let [(from, begin), (to, _)] = entities[..] {
+			let rel = doc.relation_of_type(&r.rtype);
+			let sentence = sentence_spans.iter().rposition(|s| s.0 <= begin).map(|s| SentenceId(s as RawId + 1));
+			doc.triples.push(Triple {
+				id: TripleId(doc.triples.len() as RawId + 1),
+				from_entity: Some(from),
+				to_entity: Some(to),
+				rel: Some(rel),
+				sentence_id: sentence.into_iter().collect(),
+				directional: Some(true),
+				prob: r.score.and_then(Prob::new),
+				..Default::default()
+			});
+		}
+	}
+
+	for role in &watson.semantic_roles {
+		// the sentence of the role, and where its text is searched
+		let sentence = doc.sentences.iter().position(|s| s.text == role.sentence);
+		let from = sentence.and_then(|s| sentence_spans.get(s)).map_or(0, |s| s.0);
+		let tokens = |s: &str| -> Vec<TokenId> {
+			locator.locate(s, None, from).map(|span| doc.tokens_overlapping(&[span])).unwrap_or_default()
+		};
+		let mut predicate = Predicate {
+			id: PredicateId(doc.semantic_roles.len() as RawId + 1),
+			sentence_id: sentence.map(|s| doc.sentences[s].id),
+			head: tokens(&role.action.text).last().copied().unwrap_or_default(),
+			lemma: role.action.verb.as_ref().map(|v| v.text.clone()).unwrap_or_default(),
+			..Default::default()
+		};
+		for (label, argument) in [("subject", &role.subject), ("object", &role.object)] {
+			if let Some(argument) = argument {
+				let tokens = tokens(&argument.text);
+				let mut a = Argument {
+					label: label.to_string(),
+					token_from: tokens.first().copied(),
+					token_to: tokens.last().copied(),
+					tokens,
+					..Default::default()
+				};
+				a.extra.insert("text".to_string(), json!(argument.text));
+				predicate.arguments.push(a);
+			}
+		}
+		predicate.extra.insert("action".to_string(), json!(role.action.text));
+		if !role.action.normalized.is_empty() {
+			predicate.extra.insert("normalized".to_string(), json!(role.action.normalized));
+		}
+		if let Some(tense) = role.action.verb.as_ref().map(|v| &v.tense).filter(|t| !t.is_empty()) {
+			predicate.extra.insert("tense".to_string(), json!(tense));
+		}
+		doc.semantic_roles.push(predicate);
+	}
+
+	for k in &watson.keywords {
+		let tokens = locator.locate(&k.text, None, 0).map(|span| doc.tokens_overlapping(&[span])).unwrap_or_default();
+		let mut expression = Expression {
+			id: ExpressionId(doc.expressions.len() as RawId + 1),
+			etype: "keyword".to_string(),
+			token_from: tokens.first().copied(),
+			token_to: tokens.last().copied(),
+			tokens,
+			..Default::default()
+		};
+		expression.extra.insert("text".to_string(), json!(k.text));
+		if let Some(relevance) = k.relevance {
+			expression.extra.insert("relevance".to_string(), json!(relevance));
+		}
+		if let Some(count) = k.count {
+			expression.extra.insert("count".to_string(), json!(count));
+		}
+		if let Some(emotion) = &k.emotion {
+			expression.extra.insert("emotion".to_string(), emotion.clone());
+		}
+		let mut label = String::new();
+		sentiment(k.sentiment.as_ref(), &mut label, &mut expression.extra);
+		if !label.is_empty() {
+			expression.extra.insert("sentiment".to_string(), json!(label));
+		}
+		doc.expressions.push(expression);
+	}
+
+	for (key, value) in [("sentiment", watson.sentiment), ("emotion", watson.emotion)] {
+		if let Some(value) = value {
+			doc.extra.insert(key.to_string(), value);
+		}
+	}
+	for (key, value) in [("categories", watson.categories), ("concepts", watson.concepts)] {
+		if !value.is_empty() {
+			doc.extra.insert(key.to_string(), Value::Array(value));
+		}
+	}
+	Ok(doc)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RESPONSE: &str = r#"{"language":"en","analyzed_text":"Ann met Bob in Rome.","syntax":{
+		"tokens":[{"text":"Ann","location":[0,3],"part_of_speech":"PROPN"},
+			{"text":"met","location":[4,7],"part_of_speech":"VERB","lemma":"meet"},
+			{"text":"Bob","location":[8,11],"part_of_speech":"PROPN"},{"text":"in","location":[12,14]},
+			{"text":"Rome","location":[15,19],"part_of_speech":"PROPN"},{"text":".","location":[19,20]}],
+		"sentences":[{"text":"Ann met Bob in Rome.","location":[0,20]}]},
+		"entities":[{"type":"Person","text":"Ann","relevance":0.5,"mentions":[{"text":"Ann","location":[0,3]}],
+			"sentiment":{"label":"neutral","score":0.0}},
+			{"type":"Person","text":"Bob","mentions":[{"text":"Bob","location":[8,11],"confidence":0.75}]}],
+		"relations":[{"type":"locatedAt","score":0.5,"arguments":[{"text":"Bob","location":[8,11]},
+			{"text":"Rome","location":[15,19],"entities":[{"type":"GeopoliticalEntity","text":"Rome"}]}]}],
+		"semantic_roles":[{"sentence":"Ann met Bob in Rome.","subject":{"text":"Ann"},
+			"action":{"text":"met","normalized":"meet","verb":{"text":"meet","tense":"past"}},"object":{"text":"Bob"}}],
+		"keywords":[{"text":"Rome","relevance":0.9,"count":1}],"categories":[{"label":"/travel"}]}"#;
+
+	#[test]
+	fn watson_import() {
+		let doc = from_watson_nlu_json("", RESPONSE, OffsetUnit::CodePoint).expect("valid analyze response");
+		assert_eq!((doc.text.as_str(), doc.meta.language.as_str()), ("Ann met Bob in Rome.", "en"));
+		assert_eq!((doc.token_list[1].lemma.as_str(), doc.token_list[4].features().spaceafter), ("meet", Some(false)));
+		assert_eq!(doc.sentences[0].tokens.len(), 6);
+
+		let entities: Vec<_> = doc.entities.iter().map(|e| (e.etype.as_str(), e.tokens.clone())).collect();
+		assert_eq!(entities, [
+			("Person", vec![TokenId(1)]),
+			("Person", vec![TokenId(3)]),
+			("GeopoliticalEntity", vec![TokenId(5)]),
+		]);
+		assert_eq!(doc.entities[0].sentiment, "neutral");
+		assert_eq!(doc.entities[1].extra["confidence"], 0.75);
+		let t = &doc.triples[0];
+		assert_eq!((t.from_entity, t.to_entity, t.prob), (Some(EntityId(2)), Some(EntityId(3)), Prob::new(0.5)));
+		assert_eq!((doc.relations[0].rtype.as_str(), t.sentence_id.as_slice()), ("locatedAt", &[SentenceId(1)][..]));
+
+		let p = &doc.semantic_roles[0];
+		assert_eq!((p.head, p.lemma.as_str(), &p.extra["tense"]), (TokenId(2), "meet", &json!("past")));
+		let arguments: Vec<_> = p.arguments.iter().map(|a| (a.label.as_str(), a.tokens.clone())).collect();
+		assert_eq!(arguments, [("subject", vec![TokenId(1)]), ("object", vec![TokenId(3)])]);
+		assert_eq!((doc.expressions[0].tokens.as_slice(), &doc.expressions[0].extra["count"]), (
+			&[TokenId(5)][..],
+			&json!(1),
+		));
+		assert_eq!(doc.extra["categories"], json!([{ "label": "/travel" }]));
+	}
+
+	#[test]
+	fn watson_errors() {
+		let e = from_watson_nlu_json("Ann met Bob.", RESPONSE, OffsetUnit::CodePoint).unwrap_err();
+		let message = "sentence 1 \"Ann met Bob in Rome.\" is not in the text";
+		assert!(matches!(e, JsonNlpError::Format(ref m) if m == message), "{}", e);
+		assert!(from_watson_nlu_json("", r#"{"relations":[{"score":0.5}]}"#, OffsetUnit::CodePoint).is_err());
+	}
+}
@@ -0,0 +1,82 @@
+//! Export of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) documents to the
+//! [W3C Web Annotation Data Model](https://www.w3.org/TR/annotation-model/).
+
+use serde_json::{json, Value};
+
+use crate::offsets::{char_boundaries, slice_chars};
+use crate::{Document, TokenId};
+
+/// This function converts the entities, expressions, and coreference mentions of a Document to Web Annotations
+/// in JSON-LD, collected in an `AnnotationPage`. Every annotation targets the source IRI, e.g. the URL of the
+/// annotated text, with a `TextPositionSelector` of the character offsets and a `TextQuoteSelector` of the covered
+/// text. Entities and expressions are tagged with their type, and entities with a URL identify it. Coreference
+/// mentions link to the annotation of the chain's representative mention. Annotation IDs append `#entity-1`,
+/// `#expression-1`, or `#coreference-1-0` for the representative and `#coreference-1-1` etc. for the referents
+/// to the source IRI. Elements without character offsets are skipped.
+pub fn to_web_annotations(doc: &Document, source: &str) -> Value {
+	let boundaries = char_boundaries(&doc.text);
+	let target = |tokens: &dyn Fn(TokenId) -> bool| -> Option<Value> {
+		let (begin, end) = doc.char_span(tokens)?;
+		let exact = slice_chars(&doc.text, &boundaries, begin, end)?;
+		Some(json!({
+			"source": source,
+			"selector": [
+				{ "type": "TextPositionSelector", "start": begin, "end": end },
+				{ "type": "TextQuoteSelector", "exact": exact },
+			],
+		}))
+	};
+	let tag = |value: &str| json!({ "type": "TextualBody", "value": value, "purpose": "tagging" });
+	let annotation = |id: String, motivation: &str, body: Vec<Value>, target: Value| {
+		let mut annotation = json!({ "id": id, "type": "Annotation", "motivation": motivation, "target": target });
+		if !body.is_empty() {
+			annotation["body"] = Value::Array(body);
+		}
+		annotation
+	};
+
+	let mut items = Vec::new();
+	for e in &doc.entities {
+		let target = match target(&|id| e.covers(id)) {
+			Some(target) => target,
+			None => continue,
+		};
+		let mut body = Vec::new();
+		if !e.etype.is_empty() {
+			body.push(tag(&e.etype));
+		}
+		if !e.url.is_empty() {
+			body.push(json!({ "id": e.url, "purpose": "identifying" }));
+		}
+		let motivation = if e.url.is_empty() { "tagging" } else { "identifying" };
+		items.push(annotation(format!("{}#entity-{}", source, e.id), motivation, body, target));
+	}
+
+	for e in &doc.expressions {
+		let target = match target(&|id| e.covers(id)) {
+			Some(target) => target,
+			None => continue,
+		};
+		let body = if e.etype.is_empty() { vec![] } else { vec![tag(&e.etype)] };
+		items.push(annotation(format!("{}#expression-{}", source, e.id), "tagging", body, target));
+	}
+
+	for c in &doc.coreferences {
+		let representative = format!("{}#coreference-{}-0", source, c.id);
+		if let Some(target) = target(&|id| c.representative.tokens.contains(&id)) {
+			items.push(annotation(representative.clone(), "identifying", vec![], target));
+		}
+		for (i, r) in c.referents.iter().enumerate() {
+			if let Some(target) = target(&|id| r.tokens.contains(&id)) {
+				let body = vec![json!({ "id": representative, "purpose": "linking" })];
+				items.push(annotation(format!("{}#coreference-{}-{}", source, c.id, i + 1), "linking", body, target));
+			}
+		}
+	}
+
+	json!({
+		"@context": "http://www.w3.org/ns/anno.jsonld",
+		"type": "AnnotationPage",
+		"items": items,
+	})
+}
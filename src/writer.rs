@@ -0,0 +1,193 @@
+//! Incremental writing of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) collections, for pipelines that
+//! produce tokens and other layer items one at a time.
+
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::{
+	AmrGraph, Clause, ConstituentParse, Coreference, DependencyTree, DocumentId, Entity, Expression, JsonNlpError, Meta,
+	Paragraph, Predicate, Relation, Result, Sentence, Token, Triple,
+};
+
+/// the layers of a document other than the token list, in the order in which they are written.
+const LAYERS: [&str; 12] = [
+	"clauses",
+	"sentences",
+	"paragraphs",
+	"dependencyTrees",
+	"coreferences",
+	"constituents",
+	"expressions",
+	"entities",
+	"relations",
+	"triples",
+	"semanticRoles",
+	"amrGraphs",
+];
+
+/// This struct writes a JSON-NLP collection to a writer one document at a time. The collection metadata is written
+/// when the writer is created, and [CollectionWriter::finish] closes the collection.
+pub struct CollectionWriter<W: Write> {
+	writer: W,
+	docs: usize,
+}
+
+impl<W: Write> CollectionWriter<W> {
+	/// returns a writer of a collection with the given metadata to the writer, which is not buffered.
+	pub fn new(mut writer: W, meta: &Meta) -> Result<Self> {
+		writer.write_all(b"{\"meta\":")?;
+		serde_json::to_writer(&mut writer, meta).map_err(JsonNlpError::Serialize)?;
+		writer.write_all(b",\"docs\":[")?;
+		Ok(CollectionWriter { writer, docs: 0 })
+	}
+
+	/// starts a document with the given metadata and ID. The document must be finished with
+	/// [DocumentWriter::finish] before the next one is started.
+	pub fn document(&mut self, meta: &Meta, id: DocumentId) -> Result<DocumentWriter<'_, W>> {
+		if self.docs > 0 {
+			self.writer.write_all(b",")?;
+		}
+		self.docs += 1;
+		self.writer.write_all(b"{\"meta\":")?;
+		serde_json::to_writer(&mut self.writer, meta).map_err(JsonNlpError::Serialize)?;
+		self.writer.write_all(b",\"id\":")?;
+		serde_json::to_writer(&mut self.writer, &id).map_err(JsonNlpError::Serialize)?;
+		self.writer.write_all(b",\"tokenList\":[")?;
+		Ok(DocumentWriter { writer: &mut self.writer, tokens: 0, layers: Default::default(), text: String::new() })
+	}
+
+	/// returns the number of documents started so far.
+	pub fn len(&self) -> usize {
+		self.docs
+	}
+
+	/// checks whether no document has been started.
+	pub fn is_empty(&self) -> bool {
+		self.docs == 0
+	}
+
+	/// closes the collection, flushes, and returns the underlying writer.
+	pub fn finish(mut self) -> Result<W> {
+		self.writer.write_all(b"]}")?;
+		self.writer.flush()?;
+		Ok(self.writer)
+	}
+}
+
+/// This struct writes one document of a [CollectionWriter]. Tokens are written to the underlying writer as they
+/// are added, while the text and the items of the other layers, serialized to JSON, are kept until
+/// [DocumentWriter::finish], as they can only follow the complete token list. Dropping the writer without finishing
+/// it leaves the output incomplete.
+pub struct DocumentWriter<'a, W: Write> {
+	writer: &'a mut W,
+	tokens: usize,
+	layers: [Vec<u8>; LAYERS.len()],
+	text: String,
+}
+
+impl<W: Write> DocumentWriter<'_, W> {
+	/// writes a token.
+	pub fn token(&mut self, token: &Token) -> Result<()> {
+		if self.tokens > 0 {
+			self.writer.write_all(b",")?;
+		}
+		self.tokens += 1;
+		serde_json::to_writer(&mut *self.writer, token).map_err(JsonNlpError::Serialize)
+	}
+
+	/// returns the number of tokens written so far.
+	pub fn tokens(&self) -> usize {
+		self.tokens
+	}
+
+	fn push<T: Serialize>(&mut self, layer: usize, item: &T) -> Result<()> {
+		let buffer = &mut self.layers[layer];
+		buffer.push(if buffer.is_empty() { b'[' } else { b',' });
+		serde_json::to_writer(buffer, item).map_err(JsonNlpError::Serialize)
+	}
+
+	/// adds a clause.
+	pub fn clause(&mut self, clause: &Clause) -> Result<()> {
+		self.push(0, clause)
+	}
+
+	/// adds a sentence.
+	pub fn sentence(&mut self, sentence: &Sentence) -> Result<()> {
+		self.push(1, sentence)
+	}
+
+	/// adds a paragraph.
+	pub fn paragraph(&mut self, paragraph: &Paragraph) -> Result<()> {
+		self.push(2, paragraph)
+	}
+
+	/// adds a dependency tree.
+	pub fn dependency_tree(&mut self, tree: &DependencyTree) -> Result<()> {
+		self.push(3, tree)
+	}
+
+	/// adds a coreference.
+	pub fn coreference(&mut self, coreference: &Coreference) -> Result<()> {
+		self.push(4, coreference)
+	}
+
+	/// adds a constituent parse.
+	pub fn constituent(&mut self, constituent: &ConstituentParse) -> Result<()> {
+		self.push(5, constituent)
+	}
+
+	/// adds an expression.
+	pub fn expression(&mut self, expression: &Expression) -> Result<()> {
+		self.push(6, expression)
+	}
+
+	/// adds an entity.
+	pub fn entity(&mut self, entity: &Entity) -> Result<()> {
+		self.push(7, entity)
+	}
+
+	/// adds a relation.
+	pub fn relation(&mut self, relation: &Relation) -> Result<()> {
+		self.push(8, relation)
+	}
+
+	/// adds a triple.
+	pub fn triple(&mut self, triple: &Triple) -> Result<()> {
+		self.push(9, triple)
+	}
+
+	/// adds a predicate with its semantic roles.
+	pub fn semantic_role(&mut self, predicate: &Predicate) -> Result<()> {
+		self.push(10, predicate)
+	}
+
+	/// adds an AMR graph.
+	pub fn amr_graph(&mut self, graph: &AmrGraph) -> Result<()> {
+		self.push(11, graph)
+	}
+
+	/// appends to the text of the document.
+	pub fn text(&mut self, text: &str) {
+		self.text.push_str(text);
+	}
+
+	/// closes the token list, writes the other layers and the text, and closes the document.
+	pub fn finish(self) -> Result<()> {
+		self.writer.write_all(b"]")?;
+		for (key, buffer) in LAYERS.iter().zip(&self.layers) {
+			if buffer.is_empty() {
+				continue;
+			}
+			write!(self.writer, ",\"{}\":", key)?;
+			self.writer.write_all(buffer)?;
+			self.writer.write_all(b"]")?;
+		}
+		if !self.text.is_empty() {
+			self.writer.write_all(b",\"text\":")?;
+			serde_json::to_writer(&mut *self.writer, &self.text).map_err(JsonNlpError::Serialize)?;
+		}
+		self.writer.write_all(b"}")?;
+		Ok(())
+	}
+}
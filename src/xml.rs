@@ -0,0 +1,227 @@
+//! A generic XML representation of the [JSON-NLP](https://github.com/SemiringInc/JSON-NLP) model, for pipelines
+//! that only process XML.
+//!
+//! Every JSON object becomes an element, with its scalar properties as attributes and its other properties as child
+//! elements named after the property. Arrays become an element per layer, e.g. `<tokenList>`, with one child element
+//! per item, e.g. `<token>`, whose scalar values are given as text. Numbers, booleans, null, and empty arrays and
+//! objects are written as JSON, and strings that would be read as JSON are written as JSON strings, so that the
+//! types survive a round-trip. Property names that are not XML names are encoded as `_xHHHH_` sequences.
+
+use serde_json::{Map, Value};
+
+use crate::tei::escape_xml;
+use crate::{JsonNlpError, Result, JSONNLP};
+
+/// returns the element name of the items of an array property.
+fn item_name(key: &str) -> &'static str {
+	match key {
+		"docs" => "document",
+		"tokenList" | "tokens" | "gov" | "dep" | "terminals" => "token",
+		"sentences" | "sentenceID" => "sentence",
+		"clauses" | "clauseID" => "clause",
+		"paragraphs" => "paragraph",
+		"dependencyTrees" => "dependencyTree",
+		"dependencies" => "dependency",
+		"coreferences" => "coreference",
+		"referents" => "referent",
+		"constituents" => "constituent",
+		"scopes" => "scope",
+		"expressions" => "expression",
+		"entities" => "entity",
+		"relations" => "relation",
+		"triples" => "triple",
+		"semanticRoles" => "predicate",
+		"arguments" => "argument",
+		"amrGraphs" => "amrGraph",
+		"nodes" => "node",
+		"edges" => "edge",
+		"attributes" => "attribute",
+		_ => "item",
+	}
+}
+
+/// returns a property name as an XML name, encoding the characters that are not allowed, colons, underscores that
+/// would start an escape sequence, and the `x` of a leading `xmlns`.
+fn encode_name(key: &str) -> String {
+	let mut out = String::with_capacity(key.len());
+	let mut chars = key.chars().peekable();
+	let mut first = true;
+	while let Some(c) = chars.next() {
+		let valid = match c {
+			'_' => chars.peek() != Some(&'x'),
+			'x' => !(first && key.starts_with("xmlns")),
+			'a'..='z' | 'A'..='Z' => true,
+			'0'..='9' | '-' | '.' => !first,
+			c => !c.is_ascii() && c.is_alphanumeric(),
+		};
+		if valid {
+			out.push(c);
+		} else {
+			out.push_str(&format!("_x{:04X}_", c as u32));
+		}
+		first = false;
+	}
+	out
+}
+
+/// returns the property name of an encoded XML name.
+#[cfg(feature = "xml")]
+fn decode_name(name: &str) -> String {
+	let mut out = String::with_capacity(name.len());
+	let mut rest = name;
+	while let Some(i) = rest.find("_x") {
+		out.push_str(&rest[..i]);
+		let escape = &rest[i + 2..];
+		let end = escape.find('_').filter(|&end| end >= 4 && escape[..end].chars().all(|c| c.is_ascii_hexdigit()));
+		match end.and_then(|end| u32::from_str_radix(&escape[..end], 16).ok().and_then(char::from_u32).map(|c| (c, end))) {
+			Some((c, end)) => {
+				out.push(c);
+				rest = &escape[end + 1..];
+			}
+			None => {
+				out.push_str("_x");
+				rest = escape;
+			}
+		}
+	}
+	out.push_str(rest);
+	out
+}
+
+/// returns the attribute or text form of a scalar, empty array, or empty object, or None for the values that are
+/// written as elements.
+fn scalar(value: &Value) -> Option<String> {
+	match value {
+		Value::String(s) if serde_json::from_str::<Value>(s).is_ok() => Some(value.to_string()),
+		Value::String(s) => Some(s.clone()),
+		Value::Array(a) if !a.is_empty() => None,
+		Value::Object(o) if !o.is_empty() => None,
+		v => Some(v.to_string()),
+	}
+}
+
+/// returns the text with the XML special characters and the whitespace that XML parsers normalize escaped.
+fn escape(s: &str) -> String {
+	escape_xml(s).replace('\t', "&#9;").replace('\n', "&#10;").replace('\r', "&#13;")
+}
+
+fn write_object(out: &mut String, name: &str, obj: &Map<String, Value>) -> Result<()> {
+	out.push('<');
+	out.push_str(name);
+	let mut children = Vec::new();
+	for (key, value) in obj {
+		if key.is_empty() {
+			return Err(JsonNlpError::Format("XML: empty property names are not supported".to_string()));
+		}
+		match scalar(value) {
+			Some(s) => out.push_str(&format!(" {}=\"{}\"", encode_name(key), escape(&s))),
+			None => children.push((key, value)),
+		}
+	}
+	if children.is_empty() {
+		out.push_str("/>");
+		return Ok(());
+	}
+	out.push('>');
+	for (key, value) in children {
+		match value {
+			Value::Array(items) => {
+				let name = encode_name(key);
+				out.push_str(&format!("<{}>", name));
+				for item in items {
+					write_value(out, item_name(key), item)?;
+				}
+				out.push_str(&format!("</{}>", name));
+			}
+			_ => write_value(out, &encode_name(key), value)?,
+		}
+	}
+	out.push_str(&format!("</{}>", name));
+	Ok(())
+}
+
+fn write_value(out: &mut String, name: &str, value: &Value) -> Result<()> {
+	match value {
+		Value::Object(obj) if !obj.is_empty() => write_object(out, name, obj),
+		Value::Array(items) if !items.is_empty() => {
+			out.push_str(&format!("<{}>", name));
+			for item in items {
+				write_value(out, "item", item)?;
+			}
+			out.push_str(&format!("</{}>", name));
+			Ok(())
+		}
+		_ => {
+			let s = scalar(value).unwrap_or_default();
+			out.push_str(&format!("<{}>{}</{}>", name, escape(&s), name));
+			Ok(())
+		}
+	}
+}
+
+/// This function converts a JSONNLP struct/object to its generic XML representation with the root element
+/// `<jsonnlp>`, see from_xml() for reading it back.
+pub fn to_xml(j: &JSONNLP) -> Result<String> {
+	let value = serde_json::to_value(j).map_err(JsonNlpError::Serialize)?;
+	let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+	match &value {
+		Value::Object(obj) => write_object(&mut out, "jsonnlp", obj)?,
+		_ => unreachable!("JSONNLP serializes to an object"),
+	}
+	out.push('\n');
+	Ok(out)
+}
+
+/// returns the value of an attribute or of the text of an item element.
+#[cfg(feature = "xml")]
+fn parse_scalar(s: &str) -> Value {
+	serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.to_string()))
+}
+
+#[cfg(feature = "xml")]
+fn read_object(node: roxmltree::Node) -> Map<String, Value> {
+	let mut obj = Map::new();
+	for a in node.attributes() {
+		obj.insert(decode_name(a.name()), parse_scalar(a.value()));
+	}
+	for c in node.children().filter(roxmltree::Node::is_element) {
+		let key = decode_name(c.tag_name().name());
+		let item = item_name(&key);
+		let mut elements = c.children().filter(roxmltree::Node::is_element).peekable();
+		let value = if c.attributes().len() == 0 && elements.peek().is_some() && elements.all(|e| e.has_tag_name(item)) {
+			Value::Array(c.children().filter(roxmltree::Node::is_element).map(read_value).collect())
+		} else {
+			Value::Object(read_object(c))
+		};
+		obj.insert(key, value);
+	}
+	obj
+}
+
+/// returns the value of an array item, whose items are named `item` if it is an array itself.
+#[cfg(feature = "xml")]
+fn read_value(node: roxmltree::Node) -> Value {
+	let mut elements = node.children().filter(roxmltree::Node::is_element).peekable();
+	if node.attributes().len() > 0 {
+		Value::Object(read_object(node))
+	} else if elements.peek().is_none() {
+		parse_scalar(node.text().unwrap_or_default())
+	} else if elements.all(|e| e.has_tag_name("item")) {
+		Value::Array(node.children().filter(roxmltree::Node::is_element).map(read_value).collect())
+	} else {
+		Value::Object(read_object(node))
+	}
+}
+
+/// This function converts the generic XML representation written by to_xml() back to a JSONNLP struct.
+/// Errors of the model are named by the JSON pointer of the offending element.
+#[cfg(feature = "xml")]
+pub fn from_xml(xml: &str) -> Result<JSONNLP> {
+	let tree = roxmltree::Document::parse(xml).map_err(|e| JsonNlpError::Format(format!("XML: {}", e)))?;
+	let root = tree.root_element();
+	if !root.has_tag_name("jsonnlp") {
+		return Err(JsonNlpError::Format(format!("XML: unexpected root element <{}>", root.tag_name().name())));
+	}
+	let value = Value::Object(read_object(root));
+	Ok(serde_path_to_error::deserialize(value)?)
+}
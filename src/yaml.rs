@@ -0,0 +1,18 @@
+//! [YAML](https://yaml.org/) representation of [JSON-NLP](https://github.com/SemiringInc/JSON-NLP), which has the
+//! same structure as the JSON.
+
+use crate::{JsonNlpError, Result, JSONNLP};
+
+fn error(msg: String) -> JsonNlpError {
+	JsonNlpError::Format(format!("YAML: {}", msg))
+}
+
+/// This function returns the YAML representation of a JSONNLP struct/object.
+pub fn to_yaml(j: &JSONNLP) -> Result<String> {
+	serde_yaml::to_string(j).map_err(|e| error(e.to_string()))
+}
+
+/// This function converts a string containing JSON-NLP as YAML, returning a JSONNLP struct.
+pub fn from_yaml(yaml: &str) -> Result<JSONNLP> {
+	serde_yaml::from_str(yaml).map_err(|e| error(e.to_string()))
+}